@@ -50,24 +50,14 @@ pub async fn run() {
     };
     let mut fog_enabled = true;
 
-    // main loop
-    let mut color_texture = Texture2D::new_empty::<[f16; 4]>(
-        &context,
-        camera.viewport().width,
-        camera.viewport().height,
-        Interpolation::Nearest,
-        Interpolation::Nearest,
-        None,
-        Wrapping::ClampToEdge,
-        Wrapping::ClampToEdge,
-    );
-    let mut depth_texture = DepthTexture2D::new::<f32>(
+    let mut scene = SizedRenderTarget::<[f16; 4], f32>::try_new(
         &context,
         camera.viewport().width,
         camera.viewport().height,
-        Wrapping::ClampToEdge,
-        Wrapping::ClampToEdge,
-    );
+    )
+    .expect("this example requires rendering into 16 bit float textures");
+
+    // main loop
     window.render_loop(move |mut frame_input| {
         let mut change = frame_input.first_frame;
         change |= camera.set_viewport(frame_input.viewport);
@@ -85,34 +75,11 @@ pub async fn run() {
 
         if change {
             // Draw the scene to a render target if a change has occured
-            if camera.viewport().width != color_texture.width()
-                || camera.viewport().height != color_texture.height()
-            {
-                color_texture = Texture2D::new_empty::<[f16; 4]>(
-                    &context,
-                    camera.viewport().width,
-                    camera.viewport().height,
-                    Interpolation::Nearest,
-                    Interpolation::Nearest,
-                    None,
-                    Wrapping::ClampToEdge,
-                    Wrapping::ClampToEdge,
-                );
-                depth_texture = DepthTexture2D::new::<f32>(
-                    &context,
-                    camera.viewport().width,
-                    camera.viewport().height,
-                    Wrapping::ClampToEdge,
-                    Wrapping::ClampToEdge,
-                );
-            }
             camera.disable_tone_and_color_mapping();
-            RenderTarget::new(
-                color_texture.as_color_target(None),
-                depth_texture.as_depth_target(),
-            )
-            .clear(ClearState::default())
-            .render(&camera, &monkey, &[&ambient, &directional]);
+            scene
+                .render_target(camera.viewport().width, camera.viewport().height)
+                .clear(ClearState::default())
+                .render(&camera, &monkey, &[&ambient, &directional]);
         }
 
         change |= fog_enabled; // Always render if fog is enabled since it contain animation.
@@ -125,16 +92,16 @@ pub async fn run() {
                     &fog_effect,
                     &camera,
                     &[],
-                    Some(ColorTexture::Single(&color_texture)),
-                    Some(DepthTexture::Single(&depth_texture)),
+                    Some(ColorTexture::Single(scene.color_texture())),
+                    Some(DepthTexture::Single(scene.depth_texture())),
                 );
             } else {
                 frame_input.screen().apply_screen_effect(
                     &ScreenEffect::default(),
                     &camera,
                     &[],
-                    Some(ColorTexture::Single(&color_texture)),
-                    Some(DepthTexture::Single(&depth_texture)),
+                    Some(ColorTexture::Single(scene.color_texture())),
+                    Some(DepthTexture::Single(scene.depth_texture())),
                 );
             }
         }
@@ -38,26 +38,10 @@ fn main() {
         ColorMaterial::default(),
     );
 
-    // Create a color texture to render into
-    let mut texture = Texture2D::new_empty::<[u8; 4]>(
-        &context,
-        viewport.width,
-        viewport.height,
-        Interpolation::Nearest,
-        Interpolation::Nearest,
-        None,
-        Wrapping::ClampToEdge,
-        Wrapping::ClampToEdge,
-    );
-
-    // Also create a depth texture to support depth testing
-    let mut depth_texture = DepthTexture2D::new::<f32>(
-        &context,
-        viewport.width,
-        viewport.height,
-        Wrapping::ClampToEdge,
-        Wrapping::ClampToEdge,
-    );
+    // Create a multisampled render target (a combination of a color and a depth texture) to write into.
+    // Multisampling avoids aliasing, ie. jagged edges, and is resolved when reading the colors.
+    let render_target =
+        RenderTargetMultisample::<[u8; 4], f32>::new(&context, viewport.width, viewport.height, 4);
 
     // Render three frames
     for frame_index in 0..3 {
@@ -66,17 +50,13 @@ fn main() {
             (frame_index as f32 * 0.6) as f32,
         )));
 
-        // Create a render target (a combination of a color and a depth texture) to write into
-        let pixels = RenderTarget::new(
-            texture.as_color_target(None),
-            depth_texture.as_depth_target(),
-        )
-        // Clear color and depth of the render target
-        .clear(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0))
-        // Render the triangle with the per vertex colors defined at construction
-        .render(&camera, &model, &[])
-        // Read out the colors from the render target
-        .read_color();
+        let pixels = render_target
+            // Clear color and depth of the render target
+            .clear(ClearState::color_and_depth(0.8, 0.8, 0.8, 1.0, 1.0))
+            // Render the triangle with the per vertex colors defined at construction
+            .render(&camera, &model, &[])
+            // Resolve the multisampled colors and read them out
+            .read_color();
 
         // Save the rendered image
         use three_d_asset::io::Serialize;
@@ -84,8 +64,8 @@ fn main() {
         three_d_asset::io::save(
             &CpuTexture {
                 data: TextureData::RgbaU8(pixels),
-                width: texture.width(),
-                height: texture.height(),
+                width: render_target.width(),
+                height: render_target.height(),
                 ..Default::default()
             }
             .serialize(format!("headless-{}.png", frame_index))
@@ -79,16 +79,12 @@ pub async fn run() {
         );
         camera.set_viewport(viewport);
 
-        let material = ColorMaterial {
-            texture: Some(Texture2DRef {
-                texture: image.clone(),
-                transformation: Mat3::from_scale(texture_transform_scale)
-                    * Mat3::from_translation(vec2(texture_transform_x, texture_transform_y)),
-            }),
-            ..Default::default()
-        };
+        let material = ColorMaterial::textured(Texture2DRef::from(image.clone()).with_transform(
+            Mat3::from_scale(texture_transform_scale)
+                * Mat3::from_translation(vec2(texture_transform_x, texture_transform_y)),
+        ));
 
-        let mut target = Texture2D::new_empty::<[f16; 4]>(
+        let mut target = Texture2D::try_new_empty::<[f16; 4]>(
             &context,
             viewport.width,
             viewport.height,
@@ -97,7 +93,8 @@ pub async fn run() {
             None,
             Wrapping::ClampToEdge,
             Wrapping::ClampToEdge,
-        );
+        )
+        .expect("this example requires rendering into 16 bit float textures");
 
         camera.disable_tone_and_color_mapping();
         target
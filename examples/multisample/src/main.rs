@@ -15,6 +15,7 @@ pub fn main() {
     })
     .unwrap();
     let context = window.gl();
+    let window_multisamples = window.multisamples();
 
     let mut render_steps = RenderMethod::ToTexture;
 
@@ -78,7 +79,11 @@ pub fn main() {
                 use three_d::egui::*;
                 SidePanel::left("side_panel").show(gui_context, |ui| {
                     ui.heading("Debug Panel");
-                    ui.radio_value(&mut render_steps, RenderMethod::Direct, "Direct (MSAA x4)");
+                    ui.radio_value(
+                        &mut render_steps,
+                        RenderMethod::Direct,
+                        format!("Direct (MSAA x{})", window_multisamples),
+                    );
                     ui.radio_value(
                         &mut render_steps,
                         RenderMethod::ToTexture,
@@ -20,16 +20,10 @@ impl Material for FireworksMaterial {
     fn render_states(&self) -> RenderStates {
         RenderStates {
             cull: Cull::Back,
-            blend: Blend::Enabled {
-                rgb_equation: BlendEquationType::Add,
-                alpha_equation: BlendEquationType::Add,
-                source_rgb_multiplier: BlendMultiplierType::SrcAlpha,
-                source_alpha_multiplier: BlendMultiplierType::Zero,
-                destination_rgb_multiplier: BlendMultiplierType::One,
-                destination_alpha_multiplier: BlendMultiplierType::One,
-            },
+            blend: Blend::ADDITIVE,
             depth_test: DepthTest::LessOrEqual,
             write_mask: WriteMask::COLOR,
+            ..Default::default()
         }
     }
     fn material_type(&self) -> MaterialType {
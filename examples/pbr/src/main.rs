@@ -139,6 +139,14 @@ pub async fn run() {
                     },
                     render_states: model.material.render_states,
                     is_transparent: model.material.is_transparent,
+                    transmission: model.material.transmission,
+                    transmission_texture: model.material.transmission_texture.clone(),
+                    index_of_refraction: model.material.index_of_refraction,
+                    clearcoat: model.material.clearcoat,
+                    clearcoat_roughness: model.material.clearcoat_roughness,
+                    clearcoat_texture: model.material.clearcoat_texture.clone(),
+                    clearcoat_roughness_texture: model.material.clearcoat_roughness_texture.clone(),
+                    triplanar_scale: model.material.triplanar_scale,
                     lighting_model: LightingModel::Cook(
                         NormalDistributionFunction::TrowbridgeReitzGGX,
                         GeometryFunction::SmithSchlickGGX,
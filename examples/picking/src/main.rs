@@ -160,6 +160,7 @@ pub async fn run() {
 
         FrameOutput {
             swap_buffers: change,
+            wait_next_event: true,
             ..Default::default()
         }
     });
@@ -71,11 +71,23 @@ pub async fn run() {
         Some(vec3(1.0, 1.0, 0.0).normalize()),
     );
 
+    // An animated billboard which plays the four quarters of the texture as frames
+    let animated = Sprites::new(&context, &[vec3(0.0, 5.0, -10.0)], None);
+    let sprite_sheet = SpriteSheet::new(
+        material.texture.clone().unwrap(),
+        img.width / 2,
+        img.height / 2,
+        4,
+        2.0,
+    );
+    let mut animated_material = material.clone();
+
     let ambient = AmbientLight::new(&context, 1.0, Srgba::WHITE);
 
     window.render_loop(move |mut frame_input| {
         camera.set_viewport(frame_input.viewport);
         control.handle_events(&mut camera, &mut frame_input.events);
+        animated_material.texture = Some(sprite_sheet.texture(frame_input.accumulated_time));
 
         frame_input
             .screen()
@@ -94,6 +106,10 @@ pub async fn run() {
                     .chain(&Gm {
                         geometry: &sprites,
                         material: &material,
+                    })
+                    .chain(&Gm {
+                        geometry: &animated,
+                        material: &animated_material,
                     }),
                 &[&ambient],
             );
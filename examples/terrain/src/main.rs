@@ -27,6 +27,16 @@ pub async fn run() {
         0.1,
         1000.0,
     );
+    // Valley fog which is denser near the ground
+    camera.fog = Some(Fog {
+        color: Srgba::new_opaque(200, 210, 220),
+        fog_type: FogType::Height {
+            base: -1.0,
+            falloff: 0.5,
+        },
+        density: 0.03,
+        ..Default::default()
+    });
     let mut control = FirstPersonControl::new(0.01);
 
     // Source: https://polyhaven.com/
@@ -57,7 +67,9 @@ pub async fn run() {
     });
 
     let model: CpuModel = loaded.deserialize(".gltf").unwrap();
-    let terrain_material = PhysicalMaterial::new_opaque(&context, &model.materials[0]);
+    let mut terrain_material = PhysicalMaterial::new_opaque(&context, &model.materials[0]);
+    // Triplanar mapping avoids stretching the textures on steep slopes
+    terrain_material.triplanar_scale = Some(1.0);
     let mut terrain = Terrain::new(
         &context,
         terrain_material,
@@ -92,25 +104,15 @@ pub async fn run() {
             NormalDistributionFunction::TrowbridgeReitzGGX,
             GeometryFunction::SmithSchlickGGX,
         ),
+        ..Default::default()
     };
 
-    let mut color_texture = Texture2D::new_empty::<[f16; 4]>(
-        &context,
-        camera.viewport().width,
-        camera.viewport().height,
-        Interpolation::Nearest,
-        Interpolation::Nearest,
-        None,
-        Wrapping::ClampToEdge,
-        Wrapping::ClampToEdge,
-    );
-    let mut depth_texture = DepthTexture2D::new::<f32>(
+    let mut scene = SizedRenderTarget::<[f16; 4], f32>::try_new(
         &context,
         camera.viewport().width,
         camera.viewport().height,
-        Wrapping::ClampToEdge,
-        Wrapping::ClampToEdge,
-    );
+    )
+    .expect("this example requires rendering into 16 bit float textures");
     let mut gui = GUI::new(&context);
 
     let mut wavelength = 3.0;
@@ -140,6 +142,9 @@ pub async fn run() {
                     ui.add(Slider::new(&mut height, -5.0..=5.0).text("height"));
                     ui.add(Slider::new(&mut water_material.metallic, 0.0..=1.0).text("metallic"));
                     ui.add(Slider::new(&mut water_material.roughness, 0.0..=1.0).text("roughness"));
+                    ui.add(
+                        Slider::new(&mut water_material.absorption, 0.0..=5.0).text("absorption"),
+                    );
 
                     ui.label("Wave parameters");
                     parameter_change |= ui
@@ -188,6 +193,23 @@ pub async fn run() {
             },
         );
         change |= parameter_change;
+
+        // Grab the cursor for mouse look when pressing the right mouse button and release it again when pressing escape
+        let mut cursor_grab = None;
+        for event in frame_input.events.iter() {
+            match event {
+                Event::MousePress {
+                    button: MouseButton::Right,
+                    handled: false,
+                    ..
+                } => cursor_grab = Some(true),
+                Event::KeyPress {
+                    kind: Key::Escape, ..
+                } => cursor_grab = Some(false),
+                _ => {}
+            }
+        }
+        control.mouse_look = frame_input.is_cursor_grabbed();
         change |= control.handle_events(&mut camera, &mut frame_input.events);
 
         if parameter_change {
@@ -241,33 +263,10 @@ pub async fn run() {
 
         if change {
             camera.disable_tone_and_color_mapping();
-            if camera.viewport().width != color_texture.width()
-                || camera.viewport().height != color_texture.height()
-            {
-                color_texture = Texture2D::new_empty::<[f16; 4]>(
-                    &context,
-                    camera.viewport().width,
-                    camera.viewport().height,
-                    Interpolation::Nearest,
-                    Interpolation::Nearest,
-                    None,
-                    Wrapping::ClampToEdge,
-                    Wrapping::ClampToEdge,
-                );
-                depth_texture = DepthTexture2D::new::<f32>(
-                    &context,
-                    camera.viewport().width,
-                    camera.viewport().height,
-                    Wrapping::ClampToEdge,
-                    Wrapping::ClampToEdge,
-                );
-            }
-            RenderTarget::new(
-                color_texture.as_color_target(None),
-                depth_texture.as_depth_target(),
-            )
-            .clear(ClearState::color_and_depth(0.5, 0.5, 0.5, 1.0, 1.0))
-            .render(&camera, skybox.into_iter().chain(&terrain), &[&light]);
+            scene
+                .render_target(camera.viewport().width, camera.viewport().height)
+                .clear(ClearState::color_and_depth(0.5, 0.5, 0.5, 1.0, 1.0))
+                .render(&camera, skybox.into_iter().chain(&terrain), &[&light]);
         }
         camera.set_default_tone_and_color_mapping();
         frame_input
@@ -276,20 +275,23 @@ pub async fn run() {
                 &ScreenEffect::default(),
                 &camera,
                 &[],
-                Some(ColorTexture::Single(&color_texture)),
-                Some(DepthTexture::Single(&depth_texture)),
+                Some(ColorTexture::Single(scene.color_texture())),
+                Some(DepthTexture::Single(scene.depth_texture())),
             )
             .render_with_effect(
                 &water_material,
                 &camera,
                 &water,
                 &[&light],
-                Some(ColorTexture::Single(&color_texture)),
-                Some(DepthTexture::Single(&depth_texture)),
+                Some(ColorTexture::Single(scene.color_texture())),
+                Some(DepthTexture::Single(scene.depth_texture())),
             )
             .write(|| gui.render())
             .unwrap();
 
-        FrameOutput::default()
+        FrameOutput {
+            cursor_grab,
+            ..Default::default()
+        }
     });
 }
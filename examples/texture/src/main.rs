@@ -41,15 +41,24 @@ pub async fn run() {
     .unwrap();
 
     // Skybox
-    let top_tex = loaded.deserialize("top").unwrap();
+    // Limit the size of the skybox textures before uploading them to save memory
+    let max_size = context.capabilities().max_cube_map_texture_size.min(1024);
+    let mut side = |name: &str| {
+        let mut cpu_texture: CpuTexture = loaded.deserialize(name).unwrap();
+        if cpu_texture.width > max_size || cpu_texture.height > max_size {
+            cpu_texture.resize(max_size, max_size, Interpolation::Linear);
+        }
+        cpu_texture
+    };
+    let top_tex = side("top");
     let skybox = Skybox::new(
         &context,
-        &loaded.deserialize("right").unwrap(),
-        &loaded.deserialize("left").unwrap(),
+        &side("right"),
+        &side("left"),
         &top_tex,
         &top_tex,
-        &loaded.deserialize("front").unwrap(),
-        &loaded.deserialize("back").unwrap(),
+        &side("front"),
+        &side("back"),
     );
 
     // Box
@@ -57,10 +66,7 @@ pub async fn run() {
     cpu_texture.data.to_linear_srgb();
     let mut box_object = Gm::new(
         Mesh::new(&context, &CpuMesh::cube()),
-        ColorMaterial {
-            texture: Some(Texture2DRef::from_cpu_texture(&context, &cpu_texture)),
-            ..Default::default()
-        },
+        ColorMaterial::textured(Texture2DRef::from_cpu_texture(&context, &cpu_texture)),
     );
     box_object.material.render_states.cull = Cull::Back;
 
@@ -98,7 +104,10 @@ pub async fn run() {
 
     // GUI
     let mut gui = three_d::GUI::new(&context);
-    let mut max_ratio = 1;
+    let mut anisotropy = 1;
+    let mut applied_anisotropy = 1;
+    // Anisotropic filtering is not supported on all hardware
+    let max_anisotropy = context.capabilities().max_anisotropy.unwrap_or(1);
     let mut max_levels = 8;
     let mut mipmap_filter = Interpolation::Nearest;
     let mut min_filter = Interpolation::Nearest;
@@ -134,9 +143,11 @@ pub async fn run() {
                     ui.radio_value(&mut mag_filter, Interpolation::Linear, "Linear");
                     ui.label("Mipmap settings");
                     ui.add(Slider::new(&mut max_levels, 1..=8).text("Max levels"));
-                    ui.add(Slider::new(&mut max_ratio, 1..=8).text("Max ratio of anisotropy"));
                     ui.radio_value(&mut mipmap_filter, Interpolation::Nearest, "Nearest");
                     ui.radio_value(&mut mipmap_filter, Interpolation::Linear, "Linear");
+                    ui.label("Anisotropic filtering");
+                    ui.add(Slider::new(&mut anisotropy, 1..=max_anisotropy).text("Max ratio"));
+                    ui.label(format!("Applied ratio: {}", applied_anisotropy));
                 });
                 panel_width = gui_context.used_rect().width();
             },
@@ -154,7 +165,7 @@ pub async fn run() {
 
         // Update ground texture texture settings
         let mipmap = Some(Mipmap {
-            max_ratio,
+            max_ratio: 1,
             max_levels,
             filter: mipmap_filter,
         });
@@ -172,6 +183,11 @@ pub async fn run() {
             ground_object.material.texture =
                 Some(Texture2DRef::from_cpu_texture(&context, &cpu_texture));
         }
+        if let Some(texture) = &ground_object.material.texture {
+            if texture.anisotropy() != anisotropy {
+                applied_anisotropy = texture.set_anisotropy(anisotropy);
+            }
+        }
 
         // draw
         frame_input
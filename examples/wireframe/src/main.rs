@@ -97,6 +97,7 @@ pub async fn run() {
 
         FrameOutput {
             swap_buffers: redraw,
+            wait_next_event: true,
             ..Default::default()
         }
     });
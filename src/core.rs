@@ -32,6 +32,32 @@ mod scissor_box;
 #[doc(inline)]
 pub use scissor_box::*;
 
+mod gpu_timer;
+#[doc(inline)]
+pub use gpu_timer::*;
+
+mod occlusion_query;
+#[doc(inline)]
+pub use occlusion_query::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod watched_program;
+#[cfg(not(target_arch = "wasm32"))]
+#[doc(inline)]
+pub use watched_program::*;
+
+mod capabilities;
+#[doc(inline)]
+pub use capabilities::*;
+
+mod statistics;
+#[doc(inline)]
+pub use statistics::*;
+
+mod viewport_ext;
+#[doc(inline)]
+pub use viewport_ext::*;
+
 pub mod prelude {
 
     //!
@@ -55,14 +81,34 @@ pub enum CoreError {
     ContextCreation(String),
     #[error("failed rendering with error: {0}")]
     ContextError(String),
-    #[error("failed compiling {0} shader\n\nsource:\n{1}\n\nlog:\n{2}")]
-    ShaderCompilation(String, String, String),
+    #[error("{0}")]
+    ShaderCompilation(Box<ShaderCompilationError>),
     #[error("failed to link shader program: {0}")]
     ShaderLink(String),
+    #[error("failed reading shader file {0}: {1}")]
+    ShaderFileRead(String, String),
     #[error(
         "failed to compile a shader program, probably due to performance or memory constraints"
     )]
     ShaderCompilerError,
+    #[error("unsupported texture: {0}")]
+    UnsupportedTexture(String),
+    #[error("failed parsing KTX2 data: {0}")]
+    Ktx2Parsing(String),
+    #[error(
+        "the size {0} is not supported in a uniform buffer, only 1, 2, 3, 4, 9 or a multiple of 4"
+    )]
+    UnsupportedUniformSize(u32),
+    #[error("the uniform buffer index {0} is outside the range of the {1} variables")]
+    UniformBufferIndexOutOfRange(u32, usize),
+    #[error("the data for the uniform buffer variable at index {0} has length {1} but a length of {2} was expected")]
+    UniformBufferDataLength(u32, usize, usize),
+    #[error("failed loading asset: {0}")]
+    AssetLoading(#[from] three_d_asset::Error),
+    #[error(
+        "the crop rectangle at ({0}, {1}) with size {2}x{3} is outside the texture of size {4}x{5}"
+    )]
+    CropOutsideTexture(u32, u32, u32, u32, u32, u32),
 }
 
 pub(crate) fn full_screen_draw(
@@ -70,6 +70,7 @@ impl<T: BufferDataType> Buffer<T> {
     pub fn fill(&mut self, data: &[T]) {
         self.bind();
         unsafe {
+            self.context.statistics.buffer_upload();
             self.context.buffer_data_u8_slice(
                 crate::context::ARRAY_BUFFER,
                 to_byte_slice(data),
@@ -87,6 +88,7 @@ impl<T: BufferDataType> Buffer<T> {
     pub fn fill_subset(&mut self, offset: u32, data: &[T]) {
         self.bind();
         unsafe {
+            self.context.statistics.buffer_upload();
             self.context.buffer_sub_data_u8_slice(
                 crate::context::ARRAY_BUFFER,
                 offset as i32,
@@ -69,6 +69,7 @@ impl<T: ElementBufferDataType> ElementBuffer<T> {
     pub fn fill(&mut self, indices: &[T]) {
         self.bind();
         unsafe {
+            self.context.statistics.buffer_upload();
             self.context.buffer_data_u8_slice(
                 crate::context::ELEMENT_ARRAY_BUFFER,
                 to_byte_slice(indices),
@@ -87,6 +88,7 @@ impl<T: ElementBufferDataType> ElementBuffer<T> {
     pub fn fill_subset(&mut self, offset: u32, indices: &[T]) {
         self.bind();
         unsafe {
+            self.context.statistics.buffer_upload();
             self.context.buffer_sub_data_u8_slice(
                 crate::context::ELEMENT_ARRAY_BUFFER,
                 offset as i32,
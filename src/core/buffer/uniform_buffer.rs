@@ -3,11 +3,21 @@ use crate::core::*;
 ///
 /// A buffer for transferring a set of uniform variables to the shader program
 /// (see also [use_uniform_block](crate::core::Program::use_uniform_block)).
+/// The variables are packed using the `std140` layout, so the uniform block in the shader must be declared with `layout (std140)`, for example
+/// ```glsl
+/// layout (std140) uniform Camera
+/// {
+///     vec3 position;
+///     float exposure;
+///     mat4 viewProjection;
+/// };
+/// ```
 ///
 pub struct UniformBuffer {
     context: Context,
     id: crate::context::Buffer,
     offsets: Vec<usize>,
+    sizes: Vec<u32>,
     data: Vec<f32>,
 }
 
@@ -16,25 +26,23 @@ impl UniformBuffer {
     /// Creates a new uniform buffer with room for a set of variables of varying length defined by the `sizes` argument.
     /// So for example if you create a uniform buffer with `&[3, 1, 4, 16]` as the `sizes` argument, you will have a uniform buffer that has four variables:
     /// The first with 3 elements (a [Vec3]), the second with 1 element (a `f32`), the third with four elements (a [Vec4]) and the last with 16 elements (a [Mat4]).
-    /// The variables are initialized to 0.
+    /// A size of 9 is a [Mat3] and other sizes above 4 must be a multiple of 4, for example an array of [Vec4] or [Mat4].
+    /// The variables are placed in the buffer according to the alignment rules of the `std140` layout in the given order and are initialized to 0.
     ///
-    pub fn new(context: &Context, sizes: &[u32]) -> UniformBuffer {
+    /// Returns an error if a size is 0 or if a size above 4 is neither 9 nor a multiple of 4.
+    ///
+    pub fn new(context: &Context, sizes: &[u32]) -> Result<UniformBuffer, CoreError> {
+        let (offsets, length) = std140_layout(sizes)?;
         let id = unsafe { context.create_buffer().expect("Failed creating buffer") };
-
-        let mut offsets = Vec::new();
-        let mut length = 0;
-        for size in sizes {
-            offsets.push(length);
-            length += *size as usize;
-        }
         let buffer = UniformBuffer {
             context: context.clone(),
             id,
             offsets,
+            sizes: sizes.to_vec(),
             data: vec![0.0; length],
         };
         buffer.send();
-        buffer
+        Ok(buffer)
     }
 
     pub(crate) fn bind(&self, id: u32) {
@@ -47,63 +55,74 @@ impl UniformBuffer {
     ///
     /// Update the values of the variable at the given index with the given data.
     ///
-    /// # Panic
-    /// Will panic if the index is not in the range `[0-max]` where `max` is the length of the `sizes` argument given at construction.
-    /// Will panic if the data length does not match the element count of the variable (defined at construction) at the given index.
+    /// Returns an error if the index is not in the range `[0-max]` where `max` is the length of the `sizes` argument given at construction
+    /// or if the data length does not match the element count of the variable (defined at construction) at the given index.
     ///
-    pub fn update(&mut self, index: u32, data: &[f32]) {
-        if let Some((offset, length)) = self.offset_length(index as usize) {
-            if data.len() != length {
-                panic!(
-                    "data for element at index {0} has length {1} but a length of {2} was expected",
-                    index,
-                    data.len(),
-                    length,
-                );
-            }
-            self.data
-                .splice(offset..offset + length, data.iter().cloned());
-            self.send();
-        } else {
-            panic!(
-                "the index {} is outside the expected range [0, {}]",
+    pub fn update(&mut self, index: u32, data: &[f32]) -> Result<(), CoreError> {
+        let (offset, length) = self
+            .offsets
+            .get(index as usize)
+            .map(|offset| (*offset, self.sizes[index as usize] as usize))
+            .ok_or(CoreError::UniformBufferIndexOutOfRange(
                 index,
-                self.offsets.len() - 1
-            );
+                self.offsets.len(),
+            ))?;
+        if data.len() != length {
+            Err(CoreError::UniformBufferDataLength(
+                index,
+                data.len(),
+                length,
+            ))?;
         }
-        //TODO: Send to GPU (contextBufferSubData)
+        let padded_length = std140_write(&mut self.data, offset, data);
+        self.send_range(offset, padded_length);
+        Ok(())
     }
 
     ///
     /// Returns the values of the variable at the given index if inside the range of variables, otherwise `None`.
+    /// A [Mat3] is returned as it is laid out in the buffer, ie. as three columns of four elements where the last element in each column is padding.
     ///
     pub fn get(&self, index: u32) -> Option<&[f32]> {
-        self.offset_length(index as usize)
-            .map(|(offset, length)| &self.data[offset..offset + length])
+        let offset = *self.offsets.get(index as usize)?;
+        let length = match self.sizes[index as usize] {
+            9 => 12,
+            size => size as usize,
+        };
+        Some(&self.data[offset..offset + length])
     }
 
-    fn offset_length(&self, index: usize) -> Option<(usize, usize)> {
-        if index >= self.offsets.len() {
-            None
-        } else {
-            let offset = self.offsets[index];
-            let length = if index + 1 == self.offsets.len() {
-                self.data.len()
-            } else {
-                self.offsets[index + 1]
-            } - offset;
-            Some((offset, length))
-        }
+    ///
+    /// Returns all of the data in the buffer, including the padding between the variables, exactly as it is laid out in GPU memory using the `std140` layout.
+    ///
+    pub fn data(&self) -> &[f32] {
+        &self.data
     }
 
     fn send(&self) {
         unsafe {
             self.context
                 .bind_buffer(crate::context::UNIFORM_BUFFER, Some(self.id));
+            self.context.statistics.buffer_upload();
             self.context.buffer_data_u8_slice(
                 crate::context::UNIFORM_BUFFER,
                 to_byte_slice(&self.data),
-                crate::context::STATIC_DRAW,
+                crate::context::DYNAMIC_DRAW,
+            );
+            self.context
+                .bind_buffer(crate::context::UNIFORM_BUFFER, None);
+        }
+    }
+
+    fn send_range(&self, offset: usize, length: usize) {
+        unsafe {
+            self.context
+                .bind_buffer(crate::context::UNIFORM_BUFFER, Some(self.id));
+            self.context.statistics.buffer_upload();
+            self.context.buffer_sub_data_u8_slice(
+                crate::context::UNIFORM_BUFFER,
+                (offset * std::mem::size_of::<f32>()) as i32,
+                to_byte_slice(&self.data[offset..offset + length]),
             );
             self.context
                 .bind_buffer(crate::context::UNIFORM_BUFFER, None);
@@ -118,3 +137,93 @@ impl Drop for UniformBuffer {
         }
     }
 }
+
+///
+/// Returns the offset of each variable with the given sizes and the total length of the buffer, both in number of floats, using the `std140` layout.
+///
+fn std140_layout(sizes: &[u32]) -> Result<(Vec<usize>, usize), CoreError> {
+    let mut offsets = Vec::new();
+    let mut length: usize = 0;
+    for size in sizes {
+        let (alignment, padded_size) = match *size {
+            1 => (1, 1),
+            2 => (2, 2),
+            3 | 4 => (4, *size as usize),
+            // A mat3 is stored as three vec4 columns
+            9 => (4, 12),
+            s if s > 4 && s % 4 == 0 => (4, s as usize),
+            s => Err(CoreError::UnsupportedUniformSize(s))?,
+        };
+        length = length.next_multiple_of(alignment);
+        offsets.push(length);
+        length += padded_size;
+    }
+    Ok((offsets, length.next_multiple_of(4)))
+}
+
+///
+/// Writes the data of a variable at the given offset using the `std140` layout and returns the number of floats written, including padding.
+///
+fn std140_write(buffer: &mut [f32], offset: usize, data: &[f32]) -> usize {
+    if data.len() == 9 {
+        for (column, values) in data.chunks(3).enumerate() {
+            let column_offset = offset + 4 * column;
+            buffer[column_offset..column_offset + 3].copy_from_slice(values);
+        }
+        12
+    } else {
+        buffer[offset..offset + data.len()].copy_from_slice(data);
+        data.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn std140_offsets() {
+        assert_eq!(
+            std140_layout(&[3, 1, 4, 16]).unwrap(),
+            (vec![0, 3, 4, 8], 24)
+        );
+        assert_eq!(std140_layout(&[1, 3]).unwrap(), (vec![0, 4], 8));
+        assert_eq!(std140_layout(&[1, 2, 1, 2]).unwrap(), (vec![0, 2, 4, 6], 8));
+        assert_eq!(std140_layout(&[1, 9, 1]).unwrap(), (vec![0, 4, 16], 20));
+        assert_eq!(std140_layout(&[2, 8]).unwrap(), (vec![0, 4], 12));
+        assert_eq!(std140_layout(&[]).unwrap(), (vec![], 0));
+    }
+
+    #[test]
+    fn std140_unsupported_sizes() {
+        for size in [0, 5, 6, 7, 10, 13] {
+            assert!(matches!(
+                std140_layout(&[4, size]),
+                Err(CoreError::UnsupportedUniformSize(s)) if s == size
+            ));
+        }
+    }
+
+    #[test]
+    fn std140_packing() {
+        let sizes = [1, 3, 9, 2];
+        let (offsets, length) = std140_layout(&sizes).unwrap();
+        let mut buffer = vec![0.0; length];
+        assert_eq!(std140_write(&mut buffer, offsets[0], &[1.0]), 1);
+        assert_eq!(std140_write(&mut buffer, offsets[1], &[2.0, 3.0, 4.0]), 3);
+        let mat3 = [5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 13.0];
+        assert_eq!(std140_write(&mut buffer, offsets[2], &mat3), 12);
+        assert_eq!(std140_write(&mut buffer, offsets[3], &[14.0, 15.0]), 2);
+        assert_eq!(
+            buffer,
+            [
+                1.0, 0.0, 0.0, 0.0, // float and padding to the vec3 alignment
+                2.0, 3.0, 4.0, 0.0, // vec3 and padding to the mat3 alignment
+                5.0, 6.0, 7.0, 0.0, // first mat3 column
+                8.0, 9.0, 10.0, 0.0, // second mat3 column
+                11.0, 12.0, 13.0, 0.0, // third mat3 column
+                14.0, 15.0, 0.0, 0.0, // vec2 and padding to the size of a vec4
+            ]
+        );
+    }
+}
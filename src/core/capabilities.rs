@@ -0,0 +1,127 @@
+use crate::core::*;
+
+///
+/// The limits and capabilities of the graphics hardware and driver, see [Context::capabilities].
+/// Use these to avoid allocating resources that are not supported, which otherwise often fail silently, especially on web and mobile.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// The maximum width and height in texels of a [Texture2D] and [Texture2DArray].
+    pub max_texture_size: u32,
+    /// The maximum width and height in texels of each side of a [TextureCubeMap].
+    pub max_cube_map_texture_size: u32,
+    /// The maximum width, height and depth in texels of a [Texture3D].
+    pub max_3d_texture_size: u32,
+    /// The maximum number of layers in a [Texture2DArray].
+    pub max_array_texture_layers: u32,
+    /// The maximum number of textures that can be used in a fragment shader.
+    pub max_texture_units: u32,
+    /// The maximum number of textures that can be used in a [Program] in total.
+    pub max_combined_texture_units: u32,
+    /// The maximum number of color textures in a [RenderTarget].
+    pub max_color_attachments: u32,
+    /// The maximum number of samples of a multisample render target, for example [RenderTargetMultisample].
+    pub max_samples: u32,
+    /// The maximum size in bytes of a [UniformBuffer].
+    pub max_uniform_block_size: u32,
+    /// The maximum ratio of anisotropy, see [Mipmap::max_ratio], or `None` if anisotropic filtering is not supported.
+    pub max_anisotropy: Option<u32>,
+    /// Whether or not it is possible to render into textures with 32 bit float data, for example `[f32; 4]`.
+    pub float_color_renderable: bool,
+    /// Whether or not it is possible to render into textures with 16 bit float data, for example `[f16; 4]`.
+    pub half_float_color_renderable: bool,
+    /// Whether or not textures with 32 bit float data can be sampled with [Interpolation::Linear].
+    /// Textures with 16 bit float data can always be sampled with linear interpolation.
+    pub float_linear_filterable: bool,
+    is_embedded: bool,
+}
+
+impl Capabilities {
+    ///
+    /// Returns whether or not it is possible to render into a texture with the format given by the generic [TextureDataType] parameter,
+    /// for example using [Texture2D::as_color_target].
+    /// Rendering into a texture with an unsupported format fails silently on some devices, usually resulting in a black image.
+    ///
+    pub fn is_color_renderable<T: TextureDataType>(&self) -> bool {
+        // RGB float formats are not renderable on OpenGL ES and WebGL, even with the extensions
+        let supports_size = !self.is_embedded || T::size() != 3;
+        match T::data_type() {
+            crate::context::FLOAT => self.float_color_renderable && supports_size,
+            crate::context::HALF_FLOAT => self.half_float_color_renderable && supports_size,
+            _ => true,
+        }
+    }
+
+    ///
+    /// Returns whether or not a texture with the format given by the generic [TextureDataType] parameter can be sampled with [Interpolation::Linear].
+    ///
+    pub fn is_linear_filterable<T: TextureDataType>(&self) -> bool {
+        T::data_type() != crate::context::FLOAT || self.float_linear_filterable
+    }
+
+    pub(in crate::core) fn check_color_texture<T: TextureDataType>(
+        &self,
+        width: u32,
+        height: u32,
+        linear: bool,
+    ) -> Result<(), CoreError> {
+        let format = std::any::type_name::<T>();
+        if width > self.max_texture_size || height > self.max_texture_size {
+            Err(CoreError::UnsupportedTexture(format!(
+                "the size {}x{} is larger than the maximum texture size {}",
+                width, height, self.max_texture_size
+            )))
+        } else if !self.is_color_renderable::<T>() {
+            Err(CoreError::UnsupportedTexture(format!(
+                "rendering into a texture with the format {} is not supported on this device, it requires the EXT_color_buffer_float or EXT_color_buffer_half_float extension",
+                format
+            )))
+        } else if linear && !self.is_linear_filterable::<T>() {
+            Err(CoreError::UnsupportedTexture(format!(
+                "linear interpolation of a texture with the format {} is not supported on this device, it requires the OES_texture_float_linear extension",
+                format
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Context {
+    ///
+    /// Queries the limits and capabilities of the graphics hardware and driver.
+    /// This requires several calls to the graphics API, so consider storing the result instead of calling this every frame.
+    ///
+    pub fn capabilities(&self) -> Capabilities {
+        let extensions = self.supported_extensions();
+        // Extensions are prefixed with `GL_` on desktop and OpenGL ES but not on web
+        let has_extension =
+            |name: &str| extensions.contains(name) || extensions.contains(&format!("GL_{}", name));
+        let is_embedded = self.version().is_embedded;
+        let get = |parameter: u32| unsafe { self.get_parameter_i32(parameter).max(0) as u32 };
+        let float_color_renderable = !is_embedded || has_extension("EXT_color_buffer_float");
+        Capabilities {
+            max_texture_size: get(crate::context::MAX_TEXTURE_SIZE),
+            max_cube_map_texture_size: get(crate::context::MAX_CUBE_MAP_TEXTURE_SIZE),
+            max_3d_texture_size: get(crate::context::MAX_3D_TEXTURE_SIZE),
+            max_array_texture_layers: get(crate::context::MAX_ARRAY_TEXTURE_LAYERS),
+            max_texture_units: get(crate::context::MAX_TEXTURE_IMAGE_UNITS),
+            max_combined_texture_units: get(crate::context::MAX_COMBINED_TEXTURE_IMAGE_UNITS),
+            max_color_attachments: get(crate::context::MAX_COLOR_ATTACHMENTS),
+            max_samples: get(crate::context::MAX_SAMPLES),
+            max_uniform_block_size: get(crate::context::MAX_UNIFORM_BLOCK_SIZE),
+            max_anisotropy: if has_extension("EXT_texture_filter_anisotropic")
+                || has_extension("ARB_texture_filter_anisotropic")
+            {
+                Some(get(crate::context::MAX_TEXTURE_MAX_ANISOTROPY_EXT).max(1))
+            } else {
+                None
+            },
+            float_color_renderable,
+            half_float_color_renderable: float_color_renderable
+                || has_extension("EXT_color_buffer_half_float"),
+            float_linear_filterable: !is_embedded || has_extension("OES_texture_float_linear"),
+            is_embedded,
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use super::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::sync::RwLock;
 
@@ -17,6 +17,13 @@ pub struct Context {
     pub(super) vao: crate::context::VertexArray,
     /// A cache of programs to avoid recompiling a [Program] every frame.
     pub programs: Arc<RwLock<HashMap<Vec<u8>, Program>>>,
+    /// A cache of uniform buffers shared between programs, for example the camera uniform block used by the renderer.
+    pub(crate) uniform_buffers: Arc<RwLock<HashMap<&'static str, UniformBuffer>>>,
+    /// The ids of the cached programs which are recompiled the next time they are used, see [Context::reload_shaders].
+    pub(crate) outdated_programs: Arc<RwLock<HashSet<Vec<u8>>>>,
+    /// The errors from recompiling the cached programs, see [Context::take_shader_reload_errors].
+    pub(crate) shader_reload_errors: Arc<RwLock<Vec<CoreError>>>,
+    pub(super) statistics: Arc<StatisticsCounter>,
 }
 
 impl Context {
@@ -44,11 +51,37 @@ impl Context {
                 context,
                 vao,
                 programs: Arc::new(RwLock::new(HashMap::new())),
+                uniform_buffers: Arc::new(RwLock::new(HashMap::new())),
+                outdated_programs: Arc::new(RwLock::new(HashSet::new())),
+                shader_reload_errors: Arc::new(RwLock::new(Vec::new())),
+                statistics: Arc::new(StatisticsCounter::default()),
             }
         };
         Ok(c)
     }
 
+    ///
+    /// Recompiles all the cached programs, which includes the programs used for rendering with a [Material](crate::renderer::Material) or [Effect](crate::renderer::Effect),
+    /// the next time they are used. This makes it possible to edit the shaders while the application is running,
+    /// for example by reading the fragment shader of a custom material from a file in [Material::fragment_shader_source](crate::renderer::Material::fragment_shader_source)
+    /// and calling this method when the file has changed.
+    /// If a program fails to compile, the last working program is kept and the error can be retrieved using [Context::take_shader_reload_errors].
+    ///
+    pub fn reload_shaders(&self) {
+        let programs = self.programs.read().unwrap();
+        self.outdated_programs
+            .write()
+            .unwrap()
+            .extend(programs.keys().cloned());
+    }
+
+    ///
+    /// Returns the errors from recompiling the cached programs after calling [Context::reload_shaders] and clears the list of errors.
+    ///
+    pub fn take_shader_reload_errors(&self) -> Vec<CoreError> {
+        std::mem::take(&mut *self.shader_reload_errors.write().unwrap())
+    }
+
     ///
     /// Set the scissor test for this context (see [ScissorBox]).
     ///
@@ -157,6 +190,61 @@ impl Context {
         }
     }
 
+    ///
+    /// Set the stencil test for this context (see [StencilTest]).
+    ///
+    pub fn set_stencil_test(&self, stencil_test: StencilTest) {
+        unsafe {
+            if let StencilTest::Enabled {
+                function,
+                reference,
+                read_mask,
+                write_mask,
+                stencil_fail,
+                depth_fail,
+                pass,
+            } = stencil_test
+            {
+                self.enable(crate::context::STENCIL_TEST);
+                self.stencil_func(
+                    match function {
+                        StencilFunction::Never => crate::context::NEVER,
+                        StencilFunction::Less => crate::context::LESS,
+                        StencilFunction::Equal => crate::context::EQUAL,
+                        StencilFunction::LessOrEqual => crate::context::LEQUAL,
+                        StencilFunction::Greater => crate::context::GREATER,
+                        StencilFunction::NotEqual => crate::context::NOTEQUAL,
+                        StencilFunction::GreaterOrEqual => crate::context::GEQUAL,
+                        StencilFunction::Always => crate::context::ALWAYS,
+                    },
+                    reference as i32,
+                    read_mask as u32,
+                );
+                self.stencil_mask(write_mask as u32);
+                self.stencil_op(
+                    Self::stencil_const_from_operation(stencil_fail),
+                    Self::stencil_const_from_operation(depth_fail),
+                    Self::stencil_const_from_operation(pass),
+                );
+            } else {
+                self.disable(crate::context::STENCIL_TEST);
+            }
+        }
+    }
+
+    fn stencil_const_from_operation(operation: StencilOperation) -> u32 {
+        match operation {
+            StencilOperation::Keep => crate::context::KEEP,
+            StencilOperation::Zero => crate::context::ZERO,
+            StencilOperation::Replace => crate::context::REPLACE,
+            StencilOperation::Increment => crate::context::INCR,
+            StencilOperation::IncrementWrap => crate::context::INCR_WRAP,
+            StencilOperation::Decrement => crate::context::DECR,
+            StencilOperation::DecrementWrap => crate::context::DECR_WRAP,
+            StencilOperation::Invert => crate::context::INVERT,
+        }
+    }
+
     ///
     /// Set the blend state for this context (see [Blend]).
     ///
@@ -224,6 +312,9 @@ impl Context {
         } else {
             self.set_depth_test(render_states.depth_test);
         }
+        if let Some(stencil_test) = render_states.stencil_test {
+            self.set_stencil_test(stencil_test);
+        }
         self.set_blend(render_states.blend);
     }
 
@@ -611,6 +611,10 @@ impl<T: PrimitiveDataType> DataType for Matrix4<T> {
 
 pub trait DepthDataType {
     fn internal_format() -> u32;
+
+    fn attachment() -> u32 {
+        crate::context::DEPTH_ATTACHMENT
+    }
 }
 
 impl DepthDataType for f16 {
@@ -628,3 +632,12 @@ impl DepthDataType for f32 {
         crate::context::DEPTH_COMPONENT32F
     }
 }
+impl DepthDataType for f24s8 {
+    fn internal_format() -> u32 {
+        crate::context::DEPTH24_STENCIL8
+    }
+
+    fn attachment() -> u32 {
+        crate::context::DEPTH_STENCIL_ATTACHMENT
+    }
+}
@@ -0,0 +1,156 @@
+use crate::core::*;
+
+// Not defined in the context module since it is an extension constant
+const GPU_DISJOINT_EXT: u32 = 0x8FBB;
+
+///
+/// Measures the time the GPU spends on the commands issued between [Context::begin_timer] and [GpuTimer::end].
+/// Only one timer can be active at a time, ie. timers cannot be nested.
+///
+pub struct GpuTimer {
+    context: Context,
+    query: Option<crate::context::Query>,
+}
+
+impl GpuTimer {
+    ///
+    /// Ends the measurement and returns a [GpuTimerQuery] which contains the measured time when the GPU has finished executing the commands, usually a few frames later.
+    ///
+    pub fn end(mut self) -> GpuTimerQuery {
+        let query = self.query.take();
+        if query.is_some() {
+            unsafe {
+                self.context.end_query(crate::context::TIME_ELAPSED);
+            }
+        }
+        GpuTimerQuery {
+            context: self.context.clone(),
+            query,
+        }
+    }
+}
+
+impl Drop for GpuTimer {
+    fn drop(&mut self) {
+        if let Some(query) = self.query.take() {
+            unsafe {
+                self.context.end_query(crate::context::TIME_ELAPSED);
+                self.context.delete_query(query);
+            }
+        }
+    }
+}
+
+///
+/// The result of a [GpuTimer] which becomes available when the GPU has finished executing the measured commands.
+///
+pub struct GpuTimerQuery {
+    context: Context,
+    query: Option<crate::context::Query>,
+}
+
+impl GpuTimerQuery {
+    ///
+    /// Returns the measured GPU time in milliseconds if the result is available.
+    /// Returns `None` if the result is not available yet, if timer queries are not supported (see [Context::is_timer_query_supported])
+    /// or if the measurement was invalidated by the GPU, for example because of a change in the GPU frequency.
+    /// Since the result is read asynchronously, this method does not stall the GPU and can be called once per frame until it returns a value.
+    ///
+    pub fn result(&self) -> Option<f64> {
+        let query = self.query?;
+        unsafe {
+            if self
+                .context
+                .get_query_parameter_u32(query, crate::context::QUERY_RESULT_AVAILABLE)
+                == 0
+            {
+                return None;
+            }
+            if self.context.version().is_embedded
+                && self.context.get_parameter_i32(GPU_DISJOINT_EXT) != 0
+            {
+                return None;
+            }
+            // The result is 64 bit, since a 32 bit result wraps after about 4.3 seconds
+            #[cfg(not(target_arch = "wasm32"))]
+            let nanoseconds = {
+                let mut nanoseconds: u64 = 0;
+                self.context.get_query_parameter_u64_with_offset(
+                    query,
+                    crate::context::QUERY_RESULT,
+                    &mut nanoseconds as *mut u64 as usize,
+                );
+                nanoseconds
+            };
+            // Reading 64 bit query results is not exposed on web, so the result saturates at about 4.3 seconds
+            #[cfg(target_arch = "wasm32")]
+            let nanoseconds = self
+                .context
+                .get_query_parameter_u32(query, crate::context::QUERY_RESULT)
+                as u64;
+            Some(nanoseconds as f64 / 1_000_000.0)
+        }
+    }
+}
+
+impl Drop for GpuTimerQuery {
+    fn drop(&mut self) {
+        if let Some(query) = self.query.take() {
+            unsafe {
+                self.context.delete_query(query);
+            }
+        }
+    }
+}
+
+impl Context {
+    ///
+    /// Returns whether or not GPU timer queries are supported, see [Context::begin_timer].
+    /// They are supported on desktop OpenGL and on OpenGL ES/WebGL if the `EXT_disjoint_timer_query` extension is available.
+    ///
+    pub fn is_timer_query_supported(&self) -> bool {
+        let extensions = self.supported_extensions();
+        if self.version().is_embedded {
+            extensions.contains("GL_EXT_disjoint_timer_query")
+                || extensions.contains("EXT_disjoint_timer_query")
+                || extensions.contains("EXT_disjoint_timer_query_webgl2")
+        } else {
+            let version = self.version();
+            (version.major, version.minor) >= (3, 3) || extensions.contains("GL_ARB_timer_query")
+        }
+    }
+
+    ///
+    /// Starts measuring the time the GPU spends on the commands issued until [GpuTimer::end] is called,
+    /// for example to profile a render call:
+    ///
+    /// ```ignore
+    /// let timer = context.begin_timer();
+    /// target.render(&camera, &objects, &lights);
+    /// let query = timer.end();
+    /// // A few frames later
+    /// if let Some(milliseconds) = query.result() {
+    ///     println!("Render time: {} ms", milliseconds);
+    /// }
+    /// ```
+    ///
+    /// If timer queries are not supported, see [Context::is_timer_query_supported], the timer does nothing and the result is always `None`.
+    ///
+    pub fn begin_timer(&self) -> GpuTimer {
+        let query = if self.is_timer_query_supported() {
+            unsafe {
+                let query = self.create_query().ok();
+                if let Some(query) = query {
+                    self.begin_query(crate::context::TIME_ELAPSED, query);
+                }
+                query
+            }
+        } else {
+            None
+        };
+        GpuTimer {
+            context: self.clone(),
+            query,
+        }
+    }
+}
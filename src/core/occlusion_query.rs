@@ -0,0 +1,76 @@
+use crate::core::*;
+
+///
+/// A GPU occlusion query which determines whether any pixels passed the depth test for the draw commands issued in [OcclusionQuery::test],
+/// for example to test whether the bounding box of an object is hidden behind other objects.
+/// The result is available when the GPU has finished executing the commands, usually in the next frame.
+///
+pub struct OcclusionQuery {
+    context: Context,
+    query: Option<crate::context::Query>,
+}
+
+impl OcclusionQuery {
+    ///
+    /// Creates a new occlusion query.
+    /// If occlusion queries are not supported, the query does nothing and the result is always `None`.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            query: unsafe { context.create_query().ok() },
+        }
+    }
+
+    ///
+    /// Counts whether any pixels pass the depth test for the draw commands issued in the given callback.
+    /// The result replaces the result of any previous test when it becomes available.
+    ///
+    pub fn test(&self, callback: impl FnOnce()) {
+        if let Some(query) = self.query {
+            unsafe {
+                self.context
+                    .begin_query(crate::context::ANY_SAMPLES_PASSED, query);
+            }
+            callback();
+            unsafe {
+                self.context.end_query(crate::context::ANY_SAMPLES_PASSED);
+            }
+        } else {
+            callback();
+        }
+    }
+
+    ///
+    /// Returns whether or not any pixels passed the depth test in the last test if the result is available.
+    /// Returns `None` if the result is not available yet or if occlusion queries are not supported.
+    /// Since the result is read asynchronously, this method does not stall the GPU and can be called once per frame until it returns a value.
+    ///
+    pub fn result(&self) -> Option<bool> {
+        let query = self.query?;
+        unsafe {
+            if self
+                .context
+                .get_query_parameter_u32(query, crate::context::QUERY_RESULT_AVAILABLE)
+                == 0
+            {
+                return None;
+            }
+            Some(
+                self.context
+                    .get_query_parameter_u32(query, crate::context::QUERY_RESULT)
+                    != 0,
+            )
+        }
+    }
+}
+
+impl Drop for OcclusionQuery {
+    fn drop(&mut self) {
+        if let Some(query) = self.query.take() {
+            unsafe {
+                self.context.delete_query(query);
+            }
+        }
+    }
+}
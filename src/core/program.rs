@@ -64,27 +64,32 @@ impl Program {
             context.link_program(id);
 
             if !context.get_program_link_status(id) {
-                let log = context.get_shader_info_log(vert_shader);
-                if !log.is_empty() {
-                    Err(shader_compilation_error(
+                let error = if !context.get_shader_compile_status(vert_shader) {
+                    shader_compilation_error(
                         "vertex",
-                        log,
+                        context.get_shader_info_log(vert_shader),
                         vertex_shader_source,
-                    ))?;
-                }
-                let log = context.get_shader_info_log(frag_shader);
-                if !log.is_empty() {
-                    Err(shader_compilation_error(
+                        header,
+                    )
+                } else if !context.get_shader_compile_status(frag_shader) {
+                    shader_compilation_error(
                         "fragment",
-                        log,
+                        context.get_shader_info_log(frag_shader),
                         fragment_shader_source,
-                    ))?;
-                }
-                let log = context.get_program_info_log(id);
-                if !log.is_empty() {
-                    Err(CoreError::ShaderLink(log))?;
-                }
-                Err(CoreError::ShaderCompilerError)?;
+                        header,
+                    )
+                } else {
+                    let log = context.get_program_info_log(id);
+                    if !log.is_empty() {
+                        CoreError::ShaderLink(log)
+                    } else {
+                        CoreError::ShaderCompilerError
+                    }
+                };
+                context.delete_shader(vert_shader);
+                context.delete_shader(frag_shader);
+                context.delete_program(id);
+                return Err(error);
             }
 
             context.detach_shader(id, vert_shader);
@@ -132,6 +137,28 @@ impl Program {
         }
     }
 
+    ///
+    /// Creates a new shader program from the vertex and fragment glsl shader source in the given files, see [Program::from_source].
+    /// Use [WatchedProgram] to recompile the program automatically when the files are changed.
+    /// Only available on desktop.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_files(
+        context: &Context,
+        vertex_shader_path: impl AsRef<std::path::Path>,
+        fragment_shader_path: impl AsRef<std::path::Path>,
+    ) -> Result<Self, CoreError> {
+        let read = |path: &std::path::Path| {
+            std::fs::read_to_string(path)
+                .map_err(|e| CoreError::ShaderFileRead(path.display().to_string(), e.to_string()))
+        };
+        Self::from_source(
+            context,
+            &read(vertex_shader_path.as_ref())?,
+            &read(fragment_shader_path.as_ref())?,
+        )
+    }
+
     ///
     /// Send the given uniform data to this shader program and associate it with the given named variable.
     /// The glsl shader variable must be of type `uniform int` if the data is an integer, `uniform vec2` if it is of type [Vec2] etc.
@@ -436,6 +463,7 @@ impl Program {
         unsafe {
             self.context
                 .draw_arrays(crate::context::TRIANGLES, 0, count as i32);
+            self.context.statistics.draw_call(count as u64 / 3);
             for location in self.attributes.values() {
                 self.context.disable_vertex_attrib_array(*location);
             }
@@ -470,6 +498,9 @@ impl Program {
                 count as i32,
                 instance_count as i32,
             );
+            self.context
+                .statistics
+                .draw_call(count as u64 / 3 * instance_count as u64);
             self.context
                 .bind_buffer(crate::context::ELEMENT_ARRAY_BUFFER, None);
             for location in self.attributes.values() {
@@ -529,6 +560,7 @@ impl Program {
                 T::data_type(),
                 first as i32,
             );
+            self.context.statistics.draw_call(count as u64 / 3);
             self.context
                 .bind_buffer(crate::context::ELEMENT_ARRAY_BUFFER, None);
 
@@ -591,6 +623,9 @@ impl Program {
                 first as i32,
                 instance_count as i32,
             );
+            self.context
+                .statistics
+                .draw_call(count as u64 / 3 * instance_count as u64);
             self.context
                 .bind_buffer(crate::context::ELEMENT_ARRAY_BUFFER, None);
             for location in self.attributes.values() {
@@ -650,11 +685,118 @@ impl Drop for Program {
         }
     }
 }
-fn shader_compilation_error(typ: &str, log: String, source: String) -> CoreError {
-    let lines: Vec<String> = source
+fn shader_compilation_error(typ: &str, log: String, source: String, header: &str) -> CoreError {
+    let header_lines = header.lines().count();
+    let user_source = source.lines().skip(header_lines).collect::<Vec<_>>();
+    let errors = log
         .lines()
-        .enumerate()
-        .map(|(index, l)| format!("{:0>3}: {}", index + 1, l))
+        .filter(|l| !l.trim().is_empty())
+        .map(|message| {
+            let line = log_line_number(message)
+                .filter(|line| *line > header_lines)
+                .map(|line| line - header_lines);
+            let snippet = line
+                .map(|line| {
+                    let first = line.saturating_sub(2).max(1);
+                    let last = (line + 2).min(user_source.len());
+                    (first..=last)
+                        .map(|l| {
+                            format!(
+                                "{} {:>4}: {}",
+                                if l == line { ">" } else { " " },
+                                l,
+                                user_source[l - 1]
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .unwrap_or_default();
+            ShaderError {
+                line,
+                message: message.trim().to_string(),
+                snippet,
+            }
+        })
         .collect();
-    CoreError::ShaderCompilation(typ.to_string(), lines.join("\n"), log)
+    CoreError::ShaderCompilation(Box::new(ShaderCompilationError {
+        shader_type: typ.to_string(),
+        source,
+        header_lines,
+        log,
+        errors,
+    }))
+}
+
+///
+/// Returns the line number in an error message from the shader compiler, which is formatted differently by different drivers,
+/// for example `ERROR: 0:12: ...`, `0:12(5): error: ...` or `0(12) : error ...`.
+///
+fn log_line_number(message: &str) -> Option<usize> {
+    let bytes = message.as_bytes();
+    (0..bytes.len().saturating_sub(2)).find_map(|i| {
+        let is_start = bytes[i] == b'0' && (i == 0 || !bytes[i - 1].is_ascii_alphanumeric());
+        if !is_start || !(bytes[i + 1] == b':' || bytes[i + 1] == b'(') {
+            return None;
+        }
+        let digits = message[i + 2..]
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect::<String>();
+        digits.parse().ok()
+    })
+}
+
+///
+/// A detailed description of an error when compiling a shader, see [CoreError::ShaderCompilation].
+///
+#[derive(Debug, Clone)]
+pub struct ShaderCompilationError {
+    /// The type of shader, either `vertex` or `fragment`.
+    pub shader_type: String,
+    /// The full source which was compiled, including the lines added before the source given to [Program::from_source].
+    pub source: String,
+    /// The number of lines added before the source given to [Program::from_source], ie. the `#version` directive and precision qualifiers.
+    pub header_lines: usize,
+    /// The log from the shader compiler.
+    pub log: String,
+    /// The errors and warnings in the log.
+    pub errors: Vec<ShaderError>,
+}
+
+///
+/// An error or warning from the shader compiler, see [ShaderCompilationError].
+///
+#[derive(Debug, Clone)]
+pub struct ShaderError {
+    /// The line in the source given to [Program::from_source], starting at 1, if the line can be found in the message from the shader compiler.
+    pub line: Option<usize>,
+    /// The message from the shader compiler.
+    pub message: String,
+    /// The lines around [ShaderError::line] in the source given to [Program::from_source], where the line is marked with `>`.
+    pub snippet: String,
+}
+
+impl std::fmt::Display for ShaderCompilationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "failed compiling {} shader", self.shader_type)?;
+        for error in self.errors.iter() {
+            writeln!(f)?;
+            if let Some(line) = error.line {
+                writeln!(f, "line {}: {}", line, error.message)?;
+                writeln!(f, "{}", error.snippet)?;
+            } else {
+                writeln!(f, "{}", error.message)?;
+            }
+        }
+        writeln!(f, "\nsource:")?;
+        for (index, line) in self.source.lines().enumerate() {
+            if index < self.header_lines {
+                writeln!(f, "      {}", line)?;
+            } else {
+                writeln!(f, "{:>4}: {}", index + 1 - self.header_lines, line)?;
+            }
+        }
+        write!(f, "\nlog:\n{}", self.log)
+    }
 }
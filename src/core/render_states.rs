@@ -19,6 +19,17 @@ pub struct RenderStates {
     ///
     pub depth_test: DepthTest,
 
+    ///
+    /// Defines the stencil test in a render call.
+    /// The stencil test determines whether or not a fragment from the current render call should be discarded
+    /// when comparing a reference value with the stencil value of the current fragment and how to update the stencil value.
+    /// If `None`, which is the default, the stencil test is inherited from the render target that is written to (see [RenderTarget::with_stencil_test](crate::core::RenderTarget::with_stencil_test)).
+    /// Use `Some(StencilTest::Disabled)` to disable the stencil test for this render call, also when the render target has a stencil test.
+    ///
+    /// **Note:** A stencil test defined by a render call is also used by the following render calls in the same write to the render target which inherit the stencil test.
+    ///
+    pub stencil_test: Option<StencilTest>,
+
     ///
     /// Defines which type of blending to use for a render call.
     /// Blending allows combining each color channel of a render call with the color already in the
@@ -79,6 +90,126 @@ impl Default for DepthTest {
     }
 }
 
+///
+/// Determines whether or not a fragment/pixel from the current render call should be discarded
+/// when comparing a reference value with the stencil value of the current fragment/pixel and how the stencil value is updated.
+///
+/// Use [RenderStates::stencil_test] to apply a stencil test to a single render call, for example from a material or effect,
+/// [RenderTarget::with_stencil_test](crate::core::RenderTarget::with_stencil_test) to apply a stencil test to everything written into a render target
+/// and [RenderTarget::clear_stencil](crate::core::RenderTarget::clear_stencil) to clear the stencil values.
+///
+/// **Note:** Stencil test is only possible when rendering into a depth texture with a stencil component, ie. created with the [f24s8](crate::core::texture::f24s8) data type.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum StencilTest {
+    /// The stencil test is enabled.
+    Enabled {
+        /// The comparison between the reference value (left hand side) and the current stencil value (right hand side), for example [StencilFunction::Less] passes if the reference value is less than the current stencil value.
+        function: StencilFunction,
+        /// The reference value used in the comparison and possibly written to the stencil buffer (see [StencilOperation::Replace]).
+        reference: u8,
+        /// The mask that is applied to both the reference value and the current stencil value before they are compared.
+        read_mask: u8,
+        /// The mask that determines which bits of the stencil value can be written.
+        write_mask: u8,
+        /// The operation applied to the stencil value when the stencil test fails.
+        stencil_fail: StencilOperation,
+        /// The operation applied to the stencil value when the stencil test passes but the depth test fails.
+        depth_fail: StencilOperation,
+        /// The operation applied to the stencil value when both the stencil test and the depth test passes.
+        pass: StencilOperation,
+    },
+    /// The stencil test is disabled and the stencil values are not updated.
+    #[default]
+    Disabled,
+}
+
+impl StencilTest {
+    ///
+    /// Writes the given reference value to the stencil buffer for all fragments that are rendered and passes the depth test.
+    ///
+    pub const fn write(reference: u8) -> Self {
+        Self::Enabled {
+            function: StencilFunction::Always,
+            reference,
+            read_mask: 0xFF,
+            write_mask: 0xFF,
+            stencil_fail: StencilOperation::Keep,
+            depth_fail: StencilOperation::Keep,
+            pass: StencilOperation::Replace,
+        }
+    }
+
+    ///
+    /// Only renders the fragments where the stencil value is equal to the given reference value without changing the stencil values.
+    ///
+    pub const fn equal(reference: u8) -> Self {
+        Self::Enabled {
+            function: StencilFunction::Equal,
+            reference,
+            read_mask: 0xFF,
+            write_mask: 0x00,
+            stencil_fail: StencilOperation::Keep,
+            depth_fail: StencilOperation::Keep,
+            pass: StencilOperation::Keep,
+        }
+    }
+
+    ///
+    /// Only renders the fragments where the stencil value is not equal to the given reference value without changing the stencil values.
+    ///
+    pub const fn not_equal(reference: u8) -> Self {
+        Self::Enabled {
+            function: StencilFunction::NotEqual,
+            reference,
+            read_mask: 0xFF,
+            write_mask: 0x00,
+            stencil_fail: StencilOperation::Keep,
+            depth_fail: StencilOperation::Keep,
+            pass: StencilOperation::Keep,
+        }
+    }
+}
+
+///
+/// The comparison function used in the [StencilTest].
+///
+#[allow(missing_docs)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StencilFunction {
+    Never,
+    Less,
+    Equal,
+    LessOrEqual,
+    Greater,
+    NotEqual,
+    GreaterOrEqual,
+    Always,
+}
+
+///
+/// The operation applied to the stencil value in the [StencilTest].
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StencilOperation {
+    /// Keeps the current value.
+    Keep,
+    /// Sets the value to 0.
+    Zero,
+    /// Sets the value to the reference value.
+    Replace,
+    /// Increments the value and clamps to the maximum value.
+    Increment,
+    /// Increments the value and wraps to 0 when exceeding the maximum value.
+    IncrementWrap,
+    /// Decrements the value and clamps to 0.
+    Decrement,
+    /// Decrements the value and wraps to the maximum value when going below 0.
+    DecrementWrap,
+    /// Bitwise inverts the value.
+    Invert,
+}
+
 ///
 /// Defines which channels (red, green, blue, alpha and depth) to write to in a render call.
 ///
@@ -201,6 +332,58 @@ impl Blend {
         rgb_equation: BlendEquationType::Add,
         alpha_equation: BlendEquationType::Add,
     };
+
+    ///
+    /// Adds the output color of the render call, weighted by its alpha value, to the color of the render target, for example used for fire, sparks and other glowing effects.
+    /// The alpha value of the render target is not changed.
+    ///
+    pub const ADDITIVE: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::SrcAlpha,
+        source_alpha_multiplier: BlendMultiplierType::Zero,
+        destination_rgb_multiplier: BlendMultiplierType::One,
+        destination_alpha_multiplier: BlendMultiplierType::One,
+        rgb_equation: BlendEquationType::Add,
+        alpha_equation: BlendEquationType::Add,
+    };
+
+    ///
+    /// Multiplies the color of the render target with the output color of the render call, which always results in a darker color.
+    /// The alpha value of the render target is not changed.
+    ///
+    pub const MULTIPLY: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::DstColor,
+        source_alpha_multiplier: BlendMultiplierType::Zero,
+        destination_rgb_multiplier: BlendMultiplierType::Zero,
+        destination_alpha_multiplier: BlendMultiplierType::One,
+        rgb_equation: BlendEquationType::Add,
+        alpha_equation: BlendEquationType::Add,
+    };
+
+    ///
+    /// Multiplies the inverse of the color of the render target with the inverse of the output color of the render call and inverts the result, which always results in a brighter color.
+    /// The alpha value of the render target is not changed.
+    ///
+    pub const SCREEN: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::One,
+        source_alpha_multiplier: BlendMultiplierType::Zero,
+        destination_rgb_multiplier: BlendMultiplierType::OneMinusSrcColor,
+        destination_alpha_multiplier: BlendMultiplierType::One,
+        rgb_equation: BlendEquationType::Add,
+        alpha_equation: BlendEquationType::Add,
+    };
+
+    ///
+    /// Transparency blending where the output color of the render call is already multiplied by its alpha value.
+    /// The alpha value of the render target is not changed, similar to [Blend::TRANSPARENCY].
+    ///
+    pub const PREMULTIPLIED_ALPHA: Self = Self::Enabled {
+        source_rgb_multiplier: BlendMultiplierType::One,
+        source_alpha_multiplier: BlendMultiplierType::Zero,
+        destination_rgb_multiplier: BlendMultiplierType::OneMinusSrcAlpha,
+        destination_alpha_multiplier: BlendMultiplierType::One,
+        rgb_equation: BlendEquationType::Add,
+        alpha_equation: BlendEquationType::Add,
+    };
 }
 
 impl Default for Blend {
@@ -240,3 +423,156 @@ pub enum BlendEquationType {
     Max,
     Min,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///
+    /// Blends the source color into the destination color on the CPU the same way as OpenGL does with the given blend parameters.
+    ///
+    fn blend(blend: Blend, source: [f32; 4], destination: [f32; 4]) -> [f32; 4] {
+        let Blend::Enabled {
+            source_rgb_multiplier,
+            source_alpha_multiplier,
+            destination_rgb_multiplier,
+            destination_alpha_multiplier,
+            rgb_equation,
+            alpha_equation,
+        } = blend
+        else {
+            return source;
+        };
+        let factor = |multiplier: BlendMultiplierType, i: usize| match multiplier {
+            BlendMultiplierType::Zero => 0.0,
+            BlendMultiplierType::One => 1.0,
+            BlendMultiplierType::SrcColor => source[i],
+            BlendMultiplierType::OneMinusSrcColor => 1.0 - source[i],
+            BlendMultiplierType::DstColor => destination[i],
+            BlendMultiplierType::OneMinusDstColor => 1.0 - destination[i],
+            BlendMultiplierType::SrcAlpha => source[3],
+            BlendMultiplierType::OneMinusSrcAlpha => 1.0 - source[3],
+            BlendMultiplierType::DstAlpha => destination[3],
+            BlendMultiplierType::OneMinusDstAlpha => 1.0 - destination[3],
+            BlendMultiplierType::SrcAlphaSaturate => source[3].min(1.0 - destination[3]),
+        };
+        let apply = |equation: BlendEquationType, s: f32, d: f32| match equation {
+            BlendEquationType::Add => s + d,
+            BlendEquationType::Subtract => s - d,
+            BlendEquationType::ReverseSubtract => d - s,
+            BlendEquationType::Max => s.max(d),
+            BlendEquationType::Min => s.min(d),
+        };
+        let mut result = [0.0; 4];
+        for i in 0..4 {
+            let (s, d, equation) = if i < 3 {
+                (
+                    source_rgb_multiplier,
+                    destination_rgb_multiplier,
+                    rgb_equation,
+                )
+            } else {
+                (
+                    source_alpha_multiplier,
+                    destination_alpha_multiplier,
+                    alpha_equation,
+                )
+            };
+            result[i] = apply(
+                equation,
+                source[i] * factor(s, i),
+                destination[i] * factor(d, i),
+            );
+        }
+        result
+    }
+
+    fn assert_blend(preset: Blend, source: [f32; 4], destination: [f32; 4], expected: [f32; 4]) {
+        let result = blend(preset, source, destination);
+        for i in 0..4 {
+            assert!(
+                (result[i] - expected[i]).abs() < 1e-6,
+                "{:?} != {:?}",
+                result,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn blend_presets() {
+        assert_eq!(
+            Blend::ADDITIVE,
+            Blend::Enabled {
+                source_rgb_multiplier: BlendMultiplierType::SrcAlpha,
+                source_alpha_multiplier: BlendMultiplierType::Zero,
+                destination_rgb_multiplier: BlendMultiplierType::One,
+                destination_alpha_multiplier: BlendMultiplierType::One,
+                rgb_equation: BlendEquationType::Add,
+                alpha_equation: BlendEquationType::Add,
+            }
+        );
+        assert_eq!(
+            Blend::MULTIPLY,
+            Blend::Enabled {
+                source_rgb_multiplier: BlendMultiplierType::DstColor,
+                source_alpha_multiplier: BlendMultiplierType::Zero,
+                destination_rgb_multiplier: BlendMultiplierType::Zero,
+                destination_alpha_multiplier: BlendMultiplierType::One,
+                rgb_equation: BlendEquationType::Add,
+                alpha_equation: BlendEquationType::Add,
+            }
+        );
+        assert_eq!(
+            Blend::SCREEN,
+            Blend::Enabled {
+                source_rgb_multiplier: BlendMultiplierType::One,
+                source_alpha_multiplier: BlendMultiplierType::Zero,
+                destination_rgb_multiplier: BlendMultiplierType::OneMinusSrcColor,
+                destination_alpha_multiplier: BlendMultiplierType::One,
+                rgb_equation: BlendEquationType::Add,
+                alpha_equation: BlendEquationType::Add,
+            }
+        );
+        assert_eq!(
+            Blend::PREMULTIPLIED_ALPHA,
+            Blend::Enabled {
+                source_rgb_multiplier: BlendMultiplierType::One,
+                source_alpha_multiplier: BlendMultiplierType::Zero,
+                destination_rgb_multiplier: BlendMultiplierType::OneMinusSrcAlpha,
+                destination_alpha_multiplier: BlendMultiplierType::One,
+                rgb_equation: BlendEquationType::Add,
+                alpha_equation: BlendEquationType::Add,
+            }
+        );
+    }
+
+    #[test]
+    fn blend_preset_results() {
+        let source = [0.5, 0.25, 1.0, 0.5];
+        let destination = [0.5, 0.5, 0.0, 0.75];
+        assert_blend(
+            Blend::ADDITIVE,
+            source,
+            destination,
+            [0.75, 0.625, 0.5, 0.75],
+        );
+        assert_blend(
+            Blend::MULTIPLY,
+            source,
+            destination,
+            [0.25, 0.125, 0.0, 0.75],
+        );
+        // 1 - (1 - s) * (1 - d)
+        assert_blend(Blend::SCREEN, source, destination, [0.75, 0.625, 1.0, 0.75]);
+        // A premultiplied source gives the same colors as transparency blending of the non-premultiplied source
+        let premultiplied = [0.25, 0.125, 0.5, 0.5];
+        assert_blend(
+            Blend::PREMULTIPLIED_ALPHA,
+            premultiplied,
+            destination,
+            blend(Blend::TRANSPARENCY, source, destination),
+        );
+        assert_blend(Blend::Disabled, source, destination, source);
+    }
+}
@@ -26,6 +26,14 @@ mod depth_target_multisample;
 #[doc(inline)]
 pub use depth_target_multisample::*;
 
+mod sized;
+#[doc(inline)]
+pub use sized::*;
+
+mod readback;
+#[doc(inline)]
+pub use readback::*;
+
 use crate::core::*;
 
 use crate::context::Framebuffer;
@@ -37,11 +45,12 @@ use crate::context::Framebuffer;
 ///
 pub struct RenderTarget<'a> {
     id: Option<Framebuffer>,
-    color: Option<ColorTarget<'a>>,
+    colors: Vec<ColorTarget<'a>>,
     depth: Option<DepthTarget<'a>>,
     pub(crate) context: Context,
     width: u32,
     height: u32,
+    stencil_test: StencilTest,
 }
 
 impl<'a> RenderTarget<'a> {
@@ -53,10 +62,11 @@ impl<'a> RenderTarget<'a> {
         Self {
             context: context.clone(),
             id: None,
-            color: None,
+            colors: Vec::new(),
             depth: None,
             width,
             height,
+            stencil_test: StencilTest::Disabled,
         }
     }
 
@@ -69,13 +79,55 @@ impl<'a> RenderTarget<'a> {
         Self {
             context: color.context.clone(),
             id: Some(new_framebuffer(&color.context)),
-            color: Some(color),
+            colors: vec![color],
+            depth: Some(depth),
+            width,
+            height,
+            stencil_test: StencilTest::Disabled,
+        }
+    }
+
+    ///
+    /// Constructs a new render target that enables rendering into all of the given [ColorTarget]s and the [DepthTarget] at the same time.
+    /// The color targets are bound to consecutive color attachments in the given order,
+    /// so the first output of a fragment shader (`layout (location = 0) out vec4 ...;`) is written to the first color target and so on.
+    /// A color target consisting of several layers or cube map sides uses one color attachment for each layer or side.
+    /// All color targets must have the same size as the depth target.
+    ///
+    /// **Note:** Reading the color of this render target, see for example [RenderTarget::read_color], reads from the first color target.
+    ///
+    pub fn new_multiple(colors: &[ColorTarget<'a>], depth: DepthTarget<'a>) -> Self {
+        let width = depth.width();
+        let height = depth.height();
+        if colors
+            .iter()
+            .any(|color| color.width() != width || color.height() != height)
+        {
+            panic!(
+                "all color targets of a render target must have the same size as the depth target"
+            );
+        }
+        Self {
+            context: depth.context.clone(),
+            id: Some(new_framebuffer(&depth.context)),
+            colors: colors.to_vec(),
             depth: Some(depth),
             width,
             height,
+            stencil_test: StencilTest::Disabled,
         }
     }
 
+    ///
+    /// Returns this render target with the given stencil test, which is applied to everything written into this render target,
+    /// for example to only render where a mask has been written to the stencil values, see [StencilTest].
+    /// The stencil test is only possible if the depth target has a stencil component, ie. the depth texture is created with the [f24s8] data type.
+    ///
+    pub fn with_stencil_test(mut self, stencil_test: StencilTest) -> Self {
+        self.stencil_test = stencil_test;
+        self
+    }
+
     /// The width of this target.
     pub fn width(&self) -> u32 {
         self.width
@@ -87,14 +139,14 @@ impl<'a> RenderTarget<'a> {
     }
 
     ///
-    /// Clears the color and depth of this render target as defined by the given clear state.
+    /// Clears the color, depth and stencil of this render target as defined by the given clear state.
     ///
     pub fn clear(&self, clear_state: ClearState) -> &Self {
         self.clear_partially(self.scissor_box(), clear_state)
     }
 
     ///
-    /// Clears the color and depth of the part of this render target that is inside the given scissor box.
+    /// Clears the color, depth and stencil of the part of this render target that is inside the given scissor box.
     ///
     pub fn clear_partially(&self, scissor_box: ScissorBox, clear_state: ClearState) -> &Self {
         self.context.set_scissor(scissor_box);
@@ -103,6 +155,14 @@ impl<'a> RenderTarget<'a> {
         self
     }
 
+    ///
+    /// Clears the stencil values of this render target to the given value, which is the same as clearing with [ClearState::stencil].
+    /// This is only possible if the depth target has a stencil component, ie. the depth texture is created with the [f24s8] data type.
+    ///
+    pub fn clear_stencil(&self, stencil: u8) -> &Self {
+        self.clear(ClearState::stencil(stencil))
+    }
+
     ///
     /// Writes whatever rendered in the `render` closure into this render target.
     ///
@@ -123,8 +183,12 @@ impl<'a> RenderTarget<'a> {
     ) -> Result<&Self, E> {
         self.context.set_scissor(scissor_box);
         self.bind(crate::context::DRAW_FRAMEBUFFER);
-        render()?;
-        if let Some(ref color) = self.color {
+        self.context.set_stencil_test(self.stencil_test);
+        let result = render();
+        // Disable the stencil test afterwards since it might have been enabled by the render target or the render states of a render call
+        self.context.set_stencil_test(StencilTest::Disabled);
+        result?;
+        for color in self.colors.iter() {
             color.generate_mip_maps();
         }
         Ok(self)
@@ -159,22 +223,7 @@ impl<'a> RenderTarget<'a> {
     /// - 32-bit float RGBA (Specify `T` as either `Vec4<f32>` or `[f32; 4]`) which works with any render target using `f16` or `f32` as its base type.
     ///
     pub fn read_color_partially<T: TextureDataType>(&self, scissor_box: ScissorBox) -> Vec<T> {
-        if self.id.is_some() && self.color.is_none() {
-            panic!("Cannot read color from a render target without a color target");
-        }
-        let format = format_from_data_type::<T>();
-        let data_type = T::data_type();
-
-        // On web, the read format needs to be RGBA and f16 is not supported (see https://webglfundamentals.org/webgl/lessons/webgl-readpixels.html).
-        #[cfg(target_arch = "wasm32")]
-        if format != crate::context::RGBA
-            || !(data_type == crate::context::UNSIGNED_BYTE || data_type == crate::context::FLOAT)
-        {
-            panic!("Only the texture data types `Vec4<T>` and `[T; 4]` where `T` is either `u8` or `f32` are supported when reading color from a render target on web.");
-        }
-
-        self.bind(crate::context::DRAW_FRAMEBUFFER);
-        self.bind(crate::context::READ_FRAMEBUFFER);
+        let (format, data_type) = self.bind_for_color_read::<T>();
         let data_size = std::mem::size_of::<T>();
         let mut bytes =
             vec![0u8; scissor_box.width as usize * scissor_box.height as usize * data_size];
@@ -198,6 +247,66 @@ impl<'a> RenderTarget<'a> {
         pixels
     }
 
+    ///
+    /// Starts reading the colors of the pixels in this render target without waiting for the GPU to finish rendering, as opposed to [RenderTarget::read_color] which stalls until the GPU is done.
+    /// The returned [ReadbackFuture] contains the colors when the GPU has finished, usually one or two frames later.
+    /// See [RenderTarget::read_color] for the requirements on the generic parameter `T`.
+    ///
+    pub fn read_color_async<T: TextureDataType>(&self) -> ReadbackFuture<T> {
+        self.read_color_partially_async(self.scissor_box())
+    }
+
+    ///
+    /// Starts reading the colors of the pixels in this render target inside the given scissor box without waiting for the GPU to finish rendering, see [RenderTarget::read_color_async].
+    ///
+    pub fn read_color_partially_async<T: TextureDataType>(
+        &self,
+        scissor_box: ScissorBox,
+    ) -> ReadbackFuture<T> {
+        let (format, data_type) = self.bind_for_color_read::<T>();
+        ReadbackFuture::new(&self.context, scissor_box, format, data_type)
+    }
+
+    fn bind_for_color_read<T: TextureDataType>(&self) -> (u32, u32) {
+        if self.id.is_some() && self.colors.is_empty() {
+            panic!("Cannot read color from a render target without a color target");
+        }
+        let format = format_from_data_type::<T>();
+        let data_type = T::data_type();
+
+        // On web, the read format needs to be RGBA and f16 is not supported (see https://webglfundamentals.org/webgl/lessons/webgl-readpixels.html).
+        #[cfg(target_arch = "wasm32")]
+        if format != crate::context::RGBA
+            || !(data_type == crate::context::UNSIGNED_BYTE || data_type == crate::context::FLOAT)
+        {
+            panic!("Only the texture data types `Vec4<T>` and `[T; 4]` where `T` is either `u8` or `f32` are supported when reading color from a render target on web.");
+        }
+
+        self.bind(crate::context::DRAW_FRAMEBUFFER);
+        self.bind(crate::context::READ_FRAMEBUFFER);
+        (format, data_type)
+    }
+
+    ///
+    /// Returns the colors of the pixels in this render target as a [CpuTexture] with RGBA `u8` data in image orientation, ie. the first row of pixels is the top row,
+    /// for example to save a screenshot using `three_d_asset::io::save`.
+    /// The render target must have `u8` as its base type, which is always the case for the screen.
+    /// The colors are exactly as displayed, so if the colors are converted to sRGB color space when rendering to the screen, which is the default, the texture is also in sRGB color space.
+    ///
+    /// **Screen:**
+    /// The content of the screen is only defined after rendering to it and before the frame is presented,
+    /// so read the screen at the end of the render loop, after rendering, instead of at the beginning of the next frame.
+    ///
+    pub fn read_cpu_texture(&self) -> CpuTexture {
+        let scissor_box = self.scissor_box();
+        CpuTexture {
+            data: TextureData::RgbaU8(self.read_color_partially::<[u8; 4]>(scissor_box)),
+            width: scissor_box.width,
+            height: scissor_box.height,
+            ..Default::default()
+        }
+    }
+
     ///
     /// Returns the depth values in this render target.
     ///
@@ -243,11 +352,12 @@ impl<'a> RenderTarget<'a> {
     ) -> Self {
         Self {
             id: Some(framebuffer),
-            color: None,
+            colors: Vec::new(),
             depth: None,
             context: context.clone(),
             width,
             height,
+            stencil_test: StencilTest::Disabled,
         }
     }
 
@@ -262,8 +372,8 @@ impl<'a> RenderTarget<'a> {
     pub(in crate::core) fn blit_to(&self, target: &RenderTarget) {
         self.bind(crate::context::DRAW_FRAMEBUFFER);
         target.bind(crate::context::DRAW_FRAMEBUFFER);
-        let target_is_screen = target.color.is_none() && target.depth.is_none();
-        let mask = if self.color.is_some() && (target.color.is_some() || target_is_screen) {
+        let target_is_screen = target.colors.is_empty() && target.depth.is_none();
+        let mask = if !self.colors.is_empty() && (!target.colors.is_empty() || target_is_screen) {
             let mut mask = crate::context::COLOR_BUFFER_BIT;
             if self.depth.is_some() && (target.depth.is_some() || target_is_screen) {
                 mask |= crate::context::DEPTH_BUFFER_BIT;
@@ -301,10 +411,11 @@ impl<'a> RenderTarget<'a> {
         Self {
             context: color.context.clone(),
             id: Some(new_framebuffer(&color.context)),
-            color: Some(color),
+            colors: vec![color],
             depth: None,
             width,
             height,
+            stencil_test: StencilTest::Disabled,
         }
     }
 
@@ -315,9 +426,10 @@ impl<'a> RenderTarget<'a> {
             context: depth.context.clone(),
             id: Some(new_framebuffer(&depth.context)),
             depth: Some(depth),
-            color: None,
+            colors: Vec::new(),
             width,
             height,
+            stencil_test: StencilTest::Disabled,
         }
     }
 
@@ -325,8 +437,18 @@ impl<'a> RenderTarget<'a> {
         unsafe {
             self.context.bind_framebuffer(target, self.id);
         }
-        if let Some(ref color) = self.color {
-            color.bind(&self.context);
+        if !self.colors.is_empty() {
+            let mut channel = 0;
+            for color in self.colors.iter() {
+                channel += color.bind(channel);
+            }
+            unsafe {
+                self.context.draw_buffers(
+                    &(0..channel)
+                        .map(|i| crate::context::COLOR_ATTACHMENT0 + i)
+                        .collect::<Vec<u32>>(),
+                );
+            }
         }
         if let Some(ref depth) = self.depth {
             depth.bind();
@@ -429,3 +551,175 @@ impl_render_target_core_extensions!(
 );
 impl_render_target_core_extensions!(ColorTargetMultisample<C: TextureDataType>);
 impl_render_target_core_extensions!(DepthTargetMultisample<D: DepthTextureDataType>);
+
+#[cfg(all(test, feature = "headless", not(target_arch = "wasm32")))]
+mod tests {
+    use crate::core::*;
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn stencil_test() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let (width, height) = (4, 4);
+        let mut color = Texture2D::new_empty::<[u8; 4]>(
+            &context,
+            width,
+            height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let mut depth = DepthTexture2D::new::<f24s8>(
+            &context,
+            width,
+            height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let program = Program::from_source(
+            &context,
+            full_screen_vertex_shader_source(),
+            "
+            layout (location = 0) out vec4 outColor;
+            void main()
+            {
+                outColor = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+            ",
+        )
+        .unwrap();
+        let render_states = RenderStates {
+            depth_test: DepthTest::Always,
+            ..Default::default()
+        };
+        let viewport = Viewport::new_at_origo(width, height);
+
+        // Write the stencil value 1 into the left half without changing the color
+        RenderTarget::new(color.as_color_target(None), depth.as_depth_target())
+            .with_stencil_test(StencilTest::write(1))
+            .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0))
+            .clear_stencil(0)
+            .write_partially::<CoreError>(ScissorBox::new_at_origo(width / 2, height), || {
+                let render_states = RenderStates {
+                    write_mask: WriteMask::NONE,
+                    ..render_states
+                };
+                full_screen_draw(&context, &program, render_states, viewport);
+                Ok(())
+            })
+            .unwrap();
+
+        // Render red where the stencil value is 1
+        let pixels = RenderTarget::new(color.as_color_target(None), depth.as_depth_target())
+            .with_stencil_test(StencilTest::equal(1))
+            .write::<CoreError>(|| {
+                full_screen_draw(&context, &program, render_states, viewport);
+                Ok(())
+            })
+            .unwrap()
+            .read_color::<[u8; 4]>();
+
+        for (i, pixel) in pixels.iter().enumerate() {
+            let x = i as u32 % width;
+            if x < width / 2 {
+                assert_eq!(*pixel, [255, 0, 0, 255], "pixel {} should be red", i);
+            } else {
+                assert_eq!(*pixel, [0, 0, 0, 255], "pixel {} should be black", i);
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn stencil_test_from_render_states() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let (width, height) = (4, 4);
+        let mut color = Texture2D::new_empty::<[u8; 4]>(
+            &context,
+            width,
+            height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let mut depth = DepthTexture2D::new::<f24s8>(
+            &context,
+            width,
+            height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let program = Program::from_source(
+            &context,
+            full_screen_vertex_shader_source(),
+            "
+            layout (location = 0) out vec4 outColor;
+            void main()
+            {
+                outColor = vec4(0.0, 1.0, 0.0, 1.0);
+            }
+            ",
+        )
+        .unwrap();
+        let viewport = Viewport::new_at_origo(width, height);
+
+        // Write the stencil value 1 into the bottom half and render green where the stencil value is 1,
+        // both defined by the render states like a material would
+        let pixels = RenderTarget::new(color.as_color_target(None), depth.as_depth_target())
+            .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0))
+            .clear_stencil(0)
+            .write_partially::<CoreError>(ScissorBox::new_at_origo(width, height / 2), || {
+                let render_states = RenderStates {
+                    depth_test: DepthTest::Always,
+                    write_mask: WriteMask::NONE,
+                    stencil_test: Some(StencilTest::write(1)),
+                    ..Default::default()
+                };
+                full_screen_draw(&context, &program, render_states, viewport);
+                Ok(())
+            })
+            .unwrap()
+            .write::<CoreError>(|| {
+                let render_states = RenderStates {
+                    depth_test: DepthTest::Always,
+                    stencil_test: Some(StencilTest::equal(1)),
+                    ..Default::default()
+                };
+                full_screen_draw(&context, &program, render_states, viewport);
+                Ok(())
+            })
+            .unwrap()
+            .read_color::<[u8; 4]>();
+
+        for (i, pixel) in pixels.iter().enumerate() {
+            // The first row of pixels is the top row
+            let y = height - 1 - i as u32 / width;
+            if y < height / 2 {
+                assert_eq!(*pixel, [0, 255, 0, 255], "pixel {} should be green", i);
+            } else {
+                assert_eq!(*pixel, [0, 0, 0, 255], "pixel {} should be black", i);
+            }
+        }
+
+        // Disabling the stencil test in the render states overrides the stencil test of the render target
+        let pixels = RenderTarget::new(color.as_color_target(None), depth.as_depth_target())
+            .with_stencil_test(StencilTest::equal(2))
+            .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0))
+            .write::<CoreError>(|| {
+                let render_states = RenderStates {
+                    depth_test: DepthTest::Always,
+                    stencil_test: Some(StencilTest::Disabled),
+                    ..Default::default()
+                };
+                full_screen_draw(&context, &program, render_states, viewport);
+                Ok(())
+            })
+            .unwrap()
+            .read_color::<[u8; 4]>();
+        assert!(pixels.iter().all(|pixel| *pixel == [0, 255, 0, 255]));
+    }
+}
@@ -1,8 +1,9 @@
 use crate::core::*;
 
 ///
-/// Defines which channels (red, green, blue, alpha and depth) to clear when starting to write to a [RenderTarget].
+/// Defines which channels (red, green, blue, alpha, depth and stencil) to clear when starting to write to a [RenderTarget].
 /// If `None` then the channel is not cleared and if `Some(value)` the channel is cleared to that value (the value must be between 0 and 1).
+/// Channels which are not cleared are preserved.
 ///
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct ClearState {
@@ -16,6 +17,8 @@ pub struct ClearState {
     pub alpha: Option<f32>,
     /// Defines the clear value for the depth channel. A value of 1 means a depth value equal to the far plane and 0 means a depth value equal to the near plane.
     pub depth: Option<f32>,
+    /// Defines the clear value for the stencil channel which is only available if the depth texture has a stencil component, see [f24s8].
+    pub stencil: Option<u8>,
 }
 
 impl ClearState {
@@ -29,11 +32,13 @@ impl ClearState {
             blue: None,
             alpha: None,
             depth: None,
+            stencil: None,
         }
     }
 
     ///
-    /// The depth will be cleared to the given value.
+    /// The depth will be cleared to the given value while the color and stencil are preserved,
+    /// for example before rendering an overlay which should always be on top of what is already rendered.
     ///
     pub const fn depth(depth: f32) -> Self {
         Self {
@@ -42,6 +47,35 @@ impl ClearState {
             blue: None,
             alpha: None,
             depth: Some(depth),
+            stencil: None,
+        }
+    }
+
+    ///
+    /// The stencil will be cleared to the given value while the color and depth are preserved.
+    ///
+    pub const fn stencil(stencil: u8) -> Self {
+        Self {
+            red: None,
+            green: None,
+            blue: None,
+            alpha: None,
+            depth: None,
+            stencil: Some(stencil),
+        }
+    }
+
+    ///
+    /// The depth and stencil will be cleared to the given values.
+    ///
+    pub const fn depth_and_stencil(depth: f32, stencil: u8) -> Self {
+        Self {
+            red: None,
+            green: None,
+            blue: None,
+            alpha: None,
+            depth: Some(depth),
+            stencil: Some(stencil),
         }
     }
 
@@ -55,6 +89,7 @@ impl ClearState {
             blue: Some(blue),
             alpha: Some(alpha),
             depth: None,
+            stencil: None,
         }
     }
 
@@ -68,6 +103,7 @@ impl ClearState {
             blue: Some(blue),
             alpha: Some(alpha),
             depth: Some(depth),
+            stencil: None,
         }
     }
 
@@ -84,6 +120,7 @@ impl ClearState {
                 || self.green.is_some()
                 || self.blue.is_some()
                 || self.alpha.is_some();
+            let mut mask = 0;
             if clear_color {
                 context.clear_color(
                     self.red.unwrap_or(0.0),
@@ -91,17 +128,20 @@ impl ClearState {
                     self.blue.unwrap_or(0.0),
                     self.alpha.unwrap_or(1.0),
                 );
+                mask |= crate::context::COLOR_BUFFER_BIT;
             }
             if let Some(depth) = self.depth {
                 context.clear_depth_f32(depth);
+                mask |= crate::context::DEPTH_BUFFER_BIT;
+            }
+            if let Some(stencil) = self.stencil {
+                context.stencil_mask(0xFF);
+                context.clear_stencil(stencil as i32);
+                mask |= crate::context::STENCIL_BUFFER_BIT;
+            }
+            if mask != 0 {
+                context.clear(mask);
             }
-            context.clear(if clear_color && self.depth.is_some() {
-                crate::context::COLOR_BUFFER_BIT | crate::context::DEPTH_BUFFER_BIT
-            } else if clear_color {
-                crate::context::COLOR_BUFFER_BIT
-            } else {
-                crate::context::DEPTH_BUFFER_BIT
-            });
         }
     }
 }
@@ -111,3 +151,56 @@ impl Default for ClearState {
         Self::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0)
     }
 }
+
+#[cfg(all(test, feature = "headless", not(target_arch = "wasm32")))]
+mod tests {
+    use crate::core::*;
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn clear_depth_and_stencil_only() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let size = 4;
+        let mut color = Texture2D::new_empty::<[u8; 4]>(
+            &context,
+            size,
+            size,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let mut depth = DepthTexture2D::new::<f24s8>(
+            &context,
+            size,
+            size,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let render_target = RenderTarget::new(color.as_color_target(None), depth.as_depth_target());
+        render_target.clear(ClearState::color_and_depth(1.0, 0.0, 0.0, 1.0, 1.0));
+
+        // Clearing the depth leaves the color untouched
+        render_target.clear(ClearState::depth(0.5));
+        assert!(render_target
+            .read_color::<[u8; 4]>()
+            .iter()
+            .all(|pixel| *pixel == [255, 0, 0, 255]));
+        assert!(render_target
+            .read_depth()
+            .iter()
+            .all(|depth| (depth - 0.5).abs() < 0.001));
+
+        // Clearing the stencil leaves both the color and the depth untouched
+        render_target.clear(ClearState::stencil(1));
+        assert!(render_target
+            .read_color::<[u8; 4]>()
+            .iter()
+            .all(|pixel| *pixel == [255, 0, 0, 255]));
+        assert!(render_target
+            .read_depth()
+            .iter()
+            .all(|depth| (depth - 0.5).abs() < 0.001));
+    }
+}
@@ -13,6 +13,7 @@ pub struct ColorTarget<'a> {
     pub(crate) context: Context,
     mip_level: Option<u32>,
     target: Option<ColorTexture<'a>>,
+    array_layer: Option<(&'a Texture2DArray, u32)>,
     multisample_target: Option<&'a Texture2DMultisample>,
 }
 
@@ -26,6 +27,7 @@ impl<'a> ColorTarget<'a> {
             context: context.clone(),
             mip_level,
             target: Some(ColorTexture::Single(texture)),
+            array_layer: None,
             multisample_target: None,
         }
     }
@@ -40,6 +42,7 @@ impl<'a> ColorTarget<'a> {
             context: context.clone(),
             mip_level,
             target: Some(ColorTexture::CubeMap { texture, sides }),
+            array_layer: None,
             multisample_target: None,
         }
     }
@@ -54,6 +57,22 @@ impl<'a> ColorTarget<'a> {
             context: context.clone(),
             mip_level,
             target: Some(ColorTexture::Array { texture, layers }),
+            array_layer: None,
+            multisample_target: None,
+        }
+    }
+
+    pub(in crate::core) fn new_texture_2d_array_layer(
+        context: &Context,
+        texture: &'a Texture2DArray,
+        layer: u32,
+        mip_level: Option<u32>,
+    ) -> Self {
+        ColorTarget {
+            context: context.clone(),
+            mip_level,
+            target: None,
+            array_layer: Some((texture, layer)),
             multisample_target: None,
         }
     }
@@ -66,6 +85,7 @@ impl<'a> ColorTarget<'a> {
             context: context.clone(),
             mip_level: None,
             target: None,
+            array_layer: None,
             multisample_target: Some(texture),
         }
     }
@@ -85,6 +105,7 @@ impl<'a> ColorTarget<'a> {
             scissor_box,
             ClearState {
                 depth: None,
+                stencil: None,
                 ..clear_state
             },
         );
@@ -146,6 +167,14 @@ impl<'a> ColorTarget<'a> {
         self.as_render_target().read_color_partially(scissor_box)
     }
 
+    ///
+    /// Starts reading the colors of the pixels in this color target without waiting for the GPU to finish rendering, see [RenderTarget::read_color_async].
+    ///
+    pub fn read_async<T: TextureDataType>(&self) -> ReadbackFuture<T> {
+        self.as_render_target()
+            .read_color_partially_async(self.scissor_box())
+    }
+
     ///
     /// Returns the width of the color target in texels.
     /// If using the zero mip level of the underlying texture, then this is simply the width of that texture, otherwise it is the width of the given mip level.
@@ -161,6 +190,8 @@ impl<'a> ColorTarget<'a> {
                     size_with_mip(texture.width(), self.mip_level)
                 }
             }
+        } else if let Some((texture, _)) = self.array_layer {
+            size_with_mip(texture.width(), self.mip_level)
         } else {
             self.multisample_target.as_ref().unwrap().width()
         }
@@ -181,6 +212,8 @@ impl<'a> ColorTarget<'a> {
                     size_with_mip(texture.height(), self.mip_level)
                 }
             }
+        } else if let Some((texture, _)) = self.array_layer {
+            size_with_mip(texture.height(), self.mip_level)
         } else {
             self.multisample_target.as_ref().unwrap().height()
         }
@@ -209,53 +242,53 @@ impl<'a> ColorTarget<'a> {
                     }
                 }
             }
+        } else if let Some((texture, _)) = self.array_layer {
+            if self.mip_level.is_none() {
+                texture.generate_mip_maps()
+            }
         }
     }
 
-    pub(super) fn bind(&self, context: &Context) {
+    ///
+    /// Binds the color target to consecutive color attachments starting at the given channel and returns the number of color attachments used.
+    ///
+    pub(super) fn bind(&self, first_channel: u32) -> u32 {
         if let Some(target) = self.target {
             match target {
-                ColorTexture::Single(texture) => unsafe {
-                    context.draw_buffers(&[crate::context::COLOR_ATTACHMENT0]);
-                    texture.bind_as_color_target(0, self.mip_level.unwrap_or(0));
-                },
-                ColorTexture::Array { texture, layers } => unsafe {
-                    context.draw_buffers(
-                        &(0..layers.len())
-                            .map(|i| crate::context::COLOR_ATTACHMENT0 + i as u32)
-                            .collect::<Vec<u32>>(),
-                    );
+                ColorTexture::Single(texture) => {
+                    texture.bind_as_color_target(first_channel, self.mip_level.unwrap_or(0));
+                    1
+                }
+                ColorTexture::Array { texture, layers } => {
                     (0..layers.len()).for_each(|channel| {
                         texture.bind_as_color_target(
                             layers[channel],
-                            channel as u32,
+                            first_channel + channel as u32,
                             self.mip_level.unwrap_or(0),
                         );
                     });
-                },
-                ColorTexture::CubeMap { texture, sides } => unsafe {
-                    context.draw_buffers(
-                        &(0..sides.len())
-                            .map(|i| crate::context::COLOR_ATTACHMENT0 + i as u32)
-                            .collect::<Vec<u32>>(),
-                    );
+                    layers.len() as u32
+                }
+                ColorTexture::CubeMap { texture, sides } => {
                     (0..sides.len()).for_each(|channel| {
                         texture.bind_as_color_target(
                             sides[channel],
-                            channel as u32,
+                            first_channel + channel as u32,
                             self.mip_level.unwrap_or(0),
                         );
                     });
-                },
+                    sides.len() as u32
+                }
             }
+        } else if let Some((texture, layer)) = self.array_layer {
+            texture.bind_as_color_target(layer, first_channel, self.mip_level.unwrap_or(0));
+            1
         } else {
-            unsafe {
-                context.draw_buffers(&[crate::context::COLOR_ATTACHMENT0]);
-                self.multisample_target
-                    .as_ref()
-                    .unwrap()
-                    .bind_as_color_target(0);
-            }
+            self.multisample_target
+                .as_ref()
+                .unwrap()
+                .bind_as_color_target(first_channel);
+            1
         }
     }
 }
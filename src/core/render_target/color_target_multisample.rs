@@ -47,6 +47,7 @@ impl<C: TextureDataType> ColorTargetMultisample<C> {
             scissor_box,
             ClearState {
                 depth: None,
+                stencil: None,
                 ..clear_state
             },
         );
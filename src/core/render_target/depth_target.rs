@@ -72,6 +72,7 @@ impl<'a> DepthTarget<'a> {
             scissor_box,
             ClearState {
                 depth: clear_state.depth,
+                stencil: clear_state.stencil,
                 ..ClearState::none()
             },
         );
@@ -5,7 +5,7 @@ use crate::core::*;
 ///
 /// After rendering into this target, it needs to be resolved to a non-multisample texture to be able to sample it in a shader.
 /// To do this, use the [RenderTargetMultisample::resolve], [RenderTargetMultisample::resolve_to], [RenderTargetMultisample::resolve_color_to]
-/// or [RenderTargetMultisample::resolve_depth_to] methods.
+/// or [RenderTargetMultisample::resolve_depth_to] methods. To read the resolved pixels directly, use [RenderTargetMultisample::read_color].
 ///
 /// Also see [ColorTargetMultisample] and [DepthTargetMultisample].
 ///
@@ -159,6 +159,24 @@ impl<C: TextureDataType, D: DepthTextureDataType> RenderTargetMultisample<C, D>
         depth_texture
     }
 
+    ///
+    /// Resolves the color of this target and returns the colors of the pixels, for example to save an antialiased image when rendering offscreen.
+    /// See [RenderTarget::read_color] for the requirements on the generic parameter `T`.
+    ///
+    pub fn read_color<T: TextureDataType>(&self) -> Vec<T> {
+        let mut color_texture = self.resolve_color();
+        color_texture.as_color_target(None).read()
+    }
+
+    ///
+    /// Resolves the depth of this target and returns the depth values of the pixels.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read_depth(&self) -> Vec<f32> {
+        let mut depth_texture = self.resolve_depth();
+        depth_texture.as_depth_target().read()
+    }
+
     ///
     /// Resolves the multisample render target to default non-multisample [Texture2D] and [DepthTexture2D].
     /// Use [RenderTargetMultisample::resolve_to] to resolve to custom non-multisample textures.
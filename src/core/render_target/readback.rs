@@ -0,0 +1,174 @@
+use crate::core::*;
+
+///
+/// Pixel data which is read asynchronously from a render target, see [RenderTarget::read_color_async].
+/// The data becomes available when the GPU has finished the commands issued before the read, usually one or two frames later.
+///
+pub struct ReadbackFuture<T: TextureDataType> {
+    context: Context,
+    buffer: crate::context::Buffer,
+    fence: crate::context::Fence,
+    width: u32,
+    height: u32,
+    _data: std::marker::PhantomData<T>,
+}
+
+impl<T: TextureDataType> ReadbackFuture<T> {
+    pub(super) fn new(
+        context: &Context,
+        scissor_box: ScissorBox,
+        format: u32,
+        data_type: u32,
+    ) -> Self {
+        let byte_size =
+            scissor_box.width as usize * scissor_box.height as usize * std::mem::size_of::<T>();
+        unsafe {
+            let buffer = context.create_buffer().expect("Failed creating buffer");
+            context.bind_buffer(crate::context::PIXEL_PACK_BUFFER, Some(buffer));
+            context.buffer_data_size(
+                crate::context::PIXEL_PACK_BUFFER,
+                byte_size as i32,
+                crate::context::STREAM_READ,
+            );
+            // Copies the pixels into the buffer on the GPU, so this does not wait for the GPU to finish
+            context.read_pixels(
+                scissor_box.x,
+                scissor_box.y,
+                scissor_box.width as i32,
+                scissor_box.height as i32,
+                format,
+                data_type,
+                crate::context::PixelPackData::BufferOffset(0),
+            );
+            context.bind_buffer(crate::context::PIXEL_PACK_BUFFER, None);
+            let fence = context
+                .fence_sync(crate::context::SYNC_GPU_COMMANDS_COMPLETE, 0)
+                .expect("Failed creating fence");
+            context.flush();
+            Self {
+                context: context.clone(),
+                buffer,
+                fence,
+                width: scissor_box.width,
+                height: scissor_box.height,
+                _data: std::marker::PhantomData,
+            }
+        }
+    }
+
+    ///
+    /// Returns whether or not the data is available, in which case [ReadbackFuture::result] returns the data without stalling.
+    ///
+    pub fn is_ready(&self) -> bool {
+        unsafe { self.context.get_sync_status(self.fence) == crate::context::SIGNALED }
+    }
+
+    ///
+    /// Returns the pixel data if it is available, otherwise `None`.
+    /// The data is in the same format and order as the data returned from [RenderTarget::read_color].
+    /// Since the result is read asynchronously, this method does not stall the GPU and can be called once per frame until it returns a value.
+    ///
+    pub fn result(&self) -> Option<Vec<T>> {
+        if !self.is_ready() {
+            return None;
+        }
+        let mut bytes =
+            vec![0u8; self.width as usize * self.height as usize * std::mem::size_of::<T>()];
+        unsafe {
+            self.context
+                .bind_buffer(crate::context::PIXEL_PACK_BUFFER, Some(self.buffer));
+            self.context
+                .get_buffer_sub_data(crate::context::PIXEL_PACK_BUFFER, 0, &mut bytes);
+            self.context
+                .bind_buffer(crate::context::PIXEL_PACK_BUFFER, None);
+        }
+        let mut pixels = from_byte_slice(&bytes).to_vec();
+        flip_y(&mut pixels, self.width as usize, self.height as usize);
+        Some(pixels)
+    }
+}
+
+impl<T: TextureDataType> Drop for ReadbackFuture<T> {
+    fn drop(&mut self) {
+        unsafe {
+            self.context.delete_sync(self.fence);
+            self.context.delete_buffer(self.buffer);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "headless", not(target_arch = "wasm32")))]
+mod tests {
+    use crate::core::*;
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn read_color_async() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let (width, height) = (8, 4);
+        let mut color = Texture2D::new_empty::<[u8; 4]>(
+            &context,
+            width,
+            height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let program = Program::from_source(
+            &context,
+            full_screen_vertex_shader_source(),
+            "
+            in vec2 uvs;
+            layout (location = 0) out vec4 outColor;
+            void main()
+            {
+                outColor = vec4(uvs, 0.5, 1.0);
+            }
+            ",
+        )
+        .unwrap();
+        let color_target = color.as_color_target(None);
+        color_target
+            .write::<CoreError>(|| {
+                full_screen_draw(
+                    &context,
+                    &program,
+                    RenderStates {
+                        depth_test: DepthTest::Always,
+                        ..Default::default()
+                    },
+                    Viewport::new_at_origo(width, height),
+                );
+                Ok(())
+            })
+            .unwrap();
+        let render_target = color_target.as_render_target();
+        let scissor_box = ScissorBox {
+            x: 2,
+            y: 1,
+            width: 4,
+            height: 2,
+        };
+        let futures = (
+            render_target.read_color_async::<[u8; 4]>(),
+            render_target.read_color_partially_async::<[u8; 4]>(scissor_box),
+        );
+
+        // Poll until the results are ready, which is a long time for such a small read
+        let mut results = (None, None);
+        for _ in 0..1000 {
+            results = (futures.0.result(), futures.1.result());
+            if results.0.is_some() && results.1.is_some() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(results.0.unwrap(), render_target.read_color::<[u8; 4]>());
+        assert_eq!(
+            results.1.unwrap(),
+            render_target.read_color_partially::<[u8; 4]>(scissor_box)
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use crate::core::*;
+
+///
+/// A render target which owns a color and a depth texture and only reallocates them when the requested size changes.
+/// Use this instead of manually checking the size of the textures and reallocating them, for example when the textures should have the same size as the viewport.
+///
+pub struct SizedRenderTarget<C: TextureDataType, D: DepthTextureDataType> {
+    context: Context,
+    color: Texture2D,
+    depth: DepthTexture2D,
+    _c: std::marker::PhantomData<C>,
+    _d: std::marker::PhantomData<D>,
+}
+
+impl<C: TextureDataType, D: DepthTextureDataType> SizedRenderTarget<C, D> {
+    ///
+    /// Constructs a new render target with a color and depth texture of the given size.
+    /// This constructor does not panic if rendering into a color texture with the format `C` is not supported on this device, instead rendering fails silently,
+    /// so use [SizedRenderTarget::try_new] to get an error in that case.
+    ///
+    pub fn new(context: &Context, width: u32, height: u32) -> Self {
+        Self {
+            context: context.clone(),
+            color: Self::new_color_texture(context, width, height),
+            depth: Self::new_depth_texture(context, width, height),
+            _c: std::marker::PhantomData,
+            _d: std::marker::PhantomData,
+        }
+    }
+
+    ///
+    /// Same as [SizedRenderTarget::new] except that it returns an error if rendering into a color texture with the format `C` is not supported on this device, see [Capabilities::is_color_renderable].
+    ///
+    pub fn try_new(context: &Context, width: u32, height: u32) -> Result<Self, CoreError> {
+        context
+            .capabilities()
+            .check_color_texture::<C>(width, height, false)?;
+        Ok(Self::new(context, width, height))
+    }
+
+    ///
+    /// Reallocates the color and depth textures if the given size is different from the current size.
+    /// Returns true if the textures were reallocated, in which case the content of the textures is lost.
+    ///
+    pub fn resize(&mut self, width: u32, height: u32) -> bool {
+        if self.width() == width && self.height() == height {
+            return false;
+        }
+        self.color = Self::new_color_texture(&self.context, width, height);
+        self.depth = Self::new_depth_texture(&self.context, width, height);
+        true
+    }
+
+    ///
+    /// Returns a [RenderTarget] writing to the color and depth textures after resizing them to the given size, see [SizedRenderTarget::resize].
+    ///
+    pub fn render_target(&mut self, width: u32, height: u32) -> RenderTarget<'_> {
+        self.resize(width, height);
+        RenderTarget::new(
+            self.color.as_color_target(None),
+            self.depth.as_depth_target(),
+        )
+    }
+
+    ///
+    /// Returns the color texture.
+    ///
+    pub fn color_texture(&self) -> &Texture2D {
+        &self.color
+    }
+
+    ///
+    /// Returns the depth texture.
+    ///
+    pub fn depth_texture(&self) -> &DepthTexture2D {
+        &self.depth
+    }
+
+    /// The width of this target.
+    pub fn width(&self) -> u32 {
+        self.color.width()
+    }
+
+    /// The height of this target.
+    pub fn height(&self) -> u32 {
+        self.color.height()
+    }
+
+    fn new_color_texture(context: &Context, width: u32, height: u32) -> Texture2D {
+        Texture2D::new_empty::<C>(
+            context,
+            width,
+            height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        )
+    }
+
+    fn new_depth_texture(context: &Context, width: u32, height: u32) -> DepthTexture2D {
+        DepthTexture2D::new::<D>(
+            context,
+            width,
+            height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        )
+    }
+}
+
+#[cfg(all(test, feature = "headless", not(target_arch = "wasm32")))]
+mod tests {
+    use crate::core::*;
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn resize_only_on_change() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let mut target = SizedRenderTarget::<[u8; 4], f32>::new(&context, 4, 4);
+        let texture_id = unsafe { target.color_texture().id() };
+
+        // The same size keeps the textures
+        assert!(!target.resize(4, 4));
+        target.render_target(4, 4);
+        assert!(unsafe { target.color_texture().id() } == texture_id);
+
+        // A different size reallocates the textures
+        assert!(target.resize(8, 2));
+        assert!(unsafe { target.color_texture().id() } != texture_id);
+        assert_eq!((target.width(), target.height()), (8, 2));
+        assert_eq!(
+            (
+                target.depth_texture().width(),
+                target.depth_texture().height()
+            ),
+            (8, 2)
+        );
+        let texture_id = unsafe { target.color_texture().id() };
+        target.render_target(8, 2);
+        assert!(unsafe { target.color_texture().id() } == texture_id);
+    }
+}
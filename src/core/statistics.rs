@@ -0,0 +1,81 @@
+use crate::core::*;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+///
+/// Statistics about the work submitted to the GPU since the statistics were last reset, see [Context::statistics].
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Statistics {
+    /// The number of draw calls.
+    pub draw_calls: u64,
+    /// The number of triangles submitted in the draw calls, including all instances.
+    pub triangles: u64,
+    /// The number of times data was uploaded to a buffer.
+    pub buffer_uploads: u64,
+    /// The number of times data was uploaded to a texture.
+    pub texture_uploads: u64,
+}
+
+#[derive(Default)]
+pub(super) struct StatisticsCounter {
+    enabled: AtomicBool,
+    draw_calls: AtomicU64,
+    triangles: AtomicU64,
+    buffer_uploads: AtomicU64,
+    texture_uploads: AtomicU64,
+}
+
+impl StatisticsCounter {
+    pub fn draw_call(&self, triangles: u64) {
+        if self.enabled.load(Ordering::Relaxed) {
+            self.draw_calls.fetch_add(1, Ordering::Relaxed);
+            self.triangles.fetch_add(triangles, Ordering::Relaxed);
+        }
+    }
+
+    pub fn buffer_upload(&self) {
+        if self.enabled.load(Ordering::Relaxed) {
+            self.buffer_uploads.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn texture_upload(&self) {
+        if self.enabled.load(Ordering::Relaxed) {
+            self.texture_uploads.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Context {
+    ///
+    /// Enables or disables counting the work submitted to the GPU, see [Context::statistics]. Disabled by default.
+    ///
+    pub fn enable_statistics(&self, enabled: bool) {
+        self.statistics.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    ///
+    /// Returns the statistics about the work submitted to the GPU since the statistics were last reset.
+    /// The statistics are only counted when enabled using [Context::enable_statistics].
+    /// When using the render loop in the [window](crate::window) module, the statistics are reset at the start of each frame,
+    /// so calling this method at the end of a frame returns the statistics for that frame.
+    ///
+    pub fn statistics(&self) -> Statistics {
+        Statistics {
+            draw_calls: self.statistics.draw_calls.load(Ordering::Relaxed),
+            triangles: self.statistics.triangles.load(Ordering::Relaxed),
+            buffer_uploads: self.statistics.buffer_uploads.load(Ordering::Relaxed),
+            texture_uploads: self.statistics.texture_uploads.load(Ordering::Relaxed),
+        }
+    }
+
+    ///
+    /// Resets the statistics, see [Context::statistics].
+    ///
+    pub fn reset_statistics(&self) {
+        self.statistics.draw_calls.store(0, Ordering::Relaxed);
+        self.statistics.triangles.store(0, Ordering::Relaxed);
+        self.statistics.buffer_uploads.store(0, Ordering::Relaxed);
+        self.statistics.texture_uploads.store(0, Ordering::Relaxed);
+    }
+}
@@ -37,6 +37,13 @@ mod depth_texture2d_multisample;
 #[doc(inline)]
 pub(in crate::core) use depth_texture2d_multisample::*;
 
+mod ktx2;
+use ktx2::*;
+
+mod cpu_texture_ext;
+#[doc(inline)]
+pub use cpu_texture_ext::*;
+
 use data_type::*;
 pub use three_d_asset::texture::{
     Interpolation, Mipmap, Texture2D as CpuTexture, Texture3D as CpuTexture3D, TextureData,
@@ -66,9 +73,16 @@ pub trait DepthTextureDataType: DepthDataType {}
 #[derive(Clone, Copy, Default, Debug)]
 pub struct f24 {}
 
+/// 24 bit float depth together with an 8 bit stencil value which can be used as [DepthTextureDataType].
+/// Use this data type to be able to use the stencil test (see [StencilTest]) when rendering into a depth texture.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Default, Debug)]
+pub struct f24s8 {}
+
 impl DepthTextureDataType for f16 {}
 impl DepthTextureDataType for f24 {}
 impl DepthTextureDataType for f32 {}
+impl DepthTextureDataType for f24s8 {}
 
 ///
 /// A reference to some type of texture containing colors.
@@ -278,15 +292,46 @@ fn generate(context: &Context) -> crate::context::Texture {
     unsafe { context.create_texture().expect("Failed creating texture") }
 }
 
-fn set_parameters(
+fn is_anisotropy_supported(context: &Context) -> bool {
+    let extensions = context.supported_extensions();
+    // Desktop
+    extensions.contains("GL_ARB_texture_filter_anisotropic") ||
+        extensions.contains("GL_EXT_texture_filter_anisotropic") ||
+        // Web
+        extensions.contains("EXT_texture_filter_anisotropic")
+}
+
+///
+/// Sets the maximum ratio of anisotropy of the currently bound texture, clamped to the range supported by the hardware,
+/// and returns the applied ratio which is 1 if anisotropic filtering is not supported.
+///
+fn set_anisotropy(context: &Context, target: u32, max_ratio: u32) -> u32 {
+    if !is_anisotropy_supported(context) {
+        return 1;
+    }
+    unsafe {
+        let max_ratio = max_ratio.clamp(
+            1,
+            (context.get_parameter_i32(crate::context::MAX_TEXTURE_MAX_ANISOTROPY_EXT) as u32)
+                .max(1),
+        );
+        context.tex_parameter_i32(
+            target,
+            crate::context::TEXTURE_MAX_ANISOTROPY_EXT,
+            max_ratio as i32,
+        );
+        max_ratio
+    }
+}
+
+///
+/// Sets the min filter of the currently bound texture, which depends on whether the texture is sampled using mip maps.
+///
+fn set_min_filter(
     context: &Context,
     target: u32,
     min_filter: Interpolation,
-    mag_filter: Interpolation,
     mipmap: Option<Mipmap>,
-    wrap_s: Wrapping,
-    wrap_t: Wrapping,
-    wrap_r: Option<Wrapping>,
 ) {
     unsafe {
         match mipmap {
@@ -333,24 +378,23 @@ fn set_parameters(
             }
             _ => panic!("Can only sample textures using 'NEAREST' or 'LINEAR' interpolation"),
         }
+    }
+}
+
+fn set_parameters(
+    context: &Context,
+    target: u32,
+    min_filter: Interpolation,
+    mag_filter: Interpolation,
+    mipmap: Option<Mipmap>,
+    wrap_s: Wrapping,
+    wrap_t: Wrapping,
+    wrap_r: Option<Wrapping>,
+) {
+    set_min_filter(context, target, min_filter, mipmap);
+    unsafe {
         if let Some(Mipmap { max_ratio, .. }) = mipmap {
-            let extensions = context.supported_extensions();
-            // Desktop
-            if extensions.contains("GL_ARB_texture_filter_anisotropic") ||
-                extensions.contains("GL_EXT_texture_filter_anisotropic") ||
-                // Web
-                extensions.contains("EXT_texture_filter_anisotropic")
-            {
-                let max_ratio = max_ratio.min(
-                    context.get_parameter_i32(crate::context::MAX_TEXTURE_MAX_ANISOTROPY_EXT)
-                        as u32,
-                );
-                context.tex_parameter_i32(
-                    target,
-                    crate::context::TEXTURE_MAX_ANISOTROPY_EXT,
-                    max_ratio as i32,
-                );
-            }
+            set_anisotropy(context, target, max_ratio);
         }
         context.tex_parameter_i32(
             target,
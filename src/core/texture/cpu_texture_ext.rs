@@ -0,0 +1,213 @@
+use crate::core::texture::*;
+
+///
+/// Extension methods for [CpuTexture] which modify the image data on the CPU, for example before uploading it to the GPU.
+///
+pub trait CpuTextureExt {
+    ///
+    /// Resizes the image to the given width and height, for example to limit the size of large textures before uploading them to the GPU.
+    /// If the filter is [Interpolation::Nearest], the closest pixel is used, otherwise the pixels are interpolated bilinearly
+    /// and averaged over the area covered by each new pixel, so that the image is not aliased when the size is reduced.
+    ///
+    /// # Panic
+    /// Will panic if the new width or height is zero.
+    ///
+    fn resize(&mut self, width: u32, height: u32, filter: Interpolation);
+
+    ///
+    /// Crops the image to the rectangle with the given size and with the top left corner at the given pixel coordinates.
+    ///
+    /// Returns an error and leaves the image unchanged if the rectangle is not inside the image.
+    ///
+    fn crop(&mut self, x: u32, y: u32, width: u32, height: u32) -> Result<(), CoreError>;
+}
+
+impl CpuTextureExt for CpuTexture {
+    fn resize(&mut self, width: u32, height: u32, filter: Interpolation) {
+        if width == 0 || height == 0 {
+            panic!("cannot resize a texture to a size of zero");
+        }
+        let (w, h) = (self.width, self.height);
+        match &mut self.data {
+            TextureData::RU8(data) => *data = resize_data(data, w, h, width, height, filter),
+            TextureData::RgU8(data) => *data = resize_data(data, w, h, width, height, filter),
+            TextureData::RgbU8(data) => *data = resize_data(data, w, h, width, height, filter),
+            TextureData::RgbaU8(data) => *data = resize_data(data, w, h, width, height, filter),
+            TextureData::RF16(data) => *data = resize_data(data, w, h, width, height, filter),
+            TextureData::RgF16(data) => *data = resize_data(data, w, h, width, height, filter),
+            TextureData::RgbF16(data) => *data = resize_data(data, w, h, width, height, filter),
+            TextureData::RgbaF16(data) => *data = resize_data(data, w, h, width, height, filter),
+            TextureData::RF32(data) => *data = resize_data(data, w, h, width, height, filter),
+            TextureData::RgF32(data) => *data = resize_data(data, w, h, width, height, filter),
+            TextureData::RgbF32(data) => *data = resize_data(data, w, h, width, height, filter),
+            TextureData::RgbaF32(data) => *data = resize_data(data, w, h, width, height, filter),
+        }
+        self.width = width;
+        self.height = height;
+    }
+
+    fn crop(&mut self, x: u32, y: u32, width: u32, height: u32) -> Result<(), CoreError> {
+        let inside = |start: u32, size: u32, max: u32| {
+            start
+                .checked_add(size)
+                .map(|end| end <= max)
+                .unwrap_or(false)
+        };
+        if !inside(x, width, self.width) || !inside(y, height, self.height) {
+            return Err(CoreError::CropOutsideTexture(
+                x,
+                y,
+                width,
+                height,
+                self.width,
+                self.height,
+            ));
+        }
+        let w = self.width;
+        match &mut self.data {
+            TextureData::RU8(data) => *data = crop_data(data, w, x, y, width, height),
+            TextureData::RgU8(data) => *data = crop_data(data, w, x, y, width, height),
+            TextureData::RgbU8(data) => *data = crop_data(data, w, x, y, width, height),
+            TextureData::RgbaU8(data) => *data = crop_data(data, w, x, y, width, height),
+            TextureData::RF16(data) => *data = crop_data(data, w, x, y, width, height),
+            TextureData::RgF16(data) => *data = crop_data(data, w, x, y, width, height),
+            TextureData::RgbF16(data) => *data = crop_data(data, w, x, y, width, height),
+            TextureData::RgbaF16(data) => *data = crop_data(data, w, x, y, width, height),
+            TextureData::RF32(data) => *data = crop_data(data, w, x, y, width, height),
+            TextureData::RgF32(data) => *data = crop_data(data, w, x, y, width, height),
+            TextureData::RgbF32(data) => *data = crop_data(data, w, x, y, width, height),
+            TextureData::RgbaF32(data) => *data = crop_data(data, w, x, y, width, height),
+        }
+        self.width = width;
+        self.height = height;
+        Ok(())
+    }
+}
+
+fn crop_data<P: Copy>(data: &[P], width: u32, x: u32, y: u32, w: u32, h: u32) -> Vec<P> {
+    (y..y + h)
+        .flat_map(|row| {
+            let start = row as usize * width as usize + x as usize;
+            data[start..start + w as usize].iter().copied()
+        })
+        .collect()
+}
+
+fn resize_data<P: Pixel>(
+    data: &[P],
+    width: u32,
+    height: u32,
+    new_width: u32,
+    new_height: u32,
+    filter: Interpolation,
+) -> Vec<P> {
+    let scale_x = width as f32 / new_width as f32;
+    let scale_y = height as f32 / new_height as f32;
+    let pixel = |x: u32, y: u32| data[(y.min(height - 1) * width + x.min(width - 1)) as usize];
+    // The number of samples in each direction needed to cover the area of a new pixel
+    let samples_x = scale_x.ceil().max(1.0) as u32;
+    let samples_y = scale_y.ceil().max(1.0) as u32;
+    let mut result = Vec::with_capacity((new_width * new_height) as usize);
+    for y in 0..new_height {
+        for x in 0..new_width {
+            if filter == Interpolation::Nearest {
+                result.push(pixel(
+                    ((x as f32 + 0.5) * scale_x) as u32,
+                    ((y as f32 + 0.5) * scale_y) as u32,
+                ));
+                continue;
+            }
+            let mut sum = [0.0; 4];
+            for j in 0..samples_y {
+                for i in 0..samples_x {
+                    let u = (x as f32 + (i as f32 + 0.5) / samples_x as f32) * scale_x - 0.5;
+                    let v = (y as f32 + (j as f32 + 0.5) / samples_y as f32) * scale_y - 0.5;
+                    let (u, v) = (u.max(0.0), v.max(0.0));
+                    let (x0, y0) = (u as u32, v as u32);
+                    let (tx, ty) = (u.fract(), v.fract());
+                    for (px, py, weight) in [
+                        (x0, y0, (1.0 - tx) * (1.0 - ty)),
+                        (x0 + 1, y0, tx * (1.0 - ty)),
+                        (x0, y0 + 1, (1.0 - tx) * ty),
+                        (x0 + 1, y0 + 1, tx * ty),
+                    ] {
+                        let values = pixel(px, py).to_array();
+                        for c in 0..4 {
+                            sum[c] += weight * values[c];
+                        }
+                    }
+                }
+            }
+            let count = (samples_x * samples_y) as f32;
+            result.push(P::from_array(sum.map(|s| s / count)));
+        }
+    }
+    result
+}
+
+trait Channel: Copy {
+    fn to_f32(self) -> f32;
+    fn from_f32(value: f32) -> Self;
+}
+
+impl Channel for u8 {
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+    fn from_f32(value: f32) -> Self {
+        value.round().clamp(0.0, 255.0) as u8
+    }
+}
+
+impl Channel for f16 {
+    fn to_f32(self) -> f32 {
+        f16::to_f32(self)
+    }
+    fn from_f32(value: f32) -> Self {
+        f16::from_f32(value)
+    }
+}
+
+impl Channel for f32 {
+    fn to_f32(self) -> f32 {
+        self
+    }
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+}
+
+trait Pixel: Copy {
+    fn to_array(self) -> [f32; 4];
+    fn from_array(values: [f32; 4]) -> Self;
+}
+
+macro_rules! impl_pixel {
+    ($channel:ty) => {
+        impl Pixel for $channel {
+            fn to_array(self) -> [f32; 4] {
+                [self.to_f32(), 0.0, 0.0, 0.0]
+            }
+            fn from_array(values: [f32; 4]) -> Self {
+                <$channel>::from_f32(values[0])
+            }
+        }
+
+        impl<const N: usize> Pixel for [$channel; N] {
+            fn to_array(self) -> [f32; 4] {
+                let mut values = [0.0; 4];
+                for (value, channel) in values.iter_mut().zip(self) {
+                    *value = channel.to_f32();
+                }
+                values
+            }
+            fn from_array(values: [f32; 4]) -> Self {
+                std::array::from_fn(|i| <$channel>::from_f32(values[i]))
+            }
+        }
+    };
+}
+
+impl_pixel!(u8);
+impl_pixel!(f16);
+impl_pixel!(f32);
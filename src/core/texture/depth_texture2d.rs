@@ -8,6 +8,7 @@ pub struct DepthTexture2D {
     id: crate::context::Texture,
     width: u32,
     height: u32,
+    attachment: u32,
 }
 
 impl DepthTexture2D {
@@ -27,6 +28,7 @@ impl DepthTexture2D {
             id,
             width,
             height,
+            attachment: T::attachment(),
         };
         texture.bind();
         set_parameters(
@@ -73,7 +75,7 @@ impl DepthTexture2D {
         unsafe {
             self.context.framebuffer_texture_2d(
                 crate::context::FRAMEBUFFER,
-                crate::context::DEPTH_ATTACHMENT,
+                self.attachment,
                 crate::context::TEXTURE_2D,
                 Some(self.id),
                 0,
@@ -9,6 +9,7 @@ pub struct DepthTexture2DArray {
     width: u32,
     height: u32,
     depth: u32,
+    attachment: u32,
 }
 
 impl DepthTexture2DArray {
@@ -30,6 +31,7 @@ impl DepthTexture2DArray {
             width,
             height,
             depth,
+            attachment: T::attachment(),
         };
         texture.bind();
         set_parameters(
@@ -82,7 +84,7 @@ impl DepthTexture2DArray {
         unsafe {
             self.context.framebuffer_texture_layer(
                 crate::context::DRAW_FRAMEBUFFER,
-                crate::context::DEPTH_ATTACHMENT,
+                self.attachment,
                 Some(self.id),
                 0,
                 layer as i32,
@@ -6,6 +6,7 @@ pub struct DepthTexture2DMultisample {
     width: u32,
     height: u32,
     number_of_samples: u32,
+    attachment: u32,
 }
 
 impl DepthTexture2DMultisample {
@@ -26,6 +27,7 @@ impl DepthTexture2DMultisample {
             width,
             height,
             number_of_samples,
+            attachment: T::attachment(),
         };
         texture.bind();
         // CHECK: Omitted `set_parameters` since neither filtering, nor mipmap levels, nor clamping makes sense for multisampled textures.
@@ -60,7 +62,7 @@ impl DepthTexture2DMultisample {
         unsafe {
             self.context.framebuffer_renderbuffer(
                 crate::context::FRAMEBUFFER,
-                crate::context::DEPTH_ATTACHMENT,
+                self.attachment,
                 crate::context::RENDERBUFFER,
                 Some(self.id),
             );
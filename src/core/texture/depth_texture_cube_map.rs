@@ -8,6 +8,7 @@ pub struct DepthTextureCubeMap {
     id: crate::context::Texture,
     width: u32,
     height: u32,
+    attachment: u32,
 }
 
 impl DepthTextureCubeMap {
@@ -28,6 +29,7 @@ impl DepthTextureCubeMap {
             id,
             width,
             height,
+            attachment: T::attachment(),
         };
         texture.bind();
         set_parameters(
@@ -74,7 +76,7 @@ impl DepthTextureCubeMap {
         unsafe {
             self.context.framebuffer_texture_2d(
                 crate::context::DRAW_FRAMEBUFFER,
-                crate::context::DEPTH_ATTACHMENT,
+                self.attachment,
                 side.to_const(),
                 Some(self.id),
                 0,
@@ -0,0 +1,460 @@
+use crate::core::*;
+use std::borrow::Cow;
+
+mod basis;
+mod decode;
+use decode::*;
+
+const IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+
+// Not defined in the context module since they are extension constants
+const COMPRESSED_RGB_S3TC_DXT1: u32 = 0x83F0;
+const COMPRESSED_RGBA_S3TC_DXT1: u32 = 0x83F1;
+const COMPRESSED_RGBA_S3TC_DXT3: u32 = 0x83F2;
+const COMPRESSED_RGBA_S3TC_DXT5: u32 = 0x83F3;
+const COMPRESSED_SRGB_S3TC_DXT1: u32 = 0x8C4C;
+const COMPRESSED_SRGB_ALPHA_S3TC_DXT1: u32 = 0x8C4D;
+const COMPRESSED_SRGB_ALPHA_S3TC_DXT3: u32 = 0x8C4E;
+const COMPRESSED_SRGB_ALPHA_S3TC_DXT5: u32 = 0x8C4F;
+const COMPRESSED_RED_RGTC1: u32 = 0x8DBB;
+const COMPRESSED_SIGNED_RED_RGTC1: u32 = 0x8DBC;
+const COMPRESSED_RG_RGTC2: u32 = 0x8DBD;
+const COMPRESSED_SIGNED_RG_RGTC2: u32 = 0x8DBE;
+const COMPRESSED_RGBA_BPTC_UNORM: u32 = 0x8E8C;
+const COMPRESSED_SRGB_ALPHA_BPTC_UNORM: u32 = 0x8E8D;
+const COMPRESSED_RGB_BPTC_SIGNED_FLOAT: u32 = 0x8E8E;
+const COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT: u32 = 0x8E8F;
+const COMPRESSED_R11_EAC: u32 = 0x9270;
+const COMPRESSED_RG11_EAC: u32 = 0x9272;
+const COMPRESSED_RGB8_ETC2: u32 = 0x9274;
+const COMPRESSED_SRGB8_ETC2: u32 = 0x9275;
+const COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2: u32 = 0x9276;
+const COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2: u32 = 0x9277;
+const COMPRESSED_RGBA8_ETC2_EAC: u32 = 0x9278;
+const COMPRESSED_SRGB8_ALPHA8_ETC2_EAC: u32 = 0x9279;
+const COMPRESSED_RGBA_ASTC_4X4: u32 = 0x93B0;
+const COMPRESSED_SRGB8_ALPHA8_ASTC_4X4: u32 = 0x93D0;
+
+/// The largest width and height that is accepted, which is at least the maximum texture size of any current device.
+/// This also limits the size of the data which is decoded or transcoded from a KTX2 file with a corrupt header.
+const MAX_SIZE: u32 = 1 << 15;
+
+const SUPERCOMPRESSION_BASIS_LZ: u32 = 1;
+const COLOR_MODEL_ETC1S: u8 = 163;
+const TRANSFER_FUNCTION_SRGB: u8 = 2;
+
+///
+/// The image data of a KTX2 file, see [Texture2D::new_ktx2].
+///
+pub(in crate::core) struct Ktx2<'a> {
+    pub width: u32,
+    pub height: u32,
+    /// The internal format of the texture and whether or not it is compressed.
+    pub internal_format: u32,
+    pub compressed: bool,
+    /// The data of each mip level, starting with the 0 level mip level.
+    pub levels: Vec<Cow<'a, [u8]>>,
+}
+
+impl<'a> Ktx2<'a> {
+    ///
+    /// Returns whether or not the given data starts with the KTX2 file identifier.
+    ///
+    pub fn is_ktx2(bytes: &[u8]) -> bool {
+        bytes.starts_with(&IDENTIFIER)
+    }
+
+    ///
+    /// Parses the given KTX2 data. The given function returns whether or not a compressed format is supported,
+    /// which is used to choose the format that Basis Universal data is transcoded to.
+    ///
+    pub fn parse(
+        bytes: &'a [u8],
+        is_format_supported: impl Fn(u32) -> bool,
+    ) -> Result<Self, CoreError> {
+        if bytes.len() < 80 || bytes[..12] != IDENTIFIER {
+            return Err(CoreError::Ktx2Parsing(
+                "the data is not a KTX2 file".to_string(),
+            ));
+        }
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+        let vk_format = u32_at(12);
+        let width = u32_at(20);
+        let height = u32_at(24);
+        let depth = u32_at(28);
+        let layer_count = u32_at(32);
+        let face_count = u32_at(36);
+        let level_count = u32_at(40).max(1);
+        let supercompression_scheme = u32_at(44);
+
+        if supercompression_scheme != 0 && supercompression_scheme != SUPERCOMPRESSION_BASIS_LZ
+            || supercompression_scheme == 0 && vk_format == 0
+        {
+            return Err(CoreError::UnsupportedTexture(
+                "KTX2 files with UASTC or Zstandard supercompression are not supported, transcode the file to ETC1S or a GPU format like BC7, ETC2 or ASTC instead".to_string(),
+            ));
+        }
+        if level_count > 32 {
+            return Err(CoreError::Ktx2Parsing(format!(
+                "the number of mip levels {} is invalid",
+                level_count
+            )));
+        }
+        if depth > 1 || layer_count > 1 || face_count > 1 {
+            return Err(CoreError::UnsupportedTexture(
+                "only KTX2 files containing a single 2D texture are supported".to_string(),
+            ));
+        }
+        // A height of zero specifies a 1D texture
+        if width == 0 || height == 0 {
+            return Err(CoreError::Ktx2Parsing(format!(
+                "the size {}x{} is invalid for a 2D texture",
+                width, height
+            )));
+        }
+        if width > MAX_SIZE || height > MAX_SIZE {
+            return Err(CoreError::UnsupportedTexture(format!(
+                "the size {}x{} is larger than the maximum texture size {}",
+                width, height, MAX_SIZE
+            )));
+        }
+
+        let mut levels = Vec::new();
+        for level in 0..level_count as usize {
+            let index = 80 + level * 24;
+            if bytes.len() < index + 16 {
+                return Err(CoreError::Ktx2Parsing(
+                    "the level index is out of bounds".to_string(),
+                ));
+            }
+            let offset = u64_at(index);
+            let length = u64_at(index + 8);
+            let data = offset
+                .checked_add(length)
+                .and_then(|end| {
+                    bytes.get(usize::try_from(offset).ok()?..usize::try_from(end).ok()?)
+                })
+                .ok_or_else(|| {
+                    CoreError::Ktx2Parsing(format!(
+                        "the data of mip level {} is out of bounds",
+                        level
+                    ))
+                })?;
+            levels.push(Cow::Borrowed(data));
+        }
+
+        if supercompression_scheme == SUPERCOMPRESSION_BASIS_LZ {
+            return Self::transcode_basis_lz(bytes, width, height, levels, is_format_supported);
+        }
+        let (internal_format, compressed) =
+            internal_format_from_vk_format(vk_format).ok_or_else(|| {
+                CoreError::UnsupportedTexture(format!(
+                    "the KTX2 format with vkFormat {} is not supported",
+                    vk_format
+                ))
+            })?;
+        Ok(Self {
+            width,
+            height,
+            internal_format,
+            compressed,
+            levels,
+        })
+    }
+
+    ///
+    /// Transcodes the levels of a Basis Universal ETC1S file, which are supercompressed with BasisLZ, to BC1 or BC3 if the file has an alpha channel.
+    /// If those formats are not supported, the levels are instead transcoded to ETC2 or RGBA8 if the file has an alpha channel.
+    ///
+    fn transcode_basis_lz(
+        bytes: &[u8],
+        width: u32,
+        height: u32,
+        levels: Vec<Cow<'a, [u8]>>,
+        is_format_supported: impl Fn(u32) -> bool,
+    ) -> Result<Self, CoreError> {
+        let u32_at = |offset: usize| {
+            bytes
+                .get(offset..offset + 4)
+                .map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize)
+        };
+        let u64_at = |offset: usize| {
+            bytes
+                .get(offset..offset + 8)
+                .and_then(|b| usize::try_from(u64::from_le_bytes(b.try_into().unwrap())).ok())
+        };
+        let out_of_bounds =
+            || CoreError::Ktx2Parsing("the BasisLZ data is out of bounds".to_string());
+
+        // The color model and transfer function are stored in the basic data format descriptor block
+        let descriptor = u32_at(48).ok_or_else(out_of_bounds)?;
+        let descriptor = bytes
+            .get(descriptor..)
+            .filter(|descriptor| descriptor.len() >= 16)
+            .ok_or_else(out_of_bounds)?;
+        let (color_model, transfer_function) = (descriptor[12], descriptor[14]);
+        if color_model != COLOR_MODEL_ETC1S {
+            return Err(CoreError::UnsupportedTexture(
+                "only KTX2 files with BasisLZ supercompressed ETC1S data are supported".to_string(),
+            ));
+        }
+        let global_data_offset = u64_at(64).ok_or_else(out_of_bounds)?;
+        let global_data = u64_at(72)
+            .and_then(|length| {
+                bytes.get(global_data_offset..global_data_offset.checked_add(length)?)
+            })
+            .ok_or_else(out_of_bounds)?;
+
+        // Each level has an image description after the 20 byte header of the global data
+        let slices = levels
+            .iter()
+            .enumerate()
+            .map(|(level, data)| {
+                let image = 20 + level * 20;
+                let field = |index: usize| {
+                    global_data
+                        .get(image + index * 4..image + index * 4 + 4)
+                        .map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize)
+                };
+                let slice = |offset: usize, length: usize| {
+                    data.get(field(offset)?..field(offset)?.checked_add(field(length)?)?)
+                };
+                Ok(basis::Slices {
+                    rgb: slice(1, 2).ok_or_else(out_of_bounds)?,
+                    alpha: slice(3, 4).ok_or_else(out_of_bounds)?,
+                })
+            })
+            .collect::<Result<Vec<_>, CoreError>>()?;
+        let has_alpha = slices.iter().any(|slices| !slices.alpha.is_empty());
+        let srgb = transfer_function == TRANSFER_FUNCTION_SRGB;
+        let bc_format = match (has_alpha, srgb) {
+            (false, false) => COMPRESSED_RGB_S3TC_DXT1,
+            (false, true) => COMPRESSED_SRGB_S3TC_DXT1,
+            (true, false) => COMPRESSED_RGBA_S3TC_DXT5,
+            (true, true) => COMPRESSED_SRGB_ALPHA_S3TC_DXT5,
+        };
+        let target = if is_format_supported(bc_format) {
+            basis::Target::Bc
+        } else {
+            basis::Target::Etc
+        };
+        let levels = basis::transcode_etc1s(global_data, &slices, width, height, target)?;
+        Ok(Self {
+            width,
+            height,
+            internal_format: match (target, has_alpha, srgb) {
+                (basis::Target::Bc, _, _) => bc_format,
+                (basis::Target::Etc, false, false) => COMPRESSED_RGB8_ETC2,
+                (basis::Target::Etc, false, true) => COMPRESSED_SRGB8_ETC2,
+                (basis::Target::Etc, true, false) => crate::context::RGBA8,
+                (basis::Target::Etc, true, true) => crate::context::SRGB8_ALPHA8,
+            },
+            compressed: target == basis::Target::Bc || !has_alpha,
+            levels: levels.into_iter().map(Cow::Owned).collect(),
+        })
+    }
+
+    ///
+    /// Returns whether or not the format of this texture is supported by the given context.
+    ///
+    pub fn is_supported(&self, context: &Context) -> bool {
+        !self.compressed || Self::is_format_supported(context, self.internal_format)
+    }
+
+    ///
+    /// Returns whether or not the given compressed format is supported by the given context.
+    ///
+    pub fn is_format_supported(context: &Context, internal_format: u32) -> bool {
+        let extensions = context.supported_extensions();
+        let has_extension = |names: &[&str]| {
+            names.iter().any(|name| {
+                extensions.contains(*name) || extensions.contains(&format!("GL_{}", name))
+            })
+        };
+        let is_embedded = context.version().is_embedded;
+        match internal_format {
+            COMPRESSED_RGB_S3TC_DXT1..=COMPRESSED_RGBA_S3TC_DXT5 => has_extension(&[
+                "EXT_texture_compression_s3tc",
+                "WEBGL_compressed_texture_s3tc",
+            ]),
+            COMPRESSED_SRGB_S3TC_DXT1..=COMPRESSED_SRGB_ALPHA_S3TC_DXT5 => {
+                has_extension(&["EXT_texture_sRGB", "WEBGL_compressed_texture_s3tc_srgb"])
+            }
+            COMPRESSED_RED_RGTC1..=COMPRESSED_SIGNED_RG_RGTC2 => {
+                !is_embedded || has_extension(&["EXT_texture_compression_rgtc"])
+            }
+            COMPRESSED_RGBA_BPTC_UNORM..=COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT => has_extension(&[
+                "ARB_texture_compression_bptc",
+                "EXT_texture_compression_bptc",
+            ]),
+            // ETC2 and EAC are part of OpenGL ES 3.0, but not WebGL 2
+            COMPRESSED_R11_EAC..=COMPRESSED_SRGB8_ALPHA8_ETC2_EAC => {
+                has_extension(&["ARB_ES3_compatibility", "WEBGL_compressed_texture_etc"])
+                    || (is_embedded && !cfg!(target_arch = "wasm32"))
+            }
+            _ => has_extension(&[
+                "KHR_texture_compression_astc_ldr",
+                "WEBGL_compressed_texture_astc",
+            ]),
+        }
+    }
+
+    ///
+    /// Decodes the compressed data into RGBA8 data, which is used as a fallback when the compressed format is not supported by the device.
+    /// Returns an error if there is no software decoder for the format, which is the case for BPTC and ASTC.
+    ///
+    pub fn decode(self) -> Result<Self, CoreError> {
+        if !self.compressed {
+            return Ok(self);
+        }
+        let levels = self
+            .levels
+            .iter()
+            .enumerate()
+            .map(|(level, data)| {
+                decode_to_rgba(
+                    self.internal_format,
+                    (self.width >> level).max(1),
+                    (self.height >> level).max(1),
+                    data,
+                )
+                .map(Cow::Owned)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            internal_format: if is_srgb(self.internal_format) {
+                crate::context::SRGB8_ALPHA8
+            } else {
+                crate::context::RGBA8
+            },
+            compressed: false,
+            levels,
+            ..self
+        })
+    }
+}
+
+///
+/// Returns the OpenGL internal format and whether or not it is compressed from the given Vulkan format, which is used in KTX2 files.
+///
+fn internal_format_from_vk_format(vk_format: u32) -> Option<(u32, bool)> {
+    let compressed = |format| Some((format, true));
+    match vk_format {
+        37 => Some((crate::context::RGBA8, false)),
+        43 => Some((crate::context::SRGB8_ALPHA8, false)),
+        131 => compressed(COMPRESSED_RGB_S3TC_DXT1),
+        132 => compressed(COMPRESSED_SRGB_S3TC_DXT1),
+        133 => compressed(COMPRESSED_RGBA_S3TC_DXT1),
+        134 => compressed(COMPRESSED_SRGB_ALPHA_S3TC_DXT1),
+        135 => compressed(COMPRESSED_RGBA_S3TC_DXT3),
+        136 => compressed(COMPRESSED_SRGB_ALPHA_S3TC_DXT3),
+        137 => compressed(COMPRESSED_RGBA_S3TC_DXT5),
+        138 => compressed(COMPRESSED_SRGB_ALPHA_S3TC_DXT5),
+        139 => compressed(COMPRESSED_RED_RGTC1),
+        140 => compressed(COMPRESSED_SIGNED_RED_RGTC1),
+        141 => compressed(COMPRESSED_RG_RGTC2),
+        142 => compressed(COMPRESSED_SIGNED_RG_RGTC2),
+        143 => compressed(COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT),
+        144 => compressed(COMPRESSED_RGB_BPTC_SIGNED_FLOAT),
+        145 => compressed(COMPRESSED_RGBA_BPTC_UNORM),
+        146 => compressed(COMPRESSED_SRGB_ALPHA_BPTC_UNORM),
+        // ETC2 RGB, RGB with punch-through alpha and RGBA with unorm and sRGB variants
+        147..=152 => compressed(COMPRESSED_RGB8_ETC2 + vk_format - 147),
+        // EAC R11 and RG11 with unsigned and signed variants
+        153..=156 => compressed(COMPRESSED_R11_EAC + vk_format - 153),
+        // ASTC with block sizes from 4x4 to 12x12, alternating between unorm and sRGB
+        157..=184 => {
+            let block = (vk_format - 157) / 2;
+            if (vk_format - 157) % 2 == 1 {
+                compressed(COMPRESSED_SRGB8_ALPHA8_ASTC_4X4 + block)
+            } else {
+                compressed(COMPRESSED_RGBA_ASTC_4X4 + block)
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(vk_format: u32, level_count: u32) -> Vec<u8> {
+        let mut bytes = IDENTIFIER.to_vec();
+        for value in [vk_format, 1, 4, 4, 0, 0, 1, level_count, 0] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.resize(80, 0);
+        bytes
+    }
+
+    #[test]
+    fn parse_level_out_of_bounds() {
+        let mut bytes = header(131, 1);
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+        bytes.extend_from_slice(&2u64.to_le_bytes());
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        assert!(matches!(
+            Ktx2::parse(&bytes, |_| false),
+            Err(CoreError::Ktx2Parsing(_))
+        ));
+    }
+
+    /// Returns a 4x4 sRGB BC1 file with a single red block.
+    fn bc1_file() -> Vec<u8> {
+        let mut bytes = header(132, 1);
+        bytes.extend_from_slice(&104u64.to_le_bytes());
+        bytes.extend_from_slice(&8u64.to_le_bytes());
+        bytes.extend_from_slice(&8u64.to_le_bytes());
+        bytes.extend_from_slice(&[0x00, 0xF8, 0x00, 0xF8, 0, 0, 0, 0]);
+        bytes
+    }
+
+    #[test]
+    fn parse_truncated() {
+        let bytes = bc1_file();
+        for length in 0..bytes.len() {
+            assert!(Ktx2::parse(&bytes[..length], |_| false).is_err());
+        }
+    }
+
+    #[test]
+    fn parse_zero_size() {
+        for offset in [20, 24] {
+            let mut bytes = bc1_file();
+            bytes[offset..offset + 4].copy_from_slice(&[0; 4]);
+            assert!(matches!(
+                Ktx2::parse(&bytes, |_| false),
+                Err(CoreError::Ktx2Parsing(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn parse_too_large() {
+        let mut bytes = bc1_file();
+        bytes[20..28].copy_from_slice(&[0xFF; 8]);
+        assert!(matches!(
+            Ktx2::parse(&bytes, |_| false),
+            Err(CoreError::UnsupportedTexture(_))
+        ));
+    }
+
+    #[test]
+    fn parse_and_decode() {
+        let bytes = bc1_file();
+        let ktx2 = Ktx2::parse(&bytes, |_| false).unwrap();
+        assert_eq!(ktx2.internal_format, COMPRESSED_SRGB_S3TC_DXT1);
+        assert!(ktx2.compressed);
+        let ktx2 = ktx2.decode().unwrap();
+        assert_eq!(ktx2.internal_format, crate::context::SRGB8_ALPHA8);
+        assert!(!ktx2.compressed);
+        assert_eq!(ktx2.levels[0].len(), 4 * 4 * 4);
+        assert_eq!(ktx2.levels[0][..4], [255, 0, 0, 255]);
+    }
+}
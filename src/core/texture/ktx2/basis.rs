@@ -0,0 +1,1131 @@
+use super::*;
+
+/// The intensity modifiers of ETC1S blocks ordered from the most negative to the most positive modifier, which is the order of the selector values in Basis Universal.
+const INTENSITY_MODIFIERS: [[i32; 4]; 8] = [
+    [-8, -2, 2, 8],
+    [-17, -5, 5, 17],
+    [-29, -9, 9, 29],
+    [-42, -13, 13, 42],
+    [-60, -18, 18, 60],
+    [-80, -24, 24, 80],
+    [-106, -33, 33, 106],
+    [-183, -47, 47, 183],
+];
+
+/// The ETC1 pixel index of each Basis Universal selector value.
+const ETC1_PIXEL_INDICES: [u32; 4] = [3, 2, 0, 1];
+
+/// The order in which the code lengths of the code length table are stored.
+const CODE_LENGTH_ORDER: [usize; 21] = [
+    17, 18, 19, 20, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15, 16,
+];
+
+const MAX_CODE_LENGTH: usize = 16;
+const MAX_SELECTOR_HISTORY_SIZE: usize = 4096;
+
+///
+/// The compressed slices of a single mip level of a BasisLZ supercompressed KTX2 file.
+/// The alpha slice is empty if the texture has no alpha channel.
+///
+pub(super) struct Slices<'a> {
+    pub rgb: &'a [u8],
+    pub alpha: &'a [u8],
+}
+
+///
+/// The format which the ETC1S data is transcoded to.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Target {
+    /// ETC1 blocks, which are stored as ETC2 since ETC2 is a superset of ETC1, or RGBA8 data if the texture has an alpha channel.
+    Etc,
+    /// BC1 blocks or BC3 blocks if the texture has an alpha channel.
+    Bc,
+}
+
+///
+/// Transcodes the mip levels of a BasisLZ supercompressed KTX2 file, which uses the ETC1S format, given the supercompression global data.
+///
+pub(super) fn transcode_etc1s(
+    global_data: &[u8],
+    levels: &[Slices],
+    width: u32,
+    height: u32,
+    target: Target,
+) -> Result<Vec<Vec<u8>>, CoreError> {
+    let error = |message: &str| CoreError::Ktx2Parsing(message.to_string());
+    if global_data.len() < 20 {
+        return Err(error("the BasisLZ global data is too small"));
+    }
+    let u16_at = |offset: usize| u16::from_le_bytes([global_data[offset], global_data[offset + 1]]);
+    let u32_at = |offset: usize| {
+        u32::from_le_bytes(global_data[offset..offset + 4].try_into().unwrap()) as usize
+    };
+    let endpoint_count = u16_at(0) as usize;
+    let selector_count = u16_at(2) as usize;
+    let endpoints_start = 20 + levels.len() * 20;
+    let out_of_bounds = || error("the BasisLZ global data is out of bounds");
+    let selectors_start = endpoints_start
+        .checked_add(u32_at(4))
+        .ok_or_else(out_of_bounds)?;
+    let tables_start = selectors_start
+        .checked_add(u32_at(8))
+        .ok_or_else(out_of_bounds)?;
+    let tables_end = tables_start
+        .checked_add(u32_at(12))
+        .ok_or_else(out_of_bounds)?;
+    if global_data.len() < tables_end {
+        return Err(out_of_bounds());
+    }
+    if endpoint_count == 0 || selector_count == 0 {
+        return Err(error("the BasisLZ codebooks are empty"));
+    }
+
+    let endpoints = decode_endpoints(
+        &global_data[endpoints_start..selectors_start],
+        endpoint_count,
+    )?;
+    let selectors = decode_selectors(&global_data[selectors_start..tables_start], selector_count)?;
+    let tables = Tables::decode(&global_data[tables_start..tables_end])?;
+
+    levels
+        .iter()
+        .enumerate()
+        .map(|(level, slices)| {
+            let width = (width >> level).max(1) as usize;
+            let height = (height >> level).max(1) as usize;
+            let blocks_x = width.div_ceil(4);
+            let blocks_y = height.div_ceil(4);
+            let rgb =
+                tables.decode_slice(slices.rgb, blocks_x, blocks_y, &endpoints, &selectors)?;
+            if slices.alpha.is_empty() {
+                return Ok(rgb
+                    .iter()
+                    .flat_map(|&(endpoint, selector)| match target {
+                        Target::Etc => etc1_block(&endpoints[endpoint], &selectors[selector]),
+                        Target::Bc => bc1_block(&endpoints[endpoint], &selectors[selector]),
+                    })
+                    .collect());
+            }
+            let alpha =
+                tables.decode_slice(slices.alpha, blocks_x, blocks_y, &endpoints, &selectors)?;
+            if target == Target::Bc {
+                return Ok(rgb
+                    .iter()
+                    .zip(alpha.iter())
+                    .flat_map(
+                        |(&(rgb_endpoint, rgb_selector), &(alpha_endpoint, alpha_selector))| {
+                            let alpha =
+                                bc4_block(&endpoints[alpha_endpoint], &selectors[alpha_selector]);
+                            let color =
+                                bc1_block(&endpoints[rgb_endpoint], &selectors[rgb_selector]);
+                            alpha.into_iter().chain(color)
+                        },
+                    )
+                    .collect());
+            }
+            let mut rgba = vec![0; width * height * 4];
+            for (block, (&(rgb_endpoint, rgb_selector), &(alpha_endpoint, alpha_selector))) in
+                rgb.iter().zip(alpha.iter()).enumerate()
+            {
+                let rows = selectors[rgb_selector]
+                    .iter()
+                    .zip(&selectors[alpha_selector]);
+                for (y, (rgb_row, alpha_row)) in rows.enumerate() {
+                    for (x, (&rgb_value, &alpha_value)) in rgb_row.iter().zip(alpha_row).enumerate()
+                    {
+                        let pixel_x = (block % blocks_x) * 4 + x;
+                        let pixel_y = (block / blocks_x) * 4 + y;
+                        if pixel_x < width && pixel_y < height {
+                            let color = endpoints[rgb_endpoint].color(rgb_value);
+                            let alpha = endpoints[alpha_endpoint].color(alpha_value);
+                            let index = (pixel_y * width + pixel_x) * 4;
+                            rgba[index..index + 4]
+                                .copy_from_slice(&[color[0], color[1], color[2], alpha[1]]);
+                        }
+                    }
+                }
+            }
+            Ok(rgba)
+        })
+        .collect()
+}
+
+/// The selector values of a block in row-major order.
+type Selector = [[u8; 4]; 4];
+
+struct Endpoint {
+    color: [u8; 3],
+    intensity: usize,
+}
+
+impl Endpoint {
+    fn color(&self, selector: u8) -> [u8; 3] {
+        let modifier = INTENSITY_MODIFIERS[self.intensity][selector as usize];
+        self.color
+            .map(|c| ((((c << 3) | (c >> 2)) as i32) + modifier).clamp(0, 255) as u8)
+    }
+}
+
+fn etc1_block(endpoint: &Endpoint, selector: &Selector) -> [u8; 8] {
+    let mut msb = 0u32;
+    let mut lsb = 0u32;
+    for (y, row) in selector.iter().enumerate() {
+        for (x, &value) in row.iter().enumerate() {
+            // The pixels are stored in column-major order
+            let pixel = x * 4 + y;
+            let index = ETC1_PIXEL_INDICES[value as usize];
+            msb |= (index >> 1) << pixel;
+            lsb |= (index & 1) << pixel;
+        }
+    }
+    let intensity = endpoint.intensity as u8;
+    let [r, g, b] = endpoint.color;
+    // Differential mode with zero delta and without flip, so both sub-blocks use the same color and intensity
+    let low = ((msb << 16) | lsb).to_be_bytes();
+    [
+        r << 3,
+        g << 3,
+        b << 3,
+        (intensity << 5) | (intensity << 2) | 0b10,
+        low[0],
+        low[1],
+        low[2],
+        low[3],
+    ]
+}
+
+///
+/// Returns a BC1 block which approximates the given ETC1S block.
+/// The colors of an ETC1S block are on a line from the darkest to the brightest color, except where they are clamped,
+/// so each ETC1S color is assigned to one of the four colors of the BC1 block in the same order and the end points are fitted to the colors used by the pixels with the least squares method.
+/// The block with the smallest error of all the possible assignments is returned.
+///
+fn bc1_block(endpoint: &Endpoint, selector: &Selector) -> [u8; 8] {
+    let colors: [[u8; 3]; 4] = std::array::from_fn(|value| endpoint.color(value as u8));
+    let mut weights = [0u32; 4];
+    selector
+        .iter()
+        .flatten()
+        .for_each(|&value| weights[value as usize] += 1);
+
+    let mut best = (u32::MAX, [0; 8]);
+    for p0 in 0..4 {
+        for p1 in p0..4 {
+            for p2 in p1..4 {
+                for p3 in p2..4 {
+                    let positions = [p0, p1, p2, p3].map(|p| p as f32 / 3.0);
+                    let block = bc1_block_with_end_points(
+                        fit_end_points(&colors, &weights, &positions),
+                        &colors,
+                        selector,
+                    );
+                    if block.0 < best.0 {
+                        best = block;
+                    }
+                }
+            }
+        }
+    }
+    best.1
+}
+
+///
+/// Returns the end points of a line where the given colors with the given weights are closest to the given positions on the line from the first to the second end point.
+///
+fn fit_end_points(
+    colors: &[[u8; 3]; 4],
+    weights: &[u32; 4],
+    positions: &[f32; 4],
+) -> [[f32; 3]; 2] {
+    let sum = |f: &dyn Fn(usize) -> f32| (0..4).map(|i| weights[i] as f32 * f(i)).sum::<f32>();
+    let a = sum(&|i| (1.0 - positions[i]).powi(2));
+    let b = sum(&|i| positions[i] * (1.0 - positions[i]));
+    let c = sum(&|i| positions[i].powi(2));
+    let determinant = a * c - b * b;
+    if determinant < 1e-3 {
+        // All colors are at the same position, so both end points are the average color
+        let mean =
+            std::array::from_fn(|channel| sum(&|i| colors[i][channel] as f32) / sum(&|_| 1.0));
+        return [mean; 2];
+    }
+    let x: [f32; 3] =
+        std::array::from_fn(|channel| sum(&|i| (1.0 - positions[i]) * colors[i][channel] as f32));
+    let y: [f32; 3] =
+        std::array::from_fn(|channel| sum(&|i| positions[i] * colors[i][channel] as f32));
+    [
+        std::array::from_fn(|channel| (c * x[channel] - b * y[channel]) / determinant),
+        std::array::from_fn(|channel| (a * y[channel] - b * x[channel]) / determinant),
+    ]
+}
+
+///
+/// Returns the squared error and the BC1 block with the given end points where each pixel uses the closest color in the BC1 palette.
+///
+fn bc1_block_with_end_points(
+    end_points: [[f32; 3]; 2],
+    colors: &[[u8; 3]; 4],
+    selector: &Selector,
+) -> (u32, [u8; 8]) {
+    let to_565 = |color: [f32; 3]| {
+        let [r, g, b] = color.map(|c| c.clamp(0.0, 255.0));
+        (((r * 31.0 / 255.0).round() as u16) << 11)
+            | (((g * 63.0 / 255.0).round() as u16) << 5)
+            | (b * 31.0 / 255.0).round() as u16
+    };
+    // The first end point must be the largest to get four colors instead of three colors and black
+    let (low, high) = (to_565(end_points[0]), to_565(end_points[1]));
+    let (color0, color1) = (low.max(high), low.min(high));
+    let palette = bc1_palette(color0, color1, false, false);
+    let error = |index: usize, value: usize| {
+        (0..3)
+            .map(|c| (palette[index][c] as i32 - colors[value][c] as i32).pow(2) as u32)
+            .sum::<u32>()
+    };
+    let closest: [usize; 4] =
+        std::array::from_fn(|value| (0..4).min_by_key(|&index| error(index, value)).unwrap());
+    let mut total_error = 0;
+    let mut indices = 0u32;
+    for (y, row) in selector.iter().enumerate() {
+        for (x, &value) in row.iter().enumerate() {
+            let index = closest[value as usize];
+            total_error += error(index, value as usize);
+            indices |= (index as u32) << (2 * (y * 4 + x));
+        }
+    }
+    let mut block = [0; 8];
+    block[..2].copy_from_slice(&color0.to_le_bytes());
+    block[2..4].copy_from_slice(&color1.to_le_bytes());
+    block[4..].copy_from_slice(&indices.to_le_bytes());
+    (total_error, block)
+}
+
+///
+/// Returns a BC4 block of the green channel of the given ETC1S block, which is where the alpha values of an alpha slice are stored.
+///
+fn bc4_block(endpoint: &Endpoint, selector: &Selector) -> [u8; 8] {
+    let values: [u8; 4] = std::array::from_fn(|value| endpoint.color(value as u8)[1]);
+    // The first end point is at least as large as the second, so the values are interpolated between the end points
+    let palette = bc4_palette(values[3], values[0]);
+    let closest: [u64; 4] = std::array::from_fn(|value| {
+        (0..8)
+            .min_by_key(|&index| palette[index as usize].abs_diff(values[value]))
+            .unwrap()
+    });
+    let mut indices = 0u64;
+    for (y, row) in selector.iter().enumerate() {
+        for (x, &value) in row.iter().enumerate() {
+            indices |= closest[value as usize] << (3 * (y * 4 + x));
+        }
+    }
+    let mut block = [0; 8];
+    block[0] = values[3];
+    block[1] = values[0];
+    block[2..].copy_from_slice(&indices.to_le_bytes()[..6]);
+    block
+}
+
+fn decode_endpoints(data: &[u8], count: usize) -> Result<Vec<Endpoint>, CoreError> {
+    let mut reader = BitReader::new(data);
+    let color_models = [
+        reader.huffman_table()?,
+        reader.huffman_table()?,
+        reader.huffman_table()?,
+    ];
+    let intensity_model = reader.huffman_table()?;
+    let grayscale = reader.bits(1)? == 1;
+
+    let mut endpoints = Vec::with_capacity(count);
+    let mut previous = Endpoint {
+        color: [16; 3],
+        intensity: 0,
+    };
+    for _ in 0..count {
+        let intensity = (reader.symbol(&intensity_model)? as usize + previous.intensity) & 7;
+        let mut color = [0u8; 3];
+        let channels = if grayscale { 1 } else { 3 };
+        for (value, previous) in color.iter_mut().zip(previous.color).take(channels) {
+            let model = match previous {
+                0..=9 => &color_models[0],
+                10..=21 => &color_models[1],
+                _ => &color_models[2],
+            };
+            *value = ((reader.symbol(model)? + previous as u32) & 31) as u8;
+        }
+        if grayscale {
+            color = [color[0]; 3];
+        }
+        previous = Endpoint { color, intensity };
+        endpoints.push(Endpoint { color, intensity });
+    }
+    Ok(endpoints)
+}
+
+fn decode_selectors(data: &[u8], count: usize) -> Result<Vec<Selector>, CoreError> {
+    let mut reader = BitReader::new(data);
+    if reader.bits(1)? == 1 || reader.bits(1)? == 1 {
+        return Err(CoreError::Ktx2Parsing(
+            "BasisLZ global or hybrid selector codebooks are not supported".to_string(),
+        ));
+    }
+    let raw = reader.bits(1)? == 1;
+    let delta_model = if raw {
+        None
+    } else {
+        Some(reader.huffman_table()?)
+    };
+    let mut selectors: Vec<Selector> = Vec::with_capacity(count);
+    let mut previous = [0u8; 4];
+    for i in 0..count {
+        let mut rows = [0u8; 4];
+        for (j, row) in rows.iter_mut().enumerate() {
+            *row = match &delta_model {
+                Some(model) if i > 0 => reader.symbol(model)? as u8 ^ previous[j],
+                _ => reader.bits(8)? as u8,
+            };
+        }
+        previous = rows;
+        selectors.push(rows.map(|row| std::array::from_fn(|x| (row >> (x * 2)) & 3)));
+    }
+    Ok(selectors)
+}
+
+struct Tables {
+    endpoint_prediction_model: HuffmanTable,
+    endpoint_delta_model: HuffmanTable,
+    selector_model: HuffmanTable,
+    selector_run_model: HuffmanTable,
+    selector_history_size: usize,
+}
+
+impl Tables {
+    fn decode(data: &[u8]) -> Result<Self, CoreError> {
+        let mut reader = BitReader::new(data);
+        Ok(Self {
+            endpoint_prediction_model: reader.huffman_table()?,
+            endpoint_delta_model: reader.huffman_table()?,
+            selector_model: reader.huffman_table()?,
+            selector_run_model: reader.huffman_table()?,
+            selector_history_size: reader.bits(13)? as usize,
+        })
+    }
+
+    ///
+    /// Decodes the endpoint and selector index of each block in the given slice.
+    ///
+    fn decode_slice(
+        &self,
+        data: &[u8],
+        blocks_x: usize,
+        blocks_y: usize,
+        endpoints: &[Endpoint],
+        selectors: &[Selector],
+    ) -> Result<Vec<(usize, usize)>, CoreError> {
+        let error = |message: &str| CoreError::Ktx2Parsing(message.to_string());
+        let history_size = self.selector_history_size;
+        if history_size > MAX_SELECTOR_HISTORY_SIZE {
+            return Err(error("the BasisLZ selector history is too large"));
+        }
+        let endpoint_count = endpoints.len();
+        let selector_count = selectors.len();
+        let run_symbol = selector_count + history_size;
+
+        let mut reader = BitReader::new(data);
+        let mut history = SelectorHistory::new(history_size);
+        let mut selector_run = 0;
+        let mut prediction_run = 0;
+        // The prediction bits and endpoint index of each block in the previous and current row of blocks
+        let mut rows = [
+            vec![(0u32, 0usize); blocks_x],
+            vec![(0u32, 0usize); blocks_x],
+        ];
+        let mut blocks = Vec::new();
+        let mut previous_endpoint = 0;
+        let mut previous_prediction_symbol = 0;
+
+        for block_y in 0..blocks_y {
+            let current = block_y & 1;
+            let previous_row = current ^ 1;
+            let mut prediction_bits = 0;
+            for block_x in 0..blocks_x {
+                // The endpoint predictions of each 2x2 group of blocks are decoded together
+                if block_x & 1 == 0 {
+                    if block_y & 1 == 0 {
+                        if prediction_run > 0 {
+                            prediction_run -= 1;
+                            prediction_bits = previous_prediction_symbol;
+                        } else {
+                            let symbol = reader.symbol(&self.endpoint_prediction_model)?;
+                            if symbol == 256 {
+                                prediction_run = reader
+                                    .vlc(4)?
+                                    .checked_add(2)
+                                    .ok_or_else(|| error("invalid BasisLZ run length"))?;
+                                prediction_bits = previous_prediction_symbol;
+                            } else {
+                                prediction_bits = symbol;
+                                previous_prediction_symbol = symbol;
+                            }
+                        }
+                        rows[previous_row][block_x].0 = prediction_bits >> 4;
+                    } else {
+                        prediction_bits = rows[current][block_x].0;
+                    }
+                }
+                let prediction = prediction_bits & 3;
+                prediction_bits >>= 2;
+
+                let endpoint = match prediction {
+                    0 => previous_endpoint,
+                    1 => rows[previous_row][block_x].1,
+                    2 if block_x > 0 => rows[previous_row][block_x - 1].1,
+                    2 => return Err(error("invalid BasisLZ endpoint prediction")),
+                    _ => {
+                        let delta = reader.symbol(&self.endpoint_delta_model)? as usize;
+                        if delta >= endpoint_count {
+                            return Err(error("invalid BasisLZ endpoint delta"));
+                        }
+                        (previous_endpoint + delta) % endpoint_count
+                    }
+                };
+                if endpoint >= endpoint_count {
+                    return Err(error("invalid BasisLZ endpoint index"));
+                }
+                previous_endpoint = endpoint;
+                rows[current][block_x].1 = endpoint;
+
+                let selector = if selector_run > 0 {
+                    selector_run -= 1;
+                    history.get(0)
+                } else {
+                    let symbol = reader.symbol(&self.selector_model)? as usize;
+                    if symbol == run_symbol {
+                        let run = reader.symbol(&self.selector_run_model)?;
+                        selector_run = if run == 63 {
+                            reader
+                                .vlc(7)?
+                                .checked_add(3)
+                                .ok_or_else(|| error("invalid BasisLZ run length"))?
+                        } else {
+                            run + 3
+                        } - 1;
+                        history.get(0)
+                    } else if symbol >= selector_count {
+                        history.use_index(symbol - selector_count)
+                    } else {
+                        history.add(symbol);
+                        Some(symbol)
+                    }
+                }
+                .ok_or_else(|| error("invalid BasisLZ selector history index"))?;
+                if selector >= selector_count {
+                    return Err(error("invalid BasisLZ selector index"));
+                }
+                blocks.push((endpoint, selector));
+            }
+        }
+        Ok(blocks)
+    }
+}
+
+///
+/// A move-to-front like history of recently used selectors.
+///
+struct SelectorHistory {
+    values: Vec<usize>,
+    size: usize,
+    rover: usize,
+}
+
+impl SelectorHistory {
+    fn new(size: usize) -> Self {
+        Self {
+            values: Vec::with_capacity(size),
+            size,
+            rover: 0,
+        }
+    }
+
+    fn get(&self, index: usize) -> Option<usize> {
+        self.values.get(index).copied()
+    }
+
+    fn add(&mut self, value: usize) {
+        if self.size == 0 {
+            return;
+        }
+        if self.values.len() < self.size {
+            self.values.push(value);
+        } else {
+            self.values[self.rover] = value;
+            self.rover += 1;
+            if self.rover == self.size {
+                self.rover = self.size / 2;
+            }
+        }
+    }
+
+    fn use_index(&mut self, index: usize) -> Option<usize> {
+        let value = self.get(index)?;
+        if index > 0 {
+            self.values.swap(index / 2, index);
+        }
+        Some(value)
+    }
+}
+
+///
+/// A canonical Huffman table.
+///
+struct HuffmanTable {
+    /// The number of codes of each length.
+    counts: [u32; MAX_CODE_LENGTH + 1],
+    /// The symbols sorted by code length and then by symbol.
+    symbols: Vec<u32>,
+}
+
+impl HuffmanTable {
+    fn new(code_lengths: &[u8]) -> Result<Self, CoreError> {
+        let mut counts = [0; MAX_CODE_LENGTH + 1];
+        for &length in code_lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+        let mut symbols = Vec::new();
+        for length in 1..=MAX_CODE_LENGTH as u8 {
+            symbols.extend(
+                (0..code_lengths.len() as u32).filter(|&s| code_lengths[s as usize] == length),
+            );
+        }
+        let mut available = 1i64;
+        for &count in &counts[1..] {
+            available = available * 2 - count as i64;
+            if available < 0 {
+                return Err(CoreError::Ktx2Parsing(
+                    "invalid BasisLZ Huffman code lengths".to_string(),
+                ));
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+}
+
+///
+/// Reads the least significant bits first.
+///
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    buffer: u64,
+    count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn bits(&mut self, count: u32) -> Result<u32, CoreError> {
+        while self.count < count {
+            let byte = *self.data.get(self.position).ok_or_else(|| {
+                CoreError::Ktx2Parsing("the BasisLZ data is truncated".to_string())
+            })?;
+            self.position += 1;
+            self.buffer |= (byte as u64) << self.count;
+            self.count += 8;
+        }
+        let value = (self.buffer & ((1 << count) - 1)) as u32;
+        self.buffer >>= count;
+        self.count -= count;
+        Ok(value)
+    }
+
+    ///
+    /// Reads a variable length integer which is stored in chunks of the given number of bits, each followed by a bit indicating whether there are more chunks.
+    ///
+    fn vlc(&mut self, chunk_bits: u32) -> Result<u32, CoreError> {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let chunk = self.bits(chunk_bits + 1)?;
+            value |= (chunk & ((1 << chunk_bits) - 1)) << shift;
+            shift += chunk_bits;
+            if chunk >> chunk_bits == 0 {
+                return Ok(value);
+            }
+            if shift >= 32 {
+                return Err(CoreError::Ktx2Parsing(
+                    "invalid BasisLZ variable length integer".to_string(),
+                ));
+            }
+        }
+    }
+
+    fn symbol(&mut self, table: &HuffmanTable) -> Result<u32, CoreError> {
+        let mut code = 0;
+        let mut first = 0;
+        let mut index = 0;
+        for &count in &table.counts[1..] {
+            code |= self.bits(1)?;
+            if code < first + count {
+                return Ok(table.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(CoreError::Ktx2Parsing(
+            "invalid BasisLZ Huffman code".to_string(),
+        ))
+    }
+
+    fn huffman_table(&mut self) -> Result<HuffmanTable, CoreError> {
+        let symbol_count = self.bits(14)? as usize;
+        if symbol_count == 0 {
+            return HuffmanTable::new(&[]);
+        }
+        let mut code_length_lengths = [0u8; 21];
+        for &symbol in CODE_LENGTH_ORDER.iter().take(self.bits(5)? as usize) {
+            code_length_lengths[symbol] = self.bits(3)? as u8;
+        }
+        let code_length_table = HuffmanTable::new(&code_length_lengths)?;
+
+        let mut code_lengths = Vec::with_capacity(symbol_count);
+        while code_lengths.len() < symbol_count {
+            let symbol = self.symbol(&code_length_table)?;
+            let (length, repeat) = match symbol {
+                0..=16 => (symbol as u8, 1),
+                17 => (0, self.bits(3)? + 3),
+                18 => (0, self.bits(7)? + 11),
+                19 | 20 => {
+                    let previous = *code_lengths.last().filter(|&&l| l > 0).ok_or_else(|| {
+                        CoreError::Ktx2Parsing("invalid BasisLZ Huffman code lengths".to_string())
+                    })?;
+                    let repeat = if symbol == 19 {
+                        self.bits(2)? + 3
+                    } else {
+                        self.bits(7)? + 7
+                    };
+                    (previous, repeat)
+                }
+                _ => unreachable!(),
+            };
+            if code_lengths.len() + repeat as usize > symbol_count {
+                return Err(CoreError::Ktx2Parsing(
+                    "invalid BasisLZ Huffman code lengths".to_string(),
+                ));
+            }
+            code_lengths.resize(code_lengths.len() + repeat as usize, length);
+        }
+        HuffmanTable::new(&code_lengths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct BitWriter {
+        bytes: Vec<u8>,
+        count: usize,
+    }
+
+    impl BitWriter {
+        fn bit(&mut self, bit: u32) {
+            if self.bytes.len() * 8 == self.count {
+                self.bytes.push(0);
+            }
+            *self.bytes.last_mut().unwrap() |= ((bit & 1) as u8) << (self.count % 8);
+            self.count += 1;
+        }
+
+        fn bits(&mut self, value: u32, count: u32) {
+            (0..count).for_each(|i| self.bit(value >> i));
+        }
+
+        /// Huffman codes are written starting with the most significant bit.
+        fn code(&mut self, code: u32, length: u32) {
+            (0..length).rev().for_each(|i| self.bit(code >> i));
+        }
+
+        /// Writes a Huffman table where all symbols have the same code length, so the code of each symbol is the symbol itself.
+        fn fixed_table(&mut self, symbol_count: u32) -> u32 {
+            let length = (32 - (symbol_count - 1).leading_zeros()).max(1);
+            self.bits(symbol_count, 14);
+            // The code length table only contains the code length used by all symbols
+            let position = CODE_LENGTH_ORDER
+                .iter()
+                .position(|&symbol| symbol == length as usize)
+                .unwrap();
+            self.bits(position as u32 + 1, 5);
+            for i in 0..=position {
+                self.bits((i == position) as u32, 3);
+            }
+            (0..symbol_count).for_each(|_| self.code(0, 1));
+            length
+        }
+    }
+
+    const ENDPOINTS: [([u8; 3], usize); 2] = [([4, 8, 30], 1), ([20, 24, 2], 3)];
+    const SELECTORS: [[u8; 4]; 3] = [
+        [0x1B, 0x1B, 0x1B, 0x1B],
+        [0x00, 0x55, 0xAA, 0xFF],
+        [0xE4, 0x1B, 0xE4, 0x1B],
+    ];
+    const HISTORY_SIZE: u32 = 4;
+
+    fn endpoints() -> Vec<u8> {
+        let mut writer = BitWriter::default();
+        // The color models have different sizes, so decoding with the wrong model fails
+        let lengths = [32, 33, 128].map(|symbol_count| writer.fixed_table(symbol_count));
+        let intensity_length = writer.fixed_table(8);
+        writer.bits(0, 1);
+        let mut previous = ([16u8; 3], 0);
+        for (color, intensity) in ENDPOINTS {
+            writer.code(
+                (intensity.wrapping_sub(previous.1) & 7) as u32,
+                intensity_length,
+            );
+            for (value, previous) in color.into_iter().zip(previous.0) {
+                let length = match previous {
+                    0..=9 => lengths[0],
+                    10..=21 => lengths[1],
+                    _ => lengths[2],
+                };
+                writer.code((value.wrapping_sub(previous) & 31) as u32, length);
+            }
+            previous = (color, intensity);
+        }
+        writer.bytes
+    }
+
+    fn selectors(raw: bool) -> Vec<u8> {
+        let mut writer = BitWriter::default();
+        writer.bits(0, 2);
+        writer.bits(raw as u32, 1);
+        let length = (!raw).then(|| writer.fixed_table(256));
+        let mut previous = [0; 4];
+        for (i, selector) in SELECTORS.into_iter().enumerate() {
+            for (row, previous_row) in selector.into_iter().zip(previous) {
+                match length {
+                    Some(length) if i > 0 => writer.code((row ^ previous_row) as u32, length),
+                    _ => writer.bits(row as u32, 8),
+                }
+            }
+            previous = selector;
+        }
+        writer.bytes
+    }
+
+    /// Returns the tables and a slice of 2x2 blocks which use all kinds of endpoint predictions and selector history lookups.
+    fn tables_and_slice() -> (Vec<u8>, Vec<u8>) {
+        let mut writer = BitWriter::default();
+        let prediction_length = writer.fixed_table(257);
+        let delta_length = writer.fixed_table(ENDPOINTS.len() as u32);
+        let selector_length = writer.fixed_table(SELECTORS.len() as u32 + HISTORY_SIZE + 1);
+        writer.fixed_table(64);
+        writer.bits(HISTORY_SIZE, 13);
+        let tables = writer.bytes;
+
+        let mut writer = BitWriter::default();
+        // Delta and left in the first row, upper and delta in the second row
+        writer.code(3 | (1 << 4) | (3 << 6), prediction_length);
+        writer.code(1, delta_length);
+        writer.code(2, selector_length);
+        writer.code(SELECTORS.len() as u32, selector_length);
+        writer.code(0, selector_length);
+        writer.code(1, delta_length);
+        writer.code(SELECTORS.len() as u32 + 1, selector_length);
+        (tables, writer.bytes)
+    }
+
+    fn expected_color(endpoint: usize, selector: usize, x: usize, y: usize) -> [u8; 3] {
+        let (color, intensity) = ENDPOINTS[endpoint];
+        Endpoint { color, intensity }.color((SELECTORS[selector][y] >> (x * 2)) & 3)
+    }
+
+    #[test]
+    fn huffman_codes() {
+        let table = HuffmanTable::new(&[2, 1, 3, 3]).unwrap();
+        let mut writer = BitWriter::default();
+        for (code, length) in [(0b110, 3), (0b0, 1), (0b111, 3), (0b10, 2)] {
+            writer.code(code, length);
+        }
+        let mut reader = BitReader::new(&writer.bytes);
+        for symbol in [2, 1, 3, 0] {
+            assert_eq!(reader.symbol(&table).unwrap(), symbol);
+        }
+        assert!(HuffmanTable::new(&[1, 1, 1]).is_err());
+    }
+
+    #[test]
+    fn variable_length_integer() {
+        let mut writer = BitWriter::default();
+        // 100 is stored in the 4 bit chunks 4 and 6
+        writer.bits(4 | 16, 5);
+        writer.bits(6, 5);
+        assert_eq!(BitReader::new(&writer.bytes).vlc(4).unwrap(), 100);
+    }
+
+    #[test]
+    fn codebooks() {
+        let endpoints = decode_endpoints(&endpoints(), ENDPOINTS.len()).unwrap();
+        for (endpoint, (color, intensity)) in endpoints.iter().zip(ENDPOINTS) {
+            assert_eq!(endpoint.color, color);
+            assert_eq!(endpoint.intensity, intensity);
+        }
+        for raw in [true, false] {
+            let selectors = decode_selectors(&selectors(raw), SELECTORS.len()).unwrap();
+            assert_eq!(selectors[1][1], [1, 1, 1, 1]);
+            assert_eq!(selectors[0][2], [3, 2, 1, 0]);
+            assert_eq!(selectors[2][0], [0, 1, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn slice_predictions() {
+        let endpoints = decode_endpoints(&endpoints(), ENDPOINTS.len()).unwrap();
+        let selectors = decode_selectors(&selectors(true), SELECTORS.len()).unwrap();
+        let (tables, slice) = tables_and_slice();
+        let tables = Tables::decode(&tables).unwrap();
+        let blocks = tables
+            .decode_slice(&slice, 2, 2, &endpoints, &selectors)
+            .unwrap();
+        assert_eq!(blocks, [(1, 2), (1, 2), (1, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn slice_runs() {
+        let endpoints = decode_endpoints(&endpoints(), ENDPOINTS.len()).unwrap();
+        let selectors = decode_selectors(&selectors(true), SELECTORS.len()).unwrap();
+        let (tables, _) = tables_and_slice();
+        let run_symbol = SELECTORS.len() as u32 + HISTORY_SIZE;
+        let mut writer = BitWriter::default();
+        // Delta and left prediction, selector 1 followed by a run of 3
+        writer.code(3, 9);
+        writer.code(1, 1);
+        writer.code(1, 3);
+        writer.code(run_symbol, 3);
+        writer.code(0, 6);
+        // The prediction symbol is repeated for the next two groups
+        writer.code(256, 9);
+        writer.bits(0, 5);
+        writer.code(1, 1);
+        writer.code(1, 1);
+        // Selector 2 followed by a long run, which repeats the first selector in the history and not the previous selector
+        writer.code(2, 3);
+        writer.code(run_symbol, 3);
+        writer.code(63, 6);
+        writer.bits(0, 8);
+        let blocks = Tables::decode(&tables)
+            .unwrap()
+            .decode_slice(&writer.bytes, 6, 1, &endpoints, &selectors)
+            .unwrap();
+        assert_eq!(blocks, [(1, 1), (1, 1), (0, 1), (0, 1), (1, 2), (1, 1)]);
+    }
+
+    fn ktx2_file(width: u32, height: u32, alpha: bool) -> Vec<u8> {
+        let endpoints = endpoints();
+        let selectors = selectors(false);
+        let (tables, slice) = tables_and_slice();
+        let alpha_length = if alpha { slice.len() } else { 0 };
+
+        let mut global_data = Vec::new();
+        global_data.extend_from_slice(&(ENDPOINTS.len() as u16).to_le_bytes());
+        global_data.extend_from_slice(&(SELECTORS.len() as u16).to_le_bytes());
+        for value in [endpoints.len(), selectors.len(), tables.len(), 0] {
+            global_data.extend_from_slice(&(value as u32).to_le_bytes());
+        }
+        for value in [0, 0, slice.len(), slice.len(), alpha_length] {
+            global_data.extend_from_slice(&(value as u32).to_le_bytes());
+        }
+        global_data.extend(endpoints);
+        global_data.extend(selectors);
+        global_data.extend(tables);
+
+        let descriptor = [16, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 163, 1, 1, 0];
+        let global_data_offset = 104 + descriptor.len();
+        let level_offset = global_data_offset + global_data.len();
+        let mut bytes = IDENTIFIER.to_vec();
+        for value in [0, 1, width, height, 0, 0, 1, 1, 1, 104, 16, 0, 0] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [
+            global_data_offset,
+            global_data.len(),
+            level_offset,
+            slice.len() + alpha_length,
+            0,
+        ] {
+            bytes.extend_from_slice(&(value as u64).to_le_bytes());
+        }
+        bytes.extend_from_slice(&descriptor);
+        bytes.extend(global_data);
+        bytes.extend_from_slice(&slice);
+        if alpha {
+            bytes.extend_from_slice(&slice);
+        }
+        bytes
+    }
+
+    #[test]
+    fn transcode_to_etc() {
+        let bytes = ktx2_file(8, 8, false);
+        let ktx2 = Ktx2::parse(&bytes, |_| false).unwrap();
+        assert_eq!(ktx2.internal_format, COMPRESSED_RGB8_ETC2);
+        assert!(ktx2.compressed);
+        assert_eq!(ktx2.levels[0].len(), 4 * 8);
+
+        // The ETC2 blocks are decoded with the software decoder and compared with the colors given by the endpoints and selectors
+        let ktx2 = ktx2.decode().unwrap();
+        let blocks = [(1, 2), (1, 2), (1, 0), (0, 0)];
+        for (block, (endpoint, selector)) in blocks.into_iter().enumerate() {
+            for y in 0..4 {
+                for x in 0..4 {
+                    let pixel = ((block / 2 * 4 + y) * 8 + block % 2 * 4 + x) * 4;
+                    let color = expected_color(endpoint, selector, x, y);
+                    assert_eq!(
+                        ktx2.levels[0][pixel..pixel + 4],
+                        [color[0], color[1], color[2], 255]
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn transcode_to_bc() {
+        for alpha in [false, true] {
+            let bytes = ktx2_file(8, 8, alpha);
+            let ktx2 = Ktx2::parse(&bytes, |format| {
+                format == COMPRESSED_RGB_S3TC_DXT1 || format == COMPRESSED_RGBA_S3TC_DXT5
+            })
+            .unwrap();
+            assert_eq!(
+                ktx2.internal_format,
+                if alpha {
+                    COMPRESSED_RGBA_S3TC_DXT5
+                } else {
+                    COMPRESSED_RGB_S3TC_DXT1
+                }
+            );
+            assert!(ktx2.compressed);
+            assert_eq!(ktx2.levels[0].len(), 4 * if alpha { 16 } else { 8 });
+
+            // The BC blocks only approximate the ETC1S colors because of the lower precision of the end points and the fixed interpolation weights.
+            // The error is largest for the third block, where the blue channel of the darkest ETC1S color is clamped, so the colors are not on a line.
+            let ktx2 = ktx2.decode().unwrap();
+            let mut total_error = 0;
+            let blocks = [(1, 2), (1, 2), (1, 0), (0, 0)];
+            for (block, (endpoint, selector)) in blocks.into_iter().enumerate() {
+                for y in 0..4 {
+                    for x in 0..4 {
+                        let pixel = ((block / 2 * 4 + y) * 8 + block % 2 * 4 + x) * 4;
+                        let color = expected_color(endpoint, selector, x, y);
+                        let expected = [
+                            color[0],
+                            color[1],
+                            color[2],
+                            if alpha { color[1] } else { 255 },
+                        ];
+                        for (value, expected) in
+                            ktx2.levels[0][pixel..pixel + 4].iter().zip(expected)
+                        {
+                            assert!(value.abs_diff(expected) <= 16, "{} != {}", value, expected);
+                            total_error += value.abs_diff(expected) as u32;
+                        }
+                    }
+                }
+            }
+            assert!(total_error <= 3 * 8 * 8 * 4);
+        }
+    }
+
+    #[test]
+    fn transcode_with_alpha() {
+        let bytes = ktx2_file(7, 6, true);
+        let ktx2 = Ktx2::parse(&bytes, |_| false).unwrap();
+        assert_eq!(ktx2.internal_format, crate::context::RGBA8);
+        assert!(!ktx2.compressed);
+        assert_eq!(ktx2.levels[0].len(), 7 * 6 * 4);
+        // The pixel at (6, 5) is in the last block, the alpha slice is the same as the color slice and uses the green channel
+        let color = expected_color(0, 0, 2, 1);
+        assert_eq!(
+            ktx2.levels[0][(5 * 7 + 6) * 4..][..4],
+            [color[0], color[1], color[2], color[1]]
+        );
+    }
+
+    #[test]
+    fn truncated_codebooks_and_slices() {
+        let endpoints = endpoints();
+        for length in 0..endpoints.len() {
+            assert!(decode_endpoints(&endpoints[..length], ENDPOINTS.len()).is_err());
+        }
+        for raw in [true, false] {
+            let selectors = selectors(raw);
+            for length in 0..selectors.len() {
+                assert!(decode_selectors(&selectors[..length], SELECTORS.len()).is_err());
+            }
+        }
+        let (tables, slice) = tables_and_slice();
+        for length in 0..tables.len() {
+            assert!(Tables::decode(&tables[..length]).is_err());
+        }
+        let endpoints = decode_endpoints(&endpoints, ENDPOINTS.len()).unwrap();
+        let selectors = decode_selectors(&selectors(true), SELECTORS.len()).unwrap();
+        let tables = Tables::decode(&tables).unwrap();
+        for length in 0..slice.len() {
+            assert!(tables
+                .decode_slice(&slice[..length], 2, 2, &endpoints, &selectors)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn transcode_truncated() {
+        let bytes = ktx2_file(8, 8, true);
+        for length in 0..bytes.len() {
+            assert!(Ktx2::parse(&bytes[..length], |_| false).is_err());
+        }
+    }
+
+    #[test]
+    fn transcode_corrupt() {
+        // Corrupt data either fails or is transcoded to data of the expected size, but never panics
+        for alpha in [false, true] {
+            let bytes = ktx2_file(8, 8, alpha);
+            for index in 0..bytes.len() {
+                for value in [0x00, 0x01, 0x80, 0xFF] {
+                    let mut bytes = bytes.clone();
+                    bytes[index] = value;
+                    if let Ok(ktx2) = Ktx2::parse(&bytes, |_| false) {
+                        let (width, height) = (ktx2.width as usize, ktx2.height as usize);
+                        let size = if ktx2.compressed {
+                            width.div_ceil(4) * height.div_ceil(4) * 8
+                        } else {
+                            width * height * 4
+                        };
+                        assert_eq!(ktx2.levels[0].len(), size);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unsupported_uastc() {
+        let mut bytes = ktx2_file(8, 8, false);
+        bytes[104 + 12] = 166;
+        assert!(matches!(
+            Ktx2::parse(&bytes, |_| false),
+            Err(CoreError::UnsupportedTexture(_))
+        ));
+    }
+}
@@ -0,0 +1,524 @@
+use super::*;
+
+type Block = [[u8; 4]; 16];
+
+const ETC_MODIFIERS: [[i32; 4]; 8] = [
+    [2, 8, -2, -8],
+    [5, 17, -5, -17],
+    [9, 29, -9, -29],
+    [13, 42, -13, -42],
+    [18, 60, -18, -60],
+    [24, 80, -24, -80],
+    [33, 106, -33, -106],
+    [47, 183, -47, -183],
+];
+
+const ETC_DISTANCES: [i32; 8] = [3, 6, 11, 16, 23, 32, 41, 64];
+
+const EAC_MODIFIERS: [[i32; 8]; 16] = [
+    [-3, -6, -9, -15, 2, 5, 8, 14],
+    [-3, -7, -10, -13, 2, 6, 9, 12],
+    [-2, -5, -8, -13, 1, 4, 7, 12],
+    [-2, -4, -6, -13, 1, 3, 5, 12],
+    [-3, -6, -8, -12, 2, 5, 7, 11],
+    [-3, -7, -9, -11, 2, 6, 8, 10],
+    [-4, -7, -8, -11, 3, 6, 7, 10],
+    [-3, -5, -8, -11, 2, 4, 7, 10],
+    [-2, -6, -8, -10, 1, 5, 7, 9],
+    [-2, -5, -8, -10, 1, 4, 7, 9],
+    [-2, -4, -8, -10, 1, 3, 7, 9],
+    [-2, -5, -7, -10, 1, 4, 6, 9],
+    [-3, -4, -7, -10, 2, 3, 6, 9],
+    [-1, -2, -3, -10, 0, 1, 2, 9],
+    [-4, -6, -8, -9, 3, 5, 7, 8],
+    [-3, -5, -7, -9, 2, 4, 6, 8],
+];
+
+///
+/// Returns whether or not the given compressed format contains sRGB encoded colors.
+///
+pub(super) fn is_srgb(internal_format: u32) -> bool {
+    matches!(
+        internal_format,
+        COMPRESSED_SRGB_S3TC_DXT1
+            ..=COMPRESSED_SRGB_ALPHA_S3TC_DXT5
+                | COMPRESSED_SRGB8_ETC2
+                | COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2
+                | COMPRESSED_SRGB8_ALPHA8_ETC2_EAC
+    )
+}
+
+///
+/// Decodes the data of a single mip level of the given size and compressed format into RGBA8 data.
+/// Supports the S3TC, unsigned RGTC, ETC2 and unsigned EAC formats.
+///
+pub(super) fn decode_to_rgba(
+    internal_format: u32,
+    width: u32,
+    height: u32,
+    data: &[u8],
+) -> Result<Vec<u8>, CoreError> {
+    let (block_size, decode_block): (usize, fn(&[u8]) -> Block) = match internal_format {
+        COMPRESSED_RGB_S3TC_DXT1 | COMPRESSED_SRGB_S3TC_DXT1 => {
+            (8, |block| decode_bc1(block, false, false))
+        }
+        COMPRESSED_RGBA_S3TC_DXT1 | COMPRESSED_SRGB_ALPHA_S3TC_DXT1 => {
+            (8, |block| decode_bc1(block, false, true))
+        }
+        COMPRESSED_RGBA_S3TC_DXT3 | COMPRESSED_SRGB_ALPHA_S3TC_DXT3 => (16, decode_bc2),
+        COMPRESSED_RGBA_S3TC_DXT5 | COMPRESSED_SRGB_ALPHA_S3TC_DXT5 => (16, decode_bc3),
+        COMPRESSED_RED_RGTC1 => (8, |block| {
+            decode_bc4_channel(block).map(|r| [r, 0, 0, 255])
+        }),
+        COMPRESSED_RG_RGTC2 => (16, |block| {
+            let r = decode_bc4_channel(&block[..8]);
+            let g = decode_bc4_channel(&block[8..]);
+            std::array::from_fn(|i| [r[i], g[i], 0, 255])
+        }),
+        COMPRESSED_R11_EAC => (8, |block| {
+            decode_eac_channel(block, true).map(|r| [r, 0, 0, 255])
+        }),
+        COMPRESSED_RG11_EAC => (16, |block| {
+            let r = decode_eac_channel(&block[..8], true);
+            let g = decode_eac_channel(&block[8..], true);
+            std::array::from_fn(|i| [r[i], g[i], 0, 255])
+        }),
+        COMPRESSED_RGB8_ETC2 | COMPRESSED_SRGB8_ETC2 => (8, |block| decode_etc2(block, false)),
+        COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2 | COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2 => {
+            (8, |block| decode_etc2(block, true))
+        }
+        COMPRESSED_RGBA8_ETC2_EAC | COMPRESSED_SRGB8_ALPHA8_ETC2_EAC => (16, |block| {
+            let alpha = decode_eac_channel(&block[..8], false);
+            let mut texels = decode_etc2(&block[8..], false);
+            for (texel, alpha) in texels.iter_mut().zip(alpha) {
+                texel[3] = alpha;
+            }
+            texels
+        }),
+        _ => {
+            return Err(CoreError::UnsupportedTexture(format!(
+                "the compressed texture format {:#X} is not supported on this device and cannot be decoded",
+                internal_format
+            )))
+        }
+    };
+
+    let width = width as usize;
+    let height = height as usize;
+    let blocks_x = width.div_ceil(4);
+    let blocks_y = height.div_ceil(4);
+    // The size is checked before allocating, so the number of pixels is limited by the size of the data
+    let data_size = blocks_x
+        .checked_mul(blocks_y)
+        .and_then(|blocks| blocks.checked_mul(block_size));
+    if data_size
+        .filter(|&data_size| data.len() >= data_size)
+        .is_none()
+    {
+        return Err(CoreError::Ktx2Parsing(format!(
+            "the compressed data of size {} is too small for a {}x{} texture",
+            data.len(),
+            width,
+            height
+        )));
+    }
+    let mut rgba = vec![0; width * height * 4];
+    for block_y in 0..blocks_y {
+        for block_x in 0..blocks_x {
+            let offset = (block_y * blocks_x + block_x) * block_size;
+            let texels = decode_block(&data[offset..offset + block_size]);
+            for y in 0..4 {
+                for x in 0..4 {
+                    let (pixel_x, pixel_y) = (block_x * 4 + x, block_y * 4 + y);
+                    if pixel_x < width && pixel_y < height {
+                        let index = (pixel_y * width + pixel_x) * 4;
+                        rgba[index..index + 4].copy_from_slice(&texels[y * 4 + x]);
+                    }
+                }
+            }
+        }
+    }
+    Ok(rgba)
+}
+
+fn mix(a: [u8; 4], b: [u8; 4], weight_a: u32, weight_b: u32) -> [u8; 4] {
+    std::array::from_fn(|i| {
+        ((a[i] as u32 * weight_a + b[i] as u32 * weight_b) / (weight_a + weight_b)) as u8
+    })
+}
+
+fn expand_565(color: u16) -> [u8; 4] {
+    let r = (color >> 11) & 31;
+    let g = (color >> 5) & 63;
+    let b = color & 31;
+    [
+        ((r << 3) | (r >> 2)) as u8,
+        ((g << 2) | (g >> 4)) as u8,
+        ((b << 3) | (b >> 2)) as u8,
+        255,
+    ]
+}
+
+///
+/// Returns the colors of a BC1 block with the given end points in RGB565 format.
+///
+pub(super) fn bc1_palette(
+    color0: u16,
+    color1: u16,
+    four_colors: bool,
+    transparent_black: bool,
+) -> [[u8; 4]; 4] {
+    let (c0, c1) = (expand_565(color0), expand_565(color1));
+    if four_colors || color0 > color1 {
+        [c0, c1, mix(c0, c1, 2, 1), mix(c0, c1, 1, 2)]
+    } else {
+        [
+            c0,
+            c1,
+            mix(c0, c1, 1, 1),
+            [0, 0, 0, if transparent_black { 0 } else { 255 }],
+        ]
+    }
+}
+
+fn decode_bc1(block: &[u8], four_colors: bool, transparent_black: bool) -> Block {
+    let color0 = u16::from_le_bytes([block[0], block[1]]);
+    let color1 = u16::from_le_bytes([block[2], block[3]]);
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    let palette = bc1_palette(color0, color1, four_colors, transparent_black);
+    std::array::from_fn(|i| palette[(indices >> (2 * i)) as usize & 3])
+}
+
+fn decode_bc2(block: &[u8]) -> Block {
+    let alpha = u64::from_le_bytes(block[..8].try_into().unwrap());
+    let mut texels = decode_bc1(&block[8..], true, false);
+    for (i, texel) in texels.iter_mut().enumerate() {
+        texel[3] = ((alpha >> (4 * i)) & 15) as u8 * 17;
+    }
+    texels
+}
+
+fn decode_bc3(block: &[u8]) -> Block {
+    let alpha = decode_bc4_channel(&block[..8]);
+    let mut texels = decode_bc1(&block[8..], true, false);
+    for (texel, alpha) in texels.iter_mut().zip(alpha) {
+        texel[3] = alpha;
+    }
+    texels
+}
+
+///
+/// Returns the values of a BC4 block with the given end points.
+///
+pub(super) fn bc4_palette(a0: u8, a1: u8) -> [u8; 8] {
+    let (a0, a1) = (a0 as u32, a1 as u32);
+    std::array::from_fn(|i| {
+        let i = i as u32;
+        (match i {
+            0 => a0,
+            1 => a1,
+            _ if a0 > a1 => ((8 - i) * a0 + (i - 1) * a1) / 7,
+            6 => 0,
+            7 => 255,
+            _ => ((6 - i) * a0 + (i - 1) * a1) / 5,
+        }) as u8
+    })
+}
+
+fn decode_bc4_channel(block: &[u8]) -> [u8; 16] {
+    let mut index_bytes = [0; 8];
+    index_bytes[..6].copy_from_slice(&block[2..8]);
+    let indices = u64::from_le_bytes(index_bytes);
+    let palette = bc4_palette(block[0], block[1]);
+    std::array::from_fn(|i| palette[(indices >> (3 * i)) as usize & 7])
+}
+
+fn decode_eac_channel(block: &[u8], eleven_bits: bool) -> [u8; 16] {
+    let bits = u64::from_be_bytes(block[..8].try_into().unwrap());
+    let base = block[0] as i32;
+    let multiplier = (block[1] >> 4) as i32;
+    let modifiers = EAC_MODIFIERS[(block[1] & 15) as usize];
+    std::array::from_fn(|i| {
+        // The pixels are stored in column-major order
+        let pixel = (i % 4) * 4 + i / 4;
+        let modifier = modifiers[((bits >> (45 - 3 * pixel)) & 7) as usize];
+        if eleven_bits {
+            let multiplier = if multiplier == 0 { 1 } else { multiplier * 8 };
+            let value = (base * 8 + 4 + modifier * multiplier).clamp(0, 2047);
+            ((value * 255 + 1023) / 2047) as u8
+        } else {
+            (base + modifier * multiplier).clamp(0, 255) as u8
+        }
+    })
+}
+
+fn decode_etc2(block: &[u8], punchthrough: bool) -> Block {
+    let high = u32::from_be_bytes([block[0], block[1], block[2], block[3]]);
+    let low = u32::from_be_bytes([block[4], block[5], block[6], block[7]]);
+    // With punch-through alpha, the differential bit is instead used to specify whether or not the block is opaque
+    let differential = punchthrough || (high >> 1) & 1 == 1;
+    let opaque = !punchthrough || (high >> 1) & 1 == 1;
+    let extend_4 = |value: u32| ((value & 15) * 17) as i32;
+    let extend_5 = |value: u32| (((value & 31) << 3) | ((value & 31) >> 2)) as i32;
+    let pixel_index = |i: usize| {
+        // The pixels are stored in column-major order
+        let pixel = (i % 4) * 4 + i / 4;
+        ((((low >> (pixel + 16)) & 1) << 1) | ((low >> pixel) & 1)) as usize
+    };
+    let to_texel = |color: [i32; 3]| {
+        [
+            color[0].clamp(0, 255) as u8,
+            color[1].clamp(0, 255) as u8,
+            color[2].clamp(0, 255) as u8,
+            255,
+        ]
+    };
+    let offset = |color: [i32; 3], delta: i32| color.map(|c| c + delta);
+
+    let from_paint_colors = |paint_colors: [[i32; 3]; 4]| -> Block {
+        std::array::from_fn(|i| {
+            let index = pixel_index(i);
+            if !opaque && index == 2 {
+                [0; 4]
+            } else {
+                to_texel(paint_colors[index])
+            }
+        })
+    };
+
+    let base_colors = if !differential {
+        [
+            [
+                extend_4(high >> 28),
+                extend_4(high >> 20),
+                extend_4(high >> 12),
+            ],
+            [
+                extend_4(high >> 24),
+                extend_4(high >> 16),
+                extend_4(high >> 8),
+            ],
+        ]
+    } else {
+        let delta = |value: u32| ((value as i32) << 29) >> 29;
+        let r = ((high >> 27) & 31) as i32 + delta(high >> 24);
+        let g = ((high >> 19) & 31) as i32 + delta(high >> 16);
+        let b = ((high >> 11) & 31) as i32 + delta(high >> 8);
+        if !(0..32).contains(&r) {
+            // T mode
+            let c1 = [
+                extend_4(((high >> 25) & 12) | ((high >> 24) & 3)),
+                extend_4(high >> 20),
+                extend_4(high >> 16),
+            ];
+            let c2 = [
+                extend_4(high >> 12),
+                extend_4(high >> 8),
+                extend_4(high >> 4),
+            ];
+            let distance = ETC_DISTANCES[(((high >> 1) & 6) | (high & 1)) as usize];
+            return from_paint_colors([c1, offset(c2, distance), c2, offset(c2, -distance)]);
+        } else if !(0..32).contains(&g) {
+            // H mode
+            let (r1, g1, b1) = (
+                (high >> 27) & 15,
+                ((high >> 23) & 14) | ((high >> 20) & 1),
+                ((high >> 16) & 8) | ((high >> 15) & 7),
+            );
+            let (r2, g2, b2) = ((high >> 11) & 15, (high >> 7) & 15, (high >> 3) & 15);
+            let ordering = ((r1 << 8) | (g1 << 4) | b1) >= ((r2 << 8) | (g2 << 4) | b2);
+            let distance =
+                ETC_DISTANCES[(((high & 4) | ((high & 1) << 1)) | ordering as u32) as usize];
+            let c1 = [extend_4(r1), extend_4(g1), extend_4(b1)];
+            let c2 = [extend_4(r2), extend_4(g2), extend_4(b2)];
+            return from_paint_colors([
+                offset(c1, distance),
+                offset(c1, -distance),
+                offset(c2, distance),
+                offset(c2, -distance),
+            ]);
+        } else if !(0..32).contains(&b) {
+            // Planar mode
+            let extend_6 = |value: u32| (((value & 63) << 2) | ((value & 63) >> 4)) as i32;
+            let extend_7 = |value: u32| (((value & 127) << 1) | ((value & 127) >> 6)) as i32;
+            let origin = [
+                extend_6(high >> 25),
+                extend_7(((high >> 18) & 64) | ((high >> 17) & 63)),
+                extend_6(((high >> 11) & 32) | ((high >> 8) & 24) | ((high >> 7) & 7)),
+            ];
+            let horizontal = [
+                extend_6(((high >> 1) & 62) | (high & 1)),
+                extend_7(low >> 25),
+                extend_6(low >> 19),
+            ];
+            let vertical = [extend_6(low >> 13), extend_7(low >> 6), extend_6(low)];
+            return std::array::from_fn(|i| {
+                let (x, y) = ((i % 4) as i32, (i / 4) as i32);
+                to_texel(std::array::from_fn(|c| {
+                    (x * (horizontal[c] - origin[c])
+                        + y * (vertical[c] - origin[c])
+                        + 4 * origin[c]
+                        + 2)
+                        >> 2
+                }))
+            });
+        }
+        [
+            [
+                extend_5(high >> 27),
+                extend_5(high >> 19),
+                extend_5(high >> 11),
+            ],
+            [extend_5(r as u32), extend_5(g as u32), extend_5(b as u32)],
+        ]
+    };
+
+    // Individual or differential mode
+    let flip = high & 1 == 1;
+    let tables = [(high >> 5) & 7, (high >> 2) & 7];
+    std::array::from_fn(|i| {
+        let (x, y) = (i % 4, i / 4);
+        let sub_block = if flip { y / 2 } else { x / 2 };
+        let index = pixel_index(i);
+        if !opaque && index == 2 {
+            [0; 4]
+        } else {
+            let modifier = if !opaque && index == 0 {
+                0
+            } else {
+                ETC_MODIFIERS[tables[sub_block] as usize][index]
+            };
+            to_texel(offset(base_colors[sub_block], modifier))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bc1_four_colors() {
+        // Red and blue end points with the indices 0, 1, 2 and 3 in the first row and 0 elsewhere
+        let block = [0x00, 0xF8, 0x1F, 0x00, 0b11100100, 0, 0, 0];
+        let texels = decode_bc1(&block, false, false);
+        assert_eq!(texels[0], [255, 0, 0, 255]);
+        assert_eq!(texels[1], [0, 0, 255, 255]);
+        assert_eq!(texels[2], [170, 0, 85, 255]);
+        assert_eq!(texels[3], [85, 0, 170, 255]);
+        assert_eq!(texels[15], [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn bc1_transparent_black() {
+        let block = [0x1F, 0x00, 0x00, 0xF8, 0xFF, 0xFF, 0xFF, 0xFF];
+        assert_eq!(decode_bc1(&block, false, true)[0], [0, 0, 0, 0]);
+        assert_eq!(decode_bc1(&block, false, false)[0], [0, 0, 0, 255]);
+        assert_eq!(decode_bc1(&block, true, false)[0], [170, 0, 85, 255]);
+    }
+
+    #[test]
+    fn bc4_interpolation() {
+        // All pixels use index 2 except the last, which uses index 7
+        let block = [
+            200, 60, 0b10010010, 0b00100100, 0b01001001, 0b10010010, 0b00100100, 0b11101001,
+        ];
+        let values = decode_bc4_channel(&block);
+        assert_eq!(values[0], 180);
+        assert_eq!(values[14], 180);
+        assert_eq!(values[15], 80);
+
+        // With the first end point smaller than the second, index 6 and 7 are 0 and 255
+        let block = [60, 200, 0b11110110, 0b00001111, 0, 0, 0, 0];
+        let values = decode_bc4_channel(&block);
+        assert_eq!(values[0], 0);
+        assert_eq!(values[1], 0);
+        assert_eq!(values[2], 255);
+        assert_eq!(values[3], 255);
+        assert_eq!(values[4], 60);
+    }
+
+    #[test]
+    fn etc2_individual_mode() {
+        // Base colors 0x8 and 0xF in each channel, table 0 and flipped, so the top half uses the first base color
+        let high: u32 = 0x8F8F8F01;
+        // The pixel at (1, 0) uses index 1 and the pixel at (0, 3) uses index 3, all others use index 0
+        let low: u32 = (1 << 4) | (1 << 3) | (1 << (3 + 16));
+        let mut block = [0; 8];
+        block[..4].copy_from_slice(&high.to_be_bytes());
+        block[4..].copy_from_slice(&low.to_be_bytes());
+        let texels = decode_etc2(&block, false);
+        assert_eq!(texels[0], [138, 138, 138, 255]);
+        assert_eq!(texels[1], [144, 144, 144, 255]);
+        assert_eq!(texels[8], [255, 255, 255, 255]);
+        assert_eq!(texels[12], [247, 247, 247, 255]);
+    }
+
+    #[test]
+    fn etc2_punchthrough_transparent() {
+        // Differential mode with the opaque bit unset and all pixels using index 2
+        let high: u32 = 0x80808000;
+        let low: u32 = 0xFFFF0000;
+        let mut block = [0; 8];
+        block[..4].copy_from_slice(&high.to_be_bytes());
+        block[4..].copy_from_slice(&low.to_be_bytes());
+        assert!(decode_etc2(&block, true)
+            .iter()
+            .all(|texel| *texel == [0; 4]));
+        assert!(decode_etc2(&block, false)
+            .iter()
+            .all(|texel| texel[3] == 255));
+    }
+
+    #[test]
+    fn eac_alpha() {
+        // Base 128, multiplier 2, table 0 and all pixels using index 7 except the first which uses index 0
+        let mut block = [128, 0x20, 0, 0, 0, 0, 0, 0];
+        let indices: u64 = 0x0000_1FFF_FFFF_FFFF;
+        block[2..].copy_from_slice(&indices.to_be_bytes()[2..]);
+        let values = decode_eac_channel(&block, false);
+        assert_eq!(values[0], 122);
+        assert_eq!(values[1], 156);
+        assert_eq!(values[15], 156);
+    }
+
+    #[test]
+    fn decode_partial_blocks() {
+        let block = [0x00, 0xF8, 0x00, 0xF8, 0, 0, 0, 0];
+        let rgba = decode_to_rgba(COMPRESSED_RGB_S3TC_DXT1, 2, 3, &block).unwrap();
+        assert_eq!(rgba.len(), 2 * 3 * 4);
+        assert!(rgba.chunks(4).all(|texel| texel == [255, 0, 0, 255]));
+        assert!(decode_to_rgba(COMPRESSED_RGB_S3TC_DXT1, 8, 4, &block).is_err());
+        assert!(decode_to_rgba(COMPRESSED_RGBA_BPTC_UNORM, 4, 4, &[0; 16]).is_err());
+        assert!(decode_to_rgba(COMPRESSED_RGB_S3TC_DXT1, u32::MAX, u32::MAX, &block).is_err());
+    }
+
+    #[test]
+    fn decode_arbitrary_data() {
+        // Any data of the right size is valid, so pseudo-random data is decoded without errors
+        let mut state = 1u32;
+        let data: Vec<u8> = (0..16 * 4)
+            .map(|_| {
+                state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                (state >> 24) as u8
+            })
+            .collect();
+        for internal_format in [
+            COMPRESSED_RGB_S3TC_DXT1,
+            COMPRESSED_RGBA_S3TC_DXT1,
+            COMPRESSED_RGBA_S3TC_DXT3,
+            COMPRESSED_RGBA_S3TC_DXT5,
+            COMPRESSED_RED_RGTC1,
+            COMPRESSED_RG_RGTC2,
+            COMPRESSED_R11_EAC,
+            COMPRESSED_RG11_EAC,
+            COMPRESSED_RGB8_ETC2,
+            COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2,
+            COMPRESSED_RGBA8_ETC2_EAC,
+        ] {
+            let rgba = decode_to_rgba(internal_format, 7, 5, &data).unwrap();
+            assert_eq!(rgba.len(), 7 * 5 * 4);
+            assert!(decode_to_rgba(internal_format, 7, 5, &data[..data.len() / 2 - 1]).is_err());
+        }
+    }
+}
@@ -9,7 +9,11 @@ pub struct Texture2D {
     width: u32,
     height: u32,
     number_of_mip_maps: u32,
+    min_filter: Interpolation,
+    mipmap: Option<Mipmap>,
     data_byte_size: usize,
+    internal_format: u32,
+    data_type: u32,
 }
 
 impl Texture2D {
@@ -60,6 +64,8 @@ impl Texture2D {
     /// (for example, if [u8; 4] is specified, the format is RGBA and the data type is byte).
     ///
     /// **Note:** Mip maps will not be generated for RGB16F and RGB32F format, even if `mip_map_filter` is specified.
+    /// This constructor does not panic if the format cannot be rendered into or sampled on this device, instead rendering into or sampling the texture fails silently,
+    /// so use [Texture2D::try_new_empty] to get an error in that case.
     ///
     pub fn new_empty<T: TextureDataType>(
         context: &Context,
@@ -79,7 +85,11 @@ impl Texture2D {
             width,
             height,
             number_of_mip_maps,
+            min_filter,
+            mipmap,
             data_byte_size: std::mem::size_of::<T>(),
+            internal_format: T::internal_format(),
+            data_type: T::data_type(),
         };
         texture.bind();
         set_parameters(
@@ -109,19 +119,174 @@ impl Texture2D {
         texture
     }
 
+    ///
+    /// Constructs a new texture from the content of a [KTX2](https://www.khronos.org/ktx/) file, usually containing GPU compressed data, for example BC7, ETC2 or ASTC,
+    /// which is uploaded directly to the GPU without decompression. This reduces both the load time and the memory usage compared to for example PNG and JPEG images.
+    /// The mip maps in the file are used if present, otherwise the texture has no mip maps.
+    ///
+    /// If the compressed format is not supported on this device, the data is decoded into an uncompressed RGBA8 texture instead, which is possible for the S3TC (BC1-3), RGTC (BC4-5), ETC2 and EAC formats.
+    /// Returns an error if the file cannot be parsed, for example because it is truncated or corrupt, or if the format is neither supported on this device nor possible to decode, which is the case for BPTC (BC6-7) and ASTC.
+    /// KTX2 files with Basis Universal ETC1S data are transcoded to BC1, or to BC3 if they have an alpha channel, if S3TC is supported on this device.
+    /// Otherwise, they are transcoded to ETC2, or to RGBA8 if they have an alpha channel, while Basis Universal UASTC and Zstandard supercompressed files are not supported.
+    ///
+    /// **Note:** The data is not flipped as it is when constructing a texture from a [CpuTexture],
+    /// so the image should be stored bottom-up, otherwise the texture can be flipped by setting the uv transformation when using the texture.
+    /// Also, a compressed texture cannot be rendered into and it is reallocated with an uncompressed format when filled with new data using [Texture2D::fill] or [Texture2D::update].
+    ///
+    pub fn new_ktx2(
+        context: &Context,
+        bytes: &[u8],
+        wrap_s: Wrapping,
+        wrap_t: Wrapping,
+    ) -> Result<Self, CoreError> {
+        let mut ktx2 = Ktx2::parse(bytes, |format| Ktx2::is_format_supported(context, format))?;
+        if !ktx2.is_supported(context) {
+            ktx2 = ktx2.decode()?;
+        }
+        let number_of_mip_maps = ktx2.levels.len() as u32;
+        let mipmap = (number_of_mip_maps > 1).then_some(Mipmap {
+            filter: Interpolation::Linear,
+            max_levels: number_of_mip_maps,
+            max_ratio: 1,
+        });
+        let texture = Self {
+            context: context.clone(),
+            id: generate(context),
+            width: ktx2.width,
+            height: ktx2.height,
+            number_of_mip_maps,
+            min_filter: Interpolation::Linear,
+            mipmap,
+            // Compressed data has no data type, see Texture2D::fill
+            data_byte_size: if ktx2.compressed { 0 } else { 4 },
+            internal_format: ktx2.internal_format,
+            data_type: if ktx2.compressed {
+                0
+            } else {
+                crate::context::UNSIGNED_BYTE
+            },
+        };
+        texture.bind();
+        set_parameters(
+            context,
+            crate::context::TEXTURE_2D,
+            Interpolation::Linear,
+            Interpolation::Linear,
+            mipmap,
+            wrap_s,
+            wrap_t,
+            None,
+        );
+        unsafe {
+            context.tex_parameter_i32(
+                crate::context::TEXTURE_2D,
+                crate::context::TEXTURE_MAX_LEVEL,
+                number_of_mip_maps as i32 - 1,
+            );
+            context.tex_storage_2d(
+                crate::context::TEXTURE_2D,
+                number_of_mip_maps as i32,
+                ktx2.internal_format,
+                ktx2.width as i32,
+                ktx2.height as i32,
+            );
+            context.statistics.texture_upload();
+            for (level, data) in ktx2.levels.iter().enumerate() {
+                let width = (ktx2.width >> level).max(1) as i32;
+                let height = (ktx2.height >> level).max(1) as i32;
+                if ktx2.compressed {
+                    context.compressed_tex_sub_image_2d(
+                        crate::context::TEXTURE_2D,
+                        level as i32,
+                        0,
+                        0,
+                        width,
+                        height,
+                        ktx2.internal_format,
+                        crate::context::CompressedPixelUnpackData::Slice(data),
+                    );
+                } else {
+                    context.tex_sub_image_2d(
+                        crate::context::TEXTURE_2D,
+                        level as i32,
+                        0,
+                        0,
+                        width,
+                        height,
+                        crate::context::RGBA,
+                        crate::context::UNSIGNED_BYTE,
+                        crate::context::PixelUnpackData::Slice(Some(data)),
+                    );
+                }
+            }
+        }
+        Ok(texture)
+    }
+
+    ///
+    /// Constructs a new texture from the file at the given path in the loaded assets, see [three_d_asset::io::load].
+    /// If the file is a KTX2 file, the texture is constructed using [Texture2D::new_ktx2] with repeat wrapping,
+    /// otherwise the file is deserialized into a [CpuTexture] which is then used to construct the texture.
+    ///
+    pub fn new_from_assets(
+        context: &Context,
+        raw_assets: &mut three_d_asset::io::RawAssets,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<Self, CoreError> {
+        let bytes = raw_assets.get(path.as_ref())?;
+        if Ktx2::is_ktx2(bytes) {
+            Self::new_ktx2(context, bytes, Wrapping::Repeat, Wrapping::Repeat)
+        } else {
+            let cpu_texture: CpuTexture = raw_assets.deserialize(path)?;
+            Ok(Self::new(context, &cpu_texture))
+        }
+    }
+
+    ///
+    /// Same as [Texture2D::new_empty] except that it returns an error if the texture cannot be rendered into or sampled as specified on this device, see [Context::capabilities].
+    /// For example, rendering into a `[f16; 4]` texture on web requires the `EXT_color_buffer_float` extension,
+    /// and without this check, rendering into the texture fails silently.
+    ///
+    pub fn try_new_empty<T: TextureDataType>(
+        context: &Context,
+        width: u32,
+        height: u32,
+        min_filter: Interpolation,
+        mag_filter: Interpolation,
+        mipmap: Option<Mipmap>,
+        wrap_s: Wrapping,
+        wrap_t: Wrapping,
+    ) -> Result<Self, CoreError> {
+        let linear = min_filter == Interpolation::Linear
+            || mag_filter == Interpolation::Linear
+            || mipmap.is_some_and(|m| m.filter == Interpolation::Linear);
+        context
+            .capabilities()
+            .check_color_texture::<T>(width, height, linear)?;
+        Ok(Self::new_empty::<T>(
+            context, width, height, min_filter, mag_filter, mipmap, wrap_s, wrap_t,
+        ))
+    }
+
     ///
     /// Fills this texture with the given data and generate mip maps if specified at construction.
+    /// If the texture contains compressed data, see [Texture2D::new_ktx2], it is first reallocated with the format of the given data.
     ///
     /// # Panic
     /// Will panic if the length of the data does not correspond to the width, height and format specified at construction.
     /// It is therefore necessary to create a new texture if the texture size or format has changed.
     ///
     pub fn fill<T: TextureDataType>(&mut self, data: &[T]) {
+        if self.data_byte_size == 0 {
+            // Compressed textures cannot be filled with uncompressed data
+            self.reallocate::<T>(self.width, self.height);
+        }
         check_data_length::<T>(self.width, self.height, 1, self.data_byte_size, data.len());
         self.bind();
         let mut data = data.to_owned();
         flip_y(&mut data, self.width as usize, self.height as usize);
         unsafe {
+            self.context.statistics.texture_upload();
             self.context.tex_sub_image_2d(
                 crate::context::TEXTURE_2D,
                 0,
@@ -137,11 +302,120 @@ impl Texture2D {
         self.generate_mip_maps();
     }
 
+    ///
+    /// Updates this texture with the given data of the given size, for example each frame of a video or a webcam feed.
+    /// If the size and the format are the same as for the current data, the data is uploaded to the existing texture without reallocating, which is the same as calling [Texture2D::fill].
+    /// Otherwise, the texture is reallocated with the new size and data type while keeping the interpolation, wrapping and mip map settings.
+    ///
+    /// # Panic
+    /// Will panic if the length of the data does not correspond to the given width and height.
+    ///
+    pub fn update<T: TextureDataType>(&mut self, data: &[T], width: u32, height: u32) {
+        if width != self.width || height != self.height || !self.has_format::<T>() {
+            self.reallocate::<T>(width, height);
+        }
+        self.fill(data);
+    }
+
+    ///
+    /// Updates this texture with the current frame of the given video element, see [Texture2D::update].
+    /// The texture is reallocated if the size of the video is different from the size of this texture.
+    /// Nothing happens if the video has no data yet.
+    ///
+    #[cfg(all(target_arch = "wasm32", feature = "web-sys"))]
+    pub fn update_from_video(&mut self, video: &web_sys::HtmlVideoElement) {
+        let (width, height) = (video.video_width(), video.video_height());
+        if width == 0 || height == 0 {
+            return;
+        }
+        if width != self.width || height != self.height || !self.has_format::<[u8; 4]>() {
+            self.reallocate::<[u8; 4]>(width, height);
+        }
+        // Not defined in the context module since it is a WebGL specific constant
+        const UNPACK_FLIP_Y_WEBGL: u32 = 0x9240;
+        self.bind();
+        unsafe {
+            self.context.statistics.texture_upload();
+            // The video is flipped like the data when filling the texture
+            self.context.pixel_store_bool(UNPACK_FLIP_Y_WEBGL, true);
+            self.context.tex_sub_image_2d_with_html_video(
+                crate::context::TEXTURE_2D,
+                0,
+                0,
+                0,
+                crate::context::RGBA,
+                crate::context::UNSIGNED_BYTE,
+                video,
+            );
+            self.context.pixel_store_bool(UNPACK_FLIP_Y_WEBGL, false);
+        }
+        self.generate_mip_maps();
+    }
+
+    ///
+    /// Reallocates the texture with the given size and data type while keeping the interpolation, wrapping and mip map settings.
+    ///
+    fn reallocate<T: TextureDataType>(&mut self, width: u32, height: u32) {
+        let id = generate(&self.context);
+        let number_of_mip_maps =
+            calculate_number_of_mip_maps::<T>(self.mipmap, width, height, None);
+        self.bind();
+        unsafe {
+            let parameters = [
+                crate::context::TEXTURE_MAG_FILTER,
+                crate::context::TEXTURE_WRAP_S,
+                crate::context::TEXTURE_WRAP_T,
+            ]
+            .map(|parameter| {
+                (
+                    parameter,
+                    self.context
+                        .get_tex_parameter_i32(crate::context::TEXTURE_2D, parameter),
+                )
+            });
+            let anisotropy = self.anisotropy();
+            self.context.delete_texture(self.id);
+            self.id = id;
+            self.bind();
+            for (parameter, value) in parameters {
+                self.context
+                    .tex_parameter_i32(crate::context::TEXTURE_2D, parameter, value);
+            }
+            // The min filter depends on whether the texture has mip maps, which can change with the size
+            set_min_filter(
+                &self.context,
+                crate::context::TEXTURE_2D,
+                self.min_filter,
+                (number_of_mip_maps > 1).then_some(self.mipmap).flatten(),
+            );
+            if anisotropy > 1 {
+                self.set_anisotropy(anisotropy);
+            }
+            self.context.tex_storage_2d(
+                crate::context::TEXTURE_2D,
+                number_of_mip_maps as i32,
+                T::internal_format(),
+                width as i32,
+                height as i32,
+            );
+        }
+        self.width = width;
+        self.height = height;
+        self.number_of_mip_maps = number_of_mip_maps;
+        self.data_byte_size = std::mem::size_of::<T>();
+        self.internal_format = T::internal_format();
+        self.data_type = T::data_type();
+    }
+
+    fn has_format<T: TextureDataType>(&self) -> bool {
+        self.internal_format == T::internal_format() && self.data_type == T::data_type()
+    }
+
     ///
     /// Returns a [ColorTarget] which can be used to clear, write to and read from the given mip level of this texture.
     /// Combine this together with a [DepthTarget] with [RenderTarget::new] to be able to write to both a depth and color target at the same time.
     /// If `None` is specified as the mip level, the 0 level mip level is used and mip maps are generated after a write operation if a mip map filter is specified.
-    /// Otherwise, the given mip level is used and no mip maps are generated.
+    /// Otherwise, the given mip level is used and no mip maps are generated, see [Texture2D::generate_mip_maps].
     ///
     /// **Note:** [DepthTest] is disabled if not also writing to a depth texture.
     ///
@@ -164,7 +438,40 @@ impl Texture2D {
         self.number_of_mip_maps
     }
 
-    pub(crate) fn generate_mip_maps(&self) {
+    ///
+    /// Sets the maximum ratio of anisotropy used when sampling this texture, which improves the quality when the texture is viewed at grazing angles, for example a ground texture.
+    /// This is independent of the [Mipmap] settings given at construction but is most effective with mip maps.
+    /// The ratio is clamped to the maximum supported by the hardware, see [Capabilities::max_anisotropy], and the applied ratio is returned.
+    /// If anisotropic filtering is not supported, nothing happens and 1 is returned.
+    ///
+    pub fn set_anisotropy(&self, max_ratio: u32) -> u32 {
+        self.bind();
+        set_anisotropy(&self.context, crate::context::TEXTURE_2D, max_ratio)
+    }
+
+    ///
+    /// Returns the maximum ratio of anisotropy used when sampling this texture, see [Texture2D::set_anisotropy].
+    /// Returns 1 if anisotropic filtering is not supported.
+    ///
+    pub fn anisotropy(&self) -> u32 {
+        if !is_anisotropy_supported(&self.context) {
+            return 1;
+        }
+        self.bind();
+        unsafe {
+            self.context.get_tex_parameter_f32(
+                crate::context::TEXTURE_2D,
+                crate::context::TEXTURE_MAX_ANISOTROPY_EXT,
+            ) as u32
+        }
+    }
+
+    ///
+    /// Generates the mip maps from the 0 level mip level, if this texture has mip maps.
+    /// This is done automatically after writing to the texture using a [ColorTarget] with `None` as mip level or when filling the texture,
+    /// so it is only necessary after writing to a specific mip level, for example the 0 level mip level of a dynamic reflection.
+    ///
+    pub fn generate_mip_maps(&self) {
         if self.number_of_mip_maps > 1 {
             self.bind();
             unsafe {
@@ -207,3 +514,135 @@ impl Drop for Texture2D {
         }
     }
 }
+
+#[cfg(all(test, feature = "headless", not(target_arch = "wasm32")))]
+mod tests {
+    use crate::core::*;
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn fill_compressed() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        // A 4x4 BC1 file with a single red block
+        let mut bytes = vec![
+            0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+        ];
+        for value in [131u32, 1, 4, 4, 0, 0, 1, 1, 0] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.resize(80, 0);
+        for value in [104u64, 8, 8] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[0x00, 0xF8, 0x00, 0xF8, 0, 0, 0, 0]);
+        let mut texture = Texture2D::new_ktx2(
+            &context,
+            &bytes,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        )
+        .unwrap();
+
+        // The compressed texture is reallocated with an uncompressed format before it is filled
+        texture.fill(&[[0u8, 255, 0, 255]; 16]);
+        assert!(texture.has_format::<[u8; 4]>());
+        let pixels = texture.as_color_target(None).read::<[u8; 4]>();
+        assert!(pixels.iter().all(|pixel| *pixel == [0, 255, 0, 255]));
+    }
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn update_keeps_mip_map_settings() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let new_texture = |size: u32, mipmap: Option<Mipmap>| {
+            Texture2D::new_empty::<[u8; 4]>(
+                &context,
+                size,
+                size,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                mipmap,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            )
+        };
+
+        // The maximum number of mip levels is kept when the size changes
+        let mut texture = new_texture(
+            64,
+            Some(Mipmap {
+                max_levels: 4,
+                ..Default::default()
+            }),
+        );
+        assert_eq!(texture.number_of_mip_maps(), 4);
+        texture.update(&vec![[0u8; 4]; 128 * 128], 128, 128);
+        assert_eq!(texture.number_of_mip_maps(), 4);
+
+        // A texture without mip maps because of its size gets mip maps when it grows
+        let mut texture = new_texture(1, Some(Mipmap::default()));
+        assert_eq!(texture.number_of_mip_maps(), 1);
+        texture.update(&vec![[0u8; 4]; 16 * 16], 16, 16);
+        assert_eq!(texture.number_of_mip_maps(), 4);
+
+        // A texture without mip maps does not get mip maps
+        let mut texture = new_texture(16, None);
+        texture.update(&vec![[0u8; 4]; 32 * 32], 32, 32);
+        assert_eq!(texture.number_of_mip_maps(), 1);
+    }
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn regenerate_mip_maps() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let mut texture = Texture2D::new_empty::<[u8; 4]>(
+            &context,
+            4,
+            4,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            Some(Mipmap::default()),
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        assert_eq!(texture.number_of_mip_maps(), 2);
+        texture
+            .as_color_target(Some(1))
+            .clear(ClearState::color(0.0, 1.0, 0.0, 1.0));
+
+        // Writing to a specific mip level leaves the other mip levels stale
+        let target = texture.as_color_target(Some(0));
+        target.clear(ClearState::color(0.0, 0.0, 1.0, 1.0));
+        target.clear_partially(
+            ScissorBox {
+                x: 0,
+                y: 0,
+                width: 1,
+                height: 4,
+            },
+            ClearState::color(1.0, 0.0, 0.0, 1.0),
+        );
+        let pixels = texture.as_color_target(Some(1)).read::<[u8; 4]>();
+        assert!(pixels.iter().all(|pixel| *pixel == [0, 255, 0, 255]));
+
+        // The lower mip level is the average of the mip level above after regenerating the mip maps,
+        // so the red column is blended with the neighbouring blue column
+        texture.generate_mip_maps();
+        let pixels = texture.as_color_target(Some(1)).read::<[u8; 4]>();
+        for row in pixels.chunks(2) {
+            assert!(row[0][0].abs_diff(128) <= 1 && row[0][1] == 0 && row[0][2].abs_diff(128) <= 1);
+            assert_eq!(row[1], [0, 0, 255, 255]);
+        }
+
+        // Writing to the texture without specifying a mip level regenerates the mip maps automatically
+        texture
+            .as_color_target(Some(0))
+            .clear(ClearState::color(1.0, 1.0, 1.0, 1.0));
+        texture
+            .as_color_target(None)
+            .write::<CoreError>(|| Ok(()))
+            .unwrap();
+        let pixels = texture.as_color_target(Some(1)).read::<[u8; 4]>();
+        assert!(pixels.iter().all(|pixel| *pixel == [255, 255, 255, 255]));
+    }
+}
@@ -232,6 +232,7 @@ impl Texture2DArray {
         let mut data = (*data).to_owned();
         flip_y(&mut data, self.width as usize, self.height as usize);
         unsafe {
+            self.context.statistics.texture_upload();
             self.context.tex_sub_image_3d(
                 crate::context::TEXTURE_2D_ARRAY,
                 0,
@@ -253,7 +254,7 @@ impl Texture2DArray {
     /// Returns a [ColorTarget] which can be used to clear, write to and read from the given layers and mip level of this texture.
     /// Combine this together with a [DepthTarget] with [RenderTarget::new] to be able to write to both a depth and color target at the same time.
     /// If `None` is specified as the mip level, the 0 level mip level is used and mip maps are generated after a write operation if a mip map filter is specified.
-    /// Otherwise, the given mip level is used and no mip maps are generated.
+    /// Otherwise, the given mip level is used and no mip maps are generated, see [Texture2DArray::generate_mip_maps].
     ///
     /// **Note:** [DepthTest] is disabled if not also writing to a depth texture.
     ///
@@ -265,6 +266,27 @@ impl Texture2DArray {
         ColorTarget::new_texture_2d_array(&self.context, self, layers, mip_level)
     }
 
+    ///
+    /// Returns a [ColorTarget] which can be used to clear, write to and read from a single layer and the given mip level of this texture.
+    /// This is the same as calling [Texture2DArray::as_color_target] with only one layer, except that it is not necessary to keep the layer index alive while using the color target.
+    /// If `None` is specified as the mip level, the 0 level mip level is used and mip maps are generated after a write operation if a mip map filter is specified.
+    /// Otherwise, the given mip level is used and no mip maps are generated, see [Texture2DArray::generate_mip_maps].
+    ///
+    /// **Note:** [DepthTest] is disabled if not also writing to a depth texture.
+    ///
+    /// # Panic
+    /// Will panic if the layer is not smaller than the number of layers in this texture array.
+    ///
+    pub fn as_color_target_layer(&mut self, layer: u32, mip_level: Option<u32>) -> ColorTarget<'_> {
+        if layer >= self.depth {
+            panic!(
+                "cannot render into the layer {}, since there are only {} layers in the texture array",
+                layer, self.depth
+            );
+        }
+        ColorTarget::new_texture_2d_array_layer(&self.context, self, layer, mip_level)
+    }
+
     /// The width of this texture.
     pub fn width(&self) -> u32 {
         self.width
@@ -285,7 +307,12 @@ impl Texture2DArray {
         self.number_of_mip_maps
     }
 
-    pub(in crate::core) fn generate_mip_maps(&self) {
+    ///
+    /// Generates the mip maps from the 0 level mip level, if this texture has mip maps.
+    /// This is done automatically after writing to the texture using a [ColorTarget] with `None` as mip level or when filling the texture,
+    /// so it is only necessary after writing to a specific mip level, for example the 0 level mip level of a dynamic reflection.
+    ///
+    pub fn generate_mip_maps(&self) {
         if self.number_of_mip_maps > 1 {
             self.bind();
             unsafe {
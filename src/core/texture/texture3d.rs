@@ -131,6 +131,7 @@ impl Texture3D {
         );
         self.bind();
         unsafe {
+            self.context.statistics.texture_upload();
             self.context.tex_sub_image_3d(
                 crate::context::TEXTURE_3D,
                 0,
@@ -168,7 +169,11 @@ impl Texture3D {
         self.number_of_mip_maps
     }
 
-    fn generate_mip_maps(&self) {
+    ///
+    /// Generates the mip maps from the 0 level mip level, if this texture has mip maps.
+    /// This is done automatically when filling the texture, so it is only necessary after modifying the texture using low-level calls.
+    ///
+    pub fn generate_mip_maps(&self) {
         if self.number_of_mip_maps > 1 {
             self.bind();
             unsafe {
@@ -76,6 +76,20 @@ impl CubeMapSide {
         }
     }
 
+    ///
+    /// Returns a slice containing only this side, which lives for as long as needed, for example when constructing a [ColorTarget] for this side.
+    ///
+    pub fn as_slice(&self) -> &'static [CubeMapSide] {
+        match self {
+            CubeMapSide::Right => &[CubeMapSide::Right],
+            CubeMapSide::Left => &[CubeMapSide::Left],
+            CubeMapSide::Top => &[CubeMapSide::Top],
+            CubeMapSide::Bottom => &[CubeMapSide::Bottom],
+            CubeMapSide::Front => &[CubeMapSide::Front],
+            CubeMapSide::Back => &[CubeMapSide::Back],
+        }
+    }
+
     /// The up direction that should be used when rendering into this cube map side.
     pub fn up(&self) -> Vec3 {
         match self {
@@ -422,6 +436,7 @@ impl TextureCubeMap {
                 _ => unreachable!(),
             };
             unsafe {
+                self.context.statistics.texture_upload();
                 self.context.tex_sub_image_2d(
                     crate::context::TEXTURE_CUBE_MAP_POSITIVE_X + i as u32,
                     0,
@@ -487,7 +502,7 @@ impl TextureCubeMap {
             for side in CubeMapSide::iter() {
                 let viewport = Viewport::new_at_origo(texture_size, texture_size);
                 texture
-                    .as_color_target(&[side], None)
+                    .as_color_target(side, None)
                     .clear(ClearState::default())
                     .write::<CoreError>(|| {
                         program.use_texture("equirectangularMap", &map);
@@ -505,12 +520,30 @@ impl TextureCubeMap {
     ///
     /// Returns a [ColorTarget] which can be used to clear, write to and read from the given side and mip level of this texture.
     /// Combine this together with a [DepthTarget] with [RenderTarget::new] to be able to write to both a depth and color target at the same time.
+    /// This makes it possible to only update some of the sides each frame, for example when spreading the cost of updating a dynamic reflection over several frames.
+    /// Use the partial functions on the color target, for example [ColorTarget::clear_partially] and [ColorTarget::write_partially], to only update a region of the side.
     /// If `None` is specified as the mip level, the 0 level mip level is used and mip maps are generated after a write operation if a mip map filter is specified.
-    /// Otherwise, the given mip level is used and no mip maps are generated.
+    /// Otherwise, the given mip level is used and no mip maps are generated, see [TextureCubeMap::generate_mip_maps].
     ///
     /// **Note:** [DepthTest] is disabled if not also writing to a depth texture.
     ///
-    pub fn as_color_target<'a>(
+    pub fn as_color_target(
+        &mut self,
+        side: CubeMapSide,
+        mip_level: Option<u32>,
+    ) -> ColorTarget<'_> {
+        ColorTarget::new_texture_cube_map(&self.context, self, side.as_slice(), mip_level)
+    }
+
+    ///
+    /// Returns a [ColorTarget] which can be used to clear, write to and read from the given sides and mip level of this texture.
+    /// Writing to several sides at the same time requires a fragment shader with an output for each side, see [TextureCubeMap::as_color_target] for writing to a single side.
+    /// If `None` is specified as the mip level, the 0 level mip level is used and mip maps are generated after a write operation if a mip map filter is specified.
+    /// Otherwise, the given mip level is used and no mip maps are generated, see [TextureCubeMap::generate_mip_maps].
+    ///
+    /// **Note:** [DepthTest] is disabled if not also writing to a depth texture.
+    ///
+    pub fn as_color_target_sides<'a>(
         &'a mut self,
         sides: &'a [CubeMapSide],
         mip_level: Option<u32>,
@@ -533,7 +566,12 @@ impl TextureCubeMap {
         self.number_of_mip_maps
     }
 
-    pub(in crate::core) fn generate_mip_maps(&self) {
+    ///
+    /// Generates the mip maps from the 0 level mip level, if this texture has mip maps.
+    /// This is done automatically after writing to the texture using a [ColorTarget] with `None` as mip level or when filling the texture,
+    /// so it is only necessary after writing to a specific mip level, for example the 0 level mip level of a dynamic reflection.
+    ///
+    pub fn generate_mip_maps(&self) {
         if self.number_of_mip_maps > 1 {
             self.bind();
             unsafe {
@@ -583,3 +621,73 @@ impl Drop for TextureCubeMap {
         }
     }
 }
+
+#[cfg(all(test, feature = "headless", not(target_arch = "wasm32")))]
+mod tests {
+    use crate::core::*;
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn render_into_single_side() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let size = 4;
+        let mut texture = TextureCubeMap::new_empty::<[u8; 4]>(
+            &context,
+            size,
+            size,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let program = Program::from_source(
+            &context,
+            full_screen_vertex_shader_source(),
+            "
+            layout (location = 0) out vec4 outColor;
+            void main()
+            {
+                outColor = vec4(1.0, 0.0, 0.0, 1.0);
+            }
+            ",
+        )
+        .unwrap();
+        for side in CubeMapSide::iter() {
+            texture
+                .as_color_target(side, None)
+                .clear(ClearState::color(0.0, 0.0, 0.0, 1.0));
+        }
+
+        // Render red into the front side and clear the lower left quarter of the top side to green
+        let viewport = Viewport::new_at_origo(size, size);
+        texture
+            .as_color_target(CubeMapSide::Front, None)
+            .write::<CoreError>(|| {
+                full_screen_draw(&context, &program, RenderStates::default(), viewport);
+                Ok(())
+            })
+            .unwrap();
+        texture
+            .as_color_target(CubeMapSide::Top, None)
+            .clear_partially(
+                ScissorBox::new_at_origo(size / 2, size / 2),
+                ClearState::color(0.0, 1.0, 0.0, 1.0),
+            );
+
+        for side in CubeMapSide::iter() {
+            let pixels = texture.as_color_target(side, None).read::<[u8; 4]>();
+            for (i, pixel) in pixels.iter().enumerate() {
+                // The first row of pixels is the top row
+                let (x, y) = (i as u32 % size, size - 1 - i as u32 / size);
+                let expected = match side {
+                    CubeMapSide::Front => [255, 0, 0, 255],
+                    CubeMapSide::Top if x < size / 2 && y < size / 2 => [0, 255, 0, 255],
+                    _ => [0, 0, 0, 255],
+                };
+                assert_eq!(*pixel, expected, "pixel {} of side {:?}", i, side);
+            }
+        }
+    }
+}
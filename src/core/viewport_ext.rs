@@ -0,0 +1,188 @@
+use crate::core::*;
+
+///
+/// Additional functionality for splitting a [Viewport] into several viewports, for example for split-screen rendering or multiple camera views.
+/// All sizes are given in physical pixels.
+///
+pub trait ViewportExt {
+    ///
+    /// Splits this viewport into a left and a right viewport placed next to each other with a gap of `gutter` pixels in between.
+    /// The `ratio` is the fraction of the available width (the width minus the gutter) given to the left viewport and is clamped to the range `[0..1]`.
+    ///
+    fn split_horizontal(&self, ratio: f32, gutter: u32) -> (Viewport, Viewport);
+
+    ///
+    /// Splits this viewport into a top and a bottom viewport placed on top of each other with a gap of `gutter` pixels in between.
+    /// The `ratio` is the fraction of the available height (the height minus the gutter) given to the top viewport and is clamped to the range `[0..1]`.
+    ///
+    fn split_vertical(&self, ratio: f32, gutter: u32) -> (Viewport, Viewport);
+
+    ///
+    /// Splits this viewport into a grid of `columns` times `rows` equally sized viewports with a gap of `gutter` pixels in between.
+    /// The viewports are returned row by row starting in the top left corner.
+    /// Any pixels left over when dividing the available size are given to the last column and row.
+    ///
+    fn grid(&self, columns: u32, rows: u32, gutter: u32) -> Vec<Viewport>;
+}
+
+impl ViewportExt for Viewport {
+    fn split_horizontal(&self, ratio: f32, gutter: u32) -> (Viewport, Viewport) {
+        let (left, right) = split(self.width, ratio, gutter);
+        (
+            Viewport {
+                x: self.x,
+                y: self.y,
+                width: left,
+                height: self.height,
+            },
+            Viewport {
+                x: self.x + (self.width - right) as i32,
+                y: self.y,
+                width: right,
+                height: self.height,
+            },
+        )
+    }
+
+    fn split_vertical(&self, ratio: f32, gutter: u32) -> (Viewport, Viewport) {
+        let (top, bottom) = split(self.height, ratio, gutter);
+        (
+            Viewport {
+                x: self.x,
+                y: self.y + (self.height - top) as i32,
+                width: self.width,
+                height: top,
+            },
+            Viewport {
+                x: self.x,
+                y: self.y,
+                width: self.width,
+                height: bottom,
+            },
+        )
+    }
+
+    fn grid(&self, columns: u32, rows: u32, gutter: u32) -> Vec<Viewport> {
+        let columns = columns.max(1);
+        let rows = rows.max(1);
+        let cell_width = self.width.saturating_sub(gutter * (columns - 1)) / columns;
+        let cell_height = self.height.saturating_sub(gutter * (rows - 1)) / rows;
+        let mut viewports = Vec::with_capacity((columns * rows) as usize);
+        for row in 0..rows {
+            // The last row is at the bottom and receives the leftover pixels
+            let offset_from_top = row * (cell_height + gutter);
+            let height = if row == rows - 1 {
+                self.height.saturating_sub(offset_from_top)
+            } else {
+                cell_height
+            };
+            let y = self.y + self.height.saturating_sub(offset_from_top + height) as i32;
+            for column in 0..columns {
+                let x_offset = column * (cell_width + gutter);
+                let width = if column == columns - 1 {
+                    self.width.saturating_sub(x_offset)
+                } else {
+                    cell_width
+                };
+                viewports.push(Viewport {
+                    x: self.x + x_offset as i32,
+                    y,
+                    width,
+                    height,
+                });
+            }
+        }
+        viewports
+    }
+}
+
+///
+/// Splits the given size into two sizes which, together with the gutter, sum to the given size (if the size is larger than the gutter).
+///
+fn split(size: u32, ratio: f32, gutter: u32) -> (u32, u32) {
+    let available = size.saturating_sub(gutter);
+    let first = (available as f32 * ratio.clamp(0.0, 1.0)).round() as u32;
+    (first, available - first)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VIEWPORT: Viewport = Viewport {
+        x: 10,
+        y: 20,
+        width: 301,
+        height: 200,
+    };
+
+    fn area(viewport: &Viewport) -> u32 {
+        viewport.width * viewport.height
+    }
+
+    #[test]
+    fn split_horizontal() {
+        let (left, right) = VIEWPORT.split_horizontal(0.25, 5);
+        assert_eq!(left.width + right.width + 5, VIEWPORT.width);
+        assert_eq!(
+            area(&left) + area(&right),
+            area(&VIEWPORT) - 5 * VIEWPORT.height
+        );
+        assert_eq!((left.x, left.y, left.width, left.height), (10, 20, 74, 200));
+        assert_eq!(right.x, left.x + left.width as i32 + 5);
+        assert_eq!(
+            right.x + right.width as i32,
+            VIEWPORT.x + VIEWPORT.width as i32
+        );
+
+        let (left, right) = VIEWPORT.split_horizontal(2.0, 5);
+        assert_eq!((left.width, right.width), (296, 0));
+    }
+
+    #[test]
+    fn split_vertical() {
+        let (top, bottom) = VIEWPORT.split_vertical(0.5, 4);
+        assert_eq!(top.height + bottom.height + 4, VIEWPORT.height);
+        assert_eq!(
+            area(&top) + area(&bottom),
+            area(&VIEWPORT) - 4 * VIEWPORT.width
+        );
+        assert_eq!(
+            (bottom.x, bottom.y, bottom.width, bottom.height),
+            (10, 20, 301, 98)
+        );
+        assert_eq!(top.y, bottom.y + bottom.height as i32 + 4);
+        assert_eq!(
+            top.y + top.height as i32,
+            VIEWPORT.y + VIEWPORT.height as i32
+        );
+    }
+
+    #[test]
+    fn grid() {
+        let gutter = 3;
+        let viewports = VIEWPORT.grid(3, 2, gutter);
+        assert_eq!(viewports.len(), 6);
+        let total: u32 = viewports.iter().map(area).sum();
+        let gutters = 2 * gutter * VIEWPORT.height + gutter * VIEWPORT.width - 2 * gutter * gutter;
+        assert_eq!(total, area(&VIEWPORT) - gutters);
+
+        // The first viewport is in the top left corner and the last in the bottom right corner
+        let (first, last) = (viewports[0], viewports[5]);
+        assert_eq!(first.x, VIEWPORT.x);
+        assert_eq!(
+            first.y + first.height as i32,
+            VIEWPORT.y + VIEWPORT.height as i32
+        );
+        assert_eq!(
+            last.x + last.width as i32,
+            VIEWPORT.x + VIEWPORT.width as i32
+        );
+        assert_eq!(last.y, VIEWPORT.y);
+        assert_eq!(viewports[1].x, first.x + first.width as i32 + gutter as i32);
+        assert_eq!(
+            viewports[3].y + viewports[3].height as i32 + gutter as i32,
+            first.y
+        );
+    }
+}
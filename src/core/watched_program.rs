@@ -0,0 +1,98 @@
+use crate::core::*;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+///
+/// A [Program] created from vertex and fragment shader files which is recompiled when the files are changed,
+/// so that shaders can be edited while the application is running, for example when developing a custom shader effect.
+/// Call [WatchedProgram::reload_if_changed] once each frame to check the files for changes.
+/// To recompile the shaders used by a [Material](crate::renderer::Material) or [Effect](crate::renderer::Effect), use [Context::reload_shaders] instead.
+/// If the changed shaders fail to compile, the last working program is kept and the error is returned, so the application does not crash.
+///
+/// The shader files should not contain the `#version` directive, since it is added when compiling, see [Program::from_source].
+/// Only available on desktop.
+///
+pub struct WatchedProgram {
+    context: Context,
+    vertex_shader_path: PathBuf,
+    fragment_shader_path: PathBuf,
+    modified: (Option<SystemTime>, Option<SystemTime>),
+    program: Program,
+}
+
+impl WatchedProgram {
+    ///
+    /// Creates a new program from the vertex and fragment shader source in the given files and watches the files for changes.
+    /// Returns an error if the files cannot be read or if the shaders fail to compile.
+    ///
+    pub fn new(
+        context: &Context,
+        vertex_shader_path: impl AsRef<Path>,
+        fragment_shader_path: impl AsRef<Path>,
+    ) -> Result<Self, CoreError> {
+        let vertex_shader_path = vertex_shader_path.as_ref().to_path_buf();
+        let fragment_shader_path = fragment_shader_path.as_ref().to_path_buf();
+        let modified = (
+            modified_time(&vertex_shader_path),
+            modified_time(&fragment_shader_path),
+        );
+        let program = Program::from_files(context, &vertex_shader_path, &fragment_shader_path)?;
+        Ok(Self {
+            context: context.clone(),
+            vertex_shader_path,
+            fragment_shader_path,
+            modified,
+            program,
+        })
+    }
+
+    ///
+    /// Recompiles the program if any of the shader files have been modified since the last time they were compiled.
+    /// Returns `true` if the program was recompiled, in which case all uniforms, textures and attributes must be set again before drawing.
+    /// If the modified shaders fail to compile, the error is returned and the last working program is kept.
+    /// The files are not compiled again until they are modified again.
+    ///
+    pub fn reload_if_changed(&mut self) -> Result<bool, CoreError> {
+        let modified = (
+            modified_time(&self.vertex_shader_path),
+            modified_time(&self.fragment_shader_path),
+        );
+        if modified == self.modified {
+            return Ok(false);
+        }
+        self.modified = modified;
+        self.reload()?;
+        Ok(true)
+    }
+
+    ///
+    /// Recompiles the program from the shader files regardless of whether they have been modified.
+    /// If the shaders fail to compile, the error is returned and the last working program is kept.
+    ///
+    pub fn reload(&mut self) -> Result<(), CoreError> {
+        self.program = Program::from_files(
+            &self.context,
+            &self.vertex_shader_path,
+            &self.fragment_shader_path,
+        )?;
+        Ok(())
+    }
+
+    ///
+    /// Returns the last successfully compiled program.
+    ///
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+}
+
+impl std::ops::Deref for WatchedProgram {
+    type Target = Program;
+    fn deref(&self) -> &Self::Target {
+        &self.program
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
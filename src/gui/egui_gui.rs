@@ -86,13 +86,19 @@ impl GUI {
                         handled,
                     } => {
                         if !handled {
-                            Some(egui::Event::Key {
-                                key: kind.into(),
-                                pressed: true,
-                                modifiers: modifiers.into(),
-                                repeat: false,
-                                physical_key: None,
-                            })
+                            if modifiers.command && *kind == Key::C {
+                                Some(egui::Event::Copy)
+                            } else if modifiers.command && *kind == Key::X {
+                                Some(egui::Event::Cut)
+                            } else {
+                                Some(egui::Event::Key {
+                                    key: kind.into(),
+                                    pressed: true,
+                                    modifiers: modifiers.into(),
+                                    repeat: false,
+                                    physical_key: None,
+                                })
+                            }
                         } else {
                             None
                         }
@@ -170,6 +176,7 @@ impl GUI {
                         }
                     }
                     Event::Text(text) => Some(egui::Event::Text(text.clone())),
+                    Event::Paste(text) => Some(egui::Event::Paste(text.clone())),
                     Event::MouseLeave => Some(egui::Event::PointerGone),
                     Event::MouseWheel {
                         delta,
@@ -240,6 +247,11 @@ impl GUI {
                     } => {
                         *handled = true;
                     }
+                    Event::PanGesture {
+                        ref mut handled, ..
+                    } => {
+                        *handled = true;
+                    }
                     _ => {}
                 }
             }
@@ -263,6 +275,19 @@ impl GUI {
         self.egui_context.wants_pointer_input() || self.egui_context.wants_keyboard_input()
     }
 
+    ///
+    /// Returns the text that is copied in the GUI defined in the [update](Self::update) function, for example when the user copies selected text,
+    /// or `None` if nothing is copied. Use this to copy the text to the system clipboard, for example using [Clipboard::set_text](crate::window::Clipboard::set_text).
+    /// Must be called after [update](Self::update) and before [render](Self::render).
+    ///
+    pub fn copied_text(&self) -> Option<String> {
+        self.output
+            .borrow()
+            .as_ref()
+            .map(|output| output.platform_output.copied_text.clone())
+            .filter(|text| !text.is_empty())
+    }
+
     ///
     /// Render the GUI defined in the [update](Self::update) function.
     /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
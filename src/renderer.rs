@@ -29,6 +29,8 @@ pub enum RendererError {
     InvalidBufferLength(String, usize, usize),
     #[error("the material {0} is required by the geometry {1} but could not be found")]
     MissingMaterial(String, String),
+    #[error("the mesh must have {0} to be able to compute {1}")]
+    MissingVertexData(String, String),
     #[cfg(feature = "text")]
     #[error("Failed to find font with index {0} in the given font collection")]
     MissingFont(u32),
@@ -58,6 +60,26 @@ pub use object::*;
 pub mod control;
 pub use control::*;
 
+mod nine_slice;
+#[doc(inline)]
+pub use nine_slice::*;
+
+mod sprite_sheet;
+#[doc(inline)]
+pub use sprite_sheet::*;
+
+mod pick_buffer;
+#[doc(inline)]
+pub use pick_buffer::*;
+
+mod debug_draw;
+#[doc(inline)]
+pub use debug_draw::*;
+
+mod occlusion_queries;
+#[doc(inline)]
+pub use occlusion_queries::*;
+
 #[cfg(feature = "text")]
 mod text;
 #[cfg(feature = "text")]
@@ -83,6 +105,8 @@ macro_rules! impl_render_target_extensions_body {
         /// Render the objects using the given viewer and lights into the part of this render target defined by the scissor box.
         /// Use an empty array for the `lights` argument, if the objects does not require lights to be rendered.
         /// Also, objects outside the viewer frustum are not rendered and the objects are rendered in the order given by [cmp_render_order].
+        /// Objects with a [MaterialType::Deferred] material are rendered first using deferred rendering,
+        /// then all other objects, including transparent objects, are rendered on top using forward rendering.
         ///
         pub fn render_partially(
             &self,
@@ -107,7 +131,18 @@ macro_rules! impl_render_target_extensions_body {
                     &self.context,
                     viewport.width,
                     viewport.height,
-                    3,
+                    2,
+                    Interpolation::Nearest,
+                    Interpolation::Nearest,
+                    None,
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
+                );
+                // The emissive color is stored in high precision to avoid clamping HDR values and thereby match forward rendering
+                let mut geometry_pass_emissive_texture = Texture2D::new_empty::<[f16; 4]>(
+                    &self.context,
+                    viewport.width,
+                    viewport.height,
                     Interpolation::Nearest,
                     Interpolation::Nearest,
                     None,
@@ -121,9 +156,12 @@ macro_rules! impl_render_target_extensions_body {
                     Wrapping::ClampToEdge,
                     Wrapping::ClampToEdge,
                 );
-                let gbuffer_layers = [0, 1, 2];
-                RenderTarget::new(
-                    geometry_pass_texture.as_color_target(&gbuffer_layers, None),
+                let gbuffer_layers = [0, 1];
+                RenderTarget::new_multiple(
+                    &[
+                        geometry_pass_texture.as_color_target(&gbuffer_layers, None),
+                        geometry_pass_emissive_texture.as_color_target(None),
+                    ],
                     geometry_pass_depth_texture.as_depth_target(),
                 )
                 .clear(ClearState::default())
@@ -138,7 +176,9 @@ macro_rules! impl_render_target_extensions_body {
                 // Lighting pass
                 self.apply_screen_effect_partially(
                     scissor_box,
-                    &lighting_pass::LightingPassEffect {},
+                    &lighting_pass::LightingPassEffect {
+                        emissive_texture: Some(&geometry_pass_emissive_texture),
+                    },
                     &viewer,
                     lights,
                     Some(ColorTexture::Array {
@@ -299,6 +339,68 @@ macro_rules! impl_render_target_extensions_body {
             self
         }
 
+        ///
+        /// Draws the given texture in a rectangle on top of what is already rendered, for example for HUD elements and overlays.
+        /// The rectangle is defined by the position of the bottom left corner and the size, both in physical pixels.
+        /// The texture colors are multiplied by the tint color and the result is blended using the alpha value.
+        ///
+        pub fn draw_image(
+            &self,
+            texture: &Texture2DRef,
+            position: impl Into<PhysicalPoint>,
+            width: f32,
+            height: f32,
+            tint: Srgba,
+        ) -> &Self {
+            let position = position.into();
+            let viewport = Viewport {
+                x: position.x.round() as i32,
+                y: position.y.round() as i32,
+                width: width.round().max(0.0) as u32,
+                height: height.round().max(0.0) as u32,
+            };
+            if viewport.width == 0 || viewport.height == 0 {
+                return self;
+            }
+            let material = ColorMaterial {
+                color: tint,
+                texture: Some(texture.clone()),
+                render_states: RenderStates {
+                    write_mask: WriteMask::COLOR,
+                    depth_test: DepthTest::Always,
+                    blend: Blend::TRANSPARENCY,
+                    ..Default::default()
+                },
+                is_transparent: true,
+                ..Default::default()
+            };
+            self.apply_screen_material(&material, Camera::new_2d(viewport), &[])
+        }
+
+        ///
+        /// Draws the given [NineSlice] as a panel on top of what is already rendered, for example for GUI elements.
+        /// The panel is defined by the position of the bottom left corner and the size, both in physical pixels.
+        /// If the panel is smaller than the borders, the borders are scaled down so they do not overlap.
+        ///
+        pub fn draw_nine_slice(
+            &self,
+            nine_slice: &NineSlice,
+            position: impl Into<PhysicalPoint>,
+            width: f32,
+            height: f32,
+        ) -> &Self {
+            for (rectangle, texture) in nine_slice.parts(position.into(), width, height) {
+                self.draw_image(
+                    &texture,
+                    vec2(rectangle[0], rectangle[1]),
+                    rectangle[2],
+                    rectangle[3],
+                    nine_slice.tint,
+                );
+            }
+            self
+        }
+
         ///
         /// Apply the given [Effect] to this render target.
         /// Use an empty array for the `lights` argument, if the effect does not require lights to be rendered.
@@ -387,6 +489,64 @@ impl_render_target_extensions!(
 impl_render_target_extensions!(ColorTargetMultisample<C: TextureDataType>);
 impl_render_target_extensions!(DepthTargetMultisample<D: DepthTextureDataType>);
 
+impl Context {
+    ///
+    /// Renders to a new color texture of the given size and returns the texture ready to be used in a material, for example a [ColorMaterial].
+    /// The texture is cleared using [ClearState::default] before the `render` closure is called with a [RenderTarget] writing to the texture.
+    /// The depth is written to a temporary depth texture, use [Context::render_to_texture_with_depth] to also get the depth texture.
+    ///
+    /// **Note:** The colors are written as they are output from the materials, so if the texture is used as input to another material,
+    /// you probably want to disable tone and color mapping on the camera while rendering to the texture, see [Camera::disable_tone_and_color_mapping].
+    ///
+    pub fn render_to_texture<T: TextureDataType>(
+        &self,
+        width: u32,
+        height: u32,
+        render: impl FnOnce(&RenderTarget),
+    ) -> Texture2DRef {
+        self.render_to_texture_with_depth::<T>(width, height, render)
+            .0
+    }
+
+    ///
+    /// Renders to a new color and depth texture of the given size and returns both textures.
+    /// See [Context::render_to_texture] for more information.
+    ///
+    pub fn render_to_texture_with_depth<T: TextureDataType>(
+        &self,
+        width: u32,
+        height: u32,
+        render: impl FnOnce(&RenderTarget),
+    ) -> (Texture2DRef, DepthTexture2D) {
+        let mut color_texture = Texture2D::new_empty::<T>(
+            self,
+            width,
+            height,
+            Interpolation::Linear,
+            Interpolation::Linear,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let mut depth_texture = DepthTexture2D::new::<f32>(
+            self,
+            width,
+            height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        {
+            let target = RenderTarget::new(
+                color_texture.as_color_target(None),
+                depth_texture.as_depth_target(),
+            );
+            target.clear(ClearState::default());
+            render(&target);
+        }
+        (Texture2DRef::from_texture(color_texture), depth_texture)
+    }
+}
+
 ///
 /// Combines shader ID components together into a single ID vector, to be used as a key in shader caching.
 ///
@@ -401,6 +561,45 @@ fn combine_ids(
     return id;
 }
 
+///
+/// Returns the cached program with the given id or compiles it from the given shader sources if it is not cached.
+/// If the program is outdated, see [Context::reload_shaders], it is recompiled and the last working program is kept if the compilation fails.
+///
+fn cached_program<'a>(
+    context: &Context,
+    programs: &'a mut std::collections::HashMap<Vec<u8>, Program>,
+    id: Vec<u8>,
+    vertex_shader_source: impl FnOnce() -> String,
+    fragment_shader_source: impl FnOnce() -> String,
+) -> &'a Program {
+    let has_outdated_programs = !context.outdated_programs.read().unwrap().is_empty();
+    let outdated = has_outdated_programs && context.outdated_programs.write().unwrap().remove(&id);
+    match programs.entry(id) {
+        std::collections::hash_map::Entry::Occupied(mut entry) => {
+            if outdated {
+                match Program::from_source(
+                    context,
+                    &vertex_shader_source(),
+                    &fragment_shader_source(),
+                ) {
+                    Ok(program) => {
+                        entry.insert(program);
+                    }
+                    Err(err) => context.shader_reload_errors.write().unwrap().push(err),
+                }
+            }
+            entry.into_mut()
+        }
+        std::collections::hash_map::Entry::Vacant(entry) => entry.insert(
+            match Program::from_source(context, &vertex_shader_source(), &fragment_shader_source())
+            {
+                Ok(program) => program,
+                Err(err) => panic!("{}", err.to_string()),
+            },
+        ),
+    }
+}
+
 ///
 /// Render the given [Geometry] with the given [Material].
 /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method.
@@ -413,19 +612,17 @@ pub fn render_with_material(
     material: impl Material,
     lights: &[&dyn Light],
 ) {
+    let lights = &enabled_lights(lights);
     let id = combine_ids(geometry.id(), material.id(), lights.iter().map(|l| l.id()));
 
     let mut programs = context.programs.write().unwrap();
-    let program = programs.entry(id).or_insert_with(|| {
-        match Program::from_source(
-            context,
-            &geometry.vertex_shader_source(),
-            &material.fragment_shader_source(lights),
-        ) {
-            Ok(program) => program,
-            Err(err) => panic!("{}", err.to_string()),
-        }
-    });
+    let program = cached_program(
+        context,
+        &mut programs,
+        id,
+        || geometry.vertex_shader_source(),
+        || material.fragment_shader_source(lights),
+    );
     material.use_uniforms(program, &viewer, lights);
     geometry.draw(&viewer, program, material.render_states());
 }
@@ -444,6 +641,7 @@ pub fn render_with_effect(
     color_texture: Option<ColorTexture>,
     depth_texture: Option<DepthTexture>,
 ) {
+    let lights = &enabled_lights(lights);
     let id = combine_ids(
         geometry.id(),
         effect.id(color_texture, depth_texture),
@@ -451,16 +649,13 @@ pub fn render_with_effect(
     );
 
     let mut programs = context.programs.write().unwrap();
-    let program = programs.entry(id).or_insert_with(|| {
-        match Program::from_source(
-            context,
-            &geometry.vertex_shader_source(),
-            &effect.fragment_shader_source(lights, color_texture, depth_texture),
-        ) {
-            Ok(program) => program,
-            Err(err) => panic!("{}", err.to_string()),
-        }
-    });
+    let program = cached_program(
+        context,
+        &mut programs,
+        id,
+        || geometry.vertex_shader_source(),
+        || effect.fragment_shader_source(lights, color_texture, depth_texture),
+    );
     effect.use_uniforms(program, &viewer, lights, color_texture, depth_texture);
     geometry.draw(&viewer, program, effect.render_states());
 }
@@ -476,6 +671,7 @@ pub fn apply_screen_material(
     viewer: impl Viewer,
     lights: &[&dyn Light],
 ) {
+    let lights = &enabled_lights(lights);
     let id = combine_ids(
         GeometryId::Screen,
         material.id(),
@@ -483,16 +679,13 @@ pub fn apply_screen_material(
     );
 
     let mut programs = context.programs.write().unwrap();
-    let program = programs.entry(id).or_insert_with(|| {
-        match Program::from_source(
-            context,
-            full_screen_vertex_shader_source(),
-            &material.fragment_shader_source(lights),
-        ) {
-            Ok(program) => program,
-            Err(err) => panic!("{}", err.to_string()),
-        }
-    });
+    let program = cached_program(
+        context,
+        &mut programs,
+        id,
+        || full_screen_vertex_shader_source().to_owned(),
+        || material.fragment_shader_source(lights),
+    );
     material.use_uniforms(program, &viewer, lights);
     full_screen_draw(
         context,
@@ -515,6 +708,7 @@ pub fn apply_screen_effect(
     color_texture: Option<ColorTexture>,
     depth_texture: Option<DepthTexture>,
 ) {
+    let lights = &enabled_lights(lights);
     let id = combine_ids(
         GeometryId::Screen,
         effect.id(color_texture, depth_texture),
@@ -522,23 +716,52 @@ pub fn apply_screen_effect(
     );
 
     let mut programs = context.programs.write().unwrap();
-    let program = programs.entry(id).or_insert_with(|| {
-        match Program::from_source(
-            context,
-            full_screen_vertex_shader_source(),
-            &effect.fragment_shader_source(lights, color_texture, depth_texture),
-        ) {
-            Ok(program) => program,
-            Err(err) => panic!("{}", err.to_string()),
-        }
-    });
+    let program = cached_program(
+        context,
+        &mut programs,
+        id,
+        || full_screen_vertex_shader_source().to_owned(),
+        || effect.fragment_shader_source(lights, color_texture, depth_texture),
+    );
     effect.use_uniforms(program, &viewer, lights, color_texture, depth_texture);
     full_screen_draw(context, program, effect.render_states(), viewer.viewport());
 }
 
 ///
-/// Compare function for sorting objects based on distance from the viewer.
-/// The order is opaque objects from nearest to farthest away from the viewer,
+/// Uses the `Camera` uniform block, which is shared between all geometries and contains the view projection matrix of the given viewer, in the given program.
+/// The uniform buffer is only updated when the view projection matrix is different from the last time it was used.
+///
+pub(in crate::renderer) fn use_camera_uniform_block(
+    context: &Context,
+    program: &Program,
+    viewer: &dyn Viewer,
+) {
+    let view_projection = viewer.projection() * viewer.view();
+    let data: &[f32; 16] = view_projection.as_ref();
+    let mut uniform_buffers = context.uniform_buffers.write().unwrap();
+    let buffer = uniform_buffers.entry("Camera").or_insert_with(|| {
+        UniformBuffer::new(context, &[16]).expect("a mat4 is a valid uniform buffer variable")
+    });
+    if buffer.get(0) != Some(data.as_slice()) {
+        buffer
+            .update(0, data)
+            .expect("a mat4 is a valid uniform buffer variable");
+    }
+    program.use_uniform_block("Camera", buffer);
+}
+
+fn enabled_lights<'a, 'b>(lights: &'a [&'b dyn Light]) -> std::borrow::Cow<'a, [&'b dyn Light]> {
+    if lights.iter().all(|l| l.is_enabled()) {
+        std::borrow::Cow::Borrowed(lights)
+    } else {
+        std::borrow::Cow::Owned(lights.iter().copied().filter(|l| l.is_enabled()).collect())
+    }
+}
+
+///
+/// Compare function for sorting objects based on the render order and distance from the viewer.
+/// The objects are first sorted by their [Object::render_order], from lowest to highest.
+/// Objects with the same render order are sorted as opaque objects from nearest to farthest away from the viewer,
 /// then transparent objects from farthest away to closest to the viewer.
 ///
 pub fn cmp_render_order(
@@ -546,7 +769,9 @@ pub fn cmp_render_order(
     obj0: impl Object,
     obj1: impl Object,
 ) -> std::cmp::Ordering {
-    if obj0.material_type() == MaterialType::Transparent
+    if obj0.render_order() != obj1.render_order() {
+        obj0.render_order().cmp(&obj1.render_order())
+    } else if obj0.material_type() == MaterialType::Transparent
         && obj1.material_type() != MaterialType::Transparent
     {
         std::cmp::Ordering::Greater
@@ -567,6 +792,77 @@ pub fn cmp_render_order(
     }
 }
 
+///
+/// Renders the given object into a square image of `size` times `size` pixels, for example to create a thumbnail for an asset browser.
+/// The object is viewed from a diagonal direction with a perspective camera which is moved such that the object fills the image (see [Camera::fit_to_bounds])
+/// and it is lit by a default setup of an ambient light and a directional light.
+/// The background is cleared to the given color or, if `None`, to a fully transparent background,
+/// in which case the colors of the partially covered pixels along the edges of the object are not darkened by the background.
+/// The returned texture contains RGBA `u8` data in sRGB color space in image orientation, ie. the first row of pixels is the top row,
+/// so it can be saved directly using `three_d_asset::io::save`.
+///
+pub fn render_thumbnail(
+    context: &Context,
+    object: impl Object,
+    size: u32,
+    background: Option<Srgba>,
+) -> CpuTexture {
+    let size = size.max(1);
+    let aabb = object.aabb();
+    let (center, radius) = if aabb.is_empty() || aabb.is_infinite() {
+        (Vec3::zero(), 1.0)
+    } else {
+        (
+            aabb.center(),
+            (0.5 * aabb.size().magnitude()).max(f32::EPSILON),
+        )
+    };
+    let mut camera = Camera::new_perspective(
+        Viewport::new_at_origo(size, size),
+        center + vec3(1.0, 0.7, 1.0).normalize() * 3.0 * radius,
+        center,
+        vec3(0.0, 1.0, 0.0),
+        degrees(30.0),
+        0.01 * radius,
+        100.0 * radius,
+    );
+    camera.fit_to_bounds(aabb, 0.05);
+
+    let ambient = AmbientLight::new(context, 0.4, Srgba::WHITE);
+    let directional = DirectionalLight::new(context, 2.0, Srgba::WHITE, vec3(-1.0, -1.0, -0.5));
+    let clear_color = background.map_or([0.0; 4], |color| {
+        [color.r, color.g, color.b, color.a].map(|c| c as f32 / 255.0)
+    });
+
+    let mut pixels = RenderTargetMultisample::<[u8; 4], f32>::new(context, size, size, 4)
+        .clear(ClearState::color_and_depth(
+            clear_color[0],
+            clear_color[1],
+            clear_color[2],
+            clear_color[3],
+            1.0,
+        ))
+        .render(&camera, std::iter::once(&object), &[&ambient, &directional])
+        .read_color::<[u8; 4]>();
+    if background.is_none() {
+        // Undo the blending with the transparent black background along the edges
+        for pixel in pixels.iter_mut() {
+            let alpha = pixel[3] as u32;
+            if alpha > 0 && alpha < 255 {
+                for c in pixel.iter_mut().take(3) {
+                    *c = ((*c as u32 * 255) / alpha).min(255) as u8;
+                }
+            }
+        }
+    }
+    CpuTexture {
+        data: TextureData::RgbaU8(pixels),
+        width: size,
+        height: size,
+        ..Default::default()
+    }
+}
+
 ///
 /// Finds the closest intersection between a ray from the given camera in the given pixel coordinate and the given geometries.
 /// The pixel coordinate must be in physical pixels, where (viewport.x, viewport.y) indicate the bottom left corner of the viewport
@@ -579,17 +875,36 @@ pub fn pick(
     pixel: impl Into<PhysicalPoint> + Copy,
     geometries: impl IntoIterator<Item = impl Geometry>,
 ) -> Option<IntersectionResult> {
-    let pos = camera.position_at_pixel(pixel);
-    let dir = camera.view_direction_at_pixel(pixel);
+    let (pos, dir) = camera.screen_to_ray(pixel);
     ray_intersect(
         context,
-        pos + dir * camera.z_near(),
+        pos,
         dir,
         camera.z_far() - camera.z_near(),
         geometries,
     )
 }
 
+///
+/// Finds the closest intersection for each of the given pixel coordinates in the same way as [pick],
+/// but renders the geometries only once instead of once for each pixel, which is much faster when picking many pixels, for example for area selection.
+/// Use a [PickBuffer] directly to keep the result for several queries, for example for the rest of the frame.
+/// The result for each pixel is ```None``` if no geometry was hit or if the pixel is outside the viewport of the camera.
+///
+pub fn pick_many(
+    context: &Context,
+    camera: &Camera,
+    pixels: &[impl Into<PhysicalPoint> + Copy],
+    geometries: impl IntoIterator<Item = impl Geometry>,
+) -> Vec<Option<IntersectionResult>> {
+    let mut pick_buffer = PickBuffer::new(context, camera);
+    pick_buffer.render(camera, geometries);
+    pixels
+        .iter()
+        .map(|pixel| pick_buffer.pick(*pixel))
+        .collect()
+}
+
 /// Result from an intersection test
 #[derive(Debug, Clone, Copy)]
 pub struct IntersectionResult {
@@ -709,4 +1024,87 @@ impl<T: Viewer> Viewer for GeometryPassCamera<T> {
     fn tone_mapping(&self) -> ToneMapping {
         self.0.tone_mapping()
     }
+
+    fn exposure(&self) -> f32 {
+        self.0.exposure()
+    }
+
+    fn fog(&self) -> Option<Fog> {
+        self.0.fog()
+    }
+}
+
+#[cfg(all(test, feature = "headless", not(target_arch = "wasm32")))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn multisample_edges() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let size = 32;
+        let mut camera = Camera::new_2d(Viewport::new_at_origo(size, size));
+        camera.disable_tone_and_color_mapping();
+        // A white triangle with slanted edges on a black background
+        let model = Gm::new(
+            Mesh::new(
+                &context,
+                &CpuMesh {
+                    positions: Positions::F32(vec![
+                        vec3(2.0, 3.0, 0.0),
+                        vec3(30.0, 6.0, 0.0),
+                        vec3(10.0, 29.0, 0.0),
+                    ]),
+                    ..Default::default()
+                },
+            ),
+            ColorMaterial {
+                color: Srgba::WHITE,
+                ..Default::default()
+            },
+        );
+        let clear_state = ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0);
+        let count_edge_pixels =
+            |pixels: &[[u8; 4]]| pixels.iter().filter(|p| p[0] > 0 && p[0] < 255).count();
+
+        let mut color = Texture2D::new_empty::<[u8; 4]>(
+            &context,
+            size,
+            size,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let mut depth = DepthTexture2D::new::<f32>(
+            &context,
+            size,
+            size,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let pixels = RenderTarget::new(color.as_color_target(None), depth.as_depth_target())
+            .clear(clear_state)
+            .render(&camera, &model, &[])
+            .read_color::<[u8; 4]>();
+        let multisample_pixels =
+            RenderTargetMultisample::<[u8; 4], f32>::new(&context, size, size, 4)
+                .clear(clear_state)
+                .render(&camera, &model, &[])
+                .read_color::<[u8; 4]>();
+
+        // Without multisampling, the pixels are either inside or outside the triangle,
+        // while with multisampling, the pixels along the edges are partially covered
+        assert_eq!(count_edge_pixels(&pixels), 0);
+        assert!(count_edge_pixels(&multisample_pixels) > size as usize);
+
+        // The pixels in the interior and far outside of the triangle are the same
+        let center = ((size / 2) * size + size / 3) as usize;
+        assert_eq!(pixels[center], [255, 255, 255, 255]);
+        assert_eq!(multisample_pixels[center], [255, 255, 255, 255]);
+        let corner = ((size - 1) * size + size - 1) as usize;
+        assert_eq!(pixels[corner], [0, 0, 0, 255]);
+        assert_eq!(multisample_pixels[corner], [0, 0, 0, 255]);
+    }
 }
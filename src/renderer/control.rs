@@ -72,6 +72,7 @@ pub enum Event {
         handled: bool,
     },
     /// Fired continuously when the mouse wheel or equivalent is applied.
+    /// Moving two fingers on a touch screen fires [Event::PinchGesture] and [Event::PanGesture] events instead.
     MouseWheel {
         /// The relative scrolling since the last [Event::MouseWheel] event.
         delta: (f32, f32),
@@ -82,7 +83,7 @@ pub enum Event {
         /// Whether or not this event already have been handled.
         handled: bool,
     },
-    /// Fired continuously when a pinch input gesture is recognized, such as on a Mac trackpad
+    /// Fired continuously when a pinch input gesture is recognized, such as on a Mac trackpad or with two fingers on a touch screen.
     PinchGesture {
         /// The relative pinching since the last [Event::PinchGesture] event (positive is zoom in).
         delta: f32,
@@ -93,6 +94,18 @@ pub enum Event {
         /// Whether or not this event already have been handled.
         handled: bool,
     },
+    /// Fired continuously when two fingers are moved together on a touch screen.
+    /// A [Event::PinchGesture] event is fired at the same time for the change in distance between the fingers.
+    PanGesture {
+        /// The distance in logical pixels the point between the two fingers has moved since the last [Event::PanGesture] event.
+        delta: (f32, f32),
+        /// The screen position of the point between the two fingers in physical pixels.
+        position: PhysicalPoint,
+        /// The state of modifiers.
+        modifiers: Modifiers,
+        /// Whether or not this event already have been handled.
+        handled: bool,
+    },
     /// Fired continuously when a rotation input gesture is recognized, such as on a Mac trackpad
     RotationGesture {
         /// The relative rotation since the last [Event::RotationGesture] event (positive is counterclockwise).
@@ -133,6 +146,24 @@ pub enum Event {
     },
     /// Fires when some text has been written.
     Text(String),
+    /// Fired when the user pastes text from the clipboard, for example by pressing Ctrl+V.
+    Paste(String),
+    /// Fired when a file is dragged over the window. Only fired on desktop.
+    FileHovered {
+        /// The path to the file.
+        path: std::path::PathBuf,
+    },
+    /// Fired when a file that has been dragged over the window is dragged away again without being dropped. Only fired on desktop.
+    FileHoverCancelled,
+    /// Fired when a file is dropped onto the window.
+    FileDropped {
+        /// The path to the file on desktop.
+        /// On web, this is only the name of the file, since the file system is not accessible.
+        path: std::path::PathBuf,
+        /// The content of the file on web, where it cannot be loaded from the path.
+        /// On desktop, this is `None` and the file can be loaded from the path instead.
+        data: Option<Vec<u8>>,
+    },
 }
 
 /// Keyboard key input.
@@ -7,12 +7,18 @@ use crate::renderer::*;
 pub struct FlyControl {
     /// The speed of movements.
     pub speed: f32,
+    /// Whether or not the camera is rotated when the mouse is moved without pressing any button, which is useful when the cursor is grabbed,
+    /// see [FrameOutput::cursor_grab](crate::window::FrameOutput::cursor_grab). The default is false.
+    pub mouse_look: bool,
 }
 
 impl FlyControl {
     /// Creates a new fly control with the given speed of movements.
     pub fn new(speed: f32) -> Self {
-        Self { speed }
+        Self {
+            speed,
+            mouse_look: false,
+        }
     }
 
     /// Handles the events. Must be called each frame.
@@ -27,7 +33,9 @@ impl FlyControl {
                     ..
                 } => {
                     if !*handled {
-                        if Some(MouseButton::Left) == *button {
+                        if Some(MouseButton::Left) == *button
+                            || (self.mouse_look && button.is_none())
+                        {
                             camera.yaw(radians(delta.0 * std::f32::consts::PI / 1800.0));
                             camera.pitch(radians(delta.1 * std::f32::consts::PI / 1800.0));
                             *handled = true;
@@ -52,9 +60,46 @@ impl FlyControl {
                         change = true;
                     }
                 }
+                Event::PinchGesture { delta, handled, .. } if !*handled => {
+                    // The pinch is relative, so it is scaled to move about as far as scrolling the change in distance between the fingers
+                    let v = camera.view_direction() * self.speed * 100.0 * *delta;
+                    camera.translate(v);
+                    *handled = true;
+                    change = true;
+                }
                 _ => {}
             }
         }
         change
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pinch_moves_forward() {
+        let mut camera = Camera::new_perspective(
+            Viewport::new_at_origo(100, 100),
+            vec3(0.0, 0.0, 5.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            degrees(45.0),
+            0.1,
+            100.0,
+        );
+        let mut events = [Event::PinchGesture {
+            delta: 0.1,
+            position: PhysicalPoint { x: 50.0, y: 50.0 },
+            modifiers: Modifiers::default(),
+            handled: false,
+        }];
+        assert!(FlyControl::new(0.1).handle_events(&mut camera, &mut events));
+        assert!(matches!(
+            events[0],
+            Event::PinchGesture { handled: true, .. }
+        ));
+        assert!((camera.position() - vec3(0.0, 0.0, 4.0)).magnitude() < 1e-5);
+    }
+}
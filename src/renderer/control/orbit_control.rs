@@ -73,6 +73,16 @@ impl OrbitControl {
                         change = true;
                     }
                 }
+                Event::PanGesture { delta, handled, .. } if !*handled => {
+                    // Moves both the camera and the target in the view plane
+                    let speed = 0.002 * self.target.distance(camera.position());
+                    let translation = speed
+                        * (camera.up_orthogonal() * delta.1 - camera.right_direction() * delta.0);
+                    camera.translate(translation);
+                    self.target += translation;
+                    *handled = true;
+                    change = true;
+                }
                 _ => {}
             }
         }
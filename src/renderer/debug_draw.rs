@@ -0,0 +1,280 @@
+use crate::renderer::*;
+
+///
+/// An immediate mode collector of debug primitives, like lines, points, boxes and text, which are accumulated during a frame
+/// and then rendered on top of the scene in one batched draw call per primitive type by calling [DebugDraw::render].
+/// The primitives are cleared after each render, so they need to be added again each frame.
+///
+/// The width of lines and the size of points and text are specified in pixels and are therefore independent of the distance to the camera.
+/// The GPU buffers are kept between frames and are updated in place, so they are only reallocated when the number of primitives changes.
+///
+pub struct DebugDraw {
+    context: Context,
+    /// The width of the lines in pixels. Default is 2.
+    pub line_width: f32,
+    /// The size of the points in pixels. Default is 8.
+    pub point_size: f32,
+    /// Whether or not the primitives are hidden behind the objects already rendered into the target. Default is false, ie. the primitives are always on top.
+    pub depth_test: bool,
+    lines: Vec<(Vec3, Vec3, Srgba)>,
+    points: Vec<(Vec3, Srgba)>,
+    #[cfg(feature = "text")]
+    texts: Vec<(Vec3, CpuMesh, Srgba)>,
+    lines_mesh: Option<Mesh>,
+    points_mesh: Option<Mesh>,
+    #[cfg(feature = "text")]
+    texts_mesh: Option<Mesh>,
+}
+
+impl DebugDraw {
+    ///
+    /// Creates a new empty debug draw collector.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            line_width: 2.0,
+            point_size: 8.0,
+            depth_test: false,
+            lines: Vec::new(),
+            points: Vec::new(),
+            #[cfg(feature = "text")]
+            texts: Vec::new(),
+            lines_mesh: None,
+            points_mesh: None,
+            #[cfg(feature = "text")]
+            texts_mesh: None,
+        }
+    }
+
+    ///
+    /// Adds a line from `a` to `b` in world space with the given color.
+    ///
+    pub fn line(&mut self, a: Vec3, b: Vec3, color: Srgba) {
+        self.lines.push((a, b, color));
+    }
+
+    ///
+    /// Adds a point at the given position in world space with the given color.
+    ///
+    pub fn point(&mut self, position: Vec3, color: Srgba) {
+        self.points.push((position, color));
+    }
+
+    ///
+    /// Adds the twelve edges of the given axis aligned bounding box as lines with the given color.
+    ///
+    pub fn aabb(&mut self, aabb: AxisAlignedBoundingBox, color: Srgba) {
+        if aabb.is_empty() {
+            return;
+        }
+        let (min, max) = (aabb.min(), aabb.max());
+        let corner = |i: usize| {
+            vec3(
+                if i & 1 == 0 { min.x } else { max.x },
+                if i & 2 == 0 { min.y } else { max.y },
+                if i & 4 == 0 { min.z } else { max.z },
+            )
+        };
+        for i in 0..8 {
+            for axis in [1, 2, 4] {
+                if i & axis == 0 {
+                    self.line(corner(i), corner(i | axis), color);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Adds the given text at the given position in world space with the given color.
+    /// The text always faces the camera and the glyphs are generated using the given text generator, where the size of the font is in pixels.
+    ///
+    #[cfg(feature = "text")]
+    pub fn text(
+        &mut self,
+        text_generator: &TextGenerator,
+        position: Vec3,
+        text: &str,
+        color: Srgba,
+    ) {
+        let mesh = text_generator.generate(text, TextLayoutOptions::default());
+        self.texts.push((position, mesh, color));
+    }
+
+    ///
+    /// Removes all primitives added since the last call to [DebugDraw::render] without rendering them.
+    ///
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.points.clear();
+        #[cfg(feature = "text")]
+        self.texts.clear();
+    }
+
+    ///
+    /// Renders all primitives added since the last render as seen from the given camera into the given render target
+    /// using one draw call for each type of primitive, and afterwards clears the primitives.
+    ///
+    pub fn render(&mut self, camera: &Camera, target: &RenderTarget) {
+        let lines = (!self.lines.is_empty()).then(|| self.lines_builder(camera));
+        let points = (!self.points.is_empty()).then(|| self.points_builder(camera));
+        #[cfg(feature = "text")]
+        let texts = (!self.texts.is_empty()).then(|| self.texts_builder(camera));
+        let mut meshes = Vec::new();
+        if let Some(builder) = lines {
+            meshes.push(builder.update(&self.context, &mut self.lines_mesh));
+        }
+        if let Some(builder) = points {
+            meshes.push(builder.update(&self.context, &mut self.points_mesh));
+        }
+        #[cfg(feature = "text")]
+        if let Some(builder) = texts {
+            meshes.push(builder.update(&self.context, &mut self.texts_mesh));
+        }
+        let material = ColorMaterial {
+            render_states: RenderStates {
+                depth_test: if self.depth_test {
+                    DepthTest::LessOrEqual
+                } else {
+                    DepthTest::Always
+                },
+                blend: Blend::TRANSPARENCY,
+                cull: Cull::None,
+                ..Default::default()
+            },
+            is_transparent: true,
+            ..Default::default()
+        };
+        // The bounding boxes of the meshes are not updated when the buffers are, so the meshes are rendered directly without frustum culling
+        target
+            .write::<RendererError>(|| {
+                for mesh in meshes {
+                    mesh.render_with_material(&material, camera, &[]);
+                }
+                Ok(())
+            })
+            .unwrap();
+        self.clear();
+    }
+
+    fn lines_builder(&self, camera: &Camera) -> MeshBuilder {
+        let mut builder = MeshBuilder::default();
+        for (a, b, color) in self.lines.iter() {
+            let direction = b - a;
+            let side = |p: Vec3| {
+                let side = direction.cross(view_direction_at(camera, p));
+                if side.magnitude2() > f32::EPSILON {
+                    side.normalize() * 0.5 * self.line_width * world_size_of_pixel(camera, p)
+                } else {
+                    Vec3::zero()
+                }
+            };
+            let (side_a, side_b) = (side(*a), side(*b));
+            builder.push_quad([a - side_a, a + side_a, b + side_b, b - side_b], *color);
+        }
+        builder
+    }
+
+    fn points_builder(&self, camera: &Camera) -> MeshBuilder {
+        let mut builder = MeshBuilder::default();
+        let (right, up) = (camera.right_direction(), camera.up_orthogonal());
+        for (p, color) in self.points.iter() {
+            let half_size = 0.5 * self.point_size * world_size_of_pixel(camera, *p);
+            let (right, up) = (right * half_size, up * half_size);
+            builder.push_quad(
+                [
+                    p - right - up,
+                    p + right - up,
+                    p + right + up,
+                    p - right + up,
+                ],
+                *color,
+            );
+        }
+        builder
+    }
+
+    #[cfg(feature = "text")]
+    fn texts_builder(&self, camera: &Camera) -> MeshBuilder {
+        let mut builder = MeshBuilder::default();
+        let (right, up) = (camera.right_direction(), camera.up_orthogonal());
+        for (p, mesh, color) in self.texts.iter() {
+            let pixel_size = world_size_of_pixel(camera, *p);
+            let offset = builder.positions.len() as u32;
+            builder.positions.extend(
+                mesh.positions
+                    .to_f32()
+                    .iter()
+                    .map(|v| p + (right * v.x + up * v.y) * pixel_size),
+            );
+            builder.colors.resize(builder.positions.len(), *color);
+            if let Some(indices) = mesh.indices.to_u32() {
+                builder.indices.extend(indices.iter().map(|i| i + offset));
+            }
+        }
+        builder
+    }
+}
+
+#[derive(Default)]
+struct MeshBuilder {
+    positions: Vec<Vec3>,
+    colors: Vec<Srgba>,
+    indices: Vec<u32>,
+}
+
+impl MeshBuilder {
+    fn push_quad(&mut self, corners: [Vec3; 4], color: Srgba) {
+        let offset = self.positions.len() as u32;
+        self.positions.extend(corners);
+        self.colors.extend([color; 4]);
+        self.indices
+            .extend([0, 1, 2, 0, 2, 3].iter().map(|i| i + offset));
+    }
+
+    fn update<'a>(self, context: &Context, mesh: &'a mut Option<Mesh>) -> &'a Mesh {
+        if let Some(mesh) = mesh {
+            mesh.positions_mut().fill(&self.positions);
+            let colors = self
+                .colors
+                .iter()
+                .map(|c| c.to_linear_srgb())
+                .collect::<Vec<_>>();
+            if let Some(buffer) = mesh.colors_mut() {
+                buffer.fill(&colors);
+            }
+            if let IndexBuffer::U32(buffer) = mesh.indices_mut() {
+                buffer.fill(&self.indices);
+            }
+        }
+        mesh.get_or_insert_with(|| {
+            Mesh::new(
+                context,
+                &CpuMesh {
+                    positions: Positions::F32(self.positions),
+                    colors: Some(self.colors),
+                    indices: Indices::U32(self.indices),
+                    ..Default::default()
+                },
+            )
+        })
+    }
+}
+
+fn view_direction_at(camera: &Camera, position: Vec3) -> Vec3 {
+    match camera.projection_type() {
+        ProjectionType::Perspective { .. } => position - camera.position(),
+        ProjectionType::Orthographic { .. } => camera.view_direction(),
+    }
+}
+
+fn world_size_of_pixel(camera: &Camera, position: Vec3) -> f32 {
+    let height = camera.viewport().height.max(1) as f32;
+    let scale = 2.0 / (camera.projection()[1][1] * height);
+    match camera.projection_type() {
+        ProjectionType::Perspective { .. } => {
+            scale * (position - camera.position()).dot(camera.view_direction())
+        }
+        ProjectionType::Orthographic { .. } => scale,
+    }
+}
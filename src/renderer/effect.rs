@@ -60,6 +60,34 @@ mod water;
 #[doc(inline)]
 pub use water::*;
 
+mod vignette;
+#[doc(inline)]
+pub use vignette::*;
+
+mod chromatic_aberration;
+#[doc(inline)]
+pub use chromatic_aberration::*;
+
+mod motion_blur;
+#[doc(inline)]
+pub use motion_blur::*;
+
+mod linear_depth;
+#[doc(inline)]
+pub use linear_depth::*;
+
+mod normal_reconstruction;
+#[doc(inline)]
+pub use normal_reconstruction::*;
+
+mod effect_chain;
+#[doc(inline)]
+pub use effect_chain::*;
+
+mod screen_space_reflections;
+#[doc(inline)]
+pub use screen_space_reflections::*;
+
 pub(crate) mod lighting_pass;
 
 use crate::renderer::*;
@@ -0,0 +1,75 @@
+use crate::renderer::*;
+
+///
+/// An effect that simulates the inability of a lens to focus all colors to the same point,
+/// ie. the red, green and blue channels are sampled with an offset which increases with the distance from the center of the screen.
+///
+#[derive(Clone, Debug)]
+pub struct ChromaticAberrationEffect {
+    /// The maximum offset of the red and blue channels in UV coordinates, which is reached at the corners of the screen.
+    /// The red channel is offset away from the center and the blue channel towards the center.
+    pub strength: f32,
+}
+
+impl Default for ChromaticAberrationEffect {
+    fn default() -> Self {
+        Self { strength: 0.005 }
+    }
+}
+
+impl Effect for ChromaticAberrationEffect {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> String {
+        format!(
+            "{}\n{}\n{}\n{}",
+            color_texture
+                .expect("Must supply a color texture to apply a chromatic aberration effect")
+                .fragment_shader_source(),
+            ToneMapping::fragment_shader_source(),
+            ColorMapping::fragment_shader_source(),
+            include_str!("shaders/chromatic_aberration_effect.frag")
+        )
+    }
+
+    fn id(
+        &self,
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId::ChromaticAberrationEffect(
+            color_texture
+                .expect("Must supply a color texture to apply a chromatic aberration effect"),
+        )
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) {
+        viewer
+            .tone_mapping()
+            .use_uniforms_with_exposure(program, viewer.exposure());
+        viewer.color_mapping().use_uniforms(program);
+        color_texture
+            .expect("Must supply a color texture to apply a chromatic aberration effect")
+            .use_uniforms(program);
+        program.use_uniform("strength", self.strength);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
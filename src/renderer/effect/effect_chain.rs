@@ -0,0 +1,135 @@
+use crate::renderer::*;
+
+///
+/// Applies a list of effects in sequence, where each effect is applied to the result of the previous effect.
+/// The intermediate results are rendered into textures which are owned by the chain and reused between frames,
+/// they are only reallocated when the size of the viewport changes.
+///
+/// Tone and color mapping, as specified by the [Viewer], is only applied by the last effect in the chain.
+/// The same depth texture is given to all of the effects.
+///
+pub struct EffectChain {
+    context: Context,
+    /// The effects that are applied in the order given by this list.
+    pub effects: Vec<Box<dyn Effect>>,
+    textures: Vec<Texture2D>,
+}
+
+impl EffectChain {
+    ///
+    /// Constructs a new effect chain which applies the given effects in the given order.
+    ///
+    pub fn new(context: &Context, effects: Vec<Box<dyn Effect>>) -> Self {
+        Self {
+            context: context.clone(),
+            effects,
+            textures: Vec::new(),
+        }
+    }
+
+    ///
+    /// Applies all of the effects in the chain to the given color and depth texture and writes the final result into the given render target.
+    /// Use an empty array for the `lights` argument, if none of the effects require lights to be rendered.
+    ///
+    pub fn apply(
+        &mut self,
+        target: &RenderTarget,
+        viewer: impl Viewer,
+        lights: &[&dyn Light],
+        color_texture: ColorTexture,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        let Some((last, rest)) = self.effects.split_last() else {
+            return;
+        };
+        let viewport = viewer.viewport();
+        if !rest.is_empty()
+            && (self.textures.is_empty()
+                || self.textures[0].width() != viewport.width
+                || self.textures[0].height() != viewport.height)
+        {
+            self.textures = (0..2)
+                .map(|_| {
+                    Texture2D::new_empty::<[f16; 4]>(
+                        &self.context,
+                        viewport.width,
+                        viewport.height,
+                        Interpolation::Linear,
+                        Interpolation::Linear,
+                        None,
+                        Wrapping::ClampToEdge,
+                        Wrapping::ClampToEdge,
+                    )
+                })
+                .collect();
+        }
+
+        let intermediate_viewer = IntermediateViewer(&viewer);
+        for (i, effect) in rest.iter().enumerate() {
+            let (front, back) = self.textures.split_at_mut(1);
+            let (source, destination) = if i % 2 == 0 {
+                (&back[0], &mut front[0])
+            } else {
+                (&front[0], &mut back[0])
+            };
+            let source = if i == 0 {
+                color_texture
+            } else {
+                ColorTexture::Single(source)
+            };
+            destination.as_color_target(None).apply_screen_effect(
+                effect.as_ref(),
+                &intermediate_viewer,
+                lights,
+                Some(source),
+                depth_texture,
+            );
+        }
+
+        let source = if rest.is_empty() {
+            color_texture
+        } else {
+            ColorTexture::Single(&self.textures[(rest.len() - 1) % 2])
+        };
+        target.apply_screen_effect(last.as_ref(), &viewer, lights, Some(source), depth_texture);
+    }
+}
+
+///
+/// Viewer used when rendering into the intermediate textures, which covers the entire texture and does not apply any tone and color mapping.
+///
+struct IntermediateViewer<T>(T);
+
+impl<T: Viewer> Viewer for IntermediateViewer<T> {
+    fn position(&self) -> Vec3 {
+        self.0.position()
+    }
+
+    fn view(&self) -> Mat4 {
+        self.0.view()
+    }
+
+    fn projection(&self) -> Mat4 {
+        self.0.projection()
+    }
+
+    fn viewport(&self) -> Viewport {
+        Viewport::new_at_origo(self.0.viewport().width, self.0.viewport().height)
+    }
+
+    fn z_near(&self) -> f32 {
+        self.0.z_near()
+    }
+
+    fn z_far(&self) -> f32 {
+        self.0.z_far()
+    }
+
+    fn color_mapping(&self) -> ColorMapping {
+        ColorMapping::None
+    }
+
+    fn tone_mapping(&self) -> ToneMapping {
+        ToneMapping::None
+    }
+}
@@ -2,13 +2,20 @@ use crate::renderer::*;
 
 ///
 /// An effect that simulates fog, ie. the area where it is applied gets hazy when objects are far away.
+/// See [Fog] for a simpler alternative which is applied directly when shading the objects.
 ///
 #[derive(Clone, Debug)]
 pub struct FogEffect {
     /// The color of the fog.
     pub color: Srgba,
-    /// The density of the fog.
+    /// The type of falloff used when computing the amount of fog.
+    pub fog_type: FogType,
+    /// The density of the fog. Not used by [FogType::Linear].
     pub density: f32,
+    /// The distance from the viewer where the fog starts.
+    pub start: f32,
+    /// The distance from the viewer where the fog completely covers the objects.
+    pub end: f32,
     /// Determines the variation on the density as a function of time.
     pub animation: f32,
     /// The time used for the animation.
@@ -19,7 +26,10 @@ impl Default for FogEffect {
     fn default() -> Self {
         Self {
             color: Srgba::WHITE,
+            fog_type: FogType::default(),
             density: 0.2,
+            start: 0.0,
+            end: f32::MAX,
             animation: 1.0,
             time: 0.0,
         }
@@ -34,8 +44,9 @@ impl Effect for FogEffect {
         depth_texture: Option<DepthTexture>,
     ) -> String {
         format!(
-            "{}\n{}\n{}\n{}\n{}\n{}",
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}",
             include_str!("../../core/shared.frag"),
+            FogType::fragment_shader_source(),
             color_texture
                 .expect("Must supply a depth texture to apply a fog effect")
                 .fragment_shader_source(),
@@ -67,7 +78,9 @@ impl Effect for FogEffect {
         color_texture: Option<ColorTexture>,
         depth_texture: Option<DepthTexture>,
     ) {
-        viewer.tone_mapping().use_uniforms(program);
+        viewer
+            .tone_mapping()
+            .use_uniforms_with_exposure(program, viewer.exposure());
         viewer.color_mapping().use_uniforms(program);
         color_texture
             .expect("Must supply a color texture to apply a fog effect")
@@ -80,7 +93,8 @@ impl Effect for FogEffect {
             (viewer.projection() * viewer.view()).invert().unwrap(),
         );
         program.use_uniform("fogColor", Vec4::from(self.color));
-        program.use_uniform("fogDensity", self.density);
+        self.fog_type
+            .use_uniforms(program, self.density, self.start, self.end);
         program.use_uniform("animation", self.animation);
         program.use_uniform("time", 0.001 * self.time);
         program.use_uniform("eyePosition", viewer.position());
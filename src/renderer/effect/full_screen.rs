@@ -70,7 +70,9 @@ impl Effect for ScreenEffect {
         depth_texture: Option<DepthTexture>,
     ) {
         if let Some(color_texture) = color_texture {
-            viewer.tone_mapping().use_uniforms(program);
+            viewer
+                .tone_mapping()
+                .use_uniforms_with_exposure(program, viewer.exposure());
             viewer.color_mapping().use_uniforms(program);
             color_texture.use_uniforms(program);
         }
@@ -85,6 +87,7 @@ impl Effect for ScreenEffect {
             cull: Cull::Back,
             write_mask: self.write_mask,
             blend: self.blend,
+            ..Default::default()
         }
     }
 }
@@ -1,8 +1,12 @@
 use crate::renderer::*;
 
-pub struct LightingPassEffect {}
+pub struct LightingPassEffect<'a> {
+    /// A texture containing the emissive color in high precision.
+    /// If not specified, the emissive color is sampled from the third layer of the geometry pass color texture.
+    pub emissive_texture: Option<&'a Texture2D>,
+}
 
-impl Effect for LightingPassEffect {
+impl Effect for LightingPassEffect<'_> {
     fn fragment_shader_source(
         &self,
         lights: &[&dyn Light],
@@ -10,10 +14,14 @@ impl Effect for LightingPassEffect {
         depth_texture: Option<DepthTexture>,
     ) -> String {
         let mut fragment_shader = lights_shader_source(lights);
+        if self.emissive_texture.is_some() {
+            fragment_shader.push_str("#define USE_EMISSIVE_TEXTURE\n");
+        }
         fragment_shader.push_str(&color_texture.unwrap().fragment_shader_source());
         fragment_shader.push_str(&depth_texture.unwrap().fragment_shader_source());
         fragment_shader.push_str(ToneMapping::fragment_shader_source());
         fragment_shader.push_str(ColorMapping::fragment_shader_source());
+        fragment_shader.push_str(&Fog::fragment_shader_source());
         fragment_shader.push_str(include_str!("shaders/deferred_lighting.frag"));
         fragment_shader
     }
@@ -23,7 +31,11 @@ impl Effect for LightingPassEffect {
         color_texture: Option<ColorTexture>,
         depth_texture: Option<DepthTexture>,
     ) -> EffectMaterialId {
-        EffectMaterialId::LightingPassEffect(color_texture.unwrap(), depth_texture.unwrap())
+        EffectMaterialId::LightingPassEffect(
+            color_texture.unwrap(),
+            depth_texture.unwrap(),
+            self.emissive_texture.is_some(),
+        )
     }
 
     fn use_uniforms(
@@ -41,10 +53,16 @@ impl Effect for LightingPassEffect {
                 GeometryFunction::SmithSchlickGGX,
             )),
         );
-        viewer.tone_mapping().use_uniforms(program);
+        viewer
+            .tone_mapping()
+            .use_uniforms_with_exposure(program, viewer.exposure());
         viewer.color_mapping().use_uniforms(program);
+        Fog::use_uniforms(program, viewer);
         color_texture.unwrap().use_uniforms(program);
         depth_texture.unwrap().use_uniforms(program);
+        if let Some(emissive_texture) = self.emissive_texture {
+            program.use_texture("emissiveMap", emissive_texture);
+        }
         program.use_uniform_if_required("cameraPosition", viewer.position());
         for (i, light) in lights.iter().enumerate() {
             light.use_uniforms(program, i as u32);
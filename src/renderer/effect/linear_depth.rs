@@ -0,0 +1,165 @@
+use crate::renderer::*;
+
+///
+/// An effect that converts the non-linear values in a depth texture into a linear depth in the range `[0..1]` and writes it to the color target,
+/// for example to visualize the depth or to use it in a post-processing step.
+/// The linear depth is the distance from the viewer along the view direction in world space, mapped linearly such that
+/// [LinearDepthEffect::min_distance] is 0 and [LinearDepthEffect::max_distance] is 1. Pixels without any geometry are set to 1.
+/// See [DepthMaterial] for rendering the distance to the viewer directly when rendering the objects.
+///
+#[derive(Clone, Debug, Default)]
+pub struct LinearDepthEffect {
+    /// The distance which is mapped to 0. If None, then the near plane of the viewer is used.
+    pub min_distance: Option<f32>,
+    /// The distance which is mapped to 1. If None, then the far plane of the viewer is used.
+    pub max_distance: Option<f32>,
+}
+
+impl Effect for LinearDepthEffect {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> String {
+        format!(
+            "{}\n{}",
+            depth_texture
+                .expect("Must supply a depth texture to apply a linear depth effect")
+                .fragment_shader_source(),
+            include_str!("shaders/linear_depth_effect.frag")
+        )
+    }
+
+    fn id(
+        &self,
+        _color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId::LinearDepthEffect(
+            depth_texture.expect("Must supply a depth texture to apply a linear depth effect"),
+        )
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        depth_texture
+            .expect("Must supply a depth texture to apply a linear depth effect")
+            .use_uniforms(program);
+        program.use_uniform("projectionInverse", viewer.projection().invert().unwrap());
+        program.use_uniform(
+            "minDistance",
+            self.min_distance.unwrap_or_else(|| viewer.z_near()),
+        );
+        program.use_uniform(
+            "maxDistance",
+            self.max_distance.unwrap_or_else(|| viewer.z_far()),
+        );
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "headless", not(target_arch = "wasm32")))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn linear_depth() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let size = 8;
+        let (z_near, z_far, distance) = (1.0, 11.0, 5.0);
+        let camera = Camera::new_perspective(
+            Viewport::new_at_origo(size, size),
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 0.0, -1.0),
+            vec3(0.0, 1.0, 0.0),
+            degrees(60.0),
+            z_near,
+            z_far,
+        );
+
+        // A plane facing the camera at the given distance which covers the left half of the view
+        let mut cpu_mesh = CpuMesh::square();
+        cpu_mesh
+            .transform(Mat4::from_translation(vec3(-50.0, 0.0, -distance)) * Mat4::from_scale(50.0))
+            .unwrap();
+        let model = Gm::new(
+            Mesh::new(&context, &cpu_mesh),
+            ColorMaterial {
+                color: Srgba::WHITE,
+                ..Default::default()
+            },
+        );
+        let mut color = Texture2D::new_empty::<[u8; 4]>(
+            &context,
+            size,
+            size,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let mut depth = DepthTexture2D::new::<f32>(
+            &context,
+            size,
+            size,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        RenderTarget::new(color.as_color_target(None), depth.as_depth_target())
+            .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0))
+            .render(&camera, &model, &[]);
+
+        let mut linear_depth = Texture2D::new_empty::<[f32; 4]>(
+            &context,
+            size,
+            size,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let pixels = linear_depth
+            .as_color_target(None)
+            .clear(ClearState::color(0.0, 0.0, 0.0, 0.0))
+            .apply_screen_effect(
+                &LinearDepthEffect::default(),
+                &camera,
+                &[],
+                None,
+                Some(DepthTexture::Single(&depth)),
+            )
+            .read::<[f32; 4]>();
+
+        // The distance along the view direction is the same for all pixels on the plane
+        let expected = (distance - z_near) / (z_far - z_near);
+        for (i, pixel) in pixels.iter().enumerate() {
+            let x = i as u32 % size;
+            let value = if x < size / 2 { expected } else { 1.0 };
+            assert!(
+                (pixel[0] - value).abs() < 1e-3,
+                "pixel {} is {} but should be {}",
+                i,
+                pixel[0],
+                value
+            );
+        }
+    }
+}
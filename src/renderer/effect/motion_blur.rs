@@ -0,0 +1,183 @@
+use crate::renderer::*;
+
+///
+/// An effect that blurs the image in the direction of the motion in each pixel.
+/// The motion is given by a velocity texture, for example rendered with the [VelocityMaterial], which contains the screen space velocity in uv coordinates in the red and green channels.
+/// Pixels where the velocity is zero are not blurred.
+///
+#[derive(Clone, Copy)]
+pub struct MotionBlurEffect<'a> {
+    /// The texture containing the screen space velocity in each pixel.
+    pub velocity_texture: &'a Texture2D,
+    /// The number of samples taken along the velocity in each pixel. A higher number gives a smoother blur but is more expensive.
+    pub samples: u32,
+    /// The velocity is multiplied by this value before blurring, ie. a higher value gives a longer blur.
+    pub strength: f32,
+}
+
+impl<'a> MotionBlurEffect<'a> {
+    ///
+    /// Creates a new motion blur effect using the given velocity texture, 8 samples and a strength of 1.
+    ///
+    pub fn new(velocity_texture: &'a Texture2D) -> Self {
+        Self {
+            velocity_texture,
+            samples: 8,
+            strength: 1.0,
+        }
+    }
+}
+
+impl Effect for MotionBlurEffect<'_> {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> String {
+        format!(
+            "{}\n{}\n{}\n{}",
+            color_texture
+                .expect("Must supply a color texture to apply a motion blur effect")
+                .fragment_shader_source(),
+            ToneMapping::fragment_shader_source(),
+            ColorMapping::fragment_shader_source(),
+            include_str!("shaders/motion_blur_effect.frag")
+        )
+    }
+
+    fn id(
+        &self,
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId::MotionBlurEffect(
+            color_texture.expect("Must supply a color texture to apply a motion blur effect"),
+        )
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) {
+        viewer
+            .tone_mapping()
+            .use_uniforms_with_exposure(program, viewer.exposure());
+        viewer.color_mapping().use_uniforms(program);
+        color_texture
+            .expect("Must supply a color texture to apply a motion blur effect")
+            .use_uniforms(program);
+        program.use_texture("velocityTexture", self.velocity_texture);
+        program.use_uniform("samples", self.samples.max(1) as i32);
+        program.use_uniform("strength", self.strength);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "headless", not(target_arch = "wasm32")))]
+mod tests {
+    use crate::*;
+
+    ///
+    /// Applies the motion blur effect with the given constant velocity to an image with a white vertical line in the middle
+    /// and returns the red channel of the pixels.
+    ///
+    fn blur_vertical_line(context: &Context, velocity: Vec2) -> Vec<Vec<u8>> {
+        let size = 16;
+        let new_texture = |interpolation| {
+            Texture2D::new_empty::<[u8; 4]>(
+                context,
+                size,
+                size,
+                interpolation,
+                interpolation,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            )
+        };
+        let mut color = new_texture(Interpolation::Nearest);
+        color.fill(
+            &(0..size * size)
+                .map(|i| {
+                    if i % size == size / 2 {
+                        [255, 255, 255, 255]
+                    } else {
+                        [0, 0, 0, 255]
+                    }
+                })
+                .collect::<Vec<_>>(),
+        );
+        let mut velocity_texture = Texture2D::new_empty::<[f32; 2]>(
+            context,
+            size,
+            size,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        velocity_texture.fill(&vec![[velocity.x, velocity.y]; (size * size) as usize]);
+
+        let mut camera = Camera::new_2d(Viewport::new_at_origo(size, size));
+        camera.disable_tone_and_color_mapping();
+        let mut output = new_texture(Interpolation::Nearest);
+        let pixels = output
+            .as_color_target(None)
+            .clear(ClearState::color(0.0, 0.0, 0.0, 1.0))
+            .apply_screen_effect(
+                &MotionBlurEffect::new(&velocity_texture),
+                &camera,
+                &[],
+                Some(ColorTexture::Single(&color)),
+                None,
+            )
+            .read::<[u8; 4]>();
+        pixels
+            .chunks(size as usize)
+            .map(|row| row.iter().map(|p| p[0]).collect())
+            .collect()
+    }
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn motion_blur() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let line = |row: &[u8]| {
+            row.iter()
+                .enumerate()
+                .all(|(x, &c)| c == if x == 8 { 255 } else { 0 })
+        };
+
+        // No blur where the velocity is zero
+        let rows = blur_vertical_line(&context, vec2(0.0, 0.0));
+        assert!(rows.iter().all(|row| line(row)));
+
+        // A velocity along the line does not change it
+        let rows = blur_vertical_line(&context, vec2(0.0, 0.25));
+        assert!(rows.iter().all(|row| line(row)));
+
+        // A horizontal velocity of a quarter of the width smears the line horizontally over a few pixels in each row
+        let rows = blur_vertical_line(&context, vec2(0.25, 0.0));
+        for row in rows.iter() {
+            assert_eq!(row, &rows[0]);
+            assert!(row[8] > 0 && row[8] < 255);
+            assert!(row[7] > 0 && row[9] > 0);
+            assert!(row[..5].iter().all(|&c| c == 0));
+            assert!(row[12..].iter().all(|&c| c == 0));
+        }
+    }
+}
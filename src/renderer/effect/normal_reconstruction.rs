@@ -0,0 +1,222 @@
+use crate::renderer::*;
+
+///
+/// Reconstruction of view space normals from a depth texture using screen space differences of the reconstructed positions,
+/// which is useful for screen space effects, like ambient occlusion and reflections, that need the normals but only have access to the depth texture.
+///
+/// Include [NormalReconstruction::fragment_shader_source] in the fragment shader of an [Effect] after the source of the [DepthTexture]
+/// (see [DepthTexture::fragment_shader_source]) and call [NormalReconstruction::use_uniforms] in [Effect::use_uniforms].
+/// The fragment shader can then call
+/// - `vec3 view_position_from_depth(vec2 uv)` which returns the view space position at the given uv coordinates and
+/// - `vec3 view_normal_from_depth(vec2 uv)` which returns the normalized view space normal at the given uv coordinates.
+///
+/// The normals are flat, ie. they follow the triangles and not the interpolated normals of the geometry, and are undefined where there is no geometry.
+///
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+pub enum NormalReconstruction {
+    /// Uses the center pixel and the neighbours to the right and above, which is the cheapest,
+    /// but gives wrong normals along one side of the edges of the geometries.
+    #[default]
+    ThreeTap = 0,
+    /// Uses the center pixel and all four neighbours and chooses the neighbour on each axis with the smallest depth difference,
+    /// which avoids most of the wrong normals along the edges at the cost of two additional depth samples.
+    FiveTap = 1,
+}
+
+impl NormalReconstruction {
+    ///
+    /// Returns the fragment shader source for reconstructing view space normals from depth in a shader.
+    /// Requires that the `sample_depth` function is defined before this source, for example by including [DepthTexture::fragment_shader_source].
+    ///
+    pub fn fragment_shader_source() -> &'static str {
+        "
+        uniform uint normalReconstructionType;
+        uniform mat4 normalReconstructionProjectionInverse;
+        uniform vec2 normalReconstructionTexelSize;
+
+        vec3 view_position_from_depth(vec2 uv) {
+            vec4 position = normalReconstructionProjectionInverse * vec4(uv * 2.0 - 1.0, sample_depth(uv) * 2.0 - 1.0, 1.0);
+            return position.xyz / position.w;
+        }
+
+        vec3 view_normal_from_depth(vec2 uv) {
+            vec2 dx = vec2(normalReconstructionTexelSize.x, 0.0);
+            vec2 dy = vec2(0.0, normalReconstructionTexelSize.y);
+            vec3 center = view_position_from_depth(uv);
+            vec3 right = view_position_from_depth(uv + dx) - center;
+            vec3 up = view_position_from_depth(uv + dy) - center;
+            if (normalReconstructionType == 1u) {
+                // Use the neighbour closest in depth on each axis to avoid crossing edges
+                vec3 left = center - view_position_from_depth(uv - dx);
+                vec3 down = center - view_position_from_depth(uv - dy);
+                right = abs(left.z) < abs(right.z) ? left : right;
+                up = abs(down.z) < abs(up.z) ? down : up;
+            }
+            return normalize(cross(right, up));
+        }
+        "
+    }
+
+    ///
+    /// Sends the uniform data needed to reconstruct normals from depth to the fragment shader.
+    /// The size of a pixel is computed from the viewport of the given viewer, so the depth texture is assumed to have the same size as the viewport.
+    ///
+    pub fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer) {
+        let viewport = viewer.viewport();
+        program.use_uniform("normalReconstructionType", *self as u32);
+        program.use_uniform(
+            "normalReconstructionProjectionInverse",
+            viewer.projection().invert().unwrap(),
+        );
+        program.use_uniform(
+            "normalReconstructionTexelSize",
+            vec2(
+                1.0 / viewport.width.max(1) as f32,
+                1.0 / viewport.height.max(1) as f32,
+            ),
+        );
+    }
+}
+
+#[cfg(all(test, feature = "headless", not(target_arch = "wasm32")))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn view_normal_from_depth() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let size = 16;
+        let viewport = Viewport::new_at_origo(size, size);
+        // The view space is the same as the world space
+        let camera = Camera::new_perspective(
+            viewport,
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 0.0, -1.0),
+            vec3(0.0, 1.0, 0.0),
+            degrees(60.0),
+            1.0,
+            11.0,
+        );
+
+        // A tilted plane in front of the camera which covers the entire viewport
+        let normal = vec3(1.0, 1.0, 2.0).normalize();
+        let center = vec3(0.0, 0.0, -5.0);
+        let tangent = normal.cross(vec3(0.0, 1.0, 0.0)).normalize();
+        let bitangent = normal.cross(tangent);
+        let program = Program::from_source(
+            &context,
+            "
+            uniform mat4 viewProjection;
+            uniform vec3 vertex0;
+            uniform vec3 vertex1;
+            uniform vec3 vertex2;
+            void main()
+            {
+                vec3 vertices[3] = vec3[3](vertex0, vertex1, vertex2);
+                gl_Position = viewProjection * vec4(vertices[gl_VertexID], 1.0);
+            }
+            ",
+            "
+            layout (location = 0) out vec4 outColor;
+            void main()
+            {
+                outColor = vec4(1.0);
+            }
+            ",
+        )
+        .unwrap();
+        program.use_uniform("viewProjection", camera.projection() * camera.view());
+        program.use_uniform("vertex0", center + 10.0 * (-tangent - bitangent));
+        program.use_uniform("vertex1", center + 10.0 * (3.0 * tangent - bitangent));
+        program.use_uniform("vertex2", center + 10.0 * (-tangent + 3.0 * bitangent));
+        let mut depth = DepthTexture2D::new::<f32>(
+            &context,
+            size,
+            size,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        depth
+            .as_depth_target()
+            .clear(ClearState::depth(1.0))
+            .write::<RendererError>(|| {
+                crate::core::full_screen_draw(
+                    &context,
+                    &program,
+                    RenderStates::default(),
+                    viewport,
+                );
+                Ok(())
+            })
+            .unwrap();
+
+        let depth_texture = DepthTexture::Single(&depth);
+        let program = Program::from_source(
+            &context,
+            crate::core::full_screen_vertex_shader_source(),
+            &format!(
+                "{}
+                {}
+                in vec2 uvs;
+                layout (location = 0) out vec4 outColor;
+                void main()
+                {{
+                    outColor = vec4(view_normal_from_depth(uvs), 1.0);
+                }}",
+                depth_texture.fragment_shader_source(),
+                NormalReconstruction::fragment_shader_source()
+            ),
+        )
+        .unwrap();
+        depth_texture.use_uniforms(&program);
+
+        for normal_reconstruction in [
+            NormalReconstruction::ThreeTap,
+            NormalReconstruction::FiveTap,
+        ] {
+            normal_reconstruction.use_uniforms(&program, &camera);
+            let mut color = Texture2D::new_empty::<[f32; 4]>(
+                &context,
+                size,
+                size,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            );
+            let pixels = color
+                .as_color_target(None)
+                .clear(ClearState::color(0.0, 0.0, 0.0, 0.0))
+                .write::<RendererError>(|| {
+                    crate::core::full_screen_draw(
+                        &context,
+                        &program,
+                        RenderStates::default(),
+                        viewport,
+                    );
+                    Ok(())
+                })
+                .unwrap()
+                .read::<[f32; 4]>();
+
+            // The neighbours of the pixels at the border are outside the texture, so only the inner pixels are tested
+            for y in 1..size - 1 {
+                for x in 1..size - 1 {
+                    let pixel = pixels[(y * size + x) as usize];
+                    let reconstructed = vec3(pixel[0], pixel[1], pixel[2]);
+                    assert!(
+                        reconstructed.distance(normal) < 1e-2,
+                        "{:?} reconstructed {:?} at pixel ({}, {}) but should be {:?}",
+                        normal_reconstruction,
+                        reconstructed,
+                        x,
+                        y,
+                        normal
+                    );
+                }
+            }
+        }
+    }
+}
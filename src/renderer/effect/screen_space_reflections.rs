@@ -0,0 +1,267 @@
+use crate::renderer::*;
+
+///
+/// An effect that adds reflections of the rest of the scene to a surface, for example a floor, by tracing the reflected rays through the depth texture of the rendered scene.
+/// This is a lot cheaper than rendering the scene into a cube map (see [ReflectionProbe]) but can only reflect what is visible on the screen.
+/// Where the reflected ray leaves the screen or does not hit anything, the reflection fades to the given [Background].
+///
+/// This effect should only be applied to the geometry of the reflective surface, see [RenderTarget::render_with_effect],
+/// and it needs the rendered scene in a color and depth texture.
+/// The reflections are blended on top of what is already rendered in the render target, so the scene (including the reflective surface) should be rendered first.
+///
+#[derive(Clone)]
+pub struct ScreenSpaceReflectionsEffect {
+    /// The background of the scene which is used when the reflected ray does not hit anything on the screen.
+    pub background: Background,
+    /// A value in the range `[0..1]` specifying how reflective the surface is when viewed head on. The surface is always fully reflective at grazing angles.
+    pub reflectivity: f32,
+    /// A value in the range `[0..1]` specifying how rough the surface is. A rough surface fades out the reflections.
+    pub roughness: f32,
+    /// The maximum distance, in world space, that the reflected rays are traced.
+    pub max_distance: f32,
+    /// The number of steps used when tracing the reflected rays. A higher number of steps gives more accurate reflections at a higher cost.
+    pub steps: u32,
+    /// The thickness, in world space, assumed for the objects in the depth texture when determining whether a ray hits an object.
+    pub thickness: f32,
+}
+
+impl Default for ScreenSpaceReflectionsEffect {
+    fn default() -> Self {
+        Self {
+            background: Background::default(),
+            reflectivity: 0.5,
+            roughness: 0.0,
+            max_distance: 20.0,
+            steps: 64,
+            thickness: 0.5,
+        }
+    }
+}
+
+impl Effect for ScreenSpaceReflectionsEffect {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> String {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            match &self.background {
+                Background::Color(_) => "",
+                Background::Texture(_) => "#define USE_BACKGROUND_TEXTURE",
+            },
+            color_texture
+                .expect("Must supply a color texture to apply a screen space reflections effect")
+                .fragment_shader_source(),
+            depth_texture
+                .expect("Must supply a depth texture to apply a screen space reflections effect")
+                .fragment_shader_source(),
+            ToneMapping::fragment_shader_source(),
+            ColorMapping::fragment_shader_source(),
+            include_str!("shaders/screen_space_reflections_effect.frag")
+        )
+    }
+
+    fn id(
+        &self,
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId::ScreenSpaceReflectionsEffect(
+            color_texture
+                .expect("Must supply a color texture to apply a screen space reflections effect"),
+            depth_texture
+                .expect("Must supply a depth texture to apply a screen space reflections effect"),
+            matches!(self.background, Background::Texture(_)),
+        )
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        viewer
+            .tone_mapping()
+            .use_uniforms_with_exposure(program, viewer.exposure());
+        viewer.color_mapping().use_uniforms(program);
+        color_texture
+            .expect("Must supply a color texture to apply a screen space reflections effect")
+            .use_uniforms(program);
+        depth_texture
+            .expect("Must supply a depth texture to apply a screen space reflections effect")
+            .use_uniforms(program);
+        program.use_uniform("view", viewer.view());
+        program.use_uniform("projection", viewer.projection());
+        program.use_uniform("projectionInverse", viewer.projection().invert().unwrap());
+        program.use_uniform("cameraPosition", viewer.position());
+        program.use_uniform("reflectivity", self.reflectivity);
+        program.use_uniform("roughness", self.roughness);
+        program.use_uniform("maxDistance", self.max_distance);
+        program.use_uniform("steps", self.steps.max(1) as i32);
+        program.use_uniform("thickness", self.thickness);
+        match &self.background {
+            Background::Color(color) => {
+                program.use_uniform("environmentColor", color.to_linear_srgb())
+            }
+            Background::Texture(tex) => program.use_texture_cube("environmentMap", tex),
+        }
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            depth_test: DepthTest::LessOrEqual,
+            blend: Blend::TRANSPARENCY,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "headless", not(target_arch = "wasm32")))]
+mod tests {
+    use crate::*;
+
+    ///
+    /// Renders a floor, and optionally a red wall behind it, and applies the screen space reflections effect with a blue background to the floor.
+    /// Returns the rows of pixels, starting with the bottom row.
+    ///
+    fn reflect(context: &Context, with_wall: bool) -> Vec<Vec<[u8; 4]>> {
+        let size = 16;
+        let mut camera = Camera::new_perspective(
+            Viewport::new_at_origo(size, size),
+            vec3(0.0, 1.0, 0.0),
+            vec3(0.0, 1.0, -1.0),
+            vec3(0.0, 1.0, 0.0),
+            degrees(60.0),
+            0.1,
+            100.0,
+        );
+        camera.disable_tone_and_color_mapping();
+
+        // The floor is in the xz-plane facing up and the wall is 5 units in front of the camera facing the camera
+        let mut cpu_mesh = CpuMesh::square();
+        cpu_mesh
+            .transform(Mat4::from_angle_x(degrees(-90.0)) * Mat4::from_scale(50.0))
+            .unwrap();
+        let floor = Gm::new(
+            Mesh::new(context, &cpu_mesh),
+            ColorMaterial {
+                color: Srgba::new_opaque(128, 128, 128),
+                ..Default::default()
+            },
+        );
+        let mut cpu_mesh = CpuMesh::square();
+        cpu_mesh
+            .transform(Mat4::from_translation(vec3(0.0, 50.0, -5.0)) * Mat4::from_scale(50.0))
+            .unwrap();
+        let wall = Gm::new(
+            Mesh::new(context, &cpu_mesh),
+            ColorMaterial {
+                color: Srgba::RED,
+                ..Default::default()
+            },
+        );
+        let objects = if with_wall {
+            vec![&floor, &wall]
+        } else {
+            vec![&floor]
+        };
+
+        let new_color_texture = || {
+            Texture2D::new_empty::<[u8; 4]>(
+                context,
+                size,
+                size,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            )
+        };
+        let new_depth_texture = || {
+            DepthTexture2D::new::<f32>(
+                context,
+                size,
+                size,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            )
+        };
+        let mut scene_color = new_color_texture();
+        let mut scene_depth = new_depth_texture();
+        RenderTarget::new(
+            scene_color.as_color_target(None),
+            scene_depth.as_depth_target(),
+        )
+        .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0))
+        .render(&camera, &objects, &[]);
+
+        let effect = ScreenSpaceReflectionsEffect {
+            background: Background::Color(Srgba::BLUE),
+            reflectivity: 1.0,
+            ..Default::default()
+        };
+        let mut color = new_color_texture();
+        let mut depth = new_depth_texture();
+        let pixels = RenderTarget::new(color.as_color_target(None), depth.as_depth_target())
+            .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0))
+            .render(&camera, &objects, &[])
+            .render_with_effect(
+                &effect,
+                &camera,
+                &floor,
+                &[],
+                Some(ColorTexture::Single(&scene_color)),
+                Some(DepthTexture::Single(&scene_depth)),
+            )
+            .read_color::<[u8; 4]>();
+        // The first row of pixels is the top row
+        pixels
+            .chunks(size as usize)
+            .rev()
+            .map(|row| row.to_vec())
+            .collect()
+    }
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn screen_space_reflections() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+
+        // The bottom rows show the floor and the top rows show the wall, which is not changed by the effect
+        let rows = reflect(&context, true);
+        for pixel in rows[12..].iter().flatten() {
+            assert_eq!(*pixel, [255, 0, 0, 255]);
+        }
+
+        // The reflectivity is one, so the floor is replaced by the reflection of the wall,
+        // except near the left and right edges where the reflection fades towards the background
+        for row in rows[..4].iter() {
+            for pixel in row[4..12].iter() {
+                assert!(
+                    pixel[0] > 200 && pixel[1] == 0 && pixel[2] < 55,
+                    "{:?}",
+                    pixel
+                );
+            }
+            for pixel in row.iter() {
+                assert!(
+                    pixel[1] == 0 && pixel[0] as u32 + pixel[2] as u32 >= 250,
+                    "{:?}",
+                    pixel
+                );
+            }
+        }
+
+        // Without the wall, the reflected rays leave the screen and the floor reflects the background
+        let rows = reflect(&context, false);
+        for pixel in rows[..4].iter().flatten() {
+            assert_eq!(*pixel, [0, 0, 255, 255]);
+        }
+    }
+}
@@ -0,0 +1,20 @@
+
+uniform float strength;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    // Offset direction scaled by the distance from the center, where 1 is the corners
+    vec2 offset = (uvs - 0.5) * sqrt(2.0) * strength;
+
+    vec4 color = sample_color(uvs);
+    outColor.r = sample_color(uvs + offset).r;
+    outColor.g = color.g;
+    outColor.b = sample_color(uvs - offset).b;
+    outColor.a = color.a;
+    outColor.rgb = tone_mapping(outColor.rgb);
+    outColor.rgb = color_mapping(outColor.rgb);
+}
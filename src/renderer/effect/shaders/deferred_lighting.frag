@@ -4,6 +4,9 @@ uniform float zNear;
 uniform float zFar;
 uniform vec3 cameraPosition;
 uniform int debug_type;
+#ifdef USE_EMISSIVE_TEXTURE
+uniform sampler2D emissiveMap;
+#endif
 
 in vec2 uvs;
 
@@ -33,7 +36,11 @@ void main()
     vec3 normal = normalize(vec3(n2.x, n2.y, (int(floor(n.z * 255.0)) & 128) == 128 ? z: -z));
     float roughness_factor = n.w;
     float occlusion = float(int(floor(n.z * 255.0)) & 127) / 127.0;
+#ifdef USE_EMISSIVE_TEXTURE
+    vec3 total_emissive = texture(emissiveMap, uvs).rgb;
+#else
     vec3 total_emissive = sample_layer(uvs, 2).rgb;
+#endif
 
     if(debug_type == 0) // Position
     {
@@ -62,6 +69,7 @@ void main()
     }
     else { // None
         outColor.rgb = total_emissive + calculate_lighting(cameraPosition, surface_color.rgb, position, normal, metallic_factor, roughness_factor, occlusion);
+        outColor.rgb = apply_fog(outColor.rgb, position);
         outColor.rgb = tone_mapping(outColor.rgb);
         outColor.rgb = color_mapping(outColor.rgb);
         outColor.a = surface_color.a;
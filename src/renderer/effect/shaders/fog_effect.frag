@@ -2,7 +2,6 @@
 uniform mat4 viewProjectionInverse;
 
 uniform float time;
-uniform float fogDensity;
 uniform vec4 fogColor;
 uniform float animation;
 uniform vec3 eyePosition;
@@ -132,10 +131,11 @@ void main()
     vec3 pos = world_pos_from_depth(viewProjectionInverse, depth, uvs);
 
     // Distance
-    float dist = depth < 0.999f ? distance(pos, eyePosition) : 100.f;
+    if (depth >= 0.999f) {
+        pos = eyePosition + 100.f * normalize(pos - eyePosition);
+    }
 
-    float x = dist * fogDensity;
-    float factor = 1. - 1. / exp(x * x);
+    float factor = fog_factor(pos, eyePosition);
 
     // Noise
     float n = snoise(pos);
@@ -0,0 +1,21 @@
+
+uniform mat4 projectionInverse;
+uniform float minDistance;
+uniform float maxDistance;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    float depth = sample_depth(uvs);
+    float linearDepth = 1.0;
+    if (depth < 1.0) {
+        // The distance along the view direction in world space units
+        vec4 p = projectionInverse * vec4(uvs * 2.0 - 1.0, depth * 2.0 - 1.0, 1.0);
+        float viewDistance = -p.z / p.w;
+        linearDepth = clamp((viewDistance - minDistance) / (maxDistance - minDistance), 0.0, 1.0);
+    }
+    outColor = vec4(linearDepth, linearDepth, linearDepth, 1.0);
+}
@@ -0,0 +1,27 @@
+
+uniform sampler2D velocityTexture;
+uniform int samples;
+uniform float strength;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec2 velocity = strength * texture(velocityTexture, uvs).rg;
+    vec4 color = sample_color(uvs);
+
+    // Sample along the velocity centered around the pixel
+    if(samples > 1 && dot(velocity, velocity) > 0.0) {
+        for(int i = 1; i < samples; i++) {
+            vec2 offset = velocity * (float(i) / float(samples - 1) - 0.5);
+            color += sample_color(clamp(uvs + offset, 0.0, 1.0));
+        }
+        color /= float(samples);
+    }
+
+    outColor = color;
+    outColor.rgb = tone_mapping(outColor.rgb);
+    outColor.rgb = color_mapping(outColor.rgb);
+}
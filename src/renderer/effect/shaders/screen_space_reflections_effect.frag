@@ -0,0 +1,88 @@
+uniform mat4 view;
+uniform mat4 projection;
+uniform mat4 projectionInverse;
+uniform vec3 cameraPosition;
+
+#ifdef USE_BACKGROUND_TEXTURE
+uniform samplerCube environmentMap;
+#else
+uniform vec4 environmentColor;
+#endif
+
+uniform float reflectivity;
+uniform float roughness;
+uniform float maxDistance;
+uniform int steps;
+uniform float thickness;
+
+in vec3 pos;
+in vec3 nor;
+
+layout (location = 0) out vec4 outColor;
+
+const int MAX_STEPS = 256;
+
+vec3 view_pos_from_depth(vec2 uv) {
+    vec4 clipSpacePosition = vec4(uv * 2.0 - 1.0, sample_depth(uv) * 2.0 - 1.0, 1.0);
+    vec4 position = projectionInverse * clipSpacePosition;
+    return position.xyz / position.w;
+}
+
+vec3 background_color(vec3 reflectDir)
+{
+#ifdef USE_BACKGROUND_TEXTURE
+    return texture(environmentMap, reflectDir).rgb;
+#else
+    return environmentColor.rgb;
+#endif
+}
+
+void main()
+{
+    vec3 normal = normalize(nor);
+    vec3 incidentDir = normalize(pos - cameraPosition);
+    if(!gl_FrontFacing) {
+        normal = -normal;
+    }
+    vec3 reflectDir = normalize(reflect(incidentDir, normal));
+
+    // Trace the reflected ray in view space
+    vec3 rayOrigin = (view * vec4(pos, 1.0)).xyz;
+    vec3 rayDir = normalize(mat3(view) * reflectDir);
+    float stepSize = maxDistance / float(steps);
+
+    vec3 reflectColor = background_color(reflectDir);
+    for (int i = 1; i <= MAX_STEPS; i++)
+    {
+        if(i > steps) {
+            break;
+        }
+        vec3 p = rayOrigin + rayDir * stepSize * float(i);
+        vec4 p_clip = projection * vec4(p, 1.0);
+        if(p_clip.w <= 0.0) {
+            break; // Behind the camera
+        }
+        vec2 uv = 0.5 + 0.5 * p_clip.xy / p_clip.w;
+        if(uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
+            break; // Outside the screen
+        }
+        float difference = view_pos_from_depth(uv).z - p.z;
+        if(difference > 0.0 && difference < thickness)
+        {
+            // Fade towards the background near the edges of the screen and at the end of the ray
+            vec2 edge = abs(uv * 2.0 - 1.0);
+            float fade = 1.0 - smoothstep(0.8, 1.0, max(edge.x, edge.y));
+            fade *= 1.0 - smoothstep(0.8, 1.0, float(i) / float(steps));
+            reflectColor = mix(reflectColor, sample_color(uv).rgb, fade);
+            break;
+        }
+    }
+
+    // Fresnel approximation
+    float cosAngle = max(dot(normal, -incidentDir), 0.0);
+    float fresnel = mix(reflectivity, 1.0, pow(1.0 - cosAngle, 5.0));
+
+    outColor.rgb = tone_mapping(reflectColor);
+    outColor.rgb = color_mapping(outColor.rgb);
+    outColor.a = fresnel * (1.0 - clamp(roughness, 0.0, 1.0));
+}
@@ -0,0 +1,21 @@
+
+uniform vec4 vignetteColor;
+uniform float radius;
+uniform float softness;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec4 color = sample_color(uvs);
+
+    // Distance from the center, where 1 is the corners
+    float dist = length(uvs - 0.5) * sqrt(2.0);
+    float factor = smoothstep(radius, radius + softness, dist) * vignetteColor.a;
+
+    outColor = vec4(mix(color.rgb, vignetteColor.rgb, factor), color.a);
+    outColor.rgb = tone_mapping(outColor.rgb);
+    outColor.rgb = color_mapping(outColor.rgb);
+}
@@ -11,6 +11,10 @@ uniform vec4 environmentColor;
 
 uniform float metallic;
 uniform float roughness;
+uniform float indexOfRefraction;
+uniform vec4 shallowColor;
+uniform vec4 deepColor;
+uniform float absorption;
 
 in vec3 pos;
 in vec3 nor;
@@ -18,9 +22,7 @@ in vec2 uvs;
 
 layout (location = 0) out vec4 outColor;
 
-const float Eta = 1. / 1.5; // Ratio of indices of refraction
 const float FresnelPower = 5.0;
-const float F = ((1.0-Eta) * (1.0-Eta)) / ((1.0+Eta) * (1.0+Eta));
 
 vec2 uv_at(vec3 world_pos) {
     vec4 p_s = viewProjection * vec4(world_pos, 1.);
@@ -49,16 +51,10 @@ vec3 reflect_color(vec3 incidentDir, vec3 normal)
 #endif
 }
 
-vec3 water(vec3 col, vec3 p1, vec3 p2)
+vec3 water(vec3 col, float waterDepth)
 {
-    const vec3 scattering = vec3(0.2, 0.4, 0.2); // Scattering coefficient (due to particles in the water)
-    const vec3 absorption = vec3(0.4, 0.955, 0.99); // Absorption coefficient
-    const vec3 c = scattering * absorption;
-    const vec3 equilibriumColorAtInfinity = vec3(0., 0.1, 0.14); // Water color at "infinity"
-    
-    float dist = min(distance(p1, p2), 100.);
-    vec3 colorChange = vec3(clamp( pow(c.r, dist), 0., 1.), clamp( pow(c.g, dist), 0., 1.), clamp( pow(c.b, dist), 0., 1.));
-    return colorChange * col + (1. - colorChange) * equilibriumColorAtInfinity;
+    float t = 1. - exp(-absorption * waterDepth);
+    return mix(shallowColor.rgb * col, deepColor.rgb, t);
 }
 
 void main()
@@ -67,23 +63,33 @@ void main()
     
     vec3 normal = normalize(nor);
     vec3 incidentDir = normalize(pos - cameraPosition);
-    screen_uv -= 0.05 * normal.xz; // Shift the water bottom/sky.
-    float depth = sample_depth(screen_uv);
-    vec3 backgroundPos = world_pos_from_depth(viewProjectionInverse, depth, screen_uv);
-    outColor.rgb = sample_color(screen_uv).rgb;
+    float eta = 1. / indexOfRefraction; // Ratio of indices of refraction
+    
+    // Refraction
+    vec3 backgroundPos = world_pos_from_depth(viewProjectionInverse, sample_depth(screen_uv), screen_uv);
+    vec3 refractDir = refract(incidentDir, normal, eta);
+    vec2 refract_uv = clamp(uv_at(pos + refractDir * distance(pos, backgroundPos)), 0., 1.);
+    vec3 refractPos = world_pos_from_depth(viewProjectionInverse, sample_depth(refract_uv), refract_uv);
+    if(distance(cameraPosition, refractPos) < distance(cameraPosition, pos))
+    {
+        // The refracted position is in front of the water surface, so use the unperturbed position instead
+        refract_uv = screen_uv;
+        refractPos = backgroundPos;
+    }
+    // The depth of the water, clamped to zero if the scene is above the water surface
+    float waterDepth = clamp(distance(cameraPosition, refractPos) - distance(cameraPosition, pos), 0., 100.);
+    vec3 refractColor = water(sample_color(refract_uv).rgb, waterDepth);
     
     // Compute cosine to the incident angle
     float cosAngle = dot(normal, -incidentDir);
     
     // Compute fresnel approximation
+    float F = ((1.0-eta) * (1.0-eta)) / ((1.0+eta) * (1.0+eta));
     float fresnel = mix(F, 1.f, pow(1. - max(cosAngle, 0.), FresnelPower));
     
     // Reflection
     vec3 reflectColor = reflect_color(incidentDir, normal);
     
-    // Refraction
-    vec3 refractColor = water(outColor.rgb, pos, backgroundPos);
-    
     // Mix refraction and reflection
     outColor.rgb = mix(refractColor, reflectColor, fresnel);
 
@@ -0,0 +1,82 @@
+use crate::renderer::*;
+
+///
+/// An effect that darkens the image towards the edges, ie. a radial fade from the center of the screen towards the given color.
+///
+#[derive(Clone, Debug)]
+pub struct VignetteEffect {
+    /// The color that the image fades towards at the edges. The alpha value determines the strength of the effect.
+    pub color: Srgba,
+    /// The distance from the center where the fade starts, where `0.0` is the center and `1.0` is the corners of the screen.
+    pub radius: f32,
+    /// The distance over which the image fades from no effect to full effect.
+    pub softness: f32,
+}
+
+impl Default for VignetteEffect {
+    fn default() -> Self {
+        Self {
+            color: Srgba::BLACK,
+            radius: 0.5,
+            softness: 0.5,
+        }
+    }
+}
+
+impl Effect for VignetteEffect {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> String {
+        format!(
+            "{}\n{}\n{}\n{}",
+            color_texture
+                .expect("Must supply a color texture to apply a vignette effect")
+                .fragment_shader_source(),
+            ToneMapping::fragment_shader_source(),
+            ColorMapping::fragment_shader_source(),
+            include_str!("shaders/vignette_effect.frag")
+        )
+    }
+
+    fn id(
+        &self,
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId::VignetteEffect(
+            color_texture.expect("Must supply a color texture to apply a vignette effect"),
+        )
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) {
+        viewer
+            .tone_mapping()
+            .use_uniforms_with_exposure(program, viewer.exposure());
+        viewer.color_mapping().use_uniforms(program);
+        color_texture
+            .expect("Must supply a color texture to apply a vignette effect")
+            .use_uniforms(program);
+        program.use_uniform("vignetteColor", self.color.to_linear_srgb());
+        program.use_uniform("radius", self.radius);
+        program.use_uniform("softness", self.softness.max(0.0001));
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            cull: Cull::Back,
+            ..Default::default()
+        }
+    }
+}
@@ -31,6 +31,14 @@ pub struct WaterEffect {
     pub roughness: f32,
     /// The lighting model used when rendering this effect
     pub lighting_model: LightingModel,
+    /// The index of refraction of the water which determines how much the scene below the water surface is distorted and how reflective the surface is.
+    pub index_of_refraction: f32,
+    /// The color of the water where it is shallow. The scene below the water surface is multiplied by this color.
+    pub shallow_color: Srgba,
+    /// The color of the water where it is deep.
+    pub deep_color: Srgba,
+    /// Determines how fast the water color changes from the shallow color to the deep color as the depth of the water increases.
+    pub absorption: f32,
 }
 
 impl Effect for WaterEffect {
@@ -86,7 +94,9 @@ impl Effect for WaterEffect {
         depth_texture: Option<DepthTexture>,
     ) {
         program.use_uniform_if_required("lightingModel", lighting_model_to_id(self.lighting_model));
-        viewer.tone_mapping().use_uniforms(program);
+        viewer
+            .tone_mapping()
+            .use_uniforms_with_exposure(program, viewer.exposure());
         viewer.color_mapping().use_uniforms(program);
         color_texture
             .expect("Must supply a color texture to apply a water effect")
@@ -112,6 +122,10 @@ impl Effect for WaterEffect {
         );
         program.use_uniform("metallic", self.metallic);
         program.use_uniform("roughness", self.roughness);
+        program.use_uniform("indexOfRefraction", self.index_of_refraction);
+        program.use_uniform("shallowColor", self.shallow_color.to_linear_srgb());
+        program.use_uniform("deepColor", self.deep_color.to_linear_srgb());
+        program.use_uniform("absorption", self.absorption);
         match &self.background {
             Background::Color(color) => {
                 program.use_uniform("environmentColor", color.to_linear_srgb())
@@ -128,6 +142,10 @@ impl Default for WaterEffect {
             metallic: 0.0,
             roughness: 1.0,
             lighting_model: LightingModel::Blinn,
+            index_of_refraction: 1.33,
+            shallow_color: Srgba::new_opaque(180, 240, 230),
+            deep_color: Srgba::new_opaque(0, 89, 105),
+            absorption: 0.5,
         }
     }
 }
@@ -43,6 +43,10 @@ macro_rules! impl_geometry_body {
         fn aabb(&self) -> AxisAlignedBoundingBox {
             self.$inner().aabb()
         }
+
+        fn bounding_sphere(&self) -> (Vec3, f32) {
+            self.$inner().bounding_sphere()
+        }
     };
 }
 
@@ -78,6 +82,14 @@ mod circle;
 #[doc(inline)]
 pub use circle::*;
 
+mod cpu_mesh_ext;
+#[doc(inline)]
+pub use cpu_mesh_ext::*;
+
+mod aabb_ext;
+#[doc(inline)]
+pub use aabb_ext::*;
+
 use crate::core::*;
 use crate::renderer::*;
 
@@ -152,6 +164,15 @@ pub trait Geometry {
     ///
     fn aabb(&self) -> AxisAlignedBoundingBox;
 
+    ///
+    /// Returns the center and radius of a sphere in the global coordinate system which contains this geometry,
+    /// for example for rotation invariant culling or for framing the geometry with a camera.
+    /// By default, it is the sphere containing the [AxisAlignedBoundingBox] returned by [Geometry::aabb].
+    ///
+    fn bounding_sphere(&self) -> (Vec3, f32) {
+        self.aabb().bounding_sphere()
+    }
+
     ///
     /// For updating the animation of this geometry if it is animated, if not, this method does nothing.
     /// The time parameter should be some continious time, for example the time since start.
@@ -237,6 +258,10 @@ impl<T: Geometry> Geometry for std::sync::RwLock<T> {
         self.read().unwrap().aabb()
     }
 
+    fn bounding_sphere(&self) -> (Vec3, f32) {
+        self.read().unwrap().bounding_sphere()
+    }
+
     fn animate(&mut self, time: f32) {
         self.write().unwrap().animate(time)
     }
@@ -0,0 +1,92 @@
+use crate::renderer::*;
+
+///
+/// Additional functionality for an [AxisAlignedBoundingBox].
+///
+pub trait AxisAlignedBoundingBoxExt {
+    ///
+    /// Returns the center and radius of the smallest sphere that contains this bounding box.
+    /// The radius is zero if the bounding box is empty and infinite if the bounding box is infinite.
+    ///
+    fn bounding_sphere(&self) -> (Vec3, f32);
+
+    ///
+    /// Returns the smallest bounding box that contains both this and the given bounding box.
+    /// Use [AxisAlignedBoundingBox::transformed] to get the bounding box of the transformed corners of a bounding box.
+    ///
+    fn union(&self, other: AxisAlignedBoundingBox) -> AxisAlignedBoundingBox;
+}
+
+impl AxisAlignedBoundingBoxExt for AxisAlignedBoundingBox {
+    fn bounding_sphere(&self) -> (Vec3, f32) {
+        if self.is_empty() {
+            (Vec3::zero(), 0.0)
+        } else if self.is_infinite() {
+            (Vec3::zero(), f32::INFINITY)
+        } else {
+            (self.center(), 0.5 * self.size().magnitude())
+        }
+    }
+
+    fn union(&self, other: AxisAlignedBoundingBox) -> AxisAlignedBoundingBox {
+        let mut aabb = *self;
+        aabb.expand_with_aabb(other);
+        aabb
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union() {
+        let a =
+            AxisAlignedBoundingBox::new_with_positions(&[vec3(0.0, 0.0, 0.0), vec3(1.0, 1.0, 1.0)]);
+        let b = AxisAlignedBoundingBox::new_with_positions(&[
+            vec3(-1.0, 0.5, 2.0),
+            vec3(0.5, 3.0, 4.0),
+        ]);
+        let union = a.union(b);
+        assert_eq!(union.min(), vec3(-1.0, 0.0, 0.0));
+        assert_eq!(union.max(), vec3(1.0, 3.0, 4.0));
+        assert_eq!(b.union(a).min(), union.min());
+        assert_eq!(b.union(a).max(), union.max());
+        assert_eq!(a.union(AxisAlignedBoundingBox::EMPTY).min(), a.min());
+        assert_eq!(a.union(AxisAlignedBoundingBox::EMPTY).max(), a.max());
+        assert!(a.union(AxisAlignedBoundingBox::INFINITE).is_infinite());
+    }
+
+    #[test]
+    fn transformed() {
+        let aabb = AxisAlignedBoundingBox::new_with_positions(&[
+            vec3(-1.0, -1.0, -1.0),
+            vec3(1.0, 1.0, 1.0),
+        ]);
+        // Rotating a cube by 45 degrees around the y-axis makes the box wider in the x and z directions
+        let rotated = aabb.transformed(Mat4::from_angle_y(degrees(45.0)));
+        let extent = 2.0f32.sqrt();
+        assert!(rotated.min().distance(vec3(-extent, -1.0, -extent)) < 1e-5);
+        assert!(rotated.max().distance(vec3(extent, 1.0, extent)) < 1e-5);
+        // and the union with the original box is the rotated box
+        assert!(aabb.union(rotated).min().distance(rotated.min()) < 1e-5);
+        assert!(aabb.union(rotated).max().distance(rotated.max()) < 1e-5);
+    }
+
+    #[test]
+    fn bounding_sphere() {
+        let aabb =
+            AxisAlignedBoundingBox::new_with_positions(&[vec3(1.0, 2.0, 3.0), vec3(3.0, 4.0, 4.0)]);
+        let (center, radius) = aabb.bounding_sphere();
+        assert_eq!(center, vec3(2.0, 3.0, 3.5));
+        assert_eq!(radius, 1.5);
+        assert_eq!(
+            AxisAlignedBoundingBox::EMPTY.bounding_sphere(),
+            (Vec3::zero(), 0.0)
+        );
+        assert_eq!(
+            AxisAlignedBoundingBox::INFINITE.bounding_sphere().1,
+            f32::INFINITY
+        );
+    }
+}
@@ -0,0 +1,1748 @@
+use crate::renderer::*;
+use std::collections::HashMap;
+
+///
+/// Defines how two connected segments of a polyline are joined, see [CpuMeshExt::polyline].
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JoinStyle {
+    /// The outer edges of the segments are extended until they meet in a sharp corner.
+    /// Falls back to [JoinStyle::Bevel] for very sharp angles where the corner would be more than four times the width away from the point.
+    #[default]
+    Miter,
+    /// The outer corners of the segments are connected by a straight line.
+    Bevel,
+}
+
+///
+/// Defines how the ends of a polyline are drawn, see [CpuMeshExt::polyline].
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CapStyle {
+    /// The line ends exactly at the end points.
+    #[default]
+    Butt,
+    /// The line ends in a half circle around the end points.
+    Round,
+    /// The line is extended by half the width beyond the end points.
+    Square,
+}
+
+///
+/// Additional functionality for constructing and modifying a [CpuMesh].
+///
+pub trait CpuMeshExt {
+    ///
+    /// Returns a regular grid in the xz-plane centered in origo with the normals pointing up in the y-direction.
+    /// The grid consists of `width` times `height` quads, each with a side length of `spacing`.
+    /// The uv coordinates range from `(0, 0)` to `(1, 1)` across the grid.
+    ///
+    fn grid(width: u32, height: u32, spacing: f32) -> Self;
+
+    ///
+    /// Displaces each vertex along the y-direction to the height given by the function of the (x, z) coordinates of the vertex
+    /// and recomputes smooth normals by averaging the normals of the neighbouring triangles.
+    ///
+    fn displace_with<F: Fn(f32, f32) -> f32>(&mut self, height: F);
+
+    ///
+    /// Returns a triangle mesh in the xy-plane of a line with the given width through the given points, for example for 2D plotting using a camera created by [Camera::new_2d].
+    /// The segments are joined as specified by the join style and the ends are drawn as specified by the cap style.
+    /// All triangles are counter clockwise and the normals point in the positive z-direction.
+    ///
+    fn polyline(points: &[Vec2], width: f32, join: JoinStyle, cap: CapStyle) -> Self;
+
+    ///
+    /// Returns a triangle mesh in the xy-plane filling the simple (possibly concave but not self-intersecting) polygon with the given corner points.
+    /// The points can be given in either clockwise or counter clockwise order, but the triangles are always counter clockwise and the normals point in the positive z-direction.
+    ///
+    fn polygon(points: &[Vec2]) -> Self;
+
+    ///
+    /// Returns a disk in the xy-plane with radius 1 and center in origo, approximated by the given number of segments.
+    /// The uv coordinates maps the disk to the unit square, ie. the center is at (0.5, 0.5).
+    /// All triangles are counter clockwise and the normals point in the positive z-direction.
+    ///
+    fn disk(segments: u32) -> Self;
+
+    ///
+    /// Returns a ring in the xy-plane between the inner and outer radius with center in origo, approximated by the given number of segments.
+    /// The u coordinate goes from 0 to 1 around the ring starting at the positive x-axis and the v coordinate goes from 0 at the inner radius to 1 at the outer radius.
+    /// All triangles are counter clockwise and the normals point in the positive z-direction.
+    ///
+    fn ring(inner_radius: f32, outer_radius: f32, segments: u32) -> Self;
+
+    ///
+    /// Returns a circular sector (a pie slice) in the xy-plane with the given radius and center in origo, going counter clockwise from the start angle to the end angle measured from the positive x-axis.
+    /// The arc is approximated by the given number of segments.
+    /// The uv coordinates maps the full disk with the given radius to the unit square, ie. the center is at (0.5, 0.5).
+    /// All triangles are counter clockwise and the normals point in the positive z-direction.
+    ///
+    fn arc(
+        start_angle: impl Into<Radians>,
+        end_angle: impl Into<Radians>,
+        radius: f32,
+        segments: u32,
+    ) -> Self;
+
+    ///
+    /// Returns a capsule, ie. a cylinder with hemispherical caps, around the x-axis with center in origo.
+    /// The cylinder part has the given height and the total length of the capsule is thus `height + 2.0 * radius`.
+    /// The given number of segments defines the subdivisions around the x-axis.
+    /// The u coordinate goes around the x-axis and the v coordinate goes from the cap at the negative x-axis to the cap at the positive x-axis.
+    ///
+    fn capsule(radius: f32, height: f32, segments: u32) -> Self;
+
+    ///
+    /// Returns an axis aligned cube with center in origo and the given side length, where the edges and corners are rounded with the given radius.
+    /// The given number of segments defines the subdivisions of each rounded edge. If the radius is zero, each side is a single quad.
+    /// Each side of the cube is mapped to the full uv range.
+    ///
+    fn rounded_cube(size: f32, corner_radius: f32, segments: u32) -> Self;
+
+    ///
+    /// Smooths the mesh using the given number of iterations of Loop subdivision, each of which splits every triangle into four.
+    /// The uv coordinates and colors are interpolated using the same weights as the positions, the normals are recomputed and the tangents are removed.
+    /// Vertices which are not shared between triangles, for example at uv seams, are treated as boundaries and are therefore not smoothed across.
+    ///
+    fn subdivide(&mut self, iterations: u32);
+
+    ///
+    /// Returns a simplified version of this mesh with approximately the given ratio of the triangles, for example for generating level of detail meshes.
+    /// The triangle count is reduced by collapsing the edges which changes the shape the least as measured by the quadric error metric.
+    /// Vertices on the boundary of the mesh (including vertices which are not shared between triangles, for example at uv seams) are not moved and collapses that would create non-manifold geometry or flip triangles are rejected,
+    /// so the result might contain more triangles than requested.
+    /// The uv coordinates and colors are interpolated, the normals are recomputed and the tangents are removed.
+    ///
+    fn simplify(&self, target_ratio: f32) -> CpuMesh;
+
+    ///
+    /// Generates uv coordinates by projecting the positions onto the plane orthogonal to the given axis, for example the positive z-axis for a front facing projection.
+    /// The uv coordinates are scaled such that the mesh covers the entire unit square. Any existing uv coordinates and tangents are replaced respectively removed.
+    ///
+    fn generate_planar_uvs(&mut self, axis: Vec3);
+
+    ///
+    /// Generates uv coordinates by projecting each triangle onto the side of the bounding box that it faces the most,
+    /// which works well for box-like meshes and gives an acceptable result for most other meshes.
+    /// Vertices shared between triangles facing different sides are duplicated and the mesh is indexed afterwards.
+    /// The uv coordinates are in the same scale along all axes such that the largest side of the bounding box covers the unit square.
+    /// Any existing uv coordinates and tangents are replaced respectively removed.
+    ///
+    fn generate_box_uvs(&mut self);
+
+    ///
+    /// Computes the per vertex tangents and updates the tangents of the mesh, similar to [CpuMesh::compute_tangents],
+    /// except that it returns an error instead of panicking if the mesh does not have both normals and uv coordinates.
+    /// Also, vertices where the tangent cannot be computed, for example because all of the surrounding triangles have degenerate uv coordinates,
+    /// get an arbitrary tangent orthogonal to the normal instead of an invalid tangent.
+    ///
+    fn try_compute_tangents(&mut self) -> Result<(), RendererError>;
+
+    ///
+    /// Computes the center and radius of a sphere containing all of the positions of this mesh using Ritter's algorithm.
+    /// The result is usually tighter than the sphere containing the [AxisAlignedBoundingBox] of the mesh, but is not necessarily the smallest possible sphere.
+    ///
+    fn compute_bounding_sphere(&self) -> (Vec3, f32);
+}
+
+impl CpuMeshExt for CpuMesh {
+    fn grid(width: u32, height: u32, spacing: f32) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+        let stride = width + 1;
+        let mut positions = Vec::with_capacity((stride * (height + 1)) as usize);
+        let mut uvs = Vec::with_capacity((stride * (height + 1)) as usize);
+        for iz in 0..=height {
+            for ix in 0..=width {
+                positions.push(vec3(
+                    (ix as f32 - 0.5 * width as f32) * spacing,
+                    0.0,
+                    (iz as f32 - 0.5 * height as f32) * spacing,
+                ));
+                uvs.push(vec2(ix as f32 / width as f32, iz as f32 / height as f32));
+            }
+        }
+        let mut indices = Vec::with_capacity((6 * width * height) as usize);
+        for iz in 0..height {
+            for ix in 0..width {
+                let a = iz * stride + ix;
+                let b = a + 1;
+                let c = a + stride;
+                let d = c + 1;
+                indices.extend([a, c, b, b, c, d]);
+            }
+        }
+        let normals = vec![vec3(0.0, 1.0, 0.0); positions.len()];
+        CpuMesh {
+            positions: Positions::F32(positions),
+            indices: Indices::U32(indices),
+            normals: Some(normals),
+            uvs: Some(uvs),
+            ..Default::default()
+        }
+    }
+
+    fn displace_with<F: Fn(f32, f32) -> f32>(&mut self, height: F) {
+        match &mut self.positions {
+            Positions::F32(positions) => positions.iter_mut().for_each(|p| p.y = height(p.x, p.z)),
+            Positions::F64(positions) => positions
+                .iter_mut()
+                .for_each(|p| p.y = height(p.x as f32, p.z as f32) as f64),
+        }
+        self.compute_normals();
+    }
+
+    fn polyline(points: &[Vec2], width: f32, join: JoinStyle, cap: CapStyle) -> Self {
+        let mut points = points.to_vec();
+        points.dedup_by(|a, b| a.distance2(*b) < f32::EPSILON);
+        let mut positions: Vec<Vec2> = Vec::new();
+        if points.len() >= 2 && width > 0.0 {
+            let half_width = 0.5 * width;
+            let last = points.len() - 1;
+            if cap == CapStyle::Square {
+                let start_direction = (points[0] - points[1]).normalize();
+                points[0] += start_direction * half_width;
+                let end_direction = (points[last] - points[last - 1]).normalize();
+                points[last] += end_direction * half_width;
+            }
+
+            let normal = |a: Vec2, b: Vec2| {
+                let d = (b - a).normalize();
+                vec2(-d.y, d.x) * half_width
+            };
+            for i in 0..last {
+                let (a, b) = (points[i], points[i + 1]);
+                let n = normal(a, b);
+                positions.extend([a - n, b - n, b + n, a - n, b + n, a + n]);
+            }
+
+            for i in 1..last {
+                let p = points[i];
+                let n0 = normal(points[i - 1], p);
+                let n1 = normal(p, points[i + 1]);
+                // The outer side of the join is opposite to the turning direction
+                let turn = n0.perp_dot(n1);
+                if turn.abs() < f32::EPSILON {
+                    continue;
+                }
+                let (a, b) = if turn > 0.0 { (-n0, -n1) } else { (n0, n1) };
+                positions.extend([p, p + a, p + b]);
+                if join == JoinStyle::Miter {
+                    let miter = (a + b).normalize();
+                    let length = half_width / miter.dot(a.normalize());
+                    if length <= 4.0 * width {
+                        positions.extend([p + a, p + miter * length, p + b]);
+                    }
+                }
+            }
+
+            if cap == CapStyle::Round {
+                const SUBDIVISIONS: u32 = 16;
+                for (p, n) in [
+                    (points[0], normal(points[0], points[1])),
+                    (points[last], -normal(points[last - 1], points[last])),
+                ] {
+                    for j in 0..SUBDIVISIONS {
+                        let angle0 = std::f32::consts::PI * j as f32 / SUBDIVISIONS as f32;
+                        let angle1 = std::f32::consts::PI * (j + 1) as f32 / SUBDIVISIONS as f32;
+                        let rotate = |angle: f32| Mat2::from_angle(Rad(angle)) * n;
+                        positions.extend([p, p + rotate(angle0), p + rotate(angle1)]);
+                    }
+                }
+            }
+
+            // Make sure all triangles are counter clockwise
+            for triangle in positions.chunks_mut(3) {
+                if (triangle[1] - triangle[0]).perp_dot(triangle[2] - triangle[0]) < 0.0 {
+                    triangle.swap(1, 2);
+                }
+            }
+        }
+        let positions: Vec<Vec3> = positions.into_iter().map(|p| p.extend(0.0)).collect();
+        let normals = vec![vec3(0.0, 0.0, 1.0); positions.len()];
+        CpuMesh {
+            positions: Positions::F32(positions),
+            normals: Some(normals),
+            ..Default::default()
+        }
+    }
+
+    fn polygon(points: &[Vec2]) -> Self {
+        let mut points = points.to_vec();
+        points.dedup_by(|a, b| a.distance2(*b) < f32::EPSILON);
+        if points.len() > 1 && points[0].distance2(points[points.len() - 1]) < f32::EPSILON {
+            points.pop();
+        }
+
+        // Make sure the remaining corners are in counter clockwise order
+        let area: f32 = (0..points.len())
+            .map(|i| points[i].perp_dot(points[(i + 1) % points.len()]))
+            .sum();
+        let mut remaining: Vec<usize> = if area < 0.0 {
+            (0..points.len()).rev().collect()
+        } else {
+            (0..points.len()).collect()
+        };
+
+        let is_convex = |a: Vec2, b: Vec2, c: Vec2| (b - a).perp_dot(c - b) > 0.0;
+        let is_inside = |p: Vec2, a: Vec2, b: Vec2, c: Vec2| {
+            (b - a).perp_dot(p - a) >= 0.0
+                && (c - b).perp_dot(p - b) >= 0.0
+                && (a - c).perp_dot(p - c) >= 0.0
+        };
+        let mut indices = Vec::new();
+        while remaining.len() > 3 {
+            let n = remaining.len();
+            let ear = (0..n).find(|&i| {
+                let (a, b, c) = (
+                    points[remaining[(i + n - 1) % n]],
+                    points[remaining[i]],
+                    points[remaining[(i + 1) % n]],
+                );
+                is_convex(a, b, c)
+                    && remaining.iter().all(|&j| {
+                        let p = points[j];
+                        p == a || p == b || p == c || !is_inside(p, a, b, c)
+                    })
+            });
+            // If no ear is found, the polygon is degenerate or self-intersecting, so just clip any corner to make progress
+            let i = ear.unwrap_or(0);
+            indices.extend([
+                remaining[(i + n - 1) % n] as u32,
+                remaining[i] as u32,
+                remaining[(i + 1) % n] as u32,
+            ]);
+            remaining.remove(i);
+        }
+        if remaining.len() == 3 {
+            indices.extend(remaining.iter().map(|&i| i as u32));
+        }
+
+        let normals = vec![vec3(0.0, 0.0, 1.0); points.len()];
+        CpuMesh {
+            positions: Positions::F32(points.into_iter().map(|p| p.extend(0.0)).collect()),
+            indices: Indices::U32(indices),
+            normals: Some(normals),
+            ..Default::default()
+        }
+    }
+
+    fn disk(segments: u32) -> Self {
+        Self::arc(
+            radians(0.0),
+            radians(2.0 * std::f32::consts::PI),
+            1.0,
+            segments.max(3),
+        )
+    }
+
+    fn ring(inner_radius: f32, outer_radius: f32, segments: u32) -> Self {
+        let segments = segments.max(3);
+        let mut positions = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::new();
+        for i in 0..=segments {
+            let u = i as f32 / segments as f32;
+            let angle = 2.0 * std::f32::consts::PI * u;
+            let direction = vec3(angle.cos(), angle.sin(), 0.0);
+            positions.push(direction * inner_radius);
+            positions.push(direction * outer_radius);
+            uvs.push(vec2(u, 0.0));
+            uvs.push(vec2(u, 1.0));
+            if i < segments {
+                let inner0 = 2 * i;
+                let outer0 = inner0 + 1;
+                let inner1 = inner0 + 2;
+                let outer1 = inner0 + 3;
+                indices.extend([inner0, outer0, outer1, inner0, outer1, inner1]);
+            }
+        }
+        let normals = vec![vec3(0.0, 0.0, 1.0); positions.len()];
+        CpuMesh {
+            positions: Positions::F32(positions),
+            indices: Indices::U32(indices),
+            normals: Some(normals),
+            uvs: Some(uvs),
+            ..Default::default()
+        }
+    }
+
+    fn arc(
+        start_angle: impl Into<Radians>,
+        end_angle: impl Into<Radians>,
+        radius: f32,
+        segments: u32,
+    ) -> Self {
+        let start_angle = start_angle.into().0;
+        let end_angle = end_angle.into().0;
+        let segments = segments.max(1);
+        let mut positions = vec![vec3(0.0, 0.0, 0.0)];
+        let mut uvs = vec![vec2(0.5, 0.5)];
+        let mut indices = Vec::new();
+        for i in 0..=segments {
+            let angle = start_angle + (end_angle - start_angle) * i as f32 / segments as f32;
+            let direction = vec2(angle.cos(), angle.sin());
+            positions.push(direction.extend(0.0) * radius);
+            uvs.push(direction * 0.5 + vec2(0.5, 0.5));
+            if i < segments {
+                indices.extend([0, i + 1, i + 2]);
+            }
+        }
+        // Ensure counter clockwise triangles if the end angle is smaller than the start angle
+        if end_angle < start_angle {
+            indices.chunks_mut(3).for_each(|t| t.swap(1, 2));
+        }
+        let normals = vec![vec3(0.0, 0.0, 1.0); positions.len()];
+        CpuMesh {
+            positions: Positions::F32(positions),
+            indices: Indices::U32(indices),
+            normals: Some(normals),
+            uvs: Some(uvs),
+            ..Default::default()
+        }
+    }
+
+    fn capsule(radius: f32, height: f32, segments: u32) -> Self {
+        let segments = segments.max(3);
+        let cap_rings = (segments / 4).max(1);
+        let half_height = 0.5 * height;
+        let length = std::f32::consts::PI * radius + height;
+
+        // The profile of the capsule from the pole at the negative x-axis to the pole at the positive x-axis
+        let mut profile = Vec::new();
+        for (offset, start_angle) in [(-half_height, 0.0), (half_height, 0.5)] {
+            for k in 0..=cap_rings {
+                let angle =
+                    std::f32::consts::PI * (start_angle + 0.5 * k as f32 / cap_rings as f32);
+                let distance = if offset < 0.0 {
+                    angle * radius
+                } else {
+                    angle * radius + height
+                };
+                profile.push((offset, angle, distance / length));
+            }
+        }
+
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs = Vec::new();
+        for &(offset, angle, v) in profile.iter() {
+            for j in 0..=segments {
+                let u = j as f32 / segments as f32;
+                let phi = 2.0 * std::f32::consts::PI * u;
+                let normal = vec3(
+                    -angle.cos(),
+                    angle.sin() * phi.cos(),
+                    angle.sin() * phi.sin(),
+                );
+                positions.push(vec3(offset, 0.0, 0.0) + normal * radius);
+                normals.push(normal);
+                uvs.push(vec2(u, v));
+            }
+        }
+
+        let mut indices = Vec::new();
+        let columns = segments + 1;
+        let rows = profile.len() as u32;
+        for i in 0..rows - 1 {
+            for j in 0..segments {
+                let a = i * columns + j;
+                let b = a + 1;
+                let c = a + columns + 1;
+                let d = a + columns;
+                // Skip the degenerate triangles at the poles
+                if i != 0 {
+                    indices.extend([a, b, c]);
+                }
+                if i != rows - 2 {
+                    indices.extend([a, c, d]);
+                }
+            }
+        }
+        CpuMesh {
+            positions: Positions::F32(positions),
+            indices: Indices::U32(indices),
+            normals: Some(normals),
+            uvs: Some(uvs),
+            ..Default::default()
+        }
+    }
+
+    fn rounded_cube(size: f32, corner_radius: f32, segments: u32) -> Self {
+        let segments = segments.max(1);
+        let half_size = 0.5 * size;
+        let radius = corner_radius.clamp(0.0, half_size);
+        let inner = half_size - radius;
+
+        // The coordinates along each side, distributed such that the rounded edges are evenly subdivided.
+        // Without rounding, the coordinates at each end coincide, so each side is a single quad instead.
+        let mut coordinates = Vec::new();
+        if radius > 0.0 {
+            for k in (0..=segments).rev() {
+                let angle = std::f32::consts::FRAC_PI_4 * k as f32 / segments as f32;
+                coordinates.push(-inner - radius * angle.tan());
+            }
+            for k in 0..=segments {
+                let angle = std::f32::consts::FRAC_PI_4 * k as f32 / segments as f32;
+                coordinates.push(inner + radius * angle.tan());
+            }
+        } else {
+            coordinates.extend([-half_size, half_size]);
+        }
+
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::new();
+        for (normal, tangent, bitangent) in [
+            (Vec3::unit_x(), Vec3::unit_y(), Vec3::unit_z()),
+            (-Vec3::unit_x(), Vec3::unit_z(), Vec3::unit_y()),
+            (Vec3::unit_y(), Vec3::unit_z(), Vec3::unit_x()),
+            (-Vec3::unit_y(), Vec3::unit_x(), Vec3::unit_z()),
+            (Vec3::unit_z(), Vec3::unit_x(), Vec3::unit_y()),
+            (-Vec3::unit_z(), Vec3::unit_y(), Vec3::unit_x()),
+        ] {
+            let start = positions.len() as u32;
+            for &b in coordinates.iter() {
+                for &a in coordinates.iter() {
+                    let p = normal * half_size + tangent * a + bitangent * b;
+                    let q = vec3(
+                        p.x.clamp(-inner, inner),
+                        p.y.clamp(-inner, inner),
+                        p.z.clamp(-inner, inner),
+                    );
+                    let n = if (p - q).magnitude2() > f32::EPSILON {
+                        (p - q).normalize()
+                    } else {
+                        normal
+                    };
+                    positions.push(q + n * radius);
+                    normals.push(n);
+                    uvs.push(vec2(0.5 + 0.5 * a / half_size, 0.5 + 0.5 * b / half_size));
+                }
+            }
+            let columns = coordinates.len() as u32;
+            for i in 0..columns - 1 {
+                for j in 0..columns - 1 {
+                    let a = start + i * columns + j;
+                    let b = a + 1;
+                    let c = a + columns + 1;
+                    let d = a + columns;
+                    indices.extend([a, b, c, a, c, d]);
+                }
+            }
+        }
+        CpuMesh {
+            positions: Positions::F32(positions),
+            indices: Indices::U32(indices),
+            normals: Some(normals),
+            uvs: Some(uvs),
+            ..Default::default()
+        }
+    }
+
+    fn subdivide(&mut self, iterations: u32) {
+        let (mut positions, mut indices, mut uvs, colors) = connected_vertices(self);
+        let mut colors: Option<Vec<Vec4>> = colors.map(|colors| {
+            colors
+                .into_iter()
+                .map(|c| vec4(c.r as f32, c.g as f32, c.b as f32, c.a as f32))
+                .collect()
+        });
+
+        for _ in 0..iterations {
+            // Find the vertices opposite to each edge
+            let mut edges: HashMap<(u32, u32), Vec<u32>> = HashMap::new();
+            for triangle in indices.chunks(3) {
+                for k in 0..3 {
+                    let (a, b, c) = (triangle[k], triangle[(k + 1) % 3], triangle[(k + 2) % 3]);
+                    edges.entry((a.min(b), a.max(b))).or_default().push(c);
+                }
+            }
+
+            // The weights of the vertices that make up each vertex in the subdivided mesh
+            let mut weights: Vec<Vec<(u32, f32)>> =
+                Vec::with_capacity(positions.len() + edges.len());
+            let mut neighbours = vec![Vec::new(); positions.len()];
+            let mut boundary_neighbours = vec![Vec::new(); positions.len()];
+            for (&(a, b), opposite) in edges.iter() {
+                neighbours[a as usize].push(b);
+                neighbours[b as usize].push(a);
+                if opposite.len() != 2 {
+                    boundary_neighbours[a as usize].push(b);
+                    boundary_neighbours[b as usize].push(a);
+                }
+            }
+            for i in 0..positions.len() {
+                let w = if !boundary_neighbours[i].is_empty() {
+                    if boundary_neighbours[i].len() == 2 {
+                        vec![
+                            (i as u32, 0.75),
+                            (boundary_neighbours[i][0], 0.125),
+                            (boundary_neighbours[i][1], 0.125),
+                        ]
+                    } else {
+                        // Corner vertex
+                        vec![(i as u32, 1.0)]
+                    }
+                } else {
+                    let n = neighbours[i].len();
+                    let beta = if n == 3 {
+                        3.0 / 16.0
+                    } else {
+                        3.0 / (8.0 * n as f32)
+                    };
+                    let mut w = vec![(i as u32, 1.0 - n as f32 * beta)];
+                    w.extend(neighbours[i].iter().map(|&j| (j, beta)));
+                    w
+                };
+                weights.push(w);
+            }
+            // Sort the edges to get a deterministic vertex order
+            let mut sorted_edges: Vec<_> = edges.iter().collect();
+            sorted_edges.sort_by_key(|(&edge, _)| edge);
+            let mut edge_vertices = HashMap::with_capacity(edges.len());
+            for (&(a, b), opposite) in sorted_edges {
+                edge_vertices.insert((a, b), weights.len() as u32);
+                weights.push(if opposite.len() == 2 {
+                    vec![
+                        (a, 0.375),
+                        (b, 0.375),
+                        (opposite[0], 0.125),
+                        (opposite[1], 0.125),
+                    ]
+                } else {
+                    vec![(a, 0.5), (b, 0.5)]
+                });
+            }
+
+            positions = interpolate(&positions, &weights);
+            uvs = uvs.map(|uvs| interpolate(&uvs, &weights));
+            colors = colors.map(|colors| interpolate(&colors, &weights));
+            indices = indices
+                .chunks(3)
+                .flat_map(|triangle| {
+                    let (a, b, c) = (triangle[0], triangle[1], triangle[2]);
+                    let edge = |a: u32, b: u32| edge_vertices[&(a.min(b), a.max(b))];
+                    let (ab, bc, ca) = (edge(a, b), edge(b, c), edge(c, a));
+                    [a, ab, ca, ab, b, bc, ca, bc, c, ab, bc, ca]
+                })
+                .collect();
+        }
+
+        self.positions = Positions::F32(positions);
+        self.indices = Indices::U32(indices);
+        self.uvs = uvs;
+        self.colors = colors.map(|colors| {
+            colors
+                .into_iter()
+                .map(|c| {
+                    Srgba::new(
+                        c.x.round() as u8,
+                        c.y.round() as u8,
+                        c.z.round() as u8,
+                        c.w.round() as u8,
+                    )
+                })
+                .collect()
+        });
+        self.tangents = None;
+        self.compute_normals();
+    }
+
+    fn simplify(&self, target_ratio: f32) -> CpuMesh {
+        let (mut positions, indices, mut uvs, mut colors) = connected_vertices(self);
+        let mut triangles: Vec<[u32; 3]> = indices.chunks(3).map(|t| [t[0], t[1], t[2]]).collect();
+        let mut removed = vec![false; triangles.len()];
+        let mut vertex_triangles = vec![Vec::new(); positions.len()];
+        for (t, triangle) in triangles.iter().enumerate() {
+            for &v in triangle {
+                vertex_triangles[v as usize].push(t);
+            }
+        }
+
+        // The quadric of each vertex is the sum of the squared distances to the planes of the surrounding triangles
+        let mut quadrics = vec![Matrix4::<f64>::zero(); positions.len()];
+        for triangle in triangles.iter() {
+            let [a, b, c] = triangle.map(|v| positions[v as usize].cast::<f64>().unwrap());
+            let normal = (b - a).cross(c - a);
+            if normal.magnitude2() > 0.0 {
+                let normal = normal.normalize();
+                let plane = normal.extend(-normal.dot(a));
+                let quadric = Matrix4::from_cols(
+                    plane * plane.x,
+                    plane * plane.y,
+                    plane * plane.z,
+                    plane * plane.w,
+                );
+                for &v in triangle {
+                    quadrics[v as usize] += quadric;
+                }
+            }
+        }
+
+        // Lock the vertices on boundary and non-manifold edges
+        let mut edges: HashMap<(u32, u32), u32> = HashMap::new();
+        for triangle in triangles.iter() {
+            for k in 0..3 {
+                let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+                *edges.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+        let mut locked = vec![false; positions.len()];
+        for (&(a, b), &count) in edges.iter() {
+            if count != 2 {
+                locked[a as usize] = true;
+                locked[b as usize] = true;
+            }
+        }
+
+        // Returns the cost and the interpolation factor of the best collapse of the edge between a and b
+        let collapse = |positions: &[Vec3], quadrics: &[Matrix4<f64>], a: u32, b: u32| {
+            let quadric = quadrics[a as usize] + quadrics[b as usize];
+            [0.0, 0.5, 1.0]
+                .into_iter()
+                .map(|t| {
+                    let p = positions[a as usize].lerp(positions[b as usize], t);
+                    let p = p.cast::<f64>().unwrap().extend(1.0);
+                    ((p.dot(quadric * p)).max(0.0), t)
+                })
+                .min_by(|(c0, _), (c1, _)| c0.total_cmp(c1))
+                .unwrap()
+        };
+
+        // A queue of collapses with the cheapest first, where a collapse is outdated if one of the vertices has changed since
+        let mut versions = vec![0u32; positions.len()];
+        let mut queue = std::collections::BinaryHeap::new();
+        for &(a, b) in edges.keys() {
+            if !locked[a as usize] && !locked[b as usize] {
+                let (cost, _) = collapse(&positions, &quadrics, a, b);
+                queue.push(std::cmp::Reverse((cost.to_bits(), a, b, 0, 0)));
+            }
+        }
+
+        let neighbours = |vertex_triangles: &[Vec<usize>], triangles: &[[u32; 3]], v: u32| {
+            let mut neighbours: Vec<u32> = vertex_triangles[v as usize]
+                .iter()
+                .flat_map(|&t| triangles[t])
+                .filter(|&n| n != v)
+                .collect();
+            neighbours.sort_unstable();
+            neighbours.dedup();
+            neighbours
+        };
+
+        let target = (triangles.len() as f32 * target_ratio.clamp(0.0, 1.0)).ceil() as usize;
+        let mut triangle_count = triangles.len();
+        while triangle_count > target {
+            let Some(std::cmp::Reverse((_, a, b, version_a, version_b))) = queue.pop() else {
+                break;
+            };
+            if versions[a as usize] != version_a || versions[b as usize] != version_b {
+                continue;
+            }
+            let shared: Vec<usize> = vertex_triangles[a as usize]
+                .iter()
+                .copied()
+                .filter(|&t| triangles[t].contains(&b))
+                .collect();
+
+            // The only common neighbours should be the vertices opposite to the edge, otherwise the collapse creates non-manifold geometry
+            let neighbours_b = neighbours(&vertex_triangles, &triangles, b);
+            let common_neighbours = neighbours(&vertex_triangles, &triangles, a)
+                .into_iter()
+                .filter(|n| neighbours_b.binary_search(n).is_ok())
+                .count();
+            if shared.len() != 2 || common_neighbours != 2 {
+                continue;
+            }
+
+            // Reject the collapse if any of the remaining triangles are flipped
+            let (_, t) = collapse(&positions, &quadrics, a, b);
+            let target_position = positions[a as usize].lerp(positions[b as usize], t);
+            let flips = vertex_triangles[a as usize]
+                .iter()
+                .chain(vertex_triangles[b as usize].iter())
+                .filter(|t| !shared.contains(t))
+                .any(|&t| {
+                    let corners = triangles[t].map(|v| positions[v as usize]);
+                    let moved = triangles[t].map(|v| {
+                        if v == a || v == b {
+                            target_position
+                        } else {
+                            positions[v as usize]
+                        }
+                    });
+                    let normal = (corners[1] - corners[0]).cross(corners[2] - corners[0]);
+                    let new_normal = (moved[1] - moved[0]).cross(moved[2] - moved[0]);
+                    normal.dot(new_normal) <= 0.0
+                });
+            if flips {
+                continue;
+            }
+
+            // Collapse b into a
+            positions[a as usize] = target_position;
+            if let Some(uvs) = uvs.as_mut() {
+                uvs[a as usize] = uvs[a as usize].lerp(uvs[b as usize], t);
+            }
+            if let Some(colors) = colors.as_mut() {
+                let (ca, cb) = (colors[a as usize], colors[b as usize]);
+                let mix = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+                colors[a as usize] = Srgba::new(
+                    mix(ca.r, cb.r),
+                    mix(ca.g, cb.g),
+                    mix(ca.b, cb.b),
+                    mix(ca.a, cb.a),
+                );
+            }
+            quadrics[a as usize] = quadrics[a as usize] + quadrics[b as usize];
+            for &t in shared.iter() {
+                removed[t] = true;
+                triangle_count -= 1;
+                for v in triangles[t] {
+                    vertex_triangles[v as usize].retain(|&t2| t2 != t);
+                }
+            }
+            for t in std::mem::take(&mut vertex_triangles[b as usize]) {
+                for v in triangles[t].iter_mut() {
+                    if *v == b {
+                        *v = a;
+                    }
+                }
+                vertex_triangles[a as usize].push(t);
+            }
+            versions[a as usize] += 1;
+            versions[b as usize] += 1;
+            for n in neighbours(&vertex_triangles, &triangles, a) {
+                if !locked[n as usize] {
+                    let (cost, _) = collapse(&positions, &quadrics, a, n);
+                    queue.push(std::cmp::Reverse((
+                        cost.to_bits(),
+                        a,
+                        n,
+                        versions[a as usize],
+                        versions[n as usize],
+                    )));
+                }
+            }
+        }
+
+        // Remove the unused vertices
+        let mut new_index = vec![u32::MAX; positions.len()];
+        let mut kept = Vec::new();
+        let mut indices = Vec::with_capacity(3 * triangle_count);
+        for (t, triangle) in triangles.iter().enumerate() {
+            if !removed[t] {
+                for &v in triangle {
+                    if new_index[v as usize] == u32::MAX {
+                        new_index[v as usize] = kept.len() as u32;
+                        kept.push(v as usize);
+                    }
+                    indices.push(new_index[v as usize]);
+                }
+            }
+        }
+        let mut mesh = CpuMesh {
+            positions: Positions::F32(kept.iter().map(|&v| positions[v]).collect()),
+            indices: Indices::U32(indices),
+            uvs: uvs.map(|uvs| kept.iter().map(|&v| uvs[v]).collect()),
+            colors: colors.map(|colors| kept.iter().map(|&v| colors[v]).collect()),
+            ..Default::default()
+        };
+        mesh.compute_normals();
+        mesh
+    }
+
+    fn generate_planar_uvs(&mut self, axis: Vec3) {
+        let axis = axis.normalize();
+        let up = if axis.y.abs() < 0.999 {
+            Vec3::unit_y()
+        } else {
+            Vec3::unit_z()
+        };
+        let tangent = up.cross(axis).normalize();
+        let bitangent = axis.cross(tangent);
+        let projected: Vec<Vec2> = self
+            .positions
+            .to_f32()
+            .into_iter()
+            .map(|p| vec2(p.dot(tangent), p.dot(bitangent)))
+            .collect();
+        let min = projected.iter().fold(vec2(f32::MAX, f32::MAX), |m, p| {
+            vec2(m.x.min(p.x), m.y.min(p.y))
+        });
+        let max = projected.iter().fold(vec2(f32::MIN, f32::MIN), |m, p| {
+            vec2(m.x.max(p.x), m.y.max(p.y))
+        });
+        let size = vec2(
+            (max.x - min.x).max(f32::EPSILON),
+            (max.y - min.y).max(f32::EPSILON),
+        );
+        self.uvs = Some(
+            projected
+                .into_iter()
+                .map(|p| vec2((p.x - min.x) / size.x, (p.y - min.y) / size.y))
+                .collect(),
+        );
+        self.tangents = None;
+    }
+
+    fn generate_box_uvs(&mut self) {
+        let positions = self.positions.to_f32();
+        let indices = self
+            .indices
+            .to_u32()
+            .unwrap_or_else(|| (0..positions.len() as u32).collect());
+        let aabb = self.compute_aabb();
+        let min = aabb.min();
+        let size = aabb.size();
+        let scale = size.x.max(size.y).max(size.z).max(f32::EPSILON);
+
+        // Each vertex is duplicated for each side of the box it is projected onto
+        let mut map = HashMap::new();
+        let mut kept = Vec::new();
+        let mut uvs = Vec::new();
+        let mut new_indices = Vec::with_capacity(indices.len());
+        for triangle in indices.chunks(3) {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|v| positions[v as usize]);
+            let normal = (b - a).cross(c - a);
+            let side = if normal.x.abs() >= normal.y.abs() && normal.x.abs() >= normal.z.abs() {
+                0
+            } else if normal.y.abs() >= normal.z.abs() {
+                1
+            } else {
+                2
+            };
+            for &v in triangle {
+                let index = *map.entry((v, side)).or_insert_with(|| {
+                    let p = (positions[v as usize] - min) / scale;
+                    uvs.push(match side {
+                        0 => vec2(p.z, p.y),
+                        1 => vec2(p.x, p.z),
+                        _ => vec2(p.x, p.y),
+                    });
+                    kept.push(v as usize);
+                    kept.len() as u32 - 1
+                });
+                new_indices.push(index);
+            }
+        }
+
+        self.positions = Positions::F32(kept.iter().map(|&v| positions[v]).collect());
+        self.indices = Indices::U32(new_indices);
+        self.normals = self
+            .normals
+            .take()
+            .map(|normals| kept.iter().map(|&v| normals[v]).collect());
+        self.colors = self
+            .colors
+            .take()
+            .map(|colors| kept.iter().map(|&v| colors[v]).collect());
+        self.uvs = Some(uvs);
+        self.tangents = None;
+    }
+
+    fn try_compute_tangents(&mut self) -> Result<(), RendererError> {
+        let normals = self.normals.as_ref().ok_or_else(|| {
+            RendererError::MissingVertexData("normals".to_string(), "tangents".to_string())
+        })?;
+        let uvs = self.uvs.as_ref().ok_or_else(|| {
+            RendererError::MissingVertexData("uv coordinates".to_string(), "tangents".to_string())
+        })?;
+        let positions = self.positions.to_f32();
+        let mut tan1 = vec![Vec3::zero(); positions.len()];
+        let mut tan2 = vec![Vec3::zero(); positions.len()];
+        self.for_each_triangle(|i0, i1, i2| {
+            let ba = positions[i1] - positions[i0];
+            let ca = positions[i2] - positions[i0];
+            let uvba = uvs[i1] - uvs[i0];
+            let uvca = uvs[i2] - uvs[i0];
+            let d = uvba.x * uvca.y - uvca.x * uvba.y;
+            if d.abs() > 0.00001 {
+                let r = 1.0 / d;
+                let sdir = (ba * uvca.y - ca * uvba.y) * r;
+                let tdir = (ca * uvba.x - ba * uvca.x) * r;
+                for i in [i0, i1, i2] {
+                    tan1[i] += sdir;
+                    tan2[i] += tdir;
+                }
+            }
+        });
+
+        let tangents = (0..positions.len())
+            .map(|i| {
+                let normal = normals[i];
+                let t = tan1[i] - normal * normal.dot(tan1[i]);
+                let tangent = if t.magnitude2() > f32::EPSILON && t.magnitude2().is_finite() {
+                    t.normalize()
+                } else {
+                    // Use the axis which is the least aligned with the normal to construct an orthogonal tangent
+                    let axis = if normal.x.abs() < normal.y.abs() && normal.x.abs() < normal.z.abs()
+                    {
+                        Vec3::unit_x()
+                    } else if normal.y.abs() < normal.z.abs() {
+                        Vec3::unit_y()
+                    } else {
+                        Vec3::unit_z()
+                    };
+                    let t = axis - normal * normal.dot(axis);
+                    if t.magnitude2() > f32::EPSILON {
+                        t.normalize()
+                    } else {
+                        Vec3::unit_x()
+                    }
+                };
+                let handedness = if normal.cross(tangent).dot(tan2[i]) < 0.0 {
+                    1.0
+                } else {
+                    -1.0
+                };
+                tangent.extend(handedness)
+            })
+            .collect();
+        self.tangents = Some(tangents);
+        Ok(())
+    }
+
+    fn compute_bounding_sphere(&self) -> (Vec3, f32) {
+        let positions = self.positions.to_f32();
+        let Some(&first) = positions.first() else {
+            return (Vec3::zero(), 0.0);
+        };
+        let farthest_from = |p: Vec3| {
+            positions
+                .iter()
+                .copied()
+                .max_by(|a, b| a.distance2(p).total_cmp(&b.distance2(p)))
+                .unwrap()
+        };
+        let a = farthest_from(first);
+        let b = farthest_from(a);
+        let mut center = 0.5 * (a + b);
+        let mut radius = 0.5 * a.distance(b);
+        for p in positions.iter() {
+            let distance = p.distance(center);
+            if distance > radius {
+                // Grow the sphere just enough to contain the point
+                radius = 0.5 * (radius + distance);
+                center = p + (center - p) * (radius / distance);
+            }
+        }
+        (center, radius)
+    }
+}
+
+fn interpolate<T>(values: &[T], weights: &[Vec<(u32, f32)>]) -> Vec<T>
+where
+    T: Copy + std::ops::Mul<f32, Output = T> + std::ops::Add<Output = T>,
+{
+    weights
+        .iter()
+        .map(|w| {
+            w[1..]
+                .iter()
+                .fold(values[w[0].0 as usize] * w[0].1, |acc, &(i, weight)| {
+                    acc + values[i as usize] * weight
+                })
+        })
+        .collect()
+}
+
+///
+/// Returns the positions, indices, uv coordinates and colors of the mesh, where identical vertices are merged if the mesh is not indexed such that the triangles are connected.
+///
+#[allow(clippy::type_complexity)]
+fn connected_vertices(
+    mesh: &CpuMesh,
+) -> (Vec<Vec3>, Vec<u32>, Option<Vec<Vec2>>, Option<Vec<Srgba>>) {
+    let positions = mesh.positions.to_f32();
+    if let Some(indices) = mesh.indices.to_u32() {
+        return (positions, indices, mesh.uvs.clone(), mesh.colors.clone());
+    }
+    let mut map = HashMap::new();
+    let mut indices = Vec::with_capacity(positions.len());
+    let mut kept = Vec::new();
+    for i in 0..positions.len() {
+        let key = (
+            positions[i].map(|v| v.to_bits()),
+            mesh.uvs.as_ref().map(|uvs| uvs[i].map(|v| v.to_bits())),
+            mesh.colors.as_ref().map(|colors| colors[i]),
+        );
+        let index = *map.entry(key).or_insert_with(|| {
+            kept.push(i);
+            kept.len() as u32 - 1
+        });
+        indices.push(index);
+    }
+    (
+        kept.iter().map(|&i| positions[i]).collect(),
+        indices,
+        mesh.uvs
+            .as_ref()
+            .map(|uvs| kept.iter().map(|&i| uvs[i]).collect()),
+        mesh.colors
+            .as_ref()
+            .map(|colors| kept.iter().map(|&i| colors[i]).collect()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangles(mesh: &CpuMesh) -> Vec<[Vec3; 3]> {
+        let positions = mesh.positions.to_f32();
+        let mut triangles = Vec::new();
+        mesh.for_each_triangle(|a, b, c| {
+            triangles.push([positions[a], positions[b], positions[c]])
+        });
+        triangles
+    }
+
+    fn normal(triangle: &[Vec3; 3]) -> Vec3 {
+        (triangle[1] - triangle[0]).cross(triangle[2] - triangle[0])
+    }
+
+    #[test]
+    fn grid() {
+        let mesh = CpuMesh::grid(4, 3, 0.5);
+        assert_eq!(mesh.vertex_count(), 5 * 4);
+        assert_eq!(mesh.triangle_count(), 2 * 4 * 3);
+        mesh.validate().unwrap();
+
+        let aabb = mesh.compute_aabb();
+        assert_eq!(aabb.min(), vec3(-1.0, 0.0, -0.75));
+        assert_eq!(aabb.max(), vec3(1.0, 0.0, 0.75));
+        let uvs = mesh.uvs.as_ref().unwrap();
+        assert_eq!(uvs[0], vec2(0.0, 0.0));
+        assert_eq!(uvs[uvs.len() - 1], vec2(1.0, 1.0));
+        for triangle in triangles(&mesh) {
+            let n = normal(&triangle);
+            assert!(n.y > 0.0 && n.x.abs() < 1e-6 && n.z.abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn displace_with() {
+        let mut mesh = CpuMesh::grid(4, 4, 0.25);
+        mesh.displace_with(|x, _| x);
+        for p in mesh.positions.to_f32() {
+            assert_eq!(p.y, p.x);
+        }
+        let expected = vec3(-1.0, 1.0, 0.0).normalize();
+        for n in mesh.normals.as_ref().unwrap() {
+            assert!(n.distance(expected) < 1e-5);
+        }
+        for triangle in triangles(&mesh) {
+            assert!(normal(&triangle).dot(expected) > 0.0);
+        }
+    }
+
+    fn assert_counter_clockwise(mesh: &CpuMesh) {
+        for triangle in triangles(mesh) {
+            assert!(normal(&triangle).z > 0.0);
+        }
+    }
+
+    #[test]
+    fn polyline_caps() {
+        let points = [vec2(0.0, 0.0), vec2(1.0, 0.0)];
+        let butt = CpuMesh::polyline(&points, 0.2, JoinStyle::Miter, CapStyle::Butt);
+        assert_eq!(butt.triangle_count(), 2);
+        assert_counter_clockwise(&butt);
+        let aabb = butt.compute_aabb();
+        assert!(aabb.min().distance(vec3(0.0, -0.1, 0.0)) < 1e-6);
+        assert!(aabb.max().distance(vec3(1.0, 0.1, 0.0)) < 1e-6);
+
+        let square = CpuMesh::polyline(&points, 0.2, JoinStyle::Miter, CapStyle::Square);
+        assert_eq!(square.triangle_count(), 2);
+        assert_counter_clockwise(&square);
+        let aabb = square.compute_aabb();
+        assert!(aabb.min().distance(vec3(-0.1, -0.1, 0.0)) < 1e-6);
+        assert!(aabb.max().distance(vec3(1.1, 0.1, 0.0)) < 1e-6);
+
+        let round = CpuMesh::polyline(&points, 0.2, JoinStyle::Miter, CapStyle::Round);
+        assert_eq!(round.triangle_count(), 2 + 2 * 16);
+        assert_counter_clockwise(&round);
+        let aabb = round.compute_aabb();
+        assert!(aabb.min().distance(vec3(-0.1, -0.1, 0.0)) < 1e-6);
+        assert!(aabb.max().distance(vec3(1.1, 0.1, 0.0)) < 1e-6);
+    }
+
+    #[test]
+    fn polyline_joins() {
+        let points = [vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(1.0, 1.0)];
+        let miter = CpuMesh::polyline(&points, 0.2, JoinStyle::Miter, CapStyle::Butt);
+        assert_eq!(miter.triangle_count(), 2 * 2 + 2);
+        assert_counter_clockwise(&miter);
+        let corner = vec3(1.1, -0.1, 0.0);
+        assert!(miter
+            .positions
+            .to_f32()
+            .iter()
+            .any(|p| p.distance(corner) < 1e-6));
+
+        let bevel = CpuMesh::polyline(&points, 0.2, JoinStyle::Bevel, CapStyle::Butt);
+        assert_eq!(bevel.triangle_count(), 2 * 2 + 1);
+        assert_counter_clockwise(&bevel);
+        assert!(bevel
+            .positions
+            .to_f32()
+            .iter()
+            .all(|p| p.distance(corner) > 0.01));
+
+        // Straight lines need no join and very sharp angles fall back to a bevel join
+        let straight = [vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(2.0, 0.0)];
+        let mesh = CpuMesh::polyline(&straight, 0.2, JoinStyle::Miter, CapStyle::Butt);
+        assert_eq!(mesh.triangle_count(), 2 * 2);
+        let sharp = [vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(0.0, 0.01)];
+        let mesh = CpuMesh::polyline(&sharp, 0.2, JoinStyle::Miter, CapStyle::Butt);
+        assert_eq!(mesh.triangle_count(), 2 * 2 + 1);
+        assert_counter_clockwise(&mesh);
+    }
+
+    #[test]
+    fn polyline_degenerate() {
+        let mesh = CpuMesh::polyline(&[vec2(1.0, 1.0)], 0.2, JoinStyle::Miter, CapStyle::Round);
+        assert_eq!(mesh.vertex_count(), 0);
+        let points = [vec2(0.0, 0.0), vec2(0.0, 0.0), vec2(1.0, 0.0)];
+        let mesh = CpuMesh::polyline(&points, 0.2, JoinStyle::Miter, CapStyle::Butt);
+        assert_eq!(mesh.triangle_count(), 2);
+        let mesh = CpuMesh::polyline(&points, 0.0, JoinStyle::Miter, CapStyle::Butt);
+        assert_eq!(mesh.vertex_count(), 0);
+    }
+
+    fn area(mesh: &CpuMesh) -> f32 {
+        triangles(mesh)
+            .iter()
+            .map(|t| 0.5 * normal(t).magnitude())
+            .sum()
+    }
+
+    #[test]
+    fn polygon_convex() {
+        let square = [
+            vec2(0.0, 0.0),
+            vec2(1.0, 0.0),
+            vec2(1.0, 1.0),
+            vec2(0.0, 1.0),
+        ];
+        let mesh = CpuMesh::polygon(&square);
+        assert_eq!(mesh.vertex_count(), 4);
+        assert_eq!(mesh.triangle_count(), 2);
+        assert_counter_clockwise(&mesh);
+        assert!((area(&mesh) - 1.0).abs() < 1e-6);
+
+        // Clockwise order and a closing point equal to the first point
+        let mut clockwise: Vec<_> = square.iter().rev().copied().collect();
+        clockwise.push(clockwise[0]);
+        let mesh = CpuMesh::polygon(&clockwise);
+        assert_eq!(mesh.vertex_count(), 4);
+        assert_eq!(mesh.triangle_count(), 2);
+        assert_counter_clockwise(&mesh);
+        assert!((area(&mesh) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn polygon_concave() {
+        // An L-shape with a reflex corner at (1, 1)
+        let l_shape = [
+            vec2(0.0, 0.0),
+            vec2(2.0, 0.0),
+            vec2(2.0, 1.0),
+            vec2(1.0, 1.0),
+            vec2(1.0, 2.0),
+            vec2(0.0, 2.0),
+        ];
+        for points in [l_shape.to_vec(), l_shape.iter().rev().copied().collect()] {
+            let mesh = CpuMesh::polygon(&points);
+            assert_eq!(mesh.triangle_count(), points.len() - 2);
+            assert_counter_clockwise(&mesh);
+            assert!((area(&mesh) - 3.0).abs() < 1e-6);
+        }
+
+        // A star with every other corner being a reflex corner
+        let star: Vec<_> = (0..10)
+            .map(|i| {
+                let angle = std::f32::consts::PI * i as f32 / 5.0;
+                let radius = if i % 2 == 0 { 1.0 } else { 0.4 };
+                vec2(angle.cos(), angle.sin()) * radius
+            })
+            .collect();
+        let mesh = CpuMesh::polygon(&star);
+        assert_eq!(mesh.triangle_count(), 8);
+        assert_counter_clockwise(&mesh);
+        let expected: f32 = (0..10)
+            .map(|i| 0.5 * star[i].perp_dot(star[(i + 1) % 10]))
+            .sum();
+        assert!((area(&mesh) - expected).abs() < 1e-5);
+    }
+
+    #[test]
+    fn polygon_degenerate() {
+        let mesh = CpuMesh::polygon(&[vec2(0.0, 0.0), vec2(1.0, 0.0)]);
+        assert_eq!(mesh.triangle_count(), 0);
+        let mesh = CpuMesh::polygon(&[vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(0.0, 1.0)]);
+        assert_eq!(mesh.triangle_count(), 1);
+        assert_counter_clockwise(&mesh);
+    }
+
+    fn assert_normals_towards_z(mesh: &CpuMesh) {
+        for n in mesh.normals.as_ref().unwrap() {
+            assert_eq!(*n, vec3(0.0, 0.0, 1.0));
+        }
+    }
+
+    #[test]
+    fn disk() {
+        let mesh = CpuMesh::disk(8);
+        assert_eq!(mesh.vertex_count(), 1 + 9);
+        assert_eq!(mesh.triangle_count(), 8);
+        mesh.validate().unwrap();
+        assert_counter_clockwise(&mesh);
+        assert_normals_towards_z(&mesh);
+        let expected_area = 8.0 * 0.5 * (std::f32::consts::PI / 4.0).sin();
+        assert!((area(&mesh) - expected_area).abs() < 1e-5);
+
+        let positions = mesh.positions.to_f32();
+        let uvs = mesh.uvs.as_ref().unwrap();
+        assert_eq!(positions[0], Vec3::zero());
+        assert_eq!(uvs[0], vec2(0.5, 0.5));
+        for (p, uv) in positions.iter().zip(uvs).skip(1) {
+            assert!((p.magnitude() - 1.0).abs() < 1e-6);
+            assert!(uv.distance(vec2(0.5 * p.x + 0.5, 0.5 * p.y + 0.5)) < 1e-6);
+        }
+    }
+
+    #[test]
+    fn ring() {
+        let mesh = CpuMesh::ring(0.5, 1.0, 6);
+        assert_eq!(mesh.vertex_count(), 2 * 7);
+        assert_eq!(mesh.triangle_count(), 2 * 6);
+        mesh.validate().unwrap();
+        assert_counter_clockwise(&mesh);
+        assert_normals_towards_z(&mesh);
+        let expected_area = 6.0 * 0.5 * (std::f32::consts::PI / 3.0).sin() * (1.0 - 0.25);
+        assert!((area(&mesh) - expected_area).abs() < 1e-5);
+
+        // The v coordinate is 0 at the inner radius and 1 at the outer radius
+        for (p, uv) in mesh
+            .positions
+            .to_f32()
+            .iter()
+            .zip(mesh.uvs.as_ref().unwrap())
+        {
+            let radius = if uv.y == 0.0 { 0.5 } else { 1.0 };
+            assert!((p.magnitude() - radius).abs() < 1e-6);
+        }
+        let uvs = mesh.uvs.as_ref().unwrap();
+        assert_eq!(uvs.iter().filter(|uv| uv.y == 0.0).count(), 7);
+    }
+
+    #[test]
+    fn arc() {
+        let mesh = CpuMesh::arc(degrees(0.0), degrees(90.0), 2.0, 4);
+        assert_eq!(mesh.vertex_count(), 1 + 5);
+        assert_eq!(mesh.triangle_count(), 4);
+        mesh.validate().unwrap();
+        assert_counter_clockwise(&mesh);
+        assert_normals_towards_z(&mesh);
+        for p in mesh.positions.to_f32().iter().skip(1) {
+            assert!((p.magnitude() - 2.0).abs() < 1e-5);
+            assert!(p.x > -1e-6 && p.y > -1e-6);
+        }
+
+        // The triangles are counter clockwise even if the end angle is smaller than the start angle
+        let mesh = CpuMesh::arc(degrees(90.0), degrees(0.0), 2.0, 4);
+        assert_eq!(mesh.triangle_count(), 4);
+        assert_counter_clockwise(&mesh);
+    }
+
+    ///
+    /// Returns true if every edge is shared by exactly two triangles with opposite directions,
+    /// ie. the mesh is closed, consistently oriented and without degenerate triangles, when vertices at the same position are merged.
+    ///
+    fn is_watertight(mesh: &CpuMesh) -> bool {
+        let mut ids = HashMap::new();
+        let mut id = |p: Vec3| {
+            let key = p.map(|v| (v * 1e4).round() as i32);
+            let count = ids.len();
+            *ids.entry((key.x, key.y, key.z)).or_insert(count)
+        };
+        let mut edges: HashMap<(usize, usize), u32> = HashMap::new();
+        for triangle in triangles(mesh) {
+            let [a, b, c] = triangle.map(&mut id);
+            if a == b || b == c || c == a {
+                return false;
+            }
+            for edge in [(a, b), (b, c), (c, a)] {
+                *edges.entry(edge).or_default() += 1;
+            }
+        }
+        edges
+            .iter()
+            .all(|(&(a, b), &count)| count == 1 && edges.get(&(b, a)) == Some(&1))
+    }
+
+    fn assert_outwards(mesh: &CpuMesh, center: Vec3) {
+        for triangle in triangles(mesh) {
+            let centroid = (triangle[0] + triangle[1] + triangle[2]) / 3.0;
+            assert!(normal(&triangle).dot(centroid - center) > 0.0);
+        }
+    }
+
+    #[test]
+    fn capsule() {
+        let (radius, height) = (0.5, 2.0);
+        let mesh = CpuMesh::capsule(radius, height, 8);
+        mesh.validate().unwrap();
+        assert!(is_watertight(&mesh));
+        assert_outwards(&mesh, Vec3::zero());
+
+        // The total length is the height of the cylinder part and the radius of each cap
+        let aabb = mesh.compute_aabb();
+        assert!(
+            aabb.min()
+                .distance(vec3(-0.5 * height - radius, -radius, -radius))
+                < 1e-5
+        );
+        assert!(
+            aabb.max()
+                .distance(vec3(0.5 * height + radius, radius, radius))
+                < 1e-5
+        );
+
+        // The normals have unit length and point away from the axis of the cylinder part or the center of the caps,
+        // so the normals of the caps and the cylinder part are the same where they meet
+        for (p, n) in mesh
+            .positions
+            .to_f32()
+            .iter()
+            .zip(mesh.normals.as_ref().unwrap())
+        {
+            assert!((n.magnitude() - 1.0).abs() < 1e-5);
+            let center = vec3(p.x.clamp(-0.5 * height, 0.5 * height), 0.0, 0.0);
+            assert!(n.distance((p - center) / radius) < 1e-5);
+        }
+
+        // The v coordinate goes from the pole at the negative x-axis to the pole at the positive x-axis
+        let mut poles = 0;
+        for (p, uv) in mesh
+            .positions
+            .to_f32()
+            .iter()
+            .zip(mesh.uvs.as_ref().unwrap())
+        {
+            assert!((0.0..=1.0).contains(&uv.x) && (0.0..=1.0).contains(&uv.y));
+            if (p.x + 0.5 * height + radius).abs() < 1e-5 {
+                assert!(uv.y.abs() < 1e-5);
+                poles += 1;
+            }
+            if (p.x - 0.5 * height - radius).abs() < 1e-5 {
+                assert!((uv.y - 1.0).abs() < 1e-5);
+                poles += 1;
+            }
+        }
+        assert!(poles >= 2);
+    }
+
+    #[test]
+    fn rounded_cube() {
+        let segments = 3;
+        let columns = 2 * (segments + 1);
+        let mesh = CpuMesh::rounded_cube(2.0, 0.25, segments);
+        assert_eq!(mesh.vertex_count(), 6 * (columns * columns) as usize);
+        assert_eq!(
+            mesh.triangle_count(),
+            6 * 2 * ((columns - 1) * (columns - 1)) as usize
+        );
+        mesh.validate().unwrap();
+        assert!(is_watertight(&mesh));
+        assert_outwards(&mesh, Vec3::zero());
+
+        let aabb = mesh.compute_aabb();
+        assert!(aabb.min().distance(vec3(-1.0, -1.0, -1.0)) < 1e-6);
+        assert!(aabb.max().distance(vec3(1.0, 1.0, 1.0)) < 1e-6);
+        // The corners are rounded
+        let corner_distance = 3.0f32.sqrt() * 0.75 + 0.25;
+        for p in mesh.positions.to_f32() {
+            assert!(p.magnitude() <= corner_distance + 1e-5);
+        }
+    }
+
+    #[test]
+    fn rounded_cube_without_rounding() {
+        let mesh = CpuMesh::rounded_cube(2.0, 0.0, 3);
+        assert_eq!(mesh.vertex_count(), 6 * 4);
+        assert_eq!(mesh.triangle_count(), 6 * 2);
+        assert!(is_watertight(&mesh));
+        assert_outwards(&mesh, Vec3::zero());
+        for (p, n) in mesh
+            .positions
+            .to_f32()
+            .iter()
+            .zip(mesh.normals.as_ref().unwrap())
+        {
+            assert_eq!(p.map(|v| v.abs()), vec3(1.0, 1.0, 1.0));
+            assert_eq!(p.dot(*n), 1.0);
+        }
+    }
+
+    #[test]
+    fn subdivide_closed() {
+        let mut mesh = CpuMesh::sphere(4);
+        assert!(is_watertight(&mesh));
+        let vertex_count = mesh.vertex_count();
+        let triangle_count = mesh.triangle_count();
+        // Every edge is shared by two triangles in a closed mesh
+        let edge_count = 3 * triangle_count / 2;
+
+        mesh.subdivide(1);
+        mesh.validate().unwrap();
+        assert_eq!(mesh.vertex_count(), vertex_count + edge_count);
+        assert_eq!(mesh.triangle_count(), 4 * triangle_count);
+        assert!(is_watertight(&mesh));
+        assert_outwards(&mesh, Vec3::zero());
+
+        mesh.subdivide(2);
+        assert_eq!(mesh.triangle_count(), 64 * triangle_count);
+        assert!(is_watertight(&mesh));
+        assert_outwards(&mesh, Vec3::zero());
+        // Loop subdivision smooths the surface towards the inside of the control mesh
+        for p in mesh.positions.to_f32() {
+            assert!(p.magnitude() <= 1.0 && p.magnitude() > 0.8);
+        }
+        for (p, n) in mesh
+            .positions
+            .to_f32()
+            .iter()
+            .zip(mesh.normals.as_ref().unwrap())
+        {
+            assert!(p.normalize().dot(*n) > 0.9);
+        }
+    }
+
+    #[test]
+    fn subdivide_boundary() {
+        let mut mesh = CpuMesh::polygon(&[vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(0.0, 1.0)]);
+        mesh.subdivide(1);
+        assert_eq!(mesh.vertex_count(), 6);
+        assert_eq!(mesh.triangle_count(), 4);
+        assert_counter_clockwise(&mesh);
+        // The new vertices are at the middle of the boundary edges and the old vertices are smoothed along the boundary only
+        let positions = mesh.positions.to_f32();
+        for p in [
+            vec3(0.5, 0.0, 0.0),
+            vec3(0.5, 0.5, 0.0),
+            vec3(0.0, 0.5, 0.0),
+            vec3(0.125, 0.125, 0.0),
+            vec3(0.75, 0.125, 0.0),
+            vec3(0.125, 0.75, 0.0),
+        ] {
+            assert!(positions.iter().any(|q| q.distance(p) < 1e-6));
+        }
+    }
+
+    #[test]
+    fn subdivide_unindexed() {
+        // Without uv coordinates, the vertices of the unconnected triangles are merged at equal positions
+        let mut mesh = CpuMesh::cube();
+        mesh.uvs = None;
+        mesh.subdivide(1);
+        assert_eq!(mesh.triangle_count(), 4 * 12);
+        assert!(is_watertight(&mesh));
+        assert_outwards(&mesh, Vec3::zero());
+    }
+
+    #[test]
+    fn simplify_closed() {
+        let mesh = CpuMesh::sphere(16);
+        let triangle_count = mesh.triangle_count();
+        let simplified = mesh.simplify(0.25);
+        simplified.validate().unwrap();
+        assert!(simplified.triangle_count() < triangle_count / 2);
+        assert!(is_watertight(&simplified));
+        assert_outwards(&simplified, Vec3::zero());
+        for p in simplified.positions.to_f32() {
+            assert!((p.magnitude() - 1.0).abs() < 0.1);
+        }
+
+        let unchanged = mesh.simplify(1.0);
+        assert_eq!(unchanged.triangle_count(), triangle_count);
+    }
+
+    #[test]
+    fn simplify_boundary() {
+        let mesh = CpuMesh::grid(8, 8, 1.0);
+        let simplified = mesh.simplify(0.0);
+        assert!(simplified.triangle_count() <= mesh.triangle_count() / 4);
+        // The boundary vertices are locked, so the grid still covers the same area
+        let aabb = simplified.compute_aabb();
+        assert_eq!(aabb.min(), vec3(-4.0, 0.0, -4.0));
+        assert_eq!(aabb.max(), vec3(4.0, 0.0, 4.0));
+        assert!((area(&simplified) - 64.0).abs() < 1e-3);
+        for triangle in triangles(&simplified) {
+            assert!(normal(&triangle).y > 0.0);
+        }
+    }
+
+    #[test]
+    fn generate_planar_uvs() {
+        // A 4x2 rectangle in the xy-plane
+        let mut mesh = CpuMesh::square();
+        mesh.transform(Mat4::from_nonuniform_scale(2.0, 1.0, 1.0))
+            .unwrap();
+
+        // Looking along the z-axis, u follows the x-axis and v follows the y-axis, each scaled to the unit square
+        mesh.generate_planar_uvs(vec3(0.0, 0.0, 1.0));
+        assert!(mesh.tangents.is_none());
+        for (p, uv) in mesh
+            .positions
+            .to_f32()
+            .iter()
+            .zip(mesh.uvs.as_ref().unwrap())
+        {
+            assert!(uv.distance(vec2((p.x + 2.0) / 4.0, (p.y + 1.0) / 2.0)) < 1e-6);
+        }
+
+        // Looking from the other side mirrors u
+        mesh.generate_planar_uvs(vec3(0.0, 0.0, -1.0));
+        for (p, uv) in mesh
+            .positions
+            .to_f32()
+            .iter()
+            .zip(mesh.uvs.as_ref().unwrap())
+        {
+            assert!(uv.distance(vec2((2.0 - p.x) / 4.0, (p.y + 1.0) / 2.0)) < 1e-6);
+        }
+
+        // Looking along the y-axis, the rectangle is projected onto a line
+        mesh.generate_planar_uvs(vec3(0.0, 1.0, 0.0));
+        for uv in mesh.uvs.as_ref().unwrap() {
+            assert!(uv.x.is_finite() && uv.y.is_finite());
+        }
+    }
+
+    #[test]
+    fn generate_box_uvs() {
+        // A 4x2x2 box
+        let mut mesh = CpuMesh::cube();
+        mesh.compute_tangents();
+        mesh.transform(Mat4::from_nonuniform_scale(2.0, 1.0, 1.0))
+            .unwrap();
+        mesh.generate_box_uvs();
+        mesh.validate().unwrap();
+        assert!(mesh.tangents.is_none());
+
+        // Each triangle is projected along the axis it faces, scaled by the largest side of the box
+        let positions = mesh.positions.to_f32();
+        let uvs = mesh.uvs.as_ref().unwrap();
+        let min = vec3(-2.0, -1.0, -1.0);
+        let mut sides = [0; 3];
+        mesh.for_each_triangle(|i0, i1, i2| {
+            let n = normal(&[positions[i0], positions[i1], positions[i2]]);
+            for i in [i0, i1, i2] {
+                let p = (positions[i] - min) / 4.0;
+                let expected = if n.x.abs() > 0.5 {
+                    sides[0] += 1;
+                    vec2(p.z, p.y)
+                } else if n.y.abs() > 0.5 {
+                    sides[1] += 1;
+                    vec2(p.x, p.z)
+                } else {
+                    sides[2] += 1;
+                    vec2(p.x, p.y)
+                };
+                assert!(uvs[i].distance(expected) < 1e-6);
+            }
+        });
+        assert_eq!(sides, [12, 12, 12]);
+    }
+
+    #[test]
+    fn generate_box_uvs_shared_vertices() {
+        // The corners of a cube are shared between the three sides meeting at the corner
+        let cube = CpuMesh::cube();
+        let mut positions: Vec<Vec3> = Vec::new();
+        let mut indices = Vec::new();
+        for p in cube.positions.to_f32() {
+            let index = positions.iter().position(|q| *q == p).unwrap_or_else(|| {
+                positions.push(p);
+                positions.len() - 1
+            });
+            indices.push(index as u32);
+        }
+        let mut mesh = CpuMesh {
+            positions: Positions::F32(positions),
+            indices: Indices::U32(indices),
+            ..Default::default()
+        };
+        assert_eq!(mesh.vertex_count(), 8);
+
+        // Each corner is duplicated once for each side
+        mesh.generate_box_uvs();
+        mesh.validate().unwrap();
+        assert_eq!(mesh.vertex_count(), 24);
+        assert_eq!(mesh.triangle_count(), 12);
+        assert!(is_watertight(&mesh));
+    }
+
+    #[test]
+    fn try_compute_tangents() {
+        let mut mesh = CpuMesh::grid(2, 2, 1.0);
+        mesh.try_compute_tangents().unwrap();
+        for tangent in mesh.tangents.as_ref().unwrap() {
+            assert_eq!(*tangent, vec4(1.0, 0.0, 0.0, 1.0));
+        }
+
+        // Degenerate uv coordinates give an arbitrary unit tangent orthogonal to the normal
+        mesh.uvs = Some(vec![Vec2::zero(); mesh.vertex_count()]);
+        mesh.try_compute_tangents().unwrap();
+        for (tangent, normal) in mesh
+            .tangents
+            .as_ref()
+            .unwrap()
+            .iter()
+            .zip(mesh.normals.as_ref().unwrap())
+        {
+            assert!((tangent.truncate().magnitude() - 1.0).abs() < 1e-6);
+            assert!(tangent.truncate().dot(*normal).abs() < 1e-6);
+            assert_eq!(tangent.w.abs(), 1.0);
+        }
+    }
+
+    #[test]
+    fn try_compute_tangents_missing_data() {
+        let mut mesh = CpuMesh::grid(2, 2, 1.0);
+        mesh.uvs = None;
+        assert!(matches!(
+            mesh.try_compute_tangents(),
+            Err(RendererError::MissingVertexData(..))
+        ));
+        mesh.normals = None;
+        assert!(matches!(
+            mesh.try_compute_tangents(),
+            Err(RendererError::MissingVertexData(..))
+        ));
+        assert!(mesh.tangents.is_none());
+    }
+
+    #[test]
+    fn compute_bounding_sphere() {
+        let mesh = CpuMesh::sphere(8);
+        let (center, radius) = mesh.compute_bounding_sphere();
+        assert!(center.magnitude() < 0.05);
+        assert!((1.0..1.05).contains(&radius));
+        for p in mesh.positions.to_f32() {
+            assert!(p.distance(center) <= radius + 1e-5);
+        }
+        // Tighter than the sphere around the bounding box
+        assert!(radius < mesh.compute_aabb().bounding_sphere().1);
+        assert_eq!(
+            CpuMesh::default().compute_bounding_sphere(),
+            (Vec3::zero(), 0.0)
+        );
+    }
+}
@@ -4,26 +4,35 @@ use std::sync::RwLock;
 
 use super::BaseMesh;
 
+type TransformBuffers = (
+    InstanceBuffer<Vec4>,
+    InstanceBuffer<Vec4>,
+    InstanceBuffer<Vec4>,
+);
+
 ///
 /// Similar to [Mesh], except it is possible to render many instances of the same mesh efficiently.
 ///
 pub struct InstancedMesh {
     context: Context,
     base_mesh: BaseMesh,
-    transform: RwLock<(
-        InstanceBuffer<Vec4>,
-        InstanceBuffer<Vec4>,
-        InstanceBuffer<Vec4>,
-    )>,
+    transform: RwLock<TransformBuffers>,
+    previous_transform: RwLock<Option<TransformBuffers>>,
+    previous_transform_outdated: RwLock<bool>,
     indices: RwLock<Vec<usize>>,
     tex_transform: RwLock<Option<(InstanceBuffer<Vec3>, InstanceBuffer<Vec3>)>>,
     instance_color: RwLock<Option<InstanceBuffer<Vec4>>>,
+    instance_layer: RwLock<Option<InstanceBuffer<f32>>>,
     last_camera_position: RwLock<Option<Vec3>>,
+    last_view_projection: RwLock<Option<Mat4>>,
+    instance_culling: bool,
     aabb: AxisAlignedBoundingBox, // The AABB for the base mesh without transformations applied
     transformation: Mat4,
     current_transformation: Mat4,
+    previous_transformation: Mat4,
     animation: Option<Box<dyn Fn(f32) -> Mat4 + Send + Sync>>,
     instances: Instances,
+    previous_instance_transformations: Vec<Mat4>,
 }
 
 impl InstancedMesh {
@@ -45,15 +54,22 @@ impl InstancedMesh {
                 InstanceBuffer::<Vec4>::new(context),
                 InstanceBuffer::<Vec4>::new(context),
             )),
+            previous_transform: RwLock::new(None),
+            previous_transform_outdated: RwLock::new(true),
             tex_transform: RwLock::new(None),
             instance_color: RwLock::new(None),
+            instance_layer: RwLock::new(None),
             last_camera_position: RwLock::new(None),
+            last_view_projection: RwLock::new(None),
+            instance_culling: false,
             indices: RwLock::new((0..instances.transformations.len()).collect::<Vec<usize>>()),
             aabb,
             transformation: Mat4::identity(),
             current_transformation: Mat4::identity(),
+            previous_transformation: Mat4::identity(),
             animation: None,
             instances: instances.clone(),
+            previous_instance_transformations: instances.transformations.clone(),
         };
         instanced_mesh.update_instance_buffers();
         instanced_mesh
@@ -74,6 +90,7 @@ impl InstancedMesh {
         self.transformation = transformation;
         self.current_transformation = transformation;
         *self.last_camera_position.write().unwrap() = None;
+        *self.last_view_projection.write().unwrap() = None;
     }
 
     ///
@@ -85,11 +102,56 @@ impl InstancedMesh {
         self.animation = Some(Box::new(animation));
     }
 
+    ///
+    /// Stores the current transformation, including the animation, and the current instance transformations as the transformations in the previous frame.
+    /// The transformations in the previous frame are used for calculating the velocity of each instance in the [VelocityMaterial],
+    /// so call this at the end of each frame when using the [VelocityMaterial].
+    ///
+    pub fn update_previous_transformation(&mut self) {
+        self.previous_transformation = self.current_transformation;
+        self.previous_instance_transformations = self.instances.transformations.clone();
+        *self.previous_transform_outdated.write().unwrap() = true;
+    }
+
     /// Returns the number of instances that is rendered.
     pub fn instance_count(&self) -> u32 {
         self.instances.count()
     }
 
+    ///
+    /// Returns the number of instances that was rendered the last time this mesh was drawn.
+    /// This is the same as [InstancedMesh::instance_count] unless instance culling is enabled (see [InstancedMesh::set_instance_culling]),
+    /// in which case it is the number of instances inside the frustum of the viewer.
+    ///
+    pub fn rendered_instance_count(&self) -> u32 {
+        self.indices.read().unwrap().len() as u32
+    }
+
+    ///
+    /// Returns whether or not each instance is culled individually against the frustum of the viewer, see [InstancedMesh::set_instance_culling].
+    ///
+    pub fn instance_culling(&self) -> bool {
+        self.instance_culling
+    }
+
+    ///
+    /// Enables or disables culling of each instance against the frustum of the viewer before drawing, which is disabled by default.
+    /// When enabled, the bounding box of each instance is tested against the frustum on the CPU and only the visible instances are drawn.
+    /// This is useful when many instances are scattered over a large area, for example vegetation, so that most of them are outside the view,
+    /// but the instance buffers are rebuilt each time the viewer changes, so it can be slower when most instances are visible anyway
+    /// or when the mesh is drawn with several different viewers each frame, for example when rendering shadow maps.
+    ///
+    pub fn set_instance_culling(&mut self, enabled: bool) {
+        self.instance_culling = enabled;
+        *self.last_view_projection.write().unwrap() = None;
+        if !enabled {
+            *self.indices.write().unwrap() =
+                (0..self.instances.transformations.len()).collect::<Vec<usize>>();
+            *self.last_camera_position.write().unwrap() = None;
+            self.update_instance_buffers();
+        }
+    }
+
     ///
     /// Update the instances.
     ///
@@ -100,6 +162,7 @@ impl InstancedMesh {
         *self.indices.write().unwrap() =
             (0..instances.transformations.len()).collect::<Vec<usize>>();
         *self.last_camera_position.write().unwrap() = None;
+        *self.last_view_projection.write().unwrap() = None;
 
         self.update_instance_buffers();
     }
@@ -124,6 +187,8 @@ impl InstancedMesh {
             InstanceBuffer::new_with_data(&self.context, &row3),
         );
 
+        *self.previous_transform_outdated.write().unwrap() = true;
+
         *self.tex_transform.write().unwrap() =
             self.instances
                 .texture_transformations
@@ -156,6 +221,47 @@ impl InstancedMesh {
                     .collect::<Vec<_>>();
                 InstanceBuffer::new_with_data(&self.context, &ordered_instance_colors)
             });
+        *self.instance_layer.write().unwrap() = self.instances.layers.as_ref().map(|layers| {
+            let ordered_layers = indices
+                .iter()
+                .map(|i| layers[*i] as f32)
+                .collect::<Vec<_>>();
+            InstanceBuffer::new_with_data(&self.context, &ordered_layers)
+        });
+    }
+
+    ///
+    /// This function updates the instance buffers containing the transformations in the previous frame, in the same order as the other instance buffers.
+    /// The buffers are only created when a program requires them, ie. when using the [VelocityMaterial], and then updated in place.
+    ///
+    fn update_previous_instance_buffers(&self) {
+        let indices = self.indices.read().unwrap();
+        // Instances which did not exist in the previous frame use the current transformation
+        let mut row1 = Vec::new();
+        let mut row2 = Vec::new();
+        let mut row3 = Vec::new();
+        for transformation in indices.iter().map(|i| {
+            self.previous_instance_transformations
+                .get(*i)
+                .unwrap_or(&self.instances.transformations[*i])
+        }) {
+            row1.push(transformation.row(0));
+            row2.push(transformation.row(1));
+            row3.push(transformation.row(2));
+        }
+        let mut previous_transform = self.previous_transform.write().unwrap();
+        if let Some((buffer1, buffer2, buffer3)) = previous_transform.as_mut() {
+            buffer1.fill(&row1);
+            buffer2.fill(&row2);
+            buffer3.fill(&row3);
+        } else {
+            *previous_transform = Some((
+                InstanceBuffer::new_with_data(&self.context, &row1),
+                InstanceBuffer::new_with_data(&self.context, &row2),
+                InstanceBuffer::new_with_data(&self.context, &row3),
+            ));
+        }
+        *self.previous_transform_outdated.write().unwrap() = false;
     }
 }
 
@@ -170,15 +276,36 @@ impl<'a> IntoIterator for &'a InstancedMesh {
 
 impl Geometry for InstancedMesh {
     fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        let view_projection = viewer.projection() * viewer.view();
+        // Check if we need to find the visible instances again
+        let cull = self.instance_culling
+            && *self.last_view_projection.read().unwrap() != Some(view_projection);
+        if cull {
+            *self.last_view_projection.write().unwrap() = Some(view_projection);
+            let frustum = Frustum::new(view_projection);
+            let local_aabb = self.aabb.transformed(self.current_transformation);
+            *self.indices.write().unwrap() = self
+                .instances
+                .transformations
+                .iter()
+                .enumerate()
+                .filter(|(_, transformation)| {
+                    frustum.contains(local_aabb.transformed(**transformation))
+                })
+                .map(|(i, _)| i)
+                .collect();
+        }
+
         // Check if we need a reorder the instance draw order. This only applies to transparent materials.
-        if render_states.blend != Blend::Disabled
-            && self
-                .last_camera_position
-                .read()
-                .unwrap()
-                .map(|p| p.distance2(viewer.position()) > 0.001)
-                .unwrap_or(true)
-        {
+        let sort = render_states.blend != Blend::Disabled
+            && (cull
+                || self
+                    .last_camera_position
+                    .read()
+                    .unwrap()
+                    .map(|p| p.distance2(viewer.position()) > 0.001)
+                    .unwrap_or(true));
+        if sort {
             *self.last_camera_position.write().unwrap() = Some(viewer.position());
             let distances = self
                 .instances
@@ -196,10 +323,15 @@ impl Geometry for InstancedMesh {
                     .partial_cmp(&distances[*a])
                     .unwrap_or(std::cmp::Ordering::Equal)
             });
+        }
+        if cull || sort {
             self.update_instance_buffers();
         }
+        if self.rendered_instance_count() == 0 {
+            return;
+        }
 
-        program.use_uniform("viewProjection", viewer.projection() * viewer.view());
+        use_camera_uniform_block(&self.context, program, viewer);
         program.use_uniform("modelMatrix", self.current_transformation);
 
         let (row1, row2, row3) = &*self.transform.read().unwrap();
@@ -207,6 +339,18 @@ impl Geometry for InstancedMesh {
         program.use_instance_attribute("row2", row2);
         program.use_instance_attribute("row3", row3);
 
+        if program.requires_attribute("previous_row1") {
+            program.use_uniform("previousModelMatrix", self.previous_transformation);
+            if *self.previous_transform_outdated.read().unwrap() {
+                self.update_previous_instance_buffers();
+            }
+            if let Some((row1, row2, row3)) = &*self.previous_transform.read().unwrap() {
+                program.use_instance_attribute("previous_row1", row1);
+                program.use_instance_attribute("previous_row2", row2);
+                program.use_instance_attribute("previous_row3", row3);
+            }
+        }
+
         if program.requires_attribute("tex_transform_row1") {
             if let Some((row1, row2)) = &*self.tex_transform.read().unwrap() {
                 program.use_instance_attribute("tex_transform_row1", row1);
@@ -220,13 +364,23 @@ impl Geometry for InstancedMesh {
             }
         }
 
-        self.base_mesh
-            .draw_instanced(program, render_states, viewer, self.instance_count());
+        if program.requires_attribute("instance_layer") {
+            if let Some(layer) = &*self.instance_layer.read().unwrap() {
+                program.use_instance_attribute("instance_layer", layer);
+            }
+        }
+
+        self.base_mesh.draw_instanced(
+            program,
+            render_states,
+            viewer,
+            self.rendered_instance_count(),
+        );
     }
 
     fn vertex_shader_source(&self) -> String {
         format!(
-            "#define USE_INSTANCE_TRANSFORMS\n{}{}{}",
+            "#define USE_INSTANCE_TRANSFORMS\n{}{}{}{}",
             if self.instance_color.read().unwrap().is_some() {
                 "#define USE_INSTANCE_COLORS\n"
             } else {
@@ -237,6 +391,11 @@ impl Geometry for InstancedMesh {
             } else {
                 ""
             },
+            if self.instance_layer.read().unwrap().is_some() {
+                "#define USE_INSTANCE_LAYERS\n"
+            } else {
+                ""
+            },
             self.base_mesh.vertex_shader_source()
         )
     }
@@ -249,6 +408,7 @@ impl Geometry for InstancedMesh {
             self.base_mesh.colors.is_some(),
             self.instance_color.read().unwrap().is_some(),
             self.tex_transform.read().unwrap().is_some(),
+            self.instance_layer.read().unwrap().is_some(),
         )
     }
 
@@ -265,6 +425,7 @@ impl Geometry for InstancedMesh {
         if let Some(animation) = &self.animation {
             self.current_transformation = self.transformation * animation(time);
             *self.last_camera_position.write().unwrap() = None;
+            *self.last_view_projection.write().unwrap() = None;
         }
     }
 
@@ -312,6 +473,9 @@ pub struct Instances {
     pub texture_transformations: Option<Vec<Mat3>>,
     /// Colors multiplied onto the base color of each instance.
     pub colors: Option<Vec<Srgba>>,
+    /// The layer of a texture array used by each instance, for example when using a [ColorMaterial] with a [ColorMaterial::texture_array].
+    /// If not specified, all instances use layer 0.
+    pub layers: Option<Vec<u32>>,
 }
 
 impl Instances {
@@ -339,6 +503,7 @@ impl Instances {
         )?;
         buffer_check(Some(self.transformations.len()), "transformations")?;
         buffer_check(self.colors.as_ref().map(|b| b.len()), "colors")?;
+        buffer_check(self.layers.as_ref().map(|b| b.len()), "layers")?;
 
         Ok(())
     }
@@ -12,7 +12,35 @@ pub struct Mesh {
     aabb: AxisAlignedBoundingBox,
     transformation: Mat4,
     current_transformation: Mat4,
+    previous_transformation: Mat4,
     animation: Option<Box<dyn Fn(f32) -> Mat4 + Send + Sync>>,
+    skin: Option<(VertexBuffer<Vec4>, VertexBuffer<Vec4>)>,
+    joint_texture: Option<Texture2D>,
+    morph_targets: Vec<(VertexBuffer<Vec3>, Option<VertexBuffer<Vec3>>)>,
+    morph_weights: Vec4,
+}
+
+///
+/// The skin of a skinned [Mesh], ie. the joints influencing each vertex and the corresponding weights, see [Mesh::set_skin].
+/// Since a [CpuMesh] does not contain skinning data, the skin is given separately, for example to [Model::new_with_skins].
+///
+#[derive(Debug, Clone, Default)]
+pub struct Skin {
+    /// The indices of the four joints influencing each vertex.
+    pub joints: Vec<[u16; 4]>,
+    /// The weights of the four joints influencing each vertex, which should sum to one.
+    pub weights: Vec<Vec4>,
+}
+
+///
+/// A morph target (also called blend shape) which defines a displacement of each vertex in a [Mesh], see [Mesh::set_morph_targets].
+///
+#[derive(Debug, Clone, Default)]
+pub struct MorphTarget {
+    /// The displacement of each vertex position.
+    pub positions: Vec<Vec3>,
+    /// The displacement of each vertex normal. If `None`, the normals are not changed by this morph target.
+    pub normals: Option<Vec<Vec3>>,
 }
 
 impl Mesh {
@@ -28,10 +56,20 @@ impl Mesh {
             aabb,
             transformation: Mat4::identity(),
             current_transformation: Mat4::identity(),
+            previous_transformation: Mat4::identity(),
             animation: None,
+            skin: None,
+            joint_texture: None,
+            morph_targets: Vec::new(),
+            morph_weights: Vec4::zero(),
         }
     }
 
+    ///
+    /// The maximum number of morph targets of a mesh, see [Self::set_morph_targets].
+    ///
+    pub const MAX_MORPH_TARGET_COUNT: usize = 4;
+
     pub(in crate::renderer) fn set_transformation_2d(&mut self, transformation: Mat3) {
         self.set_transformation(Mat4::new(
             transformation.x.x,
@@ -78,6 +116,188 @@ impl Mesh {
         self.animation = Some(Box::new(animation));
     }
 
+    ///
+    /// Stores the current transformation, including the animation, as the transformation in the previous frame.
+    /// The transformation in the previous frame is used for calculating the velocity in the [VelocityMaterial],
+    /// so call this at the end of each frame when using the [VelocityMaterial].
+    ///
+    pub fn update_previous_transformation(&mut self) {
+        self.previous_transformation = self.current_transformation;
+    }
+
+    ///
+    /// Makes this a skinned mesh, ie. a mesh where each vertex is transformed by a weighted sum of up to four joint transformations given by the [Skin].
+    /// Use [Self::set_joint_transformations] to set the current pose of the joints, until then all joints have the identity transformation.
+    /// Returns an error if the skin does not contain one joint and weight per vertex.
+    ///
+    /// Note: The bounding box of the mesh does not take the skinning into account.
+    ///
+    pub fn set_skin(&mut self, skin: &Skin) -> Result<(), RendererError> {
+        let vertex_count = self.vertex_count() as usize;
+        if skin.joints.len() != vertex_count {
+            Err(RendererError::InvalidBufferLength(
+                "joints".to_string(),
+                vertex_count,
+                skin.joints.len(),
+            ))?;
+        }
+        if skin.weights.len() != vertex_count {
+            Err(RendererError::InvalidBufferLength(
+                "weights".to_string(),
+                vertex_count,
+                skin.weights.len(),
+            ))?;
+        }
+        let joints = skin
+            .joints
+            .iter()
+            .map(|j| vec4(j[0] as f32, j[1] as f32, j[2] as f32, j[3] as f32))
+            .collect::<Vec<_>>();
+        self.skin = Some((
+            VertexBuffer::new_with_data(&self.context, &joints),
+            VertexBuffer::new_with_data(&self.context, &skin.weights),
+        ));
+        let joint_count = skin
+            .joints
+            .iter()
+            .flatten()
+            .max()
+            .map(|j| *j as usize + 1)
+            .unwrap_or(1);
+        self.set_joint_transformations(&vec![Mat4::identity(); joint_count]);
+        Ok(())
+    }
+
+    ///
+    /// Returns whether or not this mesh is skinned, see [Self::set_skin].
+    ///
+    pub fn is_skinned(&self) -> bool {
+        self.skin.is_some()
+    }
+
+    ///
+    /// Removes the skin set by [Self::set_skin].
+    ///
+    pub fn clear_skin(&mut self) {
+        self.skin = None;
+        self.joint_texture = None;
+    }
+
+    ///
+    /// Sets the transformation of each joint in the skin defined by [Self::set_skin].
+    /// Each transformation should be the joint's current transformation multiplied by its inverse bind matrix and transforms from the local space of the mesh to the posed local space of the mesh.
+    /// The local to world transformation defined by [Self::set_transformation] is applied afterwards.
+    ///
+    /// The transformations are stored in a texture with four texels per joint, so the number of joints is limited by the maximum texture width, which is at least 512 joints on all devices.
+    ///
+    pub fn set_joint_transformations(&mut self, joint_transformations: &[Mat4]) {
+        let joint_transformations = if joint_transformations.is_empty() {
+            &[Mat4::identity()]
+        } else {
+            joint_transformations
+        };
+        let data = joint_transformations
+            .iter()
+            .flat_map(|m| [m.x, m.y, m.z, m.w])
+            .collect::<Vec<_>>();
+        let width = data.len() as u32;
+        if let Some(texture) = &mut self.joint_texture {
+            texture.update(&data, width, 1);
+        } else {
+            let mut texture = Texture2D::new_empty::<Vec4>(
+                &self.context,
+                width,
+                1,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            );
+            texture.fill(&data);
+            self.joint_texture = Some(texture);
+        }
+    }
+
+    ///
+    /// Sets the morph targets (also called blend shapes) of this mesh.
+    /// Each morph target displaces the vertex positions and optionally normals of the mesh and the displacements are blended together
+    /// in the vertex shader using the weights given by [Self::set_morph_weights].
+    /// All weights are reset to zero.
+    ///
+    /// Note: The bounding box of the mesh does not take the morph targets into account.
+    ///
+    /// # Panic
+    /// Will panic if more than [Self::MAX_MORPH_TARGET_COUNT] morph targets are given
+    /// or if the number of displacements in a morph target is not equal to the number of vertices.
+    ///
+    pub fn set_morph_targets(&mut self, morph_targets: &[MorphTarget]) {
+        if morph_targets.len() > Self::MAX_MORPH_TARGET_COUNT {
+            panic!(
+                "a mesh supports at most {} morph targets, but {} were given",
+                Self::MAX_MORPH_TARGET_COUNT,
+                morph_targets.len()
+            );
+        }
+        let vertex_count = self.vertex_count() as usize;
+        for morph_target in morph_targets {
+            if morph_target.positions.len() != vertex_count
+                || morph_target
+                    .normals
+                    .as_ref()
+                    .is_some_and(|n| n.len() != vertex_count)
+            {
+                panic!(
+                    "the number of displacements in a morph target must be equal to the number of vertices ({})",
+                    vertex_count
+                );
+            }
+        }
+        self.morph_weights = Vec4::zero();
+        self.morph_targets.clear();
+        if morph_targets.is_empty() {
+            return;
+        }
+        // Unused morph targets are filled with zero displacements, so the shader always has the same inputs
+        let zeros = vec![Vec3::zero(); vertex_count];
+        for i in 0..Self::MAX_MORPH_TARGET_COUNT {
+            let morph_target = morph_targets.get(i);
+            let positions = morph_target.map(|m| &m.positions).unwrap_or(&zeros);
+            let normals = self.base_mesh.normals.as_ref().map(|_| {
+                let normals = morph_target
+                    .and_then(|m| m.normals.as_ref())
+                    .unwrap_or(&zeros);
+                VertexBuffer::new_with_data(&self.context, normals)
+            });
+            self.morph_targets.push((
+                VertexBuffer::new_with_data(&self.context, positions),
+                normals,
+            ));
+        }
+    }
+
+    ///
+    /// Removes the morph targets set by [Self::set_morph_targets].
+    ///
+    pub fn clear_morph_targets(&mut self) {
+        self.morph_targets.clear();
+        self.morph_weights = Vec4::zero();
+    }
+
+    ///
+    /// Sets the weight of each of the morph targets defined by [Self::set_morph_targets].
+    /// A weight of zero means the morph target has no influence and a weight of one means it is fully applied.
+    /// Morph targets without a weight are given a weight of zero.
+    ///
+    pub fn set_morph_weights(&mut self, weights: &[f32]) {
+        self.morph_weights = vec4(
+            weights.first().copied().unwrap_or(0.0),
+            weights.get(1).copied().unwrap_or(0.0),
+            weights.get(2).copied().unwrap_or(0.0),
+            weights.get(3).copied().unwrap_or(0.0),
+        );
+    }
+
     ///
     /// Returns the number of vertices in this mesh.
     ///
@@ -162,14 +382,47 @@ impl Geometry for Mesh {
             return;
         }
 
-        program.use_uniform("viewProjection", viewer.projection() * viewer.view());
+        use_camera_uniform_block(&self.context, program, viewer);
         program.use_uniform("modelMatrix", self.current_transformation);
+        program.use_uniform_if_required("previousModelMatrix", self.previous_transformation);
+
+        if let (Some((joints, weights)), Some(joint_texture)) = (&self.skin, &self.joint_texture) {
+            program.use_vertex_attribute("joint_indices", joints);
+            program.use_vertex_attribute("joint_weights", weights);
+            program.use_texture("jointTexture", joint_texture);
+        }
+
+        if !self.morph_targets.is_empty() {
+            program.use_uniform("morphWeights", self.morph_weights);
+            for (i, (positions, normals)) in self.morph_targets.iter().enumerate() {
+                program.use_vertex_attribute(&format!("morph_position{}", i), positions);
+                if let Some(normals) = normals {
+                    let name = format!("morph_normal{}", i);
+                    if program.requires_attribute(&name) {
+                        program.use_vertex_attribute(&name, normals);
+                    }
+                }
+            }
+        }
 
         self.base_mesh.draw(program, render_states, viewer);
     }
 
     fn vertex_shader_source(&self) -> String {
-        self.base_mesh.vertex_shader_source()
+        format!(
+            "{}{}{}",
+            if self.skin.is_some() {
+                "#define USE_SKINNING\n"
+            } else {
+                ""
+            },
+            if !self.morph_targets.is_empty() {
+                "#define USE_MORPH_TARGETS\n"
+            } else {
+                ""
+            },
+            self.base_mesh.vertex_shader_source()
+        )
     }
 
     fn id(&self) -> GeometryId {
@@ -178,6 +431,8 @@ impl Geometry for Mesh {
             self.base_mesh.tangents.is_some(),
             self.base_mesh.uvs.is_some(),
             self.base_mesh.colors.is_some(),
+            self.skin.is_some(),
+            !self.morph_targets.is_empty(),
         )
     }
 
@@ -217,8 +217,9 @@ impl Geometry for ParticleSystem {
             // determinant is float zero
             return;
         }
-        program.use_uniform("viewProjection", viewer.projection() * viewer.view());
+        use_camera_uniform_block(&self.context, program, viewer);
         program.use_uniform("modelMatrix", self.transformation);
+        program.use_uniform_if_required("previousModelMatrix", self.transformation);
         program.use_uniform("acceleration", self.acceleration);
         program.use_uniform("time", self.time);
 
@@ -1,8 +1,27 @@
 
-uniform mat4 viewProjection;
+layout (std140) uniform Camera
+{
+    mat4 viewProjection;
+};
 uniform mat4 modelMatrix;
+uniform mat4 previousViewProjection;
+uniform mat4 previousModelMatrix;
 in vec3 position;
 
+#ifdef USE_MORPH_TARGETS
+uniform vec4 morphWeights;
+in vec3 morph_position0;
+in vec3 morph_position1;
+in vec3 morph_position2;
+in vec3 morph_position3;
+#ifdef USE_NORMALS
+in vec3 morph_normal0;
+in vec3 morph_normal1;
+in vec3 morph_normal2;
+in vec3 morph_normal3;
+#endif
+#endif
+
 #ifdef PARTICLES
 in vec3 start_position;
 in vec3 start_velocity;
@@ -10,13 +29,33 @@ uniform vec3 acceleration;
 uniform float time;
 #endif
 
+#ifdef USE_SKINNING
+in vec4 joint_indices;
+in vec4 joint_weights;
+// The joint matrices are stored column by column in a single row texture
+uniform sampler2D jointTexture;
+
+mat4 jointMatrix(float joint) {
+    int x = 4 * int(joint);
+    return mat4(texelFetch(jointTexture, ivec2(x, 0), 0),
+        texelFetch(jointTexture, ivec2(x + 1, 0), 0),
+        texelFetch(jointTexture, ivec2(x + 2, 0), 0),
+        texelFetch(jointTexture, ivec2(x + 3, 0), 0));
+}
+#endif
+
 #ifdef USE_INSTANCE_TRANSFORMS
 in vec4 row1;
 in vec4 row2;
 in vec4 row3;
+in vec4 previous_row1;
+in vec4 previous_row2;
+in vec4 previous_row3;
 #endif
 
 out vec3 pos;
+out vec4 clipPos;
+out vec4 previousClipPos;
 
 #ifdef USE_NORMALS 
 uniform mat4 normalMatrix;
@@ -48,14 +87,32 @@ in vec4 color;
 in vec4 instance_color;
 #endif
 
+#ifdef USE_INSTANCE_LAYERS
+in float instance_layer;
+#endif
+
 out vec4 col;
 flat out int instance_id;
+flat out int layer;
 
 void main()
 {
     // *** POSITION ***
+    vec3 localPosition = position;
+#ifdef USE_MORPH_TARGETS
+    localPosition += morphWeights.x * morph_position0 + morphWeights.y * morph_position1
+        + morphWeights.z * morph_position2 + morphWeights.w * morph_position3;
+#endif
     mat4 local2World = modelMatrix;
     
+#ifdef USE_SKINNING
+    mat4 skinMatrix = joint_weights.x * jointMatrix(joint_indices.x)
+        + joint_weights.y * jointMatrix(joint_indices.y)
+        + joint_weights.z * jointMatrix(joint_indices.z)
+        + joint_weights.w * jointMatrix(joint_indices.w);
+    local2World = local2World * skinMatrix;
+#endif
+
 #ifdef USE_INSTANCE_TRANSFORMS
     mat4 transform;
     transform[0] = vec4(row1.x, row2.x, row3.x, 0.0);
@@ -65,7 +122,7 @@ void main()
     local2World = transform * local2World;
 #endif
 
-    vec4 worldPosition = local2World * vec4(position, 1.);
+    vec4 worldPosition = local2World * vec4(localPosition, 1.);
     worldPosition /= worldPosition.w;
 #ifdef PARTICLES
     worldPosition.xyz += start_position + start_velocity * time + 0.5 * acceleration * time * time;
@@ -74,14 +131,40 @@ void main()
 
     pos = worldPosition.xyz;
 
+    // *** PREVIOUS POSITION ***
+    mat4 previousLocal2World = previousModelMatrix;
+#ifdef USE_SKINNING
+    previousLocal2World = previousLocal2World * skinMatrix;
+#endif
+#ifdef USE_INSTANCE_TRANSFORMS
+    mat4 previousTransform;
+    previousTransform[0] = vec4(previous_row1.x, previous_row2.x, previous_row3.x, 0.0);
+    previousTransform[1] = vec4(previous_row1.y, previous_row2.y, previous_row3.y, 0.0);
+    previousTransform[2] = vec4(previous_row1.z, previous_row2.z, previous_row3.z, 0.0);
+    previousTransform[3] = vec4(previous_row1.w, previous_row2.w, previous_row3.w, 1.0);
+    previousLocal2World = previousTransform * previousLocal2World;
+#endif
+    vec4 previousWorldPosition = previousLocal2World * vec4(localPosition, 1.);
+    previousWorldPosition /= previousWorldPosition.w;
+#ifdef PARTICLES
+    previousWorldPosition.xyz += start_position + start_velocity * time + 0.5 * acceleration * time * time;
+#endif
+    clipPos = gl_Position;
+    previousClipPos = previousViewProjection * previousWorldPosition;
+
     // *** NORMAL ***
 #ifdef USE_NORMALS 
-#ifdef USE_INSTANCE_TRANSFORMS
+#if defined(USE_INSTANCE_TRANSFORMS) || defined(USE_SKINNING)
     mat3 normalMat = mat3(transpose(inverse(local2World)));
 #else
     mat3 normalMat = mat3(normalMatrix);
 #endif
-    nor = normalize(normalMat * normal);
+    vec3 localNormal = normal;
+#ifdef USE_MORPH_TARGETS
+    localNormal += morphWeights.x * morph_normal0 + morphWeights.y * morph_normal1
+        + morphWeights.z * morph_normal2 + morphWeights.w * morph_normal3;
+#endif
+    nor = normalize(normalMat * localNormal);
 
 #ifdef USE_TANGENTS 
     tang = normalize(normalMat * tangent.xyz);
@@ -112,4 +195,10 @@ void main()
     col *= instance_color;
 #endif
     instance_id = gl_InstanceID;
+
+    // *** LAYER ***
+    layer = 0;
+#ifdef USE_INSTANCE_LAYERS
+    layer = int(instance_layer + 0.5);
+#endif
 }
\ No newline at end of file
@@ -1,4 +1,7 @@
-uniform mat4 viewProjection;
+layout (std140) uniform Camera
+{
+    mat4 viewProjection;
+};
 uniform vec3 eye;
 uniform mat4 transformation;
 uniform vec3 direction;
@@ -85,7 +85,7 @@ impl Sprites {
 
     fn draw(&self, program: &Program, render_states: RenderStates, viewer: &dyn Viewer) {
         program.use_uniform("eye", viewer.position());
-        program.use_uniform("viewProjection", viewer.projection() * viewer.view());
+        use_camera_uniform_block(&self.context, program, viewer);
         program.use_uniform("transformation", self.transformation);
         program.use_vertex_attribute("position", &self.position_buffer);
         if program.requires_attribute("uv_coordinate") {
@@ -15,6 +15,9 @@ macro_rules! impl_light_body {
         fn id(&self) -> LightId {
             self.$inner().id()
         }
+        fn is_enabled(&self) -> bool {
+            self.$inner().is_enabled()
+        }
     };
 }
 
@@ -40,6 +43,10 @@ mod environment;
 #[doc(inline)]
 pub use environment::*;
 
+mod reflection_probe;
+#[doc(inline)]
+pub use reflection_probe::*;
+
 use crate::core::*;
 use crate::renderer::viewer::*;
 use crate::renderer::LightId;
@@ -87,6 +94,13 @@ pub trait Light {
     /// outside of this crate, always return an id in the public use range as defined by [LightId].
     ///
     fn id(&self) -> LightId;
+
+    ///
+    /// Returns whether or not this light is enabled. Disabled lights are skipped when rendering, so they do not contribute to the lighting and do not require a shader recompilation.
+    ///
+    fn is_enabled(&self) -> bool {
+        true
+    }
 }
 
 impl<T: Light + ?Sized> Light for &T {
@@ -123,6 +137,9 @@ impl<T: Light> Light for std::sync::Arc<std::sync::RwLock<T>> {
     fn id(&self) -> LightId {
         self.read().unwrap().id()
     }
+    fn is_enabled(&self) -> bool {
+        self.read().unwrap().is_enabled()
+    }
 }
 
 ///
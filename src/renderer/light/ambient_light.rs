@@ -12,6 +12,8 @@ pub struct AmbientLight {
     pub color: Srgba,
     /// The light shining from the environment. This is calculated based on an environment map.
     pub environment: Option<Environment>,
+    environment_rotation: Mat3,
+    enabled: bool,
 }
 
 impl AmbientLight {
@@ -21,9 +23,33 @@ impl AmbientLight {
             intensity,
             color,
             environment: None,
+            environment_rotation: Mat3::identity(),
+            enabled: true,
         }
     }
 
+    ///
+    /// Enables or disables this light. A disabled light is skipped when rendering, which is the same as not passing it as input to the render call.
+    ///
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    ///
+    /// Returns the rotation applied to the environment when sampling it.
+    ///
+    pub fn rotation(&self) -> Mat3 {
+        self.environment_rotation
+    }
+
+    ///
+    /// Sets the rotation applied to the environment when sampling it.
+    /// Use the same rotation as for the [Skybox] (see [Skybox::set_rotation]) to keep the lighting consistent with the sky.
+    ///
+    pub fn set_rotation(&mut self, rotation: Mat3) {
+        self.environment_rotation = rotation;
+    }
+
     /// Constructs an ambient light that shines based on the given environment map.
     pub fn new_with_environment(
         context: &Context,
@@ -35,11 +61,17 @@ impl AmbientLight {
             intensity,
             color,
             environment: Some(Environment::new(context, environment_map)),
+            environment_rotation: Mat3::identity(),
+            enabled: true,
         }
     }
 }
 
 impl Light for AmbientLight {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
     fn shader_source(&self, i: u32) -> String {
         if self.environment.is_some() {
             format!(
@@ -48,12 +80,16 @@ impl Light for AmbientLight {
                 uniform samplerCube prefilterMap;
                 uniform sampler2D brdfLUT;
                 uniform vec3 ambientColor;
+                uniform mat3 environmentRotation;
     
                 vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
                 {{
                     vec3 N = normal;
                     vec3 V = view_direction;
                     vec3 R = reflect(-V, N); 
+                    // Sample the environment in the rotated directions
+                    mat3 inverseRotation = transpose(environmentRotation);
+                    R = inverseRotation * R;
                     float NdV = max(0.001, dot(N, V));
                     
                     // calculate reflectance at normal incidence; if dia-electric (like plastic) use F0 
@@ -63,7 +99,7 @@ impl Light for AmbientLight {
                     vec3 diffuse_fresnel = 1.0 - specular_fresnel;
 
                     // Diffuse
-                    vec3 irradiance = texture(irradianceMap, N).rgb;
+                    vec3 irradiance = texture(irradianceMap, inverseRotation * N).rgb;
                     vec3 diffuse = diffuse_fresnel * mix(surface_color, vec3(0.0), metallic) * irradiance;
                     
                     // sample both the pre-filter map and the BRDF lut and combine them together as per the Split-Sum approximation to get the IBL specular part.
@@ -71,8 +107,16 @@ impl Light for AmbientLight {
                     vec3 prefilteredColor = textureLod(prefilterMap, R,  roughness * MAX_REFLECTION_LOD).rgb;    
                     vec2 brdf  = texture(brdfLUT, vec2(NdV, roughness)).rg;
                     vec3 specular = prefilteredColor * (specular_fresnel * brdf.x + brdf.y);
+                    vec3 color = diffuse + specular;
+
+                    if(clearcoat_factor > 0.0) {{
+                        float clearcoat_fresnel = clearcoat_factor * fresnel_schlick_roughness(vec3(0.04), NdV, clearcoat_roughness_factor).x;
+                        vec3 clearcoat_color = textureLod(prefilterMap, R, clearcoat_roughness_factor * MAX_REFLECTION_LOD).rgb;
+                        vec2 clearcoat_brdf = texture(brdfLUT, vec2(NdV, clearcoat_roughness_factor)).rg;
+                        color = color * (1.0 - clearcoat_fresnel) + clearcoat_factor * clearcoat_color * (0.04 * clearcoat_brdf.x + clearcoat_brdf.y);
+                    }}
     
-                    return (diffuse + specular) * occlusion * ambientColor;
+                    return color * occlusion * ambientColor;
                 }}
             
             ", i)
@@ -93,6 +137,7 @@ impl Light for AmbientLight {
             program.use_texture_cube("irradianceMap", &environment.irradiance_map);
             program.use_texture_cube("prefilterMap", &environment.prefilter_map);
             program.use_texture("brdfLUT", &environment.brdf_map);
+            program.use_uniform("environmentRotation", self.environment_rotation);
         }
         program.use_uniform(
             "ambientColor",
@@ -111,6 +156,8 @@ impl Default for AmbientLight {
             color: Srgba::WHITE,
             intensity: 1.0,
             environment: None,
+            environment_rotation: Mat3::identity(),
+            enabled: true,
         }
     }
 }
@@ -16,6 +16,7 @@ pub struct DirectionalLight {
     pub color: Srgba,
     /// The direction the light shines.
     pub direction: Vec3,
+    enabled: bool,
 }
 
 impl DirectionalLight {
@@ -33,9 +34,25 @@ impl DirectionalLight {
             intensity,
             color,
             direction,
+            enabled: true,
         }
     }
 
+    ///
+    /// Enables or disables this light. A disabled light is skipped when rendering, which is the same as not passing it as input to the render call.
+    ///
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    ///
+    /// Creates a new directional light with the given illuminance in lux, for example 100000 for direct sunlight and 500 for office lighting.
+    /// Use together with a camera exposure, for example set with [Camera::set_exposure_ev100], to get a correctly exposed image.
+    ///
+    pub fn new_lux(context: &Context, lux: f32, color: Srgba, direction: Vec3) -> DirectionalLight {
+        Self::new(context, lux, color, direction)
+    }
+
     ///
     /// Clear the shadow map, effectively disable the shadow.
     /// Only necessary if you want to disable the shadow, if you want to update the shadow, just use [DirectionalLight::generate_shadow_map].
@@ -50,12 +67,16 @@ impl DirectionalLight {
     /// It is recomended that the texture size is power of 2.
     /// If the shadows are too low resolution (the edges between shadow and non-shadow are pixelated) try to increase the texture size
     /// and/or split the scene by creating another light source with same parameters and let the two light sources shines on different parts of the scene.
+    /// Does nothing if the light is disabled, so remember to generate the shadow map after enabling the light.
     ///
     pub fn generate_shadow_map(
         &mut self,
         texture_size: u32,
         geometries: impl IntoIterator<Item = impl Geometry> + Clone,
     ) {
+        if !self.enabled {
+            return;
+        }
         let up = compute_up_direction(self.direction);
 
         let viewport = Viewport::new_at_origo(texture_size, texture_size);
@@ -127,6 +148,10 @@ impl DirectionalLight {
 }
 
 impl Light for DirectionalLight {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
     fn shader_source(&self, i: u32) -> String {
         if self.shadow_texture.is_some() {
             format!(
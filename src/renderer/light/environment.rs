@@ -56,7 +56,7 @@ impl Environment {
             let viewport = Viewport::new_at_origo(irradiance_size, irradiance_size);
             for side in CubeMapSide::iter() {
                 irradiance_map
-                    .as_color_target(&[side], None)
+                    .as_color_target(side, None)
                     .clear(ClearState::default())
                     .apply_screen_material(
                         &IrradianceMaterial {
@@ -86,8 +86,7 @@ impl Environment {
             let max_mip_levels = 5;
             for mip in 0..max_mip_levels {
                 for side in CubeMapSide::iter() {
-                    let sides = [side];
-                    let color_target = prefilter_map.as_color_target(&sides, Some(mip));
+                    let color_target = prefilter_map.as_color_target(side, Some(mip));
                     let viewport =
                         Viewport::new_at_origo(color_target.width(), color_target.height());
                     color_target
@@ -13,6 +13,7 @@ pub struct PointLight {
     pub position: Vec3,
     /// The [Attenuation] of the light.
     pub attenuation: Attenuation,
+    enabled: bool,
 }
 
 impl PointLight {
@@ -29,11 +30,42 @@ impl PointLight {
             color,
             position,
             attenuation,
+            enabled: true,
         }
     }
+
+    ///
+    /// Enables or disables this light. A disabled light is skipped when rendering, which is the same as not passing it as input to the render call.
+    ///
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    ///
+    /// Constructs a new point light with the given luminous power in lumens, for example 800 for a 60 watt incandescent light bulb.
+    /// The light is attenuated by the inverse square of the distance (in meters), which is physically correct.
+    /// Use together with a camera exposure, for example set with [Camera::set_exposure_ev100], to get a correctly exposed image.
+    ///
+    pub fn new_lumens(context: &Context, lumens: f32, color: Srgba, position: Vec3) -> PointLight {
+        Self::new(
+            context,
+            lumens / (4.0 * std::f32::consts::PI),
+            color,
+            position,
+            Attenuation {
+                constant: 0.0,
+                linear: 0.0,
+                quadratic: 1.0,
+            },
+        )
+    }
 }
 
 impl Light for PointLight {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
     fn shader_source(&self, i: u32) -> String {
         format!(
         "
@@ -0,0 +1,109 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Captures the surroundings as seen from a position in the scene into a cube map, for example to be able to see the reflections of the scene in a mirror ball.
+/// Call [ReflectionProbe::update] to render the scene into the cube map and use the [ReflectionProbe::texture] as an environment map,
+/// for example in [AmbientLight::new_with_environment], to use the captured surroundings when rendering objects with a [PhysicalMaterial].
+///
+pub struct ReflectionProbe {
+    /// The position from where the surroundings are captured.
+    pub position: Vec3,
+    /// The minimum distance from the position where objects are captured.
+    pub z_near: f32,
+    /// The maximum distance from the position where objects are captured.
+    pub z_far: f32,
+    texture: TextureCubeMap,
+    depth_texture: DepthTexture2D,
+}
+
+impl ReflectionProbe {
+    ///
+    /// Constructs a new reflection probe at the given position where each side of the cube map has the given resolution.
+    /// Nothing is captured before calling [ReflectionProbe::update] or [ReflectionProbe::update_side].
+    ///
+    pub fn new(context: &Context, position: Vec3, resolution: u32) -> Self {
+        Self {
+            position,
+            z_near: 0.01,
+            z_far: 1000.0,
+            texture: TextureCubeMap::new_empty::<[f16; 4]>(
+                context,
+                resolution,
+                resolution,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                Some(Mipmap::default()),
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+            depth_texture: DepthTexture2D::new::<f32>(
+                context,
+                resolution,
+                resolution,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+        }
+    }
+
+    ///
+    /// Renders the given objects, using the given lights, into all six sides of the cube map and then generates the mip maps.
+    ///
+    pub fn update(
+        &mut self,
+        objects: impl IntoIterator<Item = impl Object> + Clone,
+        lights: &[&dyn Light],
+    ) {
+        for side in CubeMapSide::iter() {
+            self.render_side(side, objects.clone(), lights);
+        }
+        self.texture.generate_mip_maps();
+    }
+
+    ///
+    /// Renders the given objects, using the given lights, into the given side of the cube map.
+    /// This can be used to spread the cost of updating the reflection probe over several frames.
+    ///
+    pub fn update_side(
+        &mut self,
+        side: CubeMapSide,
+        objects: impl IntoIterator<Item = impl Object>,
+        lights: &[&dyn Light],
+    ) {
+        self.render_side(side, objects, lights);
+        self.texture.generate_mip_maps();
+    }
+
+    fn render_side(
+        &mut self,
+        side: CubeMapSide,
+        objects: impl IntoIterator<Item = impl Object>,
+        lights: &[&dyn Light],
+    ) {
+        let mut camera = Camera::new_perspective(
+            Viewport::new_at_origo(self.texture.width(), self.texture.height()),
+            self.position,
+            self.position + side.direction(),
+            side.up(),
+            degrees(90.0),
+            self.z_near,
+            self.z_far,
+        );
+        camera.disable_tone_and_color_mapping();
+        RenderTarget::new(
+            self.texture.as_color_target(side, Some(0)),
+            self.depth_texture.as_depth_target(),
+        )
+        .clear(ClearState::default())
+        .render(&camera, objects, lights);
+    }
+
+    ///
+    /// Returns the cube map containing the captured surroundings.
+    ///
+    pub fn texture(&self) -> &TextureCubeMap {
+        &self.texture
+    }
+}
@@ -1,5 +1,10 @@
 uniform uint lightingModel;
 
+// The strength and roughness of a clear coat layer on top of the surface.
+// These are set by materials supporting a clear coat before calculating the lighting.
+float clearcoat_factor = 0.0;
+float clearcoat_roughness_factor = 0.0;
+
 struct BaseLight
 {
     vec3 color;
@@ -134,7 +139,20 @@ vec3 calculate_light(vec3 light_color, vec3 L, vec3 surface_color, vec3 V, vec3
     vec3 diffuse = diffuse_fresnel * mix(surface_color, vec3(0.0), metallic) / PI;
 
     // final result
-    return (diffuse + specular) * light_color * NdL;
+    vec3 color = (diffuse + specular) * light_color * NdL;
+
+    if(clearcoat_factor > 0.0) {
+        // the clear coat layer is a dielectric with a cook-torrance specular lobe which attenuates the light reaching the base surface
+        vec3 H = normalize(L + V);
+        float NdH = max(0.001, dot(N, H));
+        float HdV = max(0.001, dot(H, V));
+        float clearcoat_fresnel = clearcoat_factor * fresnel_schlick(vec3(0.04), HdV).x;
+        float D = D_GGX(clearcoat_roughness_factor, NdH);
+        float G = G_schlick(clearcoat_roughness_factor, NdV, NdL);
+        float clearcoat_specular = clearcoat_fresnel * G * D / (4.0 * NdV * NdL);
+        color = color * (1.0 - clearcoat_fresnel) + clearcoat_specular * light_color * NdL;
+    }
+    return color;
 }
 
 vec3 attenuate(vec3 light_color, vec3 attenuation, float distance)
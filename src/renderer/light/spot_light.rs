@@ -10,6 +10,7 @@ pub struct SpotLight {
     context: Context,
     shadow_texture: Option<DepthTexture2D>,
     shadow_matrix: Mat4,
+    cookie: Option<Texture2DRef>,
     /// The intensity of the light. This allows for higher intensity than 1 which can be used to simulate high intensity light sources like the sun.
     pub intensity: f32,
     /// The base color of the light.
@@ -22,6 +23,7 @@ pub struct SpotLight {
     pub cutoff: Radians,
     /// The [Attenuation] of the light.
     pub attenuation: Attenuation,
+    enabled: bool,
 }
 
 impl SpotLight {
@@ -45,9 +47,47 @@ impl SpotLight {
             cutoff: cutoff.into(),
             attenuation,
             shadow_matrix: Mat4::identity(),
+            cookie: None,
+            enabled: true,
         }
     }
 
+    ///
+    /// Enables or disables this light. A disabled light is skipped when rendering, which is the same as not passing it as input to the render call.
+    ///
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    ///
+    /// Constructs a new spot light with the given luminous power in lumens.
+    /// The intensity is the same as a [PointLight] with the same luminous power, so changing the cutoff angle does not change the brightness inside the cone.
+    /// The light is attenuated by the inverse square of the distance (in meters), which is physically correct.
+    /// Use together with a camera exposure, for example set with [Camera::set_exposure_ev100], to get a correctly exposed image.
+    ///
+    pub fn new_lumens(
+        context: &Context,
+        lumens: f32,
+        color: Srgba,
+        position: Vec3,
+        direction: Vec3,
+        cutoff: impl Into<Radians>,
+    ) -> SpotLight {
+        Self::new(
+            context,
+            lumens / (4.0 * std::f32::consts::PI),
+            color,
+            position,
+            direction,
+            cutoff,
+            Attenuation {
+                constant: 0.0,
+                linear: 0.0,
+                quadratic: 1.0,
+            },
+        )
+    }
+
     ///
     /// Clear the shadow map, effectively disable the shadow.
     /// Only necessary if you want to disable the shadow, if you want to update the shadow, just use [SpotLight::generate_shadow_map].
@@ -61,12 +101,16 @@ impl SpotLight {
     /// Generate a shadow map which is used to simulate shadows from the spot light onto the geometries given as input.
     /// It is recomended that the texture size is power of 2.
     /// If the shadows are too low resolution (the edges between shadow and non-shadow are pixelated) try to increase the texture size.
+    /// Does nothing if the light is disabled, so remember to generate the shadow map after enabling the light.
     ///
     pub fn generate_shadow_map(
         &mut self,
         texture_size: u32,
         geometries: impl IntoIterator<Item = impl Geometry> + Clone,
     ) {
+        if !self.enabled {
+            return;
+        }
         let position = self.position;
         let target = position + self.direction.normalize();
         let up = compute_up_direction(self.direction);
@@ -137,69 +181,115 @@ impl SpotLight {
     pub fn shadow_map(&self) -> Option<&DepthTexture2D> {
         self.shadow_texture.as_ref()
     }
-}
 
-impl Light for SpotLight {
-    fn shader_source(&self, i: u32) -> String {
-        if self.shadow_texture.is_some() {
-            format!(
-                "
-                    uniform sampler2D shadowMap{};
-                    uniform mat4 shadowMVP{};
-
-                    uniform vec3 color{};
-                    uniform vec3 attenuation{};
-                    uniform vec3 position{};
-                    uniform float cutoff{};
-                    uniform vec3 direction{};
-                    vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
-                    {{
-                        vec3 light_direction = position{} - position;
-                        float distance = length(light_direction);
-                        light_direction = light_direction / distance;
+    ///
+    /// Sets a texture, also called a cookie, which is projected from the light in the light direction and multiplied onto the light color,
+    /// for example to simulate the pattern of a flashlight or light shining through a window.
+    /// The texture covers the light cone, ie. the center of the texture is in the light direction and the edges of the texture are at the cutoff angle.
+    /// Outside the texture, there is no light.
+    ///
+    pub fn set_cookie(&mut self, texture: Texture2DRef) {
+        self.cookie = Some(texture);
+    }
 
-                        float angle = acos(dot(-light_direction, normalize(direction{})));
-                        float cutoff = cutoff{};
+    ///
+    /// Removes the cookie, see [SpotLight::set_cookie].
+    ///
+    pub fn clear_cookie(&mut self) {
+        self.cookie = None;
+    }
 
-                        vec3 result = vec3(0.0);
-                        if (angle < cutoff) {{
-                            vec3 light_color = attenuate(color{}, attenuation{}, distance);
-                            result = calculate_light(light_color, light_direction, surface_color, view_direction, normal,
-                                metallic, roughness) * (1.0 - smoothstep(0.75 * cutoff, cutoff, angle));
-                            result *= calculate_shadow(light_direction, normal, shadowMap{}, shadowMVP{}, position);
-                        }}
-                        return result;
-                    }}
+    ///
+    /// Returns a reference to the cookie if it has been set, see [SpotLight::set_cookie].
+    ///
+    pub fn cookie(&self) -> Option<&Texture2DRef> {
+        self.cookie.as_ref()
+    }
+
+    fn cookie_matrix(&self) -> Mat4 {
+        let camera = Camera::new_perspective(
+            Viewport::new_at_origo(1, 1),
+            self.position,
+            self.position + self.direction.normalize(),
+            compute_up_direction(self.direction),
+            radians((2.0 * self.cutoff.0).min(3.1)),
+            0.01,
+            1.0,
+        );
+        camera.projection() * camera.view()
+    }
+}
 
-                ", i, i, i, i, i, i, i, i, i, i, i, i, i, i, i)
+impl Light for SpotLight {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn shader_source(&self, i: u32) -> String {
+        let (shadow_uniforms, shadow) = if self.shadow_texture.is_some() {
+            (
+                format!(
+                    "
+                    uniform sampler2D shadowMap{i};
+                    uniform mat4 shadowMVP{i};"
+                ),
+                format!(
+                    "
+                            result *= calculate_shadow(light_direction, normal, shadowMap{i}, shadowMVP{i}, position);"
+                ),
+            )
         } else {
-            format!(
-                "
-                    uniform vec3 color{};
-                    uniform vec3 attenuation{};
-                    uniform vec3 position{};
-                    uniform float cutoff{};
-                    uniform vec3 direction{};
-                    vec3 calculate_lighting{}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
+            (String::new(), String::new())
+        };
+        let (cookie_uniforms, cookie) = if self.cookie.is_some() {
+            (
+                format!(
+                    "
+                    uniform sampler2D cookie{i};
+                    uniform mat4 cookieMVP{i};
+                    uniform mat3 cookieTransformation{i};"
+                ),
+                format!(
+                    "
+                            vec4 cookie_position = cookieMVP{i} * vec4(position, 1.0);
+                            vec2 cookie_uv = 0.5 * cookie_position.xy / cookie_position.w + 0.5;
+                            if (cookie_position.w > 0.0 && all(greaterThanEqual(cookie_uv, vec2(0.0))) && all(lessThanEqual(cookie_uv, vec2(1.0)))) {{
+                                light_color *= texture(cookie{i}, (cookieTransformation{i} * vec3(cookie_uv, 1.0)).xy).rgb;
+                            }} else {{
+                                light_color = vec3(0.0);
+                            }}"
+                ),
+            )
+        } else {
+            (String::new(), String::new())
+        };
+        format!(
+            "{shadow_uniforms}{cookie_uniforms}
+                    uniform vec3 color{i};
+                    uniform vec3 attenuation{i};
+                    uniform vec3 position{i};
+                    uniform float cutoff{i};
+                    uniform vec3 direction{i};
+                    vec3 calculate_lighting{i}(vec3 surface_color, vec3 position, vec3 normal, vec3 view_direction, float metallic, float roughness, float occlusion)
                     {{
-                        vec3 light_direction = position{} - position;
+                        vec3 light_direction = position{i} - position;
                         float distance = length(light_direction);
                         light_direction = light_direction / distance;
 
-                        float angle = acos(dot(-light_direction, normalize(direction{})));
-                        float cutoff = cutoff{};
+                        float angle = acos(dot(-light_direction, normalize(direction{i})));
+                        float cutoff = cutoff{i};
 
                         vec3 result = vec3(0.0);
                         if (angle < cutoff) {{
-                            vec3 light_color = attenuate(color{}, attenuation{}, distance);
+                            vec3 light_color = attenuate(color{i}, attenuation{i}, distance);{cookie}
                             result = calculate_light(light_color, light_direction, surface_color, view_direction, normal,
-                                metallic, roughness) * (1.0 - smoothstep(0.75 * cutoff, cutoff, angle));
+                                metallic, roughness) * (1.0 - smoothstep(0.75 * cutoff, cutoff, angle));{shadow}
                         }}
                         return result;
                     }}
 
-                ", i, i, i, i, i, i, i, i, i, i, i)
-        }
+                "
+        )
     }
     fn use_uniforms(&self, program: &Program, i: u32) {
         if let Some(ref tex) = self.shadow_texture {
@@ -221,9 +311,14 @@ impl Light for SpotLight {
         program.use_uniform(&format!("position{}", i), self.position);
         program.use_uniform(&format!("direction{}", i), self.direction.normalize());
         program.use_uniform(&format!("cutoff{}", i), self.cutoff.0);
+        if let Some(ref cookie) = self.cookie {
+            program.use_texture(&format!("cookie{}", i), cookie);
+            program.use_uniform(&format!("cookieMVP{}", i), self.cookie_matrix());
+            program.use_uniform(&format!("cookieTransformation{}", i), cookie.transformation);
+        }
     }
 
     fn id(&self) -> LightId {
-        LightId::SpotLight(self.shadow_texture.is_some())
+        LightId::SpotLight(self.shadow_texture.is_some(), self.cookie.is_some())
     }
 }
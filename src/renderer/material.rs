@@ -59,6 +59,10 @@ mod uv_material;
 #[doc(inline)]
 pub use uv_material::*;
 
+mod velocity_material;
+#[doc(inline)]
+pub use velocity_material::*;
+
 mod physical_material;
 #[doc(inline)]
 pub use physical_material::*;
@@ -105,6 +109,15 @@ impl Texture2DRef {
             transformation: Mat3::identity(),
         }
     }
+
+    ///
+    /// Returns this texture reference with the given transformation applied to the uv coordinates before the existing [Texture2DRef::transformation].
+    /// Calling this method multiple times composes the transformations, so the existing transformation, for example a texture atlas mapping, is never replaced.
+    ///
+    pub fn with_transform(mut self, transformation: Mat3) -> Self {
+        self.transformation = self.transformation * transformation;
+        self
+    }
 }
 
 impl std::ops::Deref for Texture2DRef {
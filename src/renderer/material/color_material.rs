@@ -1,5 +1,6 @@
 use crate::core::*;
 use crate::renderer::*;
+use std::sync::Arc;
 
 ///
 /// A material that renders a [Geometry] in a color defined by multiplying a color with an optional texture and optional per vertex colors.
@@ -12,6 +13,9 @@ pub struct ColorMaterial {
     /// An optional texture which is samples using uv coordinates (requires that the [Geometry] supports uv coordinates).
     /// The colors are assumed to be in linear sRGB (`RgbU8`), linear sRGB with an alpha channel (`RgbaU8`) or HDR color space.
     pub texture: Option<Texture2DRef>,
+    /// An optional texture array which is sampled using uv coordinates and the layer specified for each instance (see [Instances::layers]) and is used instead of the [ColorMaterial::texture] if specified.
+    /// The layer is 0 for all instances if the layers are not specified or if the [Geometry] is not instanced.
+    pub texture_array: Option<Arc<Texture2DArray>>,
     /// Render states.
     pub render_states: RenderStates,
     /// Whether this material should be treated as a transparent material (An object needs to be rendered differently depending on whether it is transparent or opaque).
@@ -49,6 +53,7 @@ impl ColorMaterial {
         Self {
             color: cpu_material.albedo,
             texture,
+            texture_array: None,
             is_transparent: false,
             render_states: RenderStates::default(),
         }
@@ -71,6 +76,7 @@ impl ColorMaterial {
         Self {
             color: cpu_material.albedo,
             texture,
+            texture_array: None,
             is_transparent: true,
             render_states: RenderStates {
                 write_mask: WriteMask::COLOR,
@@ -80,11 +86,24 @@ impl ColorMaterial {
         }
     }
 
+    ///
+    /// Constructs a new opaque color material which renders the given texture in its original colors.
+    /// Use [Texture2DRef::with_transform] to apply a transformation to the uv coordinates.
+    ///
+    pub fn textured(texture: impl Into<Texture2DRef>) -> Self {
+        Self {
+            color: Srgba::WHITE,
+            texture: Some(texture.into()),
+            ..Default::default()
+        }
+    }
+
     /// Creates a color material from a [PhysicalMaterial].
     pub fn from_physical_material(physical_material: &PhysicalMaterial) -> Self {
         Self {
             color: physical_material.albedo,
             texture: physical_material.albedo_texture.clone(),
+            texture_array: None,
             render_states: physical_material.render_states,
             is_transparent: physical_material.is_transparent,
         }
@@ -99,24 +118,34 @@ impl FromCpuMaterial for ColorMaterial {
 
 impl Material for ColorMaterial {
     fn id(&self) -> EffectMaterialId {
-        EffectMaterialId::ColorMaterial(self.texture.is_some())
+        if self.texture_array.is_some() {
+            EffectMaterialId::ColorMaterialTextureArray
+        } else {
+            EffectMaterialId::ColorMaterial(self.texture.is_some())
+        }
     }
 
     fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
         let mut shader = String::new();
-        if self.texture.is_some() {
+        if self.texture_array.is_some() {
+            shader.push_str("#define USE_TEXTURE_ARRAY\nin vec2 uvs;\n");
+        } else if self.texture.is_some() {
             shader.push_str("#define USE_TEXTURE\nin vec2 uvs;\n");
         }
         shader.push_str(include_str!("../../core/shared.frag"));
         shader.push_str(ColorMapping::fragment_shader_source());
+        shader.push_str(&Fog::fragment_shader_source());
         shader.push_str(include_str!("shaders/color_material.frag"));
         shader
     }
 
     fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, _lights: &[&dyn Light]) {
         viewer.color_mapping().use_uniforms(program);
+        Fog::use_uniforms(program, viewer);
         program.use_uniform("surfaceColor", self.color.to_linear_srgb());
-        if let Some(ref tex) = self.texture {
+        if let Some(ref tex) = self.texture_array {
+            program.use_texture_array("texArray", tex);
+        } else if let Some(ref tex) = self.texture {
             program.use_uniform("textureTransformation", tex.transformation);
             program.use_texture("tex", tex);
         }
@@ -4,11 +4,15 @@ use crate::renderer::*;
 ///
 /// Similar to [PhysicalMaterial] except that rendering happens in two stages which produces the same result, but is more efficient for complex scenes.
 /// This material does not support transparency but does support [alpha cutout](DeferredPhysicalMaterial::alpha_cutout).
+/// Transparent objects can be rendered together with objects with this material in the same render call, for example [RenderTarget::render].
+/// In that case, the transparent objects are excluded from the geometry pass and instead rendered on top of the result of the lighting pass using forward rendering.
 ///
 /// The first stage renders geometry information to a [RenderTarget] and the second stage uses this render target to apply lighting based on the geometry information which means the expensive lighting calculations are only done once per pixel.
 /// The [RenderTarget::render], [ColorTarget::render] or [DepthTarget::render] methods all support the two stages required by this material, so just pass the [Object] with this material applied into one of these methods.
 /// However, it is not possible to use the [Object::render] method to render a [Geometry] with this material directly to the screen.
 /// Instead render the object into a [RenderTarget] consisting of a [Texture2DArray] with three RGBA u8 layers as color target and a [DepthTexture2D] as depth target.
+/// Note that the third layer contains the emissive color which is clamped to the `[0..1]` range when stored in an u8 texture,
+/// the render calls on [RenderTarget] instead store the emissive color in a separate RGBA f16 texture so the result matches forward rendering.
 /// Then call the [DeferredPhysicalMaterial::lighting_pass] method with these textures to render to the screen.
 ///
 #[derive(Clone)]
@@ -162,7 +166,9 @@ impl DeferredPhysicalMaterial {
     ) {
         apply_screen_effect(
             context,
-            lighting_pass::LightingPassEffect {},
+            lighting_pass::LightingPassEffect {
+                emissive_texture: None,
+            },
             viewer,
             lights,
             Some(geometry_pass_color_texture),
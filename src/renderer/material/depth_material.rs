@@ -4,6 +4,8 @@ use crate::renderer::*;
 ///
 /// Used for rendering the distance from the camera to the object with this material in each pixel.
 /// Can be used for debug purposes but is also used to create shadow maps from light sources.
+/// The distance is linear and mapped to the range `[0..1]` using the minimum and maximum distance.
+/// Use the [LinearDepthEffect] to get a similar linear depth from the depth texture of an already rendered scene.
 ///
 #[derive(Default, Clone)]
 pub struct DepthMaterial {
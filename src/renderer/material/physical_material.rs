@@ -33,6 +33,7 @@ pub struct PhysicalMaterial {
     /// Render states.
     pub render_states: RenderStates,
     /// Whether this material should be treated as a transparent material (An object needs to be rendered differently depending on whether it is transparent or opaque).
+    /// The alpha of the vertex colors, if any, is only applied to a transparent material.
     pub is_transparent: bool,
     /// Color of light shining from an object.
     pub emissive: Srgba,
@@ -41,6 +42,35 @@ pub struct PhysicalMaterial {
     pub emissive_texture: Option<Texture2DRef>,
     /// The lighting model used when rendering this material
     pub lighting_model: LightingModel,
+    /// The strength of a clear coat layer on top of the surface, for example for car paint or varnished wood.
+    /// A value of 0 means no clear coat and a value of 1 means full clear coat.
+    /// The clear coat is ignored when converting to a [DeferredPhysicalMaterial].
+    pub clearcoat: f32,
+    /// The roughness of the clear coat layer.
+    pub clearcoat_roughness: f32,
+    /// Texture with the clear coat strength which is multiplied with [Self::clearcoat].
+    /// The values are sampled from the red channel.
+    pub clearcoat_texture: Option<Texture2DRef>,
+    /// Texture with the clear coat roughness which is multiplied with [Self::clearcoat_roughness].
+    /// The values are sampled from the green channel.
+    pub clearcoat_roughness_texture: Option<Texture2DRef>,
+    /// The amount of light transmitted through the surface, for example for glass and liquids, as defined by the glTF `KHR_materials_transmission` extension.
+    /// A value of 0 means no transmission and a value of 1 means that the diffuse part of the lighting is replaced by the transmitted light.
+    /// The light is transmitted straight through the surface (thin-walled) and blurred based on the roughness.
+    ///
+    /// **Note:** The transmission is only applied when rendering with this material as an [Effect], which must be given the rendered scene behind the surface in a color texture.
+    /// When rendering with this material as a [Material], for example when rendering a [Gm], the transmission is ignored.
+    pub transmission: f32,
+    /// Texture with the transmission which is multiplied with [Self::transmission].
+    /// The values are sampled from the red channel.
+    pub transmission_texture: Option<Texture2DRef>,
+    /// The index of refraction of the surface which determines how much of the transmitted light is reflected instead, especially at grazing angles.
+    /// The default value of 1.5 corresponds to glass.
+    pub index_of_refraction: f32,
+    /// If `Some`, the textures are sampled using triplanar mapping instead of the uv coordinates of the geometry, ie. they are projected along the world space x, y and z-axis and blended based on the surface normal.
+    /// This removes the need for uv coordinates and avoids seams, for example on terrains and procedural meshes.
+    /// The value is the scale applied to the world space position before sampling the textures, so a higher value gives more repetitions of the textures.
+    pub triplanar_scale: Option<f32>,
 }
 
 impl PhysicalMaterial {
@@ -63,6 +93,7 @@ impl PhysicalMaterial {
     }
 
     /// Constructs a new transparent physical material from a [CpuMaterial].
+    /// The alpha of the albedo color, the albedo texture and the vertex colors are multiplied together to give the final alpha.
     /// If the input contains an [CpuMaterial::occlusion_metallic_roughness_texture], this texture is used for both
     /// [PhysicalMaterial::metallic_roughness_texture] and [PhysicalMaterial::occlusion_texture] while any [CpuMaterial::metallic_roughness_texture] or [CpuMaterial::occlusion_texture] are ignored.
     pub fn new_transparent(context: &Context, cpu_material: &CpuMaterial) -> Self {
@@ -103,6 +134,10 @@ impl PhysicalMaterial {
             .normal_texture
             .as_ref()
             .map(|cpu_texture| Texture2DRef::from_cpu_texture(context, cpu_texture));
+        let transmission_texture = cpu_material
+            .transmission_texture
+            .as_ref()
+            .map(|cpu_texture| Texture2DRef::from_cpu_texture(context, cpu_texture));
         let emissive_texture =
             cpu_material
                 .emissive_texture
@@ -139,36 +174,38 @@ impl PhysicalMaterial {
             emissive: cpu_material.emissive,
             emissive_texture,
             lighting_model: cpu_material.lighting_model,
+            transmission: cpu_material.transmission,
+            transmission_texture,
+            index_of_refraction: cpu_material.index_of_refraction,
+            clearcoat: 0.0,
+            clearcoat_roughness: 0.0,
+            clearcoat_texture: None,
+            clearcoat_roughness_texture: None,
+            triplanar_scale: None,
         }
     }
-}
-
-impl FromCpuMaterial for PhysicalMaterial {
-    fn from_cpu_material(context: &Context, cpu_material: &CpuMaterial) -> Self {
-        Self::new(context, cpu_material)
-    }
-}
-
-impl Material for PhysicalMaterial {
-    fn id(&self) -> EffectMaterialId {
-        EffectMaterialId::PhysicalMaterial(
-            self.albedo_texture.is_some(),
-            self.metallic_roughness_texture.is_some(),
-            self.occlusion_texture.is_some(),
-            self.normal_texture.is_some(),
-            self.emissive_texture.is_some(),
-        )
-    }
 
-    fn fragment_shader_source(&self, lights: &[&dyn Light]) -> String {
+    fn fragment_shader_source_internal(
+        &self,
+        lights: &[&dyn Light],
+        use_transmission_texture: bool,
+    ) -> String {
         let mut output = lights_shader_source(lights);
         if self.albedo_texture.is_some()
             || self.metallic_roughness_texture.is_some()
             || self.normal_texture.is_some()
             || self.occlusion_texture.is_some()
             || self.emissive_texture.is_some()
+            || self.clearcoat_texture.is_some()
+            || self.clearcoat_roughness_texture.is_some()
+            || use_transmission_texture
         {
-            output.push_str("in vec2 uvs;\n");
+            output.push_str("#define USE_TEXTURES\n");
+            if self.triplanar_scale.is_some() {
+                output.push_str("#define USE_TRIPLANAR\n");
+            } else {
+                output.push_str("in vec2 uvs;\n");
+            }
             if self.albedo_texture.is_some() {
                 output.push_str("#define USE_ALBEDO_TEXTURE;\n");
             }
@@ -179,28 +216,87 @@ impl Material for PhysicalMaterial {
                 output.push_str("#define USE_OCCLUSION_TEXTURE;\n");
             }
             if self.normal_texture.is_some() {
-                output.push_str("#define USE_NORMAL_TEXTURE;\nin vec3 tang;\nin vec3 bitang;\n");
+                output.push_str("#define USE_NORMAL_TEXTURE;\n");
+                if self.triplanar_scale.is_none() {
+                    output.push_str("in vec3 tang;\nin vec3 bitang;\n");
+                }
             }
             if self.emissive_texture.is_some() {
                 output.push_str("#define USE_EMISSIVE_TEXTURE;\n");
             }
+            if self.clearcoat_texture.is_some() {
+                output.push_str("#define USE_CLEARCOAT_TEXTURE;\n");
+            }
+            if self.clearcoat_roughness_texture.is_some() {
+                output.push_str("#define USE_CLEARCOAT_ROUGHNESS_TEXTURE;\n");
+            }
+            if use_transmission_texture {
+                output.push_str("#define USE_TRANSMISSION_TEXTURE;\n");
+            }
         }
         output.push_str(ToneMapping::fragment_shader_source());
         output.push_str(ColorMapping::fragment_shader_source());
+        output.push_str(&Fog::fragment_shader_source());
         output.push_str(include_str!("shaders/physical_material.frag"));
         output
     }
+}
+
+impl FromCpuMaterial for PhysicalMaterial {
+    fn from_cpu_material(context: &Context, cpu_material: &CpuMaterial) -> Self {
+        Self::new(context, cpu_material)
+    }
+}
+
+impl Material for PhysicalMaterial {
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId::PhysicalMaterial(
+            self.albedo_texture.is_some(),
+            self.metallic_roughness_texture.is_some(),
+            self.occlusion_texture.is_some(),
+            self.normal_texture.is_some(),
+            self.emissive_texture.is_some(),
+            self.triplanar_scale.is_some(),
+            self.clearcoat_texture.is_some(),
+            self.clearcoat_roughness_texture.is_some(),
+        )
+    }
+
+    fn fragment_shader_source(&self, lights: &[&dyn Light]) -> String {
+        self.fragment_shader_source_internal(lights, false)
+    }
 
     fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, lights: &[&dyn Light]) {
         program.use_uniform_if_required("lightingModel", lighting_model_to_id(self.lighting_model));
-        viewer.tone_mapping().use_uniforms(program);
+        viewer
+            .tone_mapping()
+            .use_uniforms_with_exposure(program, viewer.exposure());
         viewer.color_mapping().use_uniforms(program);
+        Fog::use_uniforms(program, viewer);
         program.use_uniform_if_required("cameraPosition", viewer.position());
         for (i, light) in lights.iter().enumerate() {
             light.use_uniforms(program, i as u32);
         }
         program.use_uniform_if_required("metallic", self.metallic);
         program.use_uniform_if_required("roughness", self.roughness);
+        program.use_uniform_if_required("useVertexAlpha", self.is_transparent as i32);
+        program.use_uniform_if_required("clearcoat", self.clearcoat);
+        program.use_uniform_if_required("clearcoatRoughness", self.clearcoat_roughness);
+        if program.requires_uniform("clearcoatTexture") {
+            if let Some(ref texture) = self.clearcoat_texture {
+                program.use_uniform("clearcoatTexTransform", texture.transformation);
+                program.use_texture("clearcoatTexture", texture);
+            }
+        }
+        if program.requires_uniform("clearcoatRoughnessTexture") {
+            if let Some(ref texture) = self.clearcoat_roughness_texture {
+                program.use_uniform("clearcoatRoughnessTexTransform", texture.transformation);
+                program.use_texture("clearcoatRoughnessTexture", texture);
+            }
+        }
+        if let Some(triplanar_scale) = self.triplanar_scale {
+            program.use_uniform_if_required("triplanarScale", triplanar_scale);
+        }
         if program.requires_uniform("albedoTexture") {
             if let Some(ref texture) = self.albedo_texture {
                 program.use_uniform("albedoTexTransform", texture.transformation);
@@ -268,6 +364,210 @@ impl Default for PhysicalMaterial {
             emissive: Srgba::BLACK,
             emissive_texture: None,
             lighting_model: LightingModel::Blinn,
+            transmission: 0.0,
+            transmission_texture: None,
+            index_of_refraction: 1.5,
+            clearcoat: 0.0,
+            clearcoat_roughness: 0.0,
+            clearcoat_texture: None,
+            clearcoat_roughness_texture: None,
+            triplanar_scale: None,
+        }
+    }
+}
+
+impl Effect for PhysicalMaterial {
+    fn fragment_shader_source(
+        &self,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> String {
+        format!(
+            "#define USE_TRANSMISSION\n{}\n{}",
+            color_texture
+                .expect("Must supply a color texture to apply transmission")
+                .fragment_shader_source(),
+            self.fragment_shader_source_internal(lights, self.transmission_texture.is_some())
+        )
+    }
+
+    fn id(
+        &self,
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId::PhysicalMaterialTransmission(
+            Material::id(self),
+            color_texture.expect("Must supply a color texture to apply transmission"),
+            self.transmission_texture.is_some(),
+        )
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        _depth_texture: Option<DepthTexture>,
+    ) {
+        Material::use_uniforms(self, program, viewer, lights);
+        color_texture
+            .expect("Must supply a color texture to apply transmission")
+            .use_uniforms(program);
+        program.use_uniform_if_required("transmission", self.transmission);
+        program.use_uniform_if_required("indexOfRefraction", self.index_of_refraction);
+        if program.requires_uniform("transmissionTexture") {
+            if let Some(ref texture) = self.transmission_texture {
+                program.use_uniform("transmissionTexTransform", texture.transformation);
+                program.use_texture("transmissionTexture", texture);
+            }
         }
+        let viewport = viewer.viewport();
+        program.use_uniform_if_required(
+            "viewport",
+            vec4(
+                viewport.x as f32,
+                viewport.y as f32,
+                viewport.width as f32,
+                viewport.height as f32,
+            ),
+        );
+    }
+
+    fn render_states(&self) -> RenderStates {
+        self.render_states
+    }
+}
+
+#[cfg(all(test, feature = "headless", not(target_arch = "wasm32")))]
+mod tests {
+    use crate::*;
+
+    ///
+    /// Renders a square covering the viewport where the alpha of the vertex colors goes from 0 at the left edge to 1 at the right edge
+    /// and returns the red channel of the middle row of pixels.
+    ///
+    fn render_alpha_gradient(context: &Context, material: PhysicalMaterial) -> Vec<u8> {
+        let size = 8;
+        let mut cpu_mesh = CpuMesh::square();
+        cpu_mesh.colors = Some(
+            cpu_mesh
+                .positions
+                .to_f32()
+                .iter()
+                .map(|p| Srgba::new(255, 255, 255, if p.x < 0.0 { 0 } else { 255 }))
+                .collect(),
+        );
+        let model = Gm::new(Mesh::new(context, &cpu_mesh), material);
+        let camera = Camera::new_orthographic(
+            Viewport::new_at_origo(size, size),
+            vec3(0.0, 0.0, 1.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            2.0,
+            0.1,
+            10.0,
+        );
+        let light = AmbientLight::new(context, 1.0, Srgba::WHITE);
+        let mut color = Texture2D::new_empty::<[u8; 4]>(
+            context,
+            size,
+            size,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let mut depth = DepthTexture2D::new::<f32>(
+            context,
+            size,
+            size,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let pixels = RenderTarget::new(color.as_color_target(None), depth.as_depth_target())
+            .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0))
+            .render(&camera, &model, &[&light])
+            .read_color::<[u8; 4]>();
+        let row = (size / 2) as usize;
+        pixels[row * size as usize..(row + 1) * size as usize]
+            .iter()
+            .map(|p| p[0])
+            .collect()
+    }
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn vertex_alpha() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let cpu_material = CpuMaterial {
+            albedo: Srgba::WHITE,
+            ..Default::default()
+        };
+
+        // The transparent material fades from the black background on the left to the surface color on the right
+        let transparent = render_alpha_gradient(
+            &context,
+            PhysicalMaterial::new_transparent(&context, &cpu_material),
+        );
+        assert!(transparent[0] < transparent[transparent.len() - 1] / 4);
+        assert!(transparent.windows(2).all(|w| w[0] <= w[1]));
+
+        // The opaque material ignores the vertex alpha
+        let opaque = render_alpha_gradient(
+            &context,
+            PhysicalMaterial::new_opaque(&context, &cpu_material),
+        );
+        assert!(opaque[0] > 0);
+        assert!(opaque.iter().all(|&c| c == opaque[0]));
+        let right = transparent[transparent.len() - 1];
+        assert!(right <= opaque[0] && right > opaque[0] / 2);
+    }
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn transmission_from_cpu_material() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let cpu_material = CpuMaterial {
+            transmission: 0.8,
+            transmission_texture: Some(CpuTexture {
+                data: TextureData::RU8(vec![255]),
+                width: 1,
+                height: 1,
+                ..Default::default()
+            }),
+            index_of_refraction: 1.33,
+            ..Default::default()
+        };
+
+        let material = PhysicalMaterial::new(&context, &cpu_material);
+        assert_eq!(material.transmission, 0.8);
+        assert_eq!(material.index_of_refraction, 1.33);
+        assert!(material.transmission_texture.is_some());
+
+        // The transmission texture is only used by the effect, so it changes the effect id but not the material id
+        let without_texture = PhysicalMaterial {
+            transmission_texture: None,
+            ..material.clone()
+        };
+        assert!(Material::id(&material) == Material::id(&without_texture));
+        let color_texture = Texture2D::new_empty::<[u8; 4]>(
+            &context,
+            1,
+            1,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let color_texture = Some(ColorTexture::Single(&color_texture));
+        assert!(
+            Effect::id(&material, color_texture, None)
+                != Effect::id(&without_texture, color_texture, None)
+        );
     }
 }
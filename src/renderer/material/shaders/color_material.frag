@@ -5,6 +5,11 @@ uniform sampler2D tex;
 uniform mat3 textureTransformation;
 #endif
 
+#ifdef USE_TEXTURE_ARRAY
+uniform sampler2DArray texArray;
+flat in int layer;
+#endif
+
 in vec4 col;
 
 layout (location = 0) out vec4 outColor;
@@ -17,5 +22,10 @@ void main()
     outColor *= texture(tex, (textureTransformation * vec3(uvs, 1.0)).xy);
     #endif
 
+    #ifdef USE_TEXTURE_ARRAY
+    outColor *= texture(texArray, vec3(uvs, float(layer)));
+    #endif
+
+    outColor.rgb = apply_fog(outColor.rgb, fog_position());
     outColor.rgb = color_mapping(outColor.rgb);
 }
\ No newline at end of file
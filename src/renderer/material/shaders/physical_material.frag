@@ -4,6 +4,7 @@ uniform float roughness;
 uniform vec3 cameraPosition;
 
 uniform vec4 albedo;
+uniform int useVertexAlpha;
 #ifdef USE_ALBEDO_TEXTURE
 uniform sampler2D albedoTexture;
 uniform mat3 albedoTexTransform;
@@ -26,6 +27,17 @@ uniform mat3 occlusionTexTransform;
 uniform float occlusionStrength;
 #endif
 
+uniform float clearcoat;
+uniform float clearcoatRoughness;
+#ifdef USE_CLEARCOAT_TEXTURE
+uniform sampler2D clearcoatTexture;
+uniform mat3 clearcoatTexTransform;
+#endif
+#ifdef USE_CLEARCOAT_ROUGHNESS_TEXTURE
+uniform sampler2D clearcoatRoughnessTexture;
+uniform mat3 clearcoatRoughnessTexTransform;
+#endif
+
 #ifdef USE_NORMAL_TEXTURE
 uniform sampler2D normalTexture;
 uniform mat3 normalTexTransform;
@@ -36,13 +48,85 @@ in vec3 pos;
 in vec3 nor;
 in vec4 col;
 
+#ifdef USE_TRIPLANAR
+uniform float triplanarScale;
+
+// Returns the blend weights of the projections along the x, y and z-axis
+vec3 triplanar_weights()
+{
+    vec3 w = pow(abs(normalize(nor)), vec3(4.0));
+    return w / (w.x + w.y + w.z);
+}
+
+// Returns the sign of each component of the normal where zero is treated as positive
+vec3 triplanar_signs()
+{
+    return vec3(nor.x < 0.0 ? -1.0 : 1.0, nor.y < 0.0 ? -1.0 : 1.0, nor.z < 0.0 ? -1.0 : 1.0);
+}
+
+// Returns the uv coordinates of the projections along the x, y and z-axis,
+// which are flipped on the back side so the textures are not mirrored
+void triplanar_uvs(out vec2 uv_x, out vec2 uv_y, out vec2 uv_z)
+{
+    vec3 p = triplanarScale * pos;
+    vec3 s = triplanar_signs();
+    uv_x = vec2(p.z * s.x, p.y);
+    uv_y = vec2(p.x * s.y, p.z);
+    uv_z = vec2(-p.x * s.z, p.y);
+}
+#endif
+
+#ifdef USE_TEXTURES
+vec4 sample_texture(sampler2D tex, mat3 texTransform)
+{
+#ifdef USE_TRIPLANAR
+    vec2 uv_x, uv_y, uv_z;
+    triplanar_uvs(uv_x, uv_y, uv_z);
+    vec3 w = triplanar_weights();
+    return w.x * texture(tex, (texTransform * vec3(uv_x, 1.0)).xy)
+        + w.y * texture(tex, (texTransform * vec3(uv_y, 1.0)).xy)
+        + w.z * texture(tex, (texTransform * vec3(uv_z, 1.0)).xy);
+#else
+    return texture(tex, (texTransform * vec3(uvs, 1.0)).xy);
+#endif
+}
+#endif
+
+#ifdef USE_TRANSMISSION
+uniform float transmission;
+uniform float indexOfRefraction;
+uniform vec4 viewport;
+#ifdef USE_TRANSMISSION_TEXTURE
+uniform sampler2D transmissionTexture;
+uniform mat3 transmissionTexTransform;
+#endif
+
+// Samples the background behind the surface, blurred based on the roughness of the surface
+vec3 sample_transmission(float roughness)
+{
+    vec2 uv = (gl_FragCoord.xy - viewport.xy) / viewport.zw;
+    vec3 color = sample_color(uv).rgb;
+    float radius = 0.05 * roughness * roughness;
+    if (radius < 0.0001) {
+        return color;
+    }
+    const int SAMPLES = 8;
+    for (int i = 0; i < SAMPLES; i++) {
+        float angle = 2.0 * PI * float(i) / float(SAMPLES);
+        vec2 offset = radius * vec2(cos(angle), sin(angle));
+        color += sample_color(uv + offset).rgb + sample_color(uv + 0.5 * offset).rgb;
+    }
+    return color / float(2 * SAMPLES + 1);
+}
+#endif
+
 layout (location = 0) out vec4 outColor;
 
 void main()
 {
-    vec4 surface_color = albedo * col;
+    vec4 surface_color = albedo * vec4(col.rgb, useVertexAlpha == 1 ? col.a : 1.0);
 #ifdef USE_ALBEDO_TEXTURE
-    vec4 c = texture(albedoTexture, (albedoTexTransform * vec3(uvs, 1.0)).xy);
+    vec4 c = sample_texture(albedoTexture, albedoTexTransform);
     #ifdef ALPHACUT
         if (c.a < acut) discard;
     #endif
@@ -52,30 +136,82 @@ void main()
     float metallic_factor = metallic;
     float roughness_factor = roughness;
 #ifdef USE_METALLIC_ROUGHNESS_TEXTURE
-    vec2 t = texture(metallicRoughnessTexture, (metallicRoughnessTexTransform * vec3(uvs, 1.0)).xy).gb;
+    vec2 t = sample_texture(metallicRoughnessTexture, metallicRoughnessTexTransform).gb;
     roughness_factor *= t.x;
     metallic_factor *= t.y;
 #endif
 
     float occlusion = 1.0;
 #ifdef USE_OCCLUSION_TEXTURE
-    occlusion = mix(1.0, texture(occlusionTexture, (occlusionTexTransform * vec3(uvs, 1.0)).xy).r, occlusionStrength);
+    occlusion = mix(1.0, sample_texture(occlusionTexture, occlusionTexTransform).r, occlusionStrength);
 #endif
 
     vec3 normal = normalize(gl_FrontFacing ? nor : -nor);
 #ifdef USE_NORMAL_TEXTURE
+#ifdef USE_TRIPLANAR
+    // Each projection samples a tangent space normal which is reoriented to world space using a whiteout blend with the surface normal
+    vec2 uv_x, uv_y, uv_z;
+    triplanar_uvs(uv_x, uv_y, uv_z);
+    vec3 w = triplanar_weights();
+    vec3 s = triplanar_signs();
+    vec3 scale = vec3(normalScale, normalScale, 1.0);
+    vec3 tnormal_x = (2.0 * texture(normalTexture, (normalTexTransform * vec3(uv_x, 1.0)).xy).xyz - 1.0) * scale;
+    vec3 tnormal_y = (2.0 * texture(normalTexture, (normalTexTransform * vec3(uv_y, 1.0)).xy).xyz - 1.0) * scale;
+    vec3 tnormal_z = (2.0 * texture(normalTexture, (normalTexTransform * vec3(uv_z, 1.0)).xy).xyz - 1.0) * scale;
+    // Flip the x-axis of the tangent space normals to match the flipped uv coordinates
+    tnormal_x.x *= s.x;
+    tnormal_y.x *= s.y;
+    tnormal_z.x *= -s.z;
+    vec3 abs_normal = abs(normal);
+    tnormal_x = vec3(tnormal_x.xy + normal.zy, abs(tnormal_x.z) * abs_normal.x);
+    tnormal_y = vec3(tnormal_y.xy + normal.xz, abs(tnormal_y.z) * abs_normal.y);
+    tnormal_z = vec3(tnormal_z.xy + normal.xy, abs(tnormal_z.z) * abs_normal.z);
+    // Apply the sign of the surface normal to the tangent space z-axis
+    vec3 ns = vec3(normal.x < 0.0 ? -1.0 : 1.0, normal.y < 0.0 ? -1.0 : 1.0, normal.z < 0.0 ? -1.0 : 1.0);
+    tnormal_x.z *= ns.x;
+    tnormal_y.z *= ns.y;
+    tnormal_z.z *= ns.z;
+    normal = normalize(w.x * tnormal_x.zyx + w.y * tnormal_y.xzy + w.z * tnormal_z.xyz);
+#else
     vec3 tangent = normalize(gl_FrontFacing ? tang : -tang);
     vec3 bitangent = normalize(gl_FrontFacing ? bitang : -bitang);
     mat3 tbn = mat3(tangent, bitangent, normal);
-    normal = tbn * ((2.0 * texture(normalTexture, (normalTexTransform * vec3(uvs, 1.0)).xy).xyz - 1.0) * vec3(normalScale, normalScale, 1.0));
+    normal = tbn * ((2.0 * sample_texture(normalTexture, normalTexTransform).xyz - 1.0) * vec3(normalScale, normalScale, 1.0));
+#endif
 #endif
 
     vec3 total_emissive = emissive.rgb;
 #ifdef USE_EMISSIVE_TEXTURE
-    total_emissive *= texture(emissiveTexture, (emissiveTexTransform * vec3(uvs, 1.0)).xy).rgb;
+    total_emissive *= sample_texture(emissiveTexture, emissiveTexTransform).rgb;
 #endif
 
-    outColor.rgb = total_emissive + calculate_lighting(cameraPosition, surface_color.rgb, pos, normal, metallic_factor, roughness_factor, occlusion);
+    clearcoat_factor = clearcoat;
+    clearcoat_roughness_factor = clearcoatRoughness;
+#ifdef USE_CLEARCOAT_TEXTURE
+    clearcoat_factor *= sample_texture(clearcoatTexture, clearcoatTexTransform).r;
+#endif
+#ifdef USE_CLEARCOAT_ROUGHNESS_TEXTURE
+    clearcoat_roughness_factor *= sample_texture(clearcoatRoughnessTexture, clearcoatRoughnessTexTransform).g;
+#endif
+
+#ifdef USE_TRANSMISSION
+    // The transmitted light replaces the diffuse part of the lighting, while the specular part is unchanged
+    float transmission_factor = transmission * (1.0 - metallic_factor);
+#ifdef USE_TRANSMISSION_TEXTURE
+    transmission_factor *= sample_texture(transmissionTexture, transmissionTexTransform).r;
+#endif
+    vec3 lit_color = surface_color.rgb * (1.0 - transmission_factor);
+#else
+    vec3 lit_color = surface_color.rgb;
+#endif
+    outColor.rgb = total_emissive + calculate_lighting(cameraPosition, lit_color, pos, normal, metallic_factor, roughness_factor, occlusion);
+#ifdef USE_TRANSMISSION
+    float NdV = max(0.001, dot(normal, normalize(cameraPosition - pos)));
+    float f0 = (indexOfRefraction - 1.0) / (indexOfRefraction + 1.0);
+    vec3 transmission_fresnel = 1.0 - fresnel_schlick(vec3(f0 * f0), NdV);
+    outColor.rgb += transmission_factor * transmission_fresnel * surface_color.rgb * sample_transmission(roughness_factor);
+#endif
+    outColor.rgb = apply_fog(outColor.rgb, pos);
     outColor.rgb = tone_mapping(outColor.rgb);
     outColor.rgb = color_mapping(outColor.rgb);
     outColor.a = surface_color.a;
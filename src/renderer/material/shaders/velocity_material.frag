@@ -0,0 +1,12 @@
+
+in vec4 clipPos;
+in vec4 previousClipPos;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec2 uv = 0.5 * clipPos.xy / clipPos.w + 0.5;
+    vec2 previousUv = 0.5 * previousClipPos.xy / previousClipPos.w + 0.5;
+    outColor = vec4(uv - previousUv, 0.0, 1.0);
+}
@@ -0,0 +1,63 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Used for rendering the screen space velocity of the object with this material in each pixel, for example as input to the [MotionBlurEffect] or other temporal effects.
+/// The velocity is the difference between the position in the current and the previous frame in uv coordinates, ie. the entire screen is from 0 to 1 in both directions,
+/// and it is written to the red and green channels, so the color target should use a floating point data type that supports negative values, for example `[f16; 4]`.
+///
+/// The velocity is calculated from the [VelocityMaterial::previous_view_projection] and the transformations of the geometry in the previous frame
+/// which are stored by calling for example [Mesh::update_previous_transformation] or [InstancedMesh::update_previous_transformation] at the end of each frame.
+///
+/// **Note:** Only [Mesh], [InstancedMesh] and [Particles] can be rendered with this material.
+///
+#[derive(Clone)]
+pub struct VelocityMaterial {
+    /// The view-projection matrix of the viewer in the previous frame, ie. `projection * view`.
+    pub previous_view_projection: Mat4,
+    /// Render states.
+    pub render_states: RenderStates,
+}
+
+impl VelocityMaterial {
+    ///
+    /// Creates a new velocity material where the viewer in the previous frame is given by the view and projection of the given viewer.
+    ///
+    pub fn new(previous_viewer: impl Viewer) -> Self {
+        Self {
+            previous_view_projection: previous_viewer.projection() * previous_viewer.view(),
+            render_states: RenderStates::default(),
+        }
+    }
+}
+
+impl FromCpuMaterial for VelocityMaterial {
+    fn from_cpu_material(_context: &Context, _cpu_material: &CpuMaterial) -> Self {
+        Self {
+            previous_view_projection: Mat4::identity(),
+            render_states: RenderStates::default(),
+        }
+    }
+}
+
+impl Material for VelocityMaterial {
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId::VelocityMaterial
+    }
+
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        include_str!("shaders/velocity_material.frag").to_string()
+    }
+
+    fn use_uniforms(&self, program: &Program, _viewer: &dyn Viewer, _lights: &[&dyn Light]) {
+        program.use_uniform("previousViewProjection", self.previous_view_projection);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        self.render_states
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Opaque
+    }
+}
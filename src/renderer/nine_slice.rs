@@ -0,0 +1,167 @@
+use crate::renderer::*;
+
+///
+/// A texture divided into nine parts by the border insets which can be drawn as a resizable panel, for example for GUI elements, see [RenderTarget::draw_nine_slice].
+/// The four corners are drawn with a fixed size, the top and bottom edges are stretched horizontally, the left and right edges are stretched vertically and the center is stretched in both directions.
+///
+#[derive(Clone)]
+pub struct NineSlice {
+    /// The texture which is divided into nine parts.
+    pub texture: Texture2DRef,
+    /// The width in texels of the left border of the texture. The border is drawn with the same width in pixels.
+    pub left: f32,
+    /// The width in texels of the right border of the texture. The border is drawn with the same width in pixels.
+    pub right: f32,
+    /// The height in texels of the top border of the texture. The border is drawn with the same height in pixels.
+    pub top: f32,
+    /// The height in texels of the bottom border of the texture. The border is drawn with the same height in pixels.
+    pub bottom: f32,
+    /// A color multiplied with the texture colors.
+    pub tint: Srgba,
+}
+
+impl NineSlice {
+    ///
+    /// Creates a new nine slice with the given border insets in texels and a white tint.
+    ///
+    pub fn new(
+        texture: impl Into<Texture2DRef>,
+        left: f32,
+        right: f32,
+        top: f32,
+        bottom: f32,
+    ) -> Self {
+        Self {
+            texture: texture.into(),
+            left,
+            right,
+            top,
+            bottom,
+            tint: Srgba::WHITE,
+        }
+    }
+
+    ///
+    /// Returns the nine parts as the rectangle to draw (x, y, width and height in pixels) and the texture with a transformation selecting the corresponding part of the texture.
+    ///
+    pub(in crate::renderer) fn parts(
+        &self,
+        position: PhysicalPoint,
+        width: f32,
+        height: f32,
+    ) -> Vec<([f32; 4], Texture2DRef)> {
+        let insets = [self.left, self.right, self.top, self.bottom];
+        let texture_size = vec2(self.texture.width() as f32, self.texture.height() as f32);
+        slice(texture_size, insets, position, width, height)
+            .into_iter()
+            .map(|(rectangle, transformation)| {
+                (
+                    rectangle,
+                    self.texture.clone().with_transform(transformation),
+                )
+            })
+            .collect()
+    }
+}
+
+///
+/// Returns the nine parts of a texture with the given size and border insets (left, right, top and bottom) in texels, drawn as a rectangle with the given position, width and height in pixels.
+/// Each part is the rectangle to draw (x, y, width and height in pixels) and the uv transformation selecting the corresponding part of the texture.
+/// Parts without any area are left out.
+///
+fn slice(
+    texture_size: Vec2,
+    insets: [f32; 4],
+    position: PhysicalPoint,
+    width: f32,
+    height: f32,
+) -> Vec<([f32; 4], Mat3)> {
+    let [left, right, top, bottom] = insets.map(|inset| inset.max(0.0));
+    let (texture_width, texture_height) = (texture_size.x, texture_size.y);
+
+    // The borders are scaled down if they do not fit inside the rectangle or the texture, so they never overlap
+    let horizontal = (left + right).max(f32::EPSILON);
+    let vertical = (top + bottom).max(f32::EPSILON);
+    let horizontal_scale = (width.min(texture_width) / horizontal).min(1.0);
+    let vertical_scale = (height.min(texture_height) / vertical).min(1.0);
+    let left = left * horizontal_scale;
+    let right = right * horizontal_scale;
+    let bottom = bottom * vertical_scale;
+    let top = top * vertical_scale;
+
+    // The edges are rounded to whole pixels so neighbouring parts neither overlap nor leave gaps
+    let xs = [0.0, left, width - right, width].map(|x| (position.x + x).round());
+    let ys = [0.0, bottom, height - top, height].map(|y| (position.y + y).round());
+    let us = [0.0, left / texture_width, 1.0 - right / texture_width, 1.0];
+    let vs = [
+        0.0,
+        bottom / texture_height,
+        1.0 - top / texture_height,
+        1.0,
+    ];
+
+    let mut parts = Vec::with_capacity(9);
+    for j in 0..3 {
+        for i in 0..3 {
+            let w = xs[i + 1] - xs[i];
+            let h = ys[j + 1] - ys[j];
+            if w <= 0.0 || h <= 0.0 {
+                continue;
+            }
+            let transformation = Mat3::from_translation(vec2(us[i], vs[j]))
+                * Mat3::from_nonuniform_scale(us[i + 1] - us[i], vs[j + 1] - vs[j]);
+            parts.push(([xs[i], ys[j], w, h], transformation));
+        }
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(parts: &[([f32; 4], Mat3)]) -> f32 {
+        parts.iter().map(|(r, _)| r[2] * r[3]).sum()
+    }
+
+    fn uv(transformation: Mat3, u: f32, v: f32) -> Vec2 {
+        (transformation * vec3(u, v, 1.0)).truncate()
+    }
+
+    #[test]
+    fn slice() {
+        let position = PhysicalPoint { x: 5.0, y: 5.0 };
+        let parts = super::slice(vec2(30.0, 30.0), [10.0; 4], position, 100.0, 50.0);
+        assert_eq!(parts.len(), 9);
+        assert_eq!(area(&parts), 100.0 * 50.0);
+        assert_eq!(parts[0].0, [5.0, 5.0, 10.0, 10.0]);
+        assert_eq!(parts[4].0, [15.0, 15.0, 80.0, 30.0]);
+        assert_eq!(parts[8].0, [95.0, 45.0, 10.0, 10.0]);
+
+        // The corners select the corners of the texture and the center selects the center
+        assert_eq!(uv(parts[0].1, 0.0, 0.0), vec2(0.0, 0.0));
+        assert!(uv(parts[0].1, 1.0, 1.0).distance(vec2(1.0 / 3.0, 1.0 / 3.0)) < 1e-6);
+        assert!(uv(parts[4].1, 0.0, 0.0).distance(vec2(1.0 / 3.0, 1.0 / 3.0)) < 1e-6);
+        assert!(uv(parts[4].1, 1.0, 1.0).distance(vec2(2.0 / 3.0, 2.0 / 3.0)) < 1e-6);
+        assert!(uv(parts[8].1, 1.0, 1.0).distance(vec2(1.0, 1.0)) < 1e-6);
+    }
+
+    #[test]
+    fn slice_small_rectangle() {
+        // The borders are scaled down to fit the rectangle, so the center column disappears
+        let position = PhysicalPoint { x: 0.0, y: 0.0 };
+        let parts = super::slice(vec2(30.0, 30.0), [10.0; 4], position, 15.0, 40.0);
+        assert_eq!(parts.len(), 6);
+        assert_eq!(area(&parts), 15.0 * 40.0);
+        assert!(parts.iter().all(|(r, _)| r[2] > 0.0 && r[3] > 0.0));
+    }
+
+    #[test]
+    fn slice_without_borders() {
+        let position = PhysicalPoint { x: 2.0, y: 3.0 };
+        let parts = super::slice(vec2(30.0, 30.0), [0.0; 4], position, 20.0, 10.0);
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].0, [2.0, 3.0, 20.0, 10.0]);
+        assert_eq!(parts[0].1, Mat3::identity());
+    }
+}
@@ -15,6 +15,10 @@ macro_rules! impl_object_body {
         fn material_type(&self) -> MaterialType {
             self.$inner().material_type()
         }
+
+        fn render_order(&self) -> i32 {
+            self.$inner().render_order()
+        }
     };
 }
 
@@ -22,6 +26,10 @@ mod gm;
 #[doc(inline)]
 pub use gm::*;
 
+mod ordered;
+#[doc(inline)]
+pub use ordered::*;
+
 mod model;
 #[doc(inline)]
 pub use model::*;
@@ -54,6 +62,22 @@ mod axes;
 #[doc(inline)]
 pub use axes::*;
 
+mod grid;
+#[doc(inline)]
+pub use grid::*;
+
+mod decal;
+#[doc(inline)]
+pub use decal::*;
+
+mod transform_gizmo;
+#[doc(inline)]
+pub use transform_gizmo::*;
+
+mod lod_group;
+#[doc(inline)]
+pub use lod_group::*;
+
 use crate::core::*;
 use crate::renderer::*;
 
@@ -72,6 +96,15 @@ pub trait Object: Geometry {
     /// Returns the type of material applied to this object.
     ///
     fn material_type(&self) -> MaterialType;
+
+    ///
+    /// Returns the render order of this object which is used as the primary key when sorting objects before rendering, see [cmp_render_order].
+    /// An object with a lower render order is rendered before an object with a higher render order regardless of the material type and the distance to the viewer.
+    /// Objects with the same render order are sorted as usual. The default render order is `0`.
+    ///
+    fn render_order(&self) -> i32 {
+        0
+    }
 }
 
 use std::ops::Deref;
@@ -107,4 +140,8 @@ impl<T: Object> Object for std::sync::RwLock<T> {
     fn material_type(&self) -> MaterialType {
         self.read().unwrap().material_type()
     }
+
+    fn render_order(&self) -> i32 {
+        self.read().unwrap().render_order()
+    }
 }
@@ -0,0 +1,153 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A decal which projects a texture, for example a bullet hole or a logo, onto the already rendered geometry inside a box.
+/// The box is the cube from `-1` to `1` in all three directions transformed by the [Decal::transformation]
+/// and the texture is projected along the negative z-axis of the box, so the texture covers the xy-plane of the box.
+///
+/// The decal needs the depth of the rendered scene in a depth texture which is used to reconstruct the position of the geometry inside the box,
+/// so render the scene to a color and depth texture first and then render the decal on top of the color texture using [Decal::render].
+///
+pub struct Decal {
+    mesh: Mesh,
+    /// The texture projected onto the geometry.
+    pub texture: Texture2DRef,
+    /// A color multiplied with the texture color.
+    pub color: Srgba,
+    /// The decal is only applied to surfaces where the cosine of the angle between the surface normal and the negative projection direction is larger than this value.
+    /// This avoids projecting the decal onto surfaces facing away from the decal and limits the stretching on surfaces almost parallel to the projection direction.
+    pub normal_threshold: f32,
+}
+
+impl Decal {
+    ///
+    /// Creates a new decal which projects the given texture inside the box defined by the given transformation.
+    ///
+    pub fn new(context: &Context, texture: Texture2DRef, transformation: Mat4) -> Self {
+        let mut mesh = Mesh::new(context, &CpuMesh::cube());
+        mesh.set_transformation(transformation);
+        Self {
+            mesh,
+            texture,
+            color: Srgba::WHITE,
+            normal_threshold: 0.1,
+        }
+    }
+
+    ///
+    /// Returns the transformation of the box in which the decal is projected.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.mesh.transformation()
+    }
+
+    ///
+    /// Sets the transformation of the box in which the decal is projected.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.mesh.set_transformation(transformation);
+    }
+
+    ///
+    /// Renders the decal onto the geometry described by the given depth texture.
+    /// Must be called in the callback given as input to a [RenderTarget], [ColorTarget] or [DepthTarget] write method
+    /// and the depth texture must not be attached to that render target.
+    ///
+    pub fn render(&self, viewer: &dyn Viewer, depth_texture: DepthTexture) {
+        self.mesh.render_with_effect(
+            &DecalEffect { decal: self },
+            viewer,
+            &[],
+            None,
+            Some(depth_texture),
+        );
+    }
+}
+
+struct DecalEffect<'a> {
+    decal: &'a Decal,
+}
+
+impl Effect for DecalEffect<'_> {
+    fn fragment_shader_source(
+        &self,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> String {
+        format!(
+            "{}\n{}\n{}\n{}",
+            include_str!("../../core/shared.frag"),
+            depth_texture
+                .expect("Must supply a depth texture to render a decal")
+                .fragment_shader_source(),
+            ColorMapping::fragment_shader_source(),
+            include_str!("shaders/decal.frag")
+        )
+    }
+
+    fn id(
+        &self,
+        _color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) -> EffectMaterialId {
+        EffectMaterialId::DecalEffect(
+            depth_texture.expect("Must supply a depth texture to render a decal"),
+        )
+    }
+
+    fn use_uniforms(
+        &self,
+        program: &Program,
+        viewer: &dyn Viewer,
+        _lights: &[&dyn Light],
+        _color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        viewer.color_mapping().use_uniforms(program);
+        depth_texture
+            .expect("Must supply a depth texture to render a decal")
+            .use_uniforms(program);
+        let transformation = self.decal.transformation();
+        program.use_uniform(
+            "viewProjectionInverse",
+            (viewer.projection() * viewer.view()).invert().unwrap(),
+        );
+        program.use_uniform(
+            "decalInverseTransformation",
+            transformation.invert().unwrap_or(Mat4::identity()),
+        );
+        program.use_uniform(
+            "decalDirection",
+            (transformation * vec4(0.0, 0.0, -1.0, 0.0))
+                .truncate()
+                .normalize(),
+        );
+        program.use_uniform("cameraPosition", viewer.position());
+        let viewport = viewer.viewport();
+        program.use_uniform(
+            "viewport",
+            vec4(
+                viewport.x as f32,
+                viewport.y as f32,
+                viewport.width as f32,
+                viewport.height as f32,
+            ),
+        );
+        program.use_uniform("normalThreshold", self.decal.normal_threshold);
+        program.use_uniform("decalColor", self.decal.color.to_linear_srgb());
+        program.use_uniform("decalTexTransform", self.decal.texture.transformation);
+        program.use_texture("decalTexture", &self.decal.texture);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTest::Always,
+            blend: Blend::TRANSPARENCY,
+            cull: Cull::Front,
+            ..Default::default()
+        }
+    }
+}
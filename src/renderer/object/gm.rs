@@ -19,6 +19,14 @@ impl<G: Geometry, M: Material> Gm<G, M> {
     pub fn new(geometry: G, material: M) -> Self {
         Self { geometry, material }
     }
+
+    ///
+    /// Returns this object with the given render order, see [Object::render_order].
+    /// Use [Ordered::set_render_order] on the returned object to change the render order afterwards.
+    ///
+    pub fn with_render_order(self, render_order: i32) -> Ordered<Self> {
+        Ordered::new(self, render_order)
+    }
 }
 
 impl<'a, G: Geometry, M: Material> IntoIterator for &'a Gm<G, M> {
@@ -0,0 +1,163 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// An infinite grid in the xz-plane which fades out with the distance to the viewer, for example used as a ground plane in editors and other 3D tools.
+/// The grid lines are calculated in world space for each pixel on the screen,
+/// so the grid is always rendered crisp and it is depth tested against the rest of the scene.
+///
+pub struct Grid {
+    context: Context,
+    /// The distance between the grid lines.
+    pub spacing: f32,
+    /// The color of the grid lines.
+    pub line_color: Srgba,
+    /// The color of the line along the x-axis, ie. where z is zero.
+    pub x_axis_color: Srgba,
+    /// The color of the line along the z-axis, ie. where x is zero.
+    pub z_axis_color: Srgba,
+    /// The distance from the viewer where the grid is completely faded out.
+    pub fade_distance: f32,
+}
+
+impl Grid {
+    ///
+    /// Constructs a new grid with the given spacing between the grid lines.
+    ///
+    pub fn new(context: &Context, spacing: f32) -> Self {
+        Self {
+            context: context.clone(),
+            spacing,
+            line_color: Srgba::new(128, 128, 128, 255),
+            x_axis_color: Srgba::new(220, 50, 50, 255),
+            z_axis_color: Srgba::new(50, 50, 220, 255),
+            fade_distance: 100.0,
+        }
+    }
+
+    fn material(&self) -> GridMaterial {
+        GridMaterial {
+            spacing: self.spacing,
+            line_color: self.line_color,
+            x_axis_color: self.x_axis_color,
+            z_axis_color: self.z_axis_color,
+            fade_distance: self.fade_distance,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Grid {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for Grid {
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        full_screen_draw(&self.context, program, render_states, viewer.viewport());
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        full_screen_vertex_shader_source().to_owned()
+    }
+
+    fn id(&self) -> GeometryId {
+        GeometryId::Screen
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::INFINITE
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        render_with_material(&self.context, viewer, self, material, lights)
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        render_with_effect(
+            &self.context,
+            viewer,
+            self,
+            material,
+            lights,
+            color_texture,
+            depth_texture,
+        )
+    }
+}
+
+impl Object for Grid {
+    fn render(&self, viewer: &dyn Viewer, lights: &[&dyn Light]) {
+        render_with_material(&self.context, viewer, self, self.material(), lights)
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
+
+struct GridMaterial {
+    spacing: f32,
+    line_color: Srgba,
+    x_axis_color: Srgba,
+    z_axis_color: Srgba,
+    fade_distance: f32,
+}
+
+impl Material for GridMaterial {
+    fn fragment_shader_source(&self, _lights: &[&dyn Light]) -> String {
+        format!(
+            "{}{}{}{}",
+            include_str!("../../core/shared.frag"),
+            ToneMapping::fragment_shader_source(),
+            ColorMapping::fragment_shader_source(),
+            include_str!("shaders/grid.frag")
+        )
+    }
+
+    fn id(&self) -> EffectMaterialId {
+        EffectMaterialId::GridMaterial
+    }
+
+    fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, _lights: &[&dyn Light]) {
+        viewer
+            .tone_mapping()
+            .use_uniforms_with_exposure(program, viewer.exposure());
+        viewer.color_mapping().use_uniforms(program);
+        let view_projection = viewer.projection() * viewer.view();
+        program.use_uniform("viewProjection", view_projection);
+        program.use_uniform("viewProjectionInverse", view_projection.invert().unwrap());
+        program.use_uniform("cameraPosition", viewer.position());
+        program.use_uniform("spacing", self.spacing);
+        program.use_uniform("lineColor", self.line_color.to_linear_srgb());
+        program.use_uniform("xAxisColor", self.x_axis_color.to_linear_srgb());
+        program.use_uniform("zAxisColor", self.z_axis_color.to_linear_srgb());
+        program.use_uniform("fadeDistance", self.fade_distance);
+    }
+
+    fn render_states(&self) -> RenderStates {
+        RenderStates {
+            blend: Blend::TRANSPARENCY,
+            ..Default::default()
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        MaterialType::Transparent
+    }
+}
@@ -14,12 +14,16 @@ pub struct Imposters {
     context: Context,
     sprites: Sprites,
     material: ImpostersMaterial,
+    positions: Vec<Vec3>,
+    center: Vec3,
 }
 
 impl Imposters {
     ///
     /// Constructs a new [Imposters] and render the imposter texture from the given objects with the given lights.
     /// The imposters are placed at the given positions.
+    /// The objects are only rendered from the side, so the imposters only look right when viewed from approximately the same height as the objects,
+    /// use [Imposters::new_with_elevation_bands] if the imposters should also be viewed from above.
     ///
     pub fn new(
         context: &Context,
@@ -28,30 +32,70 @@ impl Imposters {
         lights: &[&dyn Light],
         max_texture_size: u32,
     ) -> Self {
+        Self::new_with_elevation_bands(context, positions, objects, lights, max_texture_size, 1)
+    }
+
+    ///
+    /// Constructs a new [Imposters] like [Imposters::new], except that the objects are rendered from a grid of views,
+    /// both from a number of angles around the objects and from the given number of elevation angles between the side of the objects and almost directly above.
+    /// When rendering, the view with the elevation closest to the elevation of the viewer is used, so the imposters also look plausible when viewed from above.
+    /// A higher number of elevation bands gives a better result at the cost of more texture memory.
+    /// With more than one elevation band, the imposters always face the viewer instead of only rotating around the y-axis.
+    ///
+    pub fn new_with_elevation_bands(
+        context: &Context,
+        positions: &[Vec3],
+        objects: impl IntoIterator<Item = impl Object> + Clone,
+        lights: &[&dyn Light],
+        max_texture_size: u32,
+        elevation_bands: u32,
+    ) -> Self {
+        let elevation_bands = elevation_bands.max(1);
         let mut aabb = AxisAlignedBoundingBox::EMPTY;
         objects
             .clone()
             .into_iter()
             .for_each(|o| aabb.expand_with_aabb(o.aabb()));
-        let mut sprites = Sprites::new(context, positions, Some(vec3(0.0, 1.0, 0.0)));
-        sprites.set_transformation(get_sprite_transform(aabb));
-        Imposters {
+        let direction = if elevation_bands == 1 {
+            Some(vec3(0.0, 1.0, 0.0))
+        } else {
+            None
+        };
+        let mut imposters = Imposters {
             context: context.clone(),
-            sprites,
-            material: ImpostersMaterial::new(context, aabb, objects, lights, max_texture_size),
-        }
+            sprites: Sprites::new(context, positions, direction),
+            material: ImpostersMaterial::new(
+                context,
+                aabb,
+                objects,
+                lights,
+                max_texture_size,
+                elevation_bands,
+            ),
+            positions: positions.to_vec(),
+            center: Vec3::zero(),
+        };
+        imposters.update_sprites(aabb);
+        imposters
     }
 
     ///
     /// Set the positions of the imposters.
     ///
     pub fn set_positions(&mut self, positions: &[Vec3]) {
-        self.sprites.set_centers(positions);
+        self.positions = positions.to_vec();
+        self.sprites.set_centers(
+            &positions
+                .iter()
+                .map(|p| p + self.center)
+                .collect::<Vec<_>>(),
+        );
     }
 
     ///
     /// Render the imposter texture from the given objects with the given lights.
     /// Use this if you want to update the look of the imposters.
+    /// The existing texture is only reallocated if the size of the texture changes.
     ///
     pub fn update_texture(
         &mut self,
@@ -64,21 +108,54 @@ impl Imposters {
             .clone()
             .into_iter()
             .for_each(|o| aabb.expand_with_aabb(o.aabb()));
-        self.sprites.set_transformation(get_sprite_transform(aabb));
         self.material
             .update(aabb, objects, lights, max_texture_size);
+        self.update_sprites(aabb);
+    }
+
+    ///
+    /// Render the imposter texture again from the given objects with the given lights, for example when the lighting or the materials of the objects have changed.
+    /// The maximum texture size is the same as the last time the texture was rendered and the existing texture is reused if the size of the objects has not changed.
+    ///
+    pub fn update(
+        &mut self,
+        objects: impl IntoIterator<Item = impl Object> + Clone,
+        lights: &[&dyn Light],
+    ) {
+        let max_texture_size = self.material.max_texture_size;
+        self.update_texture(objects, lights, max_texture_size);
+    }
+
+    fn update_sprites(&mut self, aabb: AxisAlignedBoundingBox) {
+        if aabb.is_empty() {
+            self.center = Vec3::zero();
+            self.sprites.set_transformation(Mat4::identity());
+        } else {
+            // The sprites are centered at the center of the objects which is also the center of the rendered views
+            let (width, height) = view_size(aabb, self.material.elevation_bands);
+            self.center = aabb.center();
+            self.sprites.set_transformation(Mat4::from_nonuniform_scale(
+                0.5 * width,
+                0.5 * height,
+                0.0,
+            ));
+        }
+        let positions = std::mem::take(&mut self.positions);
+        self.set_positions(&positions);
     }
 }
 
-fn get_sprite_transform(aabb: AxisAlignedBoundingBox) -> Mat4 {
-    if aabb.is_empty() {
-        Mat4::identity()
+///
+/// Returns the width and height of the views of the objects inside the given bounding box.
+/// When only viewed from the side, the views tightly fit the bounding box, otherwise the views fit the bounding sphere.
+///
+fn view_size(aabb: AxisAlignedBoundingBox, elevation_bands: u32) -> (f32, f32) {
+    let size = aabb.size();
+    if elevation_bands == 1 {
+        (f32::sqrt(size.x * size.x + size.z * size.z), size.y)
     } else {
-        let (min, max) = (aabb.min(), aabb.max());
-        let width = f32::sqrt(f32::powi(max.x - min.x, 2) + f32::powi(max.z - min.z, 2));
-        let height = max.y - min.y;
-        let center = 0.5 * min + 0.5 * max;
-        Mat4::from_translation(center) * Mat4::from_nonuniform_scale(0.5 * width, 0.5 * height, 0.0)
+        let diameter = size.magnitude();
+        (diameter, diameter)
     }
 }
 
@@ -122,6 +199,9 @@ impl Object for Imposters {
 struct ImpostersMaterial {
     context: Context,
     texture: Texture2DArray,
+    depth_texture: Option<DepthTexture2D>,
+    elevation_bands: u32,
+    max_texture_size: u32,
 }
 
 impl ImpostersMaterial {
@@ -131,6 +211,7 @@ impl ImpostersMaterial {
         objects: impl IntoIterator<Item = impl Object> + Clone,
         lights: &[&dyn Light],
         max_texture_size: u32,
+        elevation_bands: u32,
     ) -> Self {
         let mut m = Self {
             context: context.clone(),
@@ -138,13 +219,16 @@ impl ImpostersMaterial {
                 context,
                 1,
                 1,
-                NO_VIEW_ANGLES,
+                NO_VIEW_ANGLES * elevation_bands,
                 Interpolation::Nearest,
                 Interpolation::Nearest,
                 None,
                 Wrapping::ClampToEdge,
                 Wrapping::ClampToEdge,
             ),
+            depth_texture: None,
+            elevation_bands,
+            max_texture_size,
         };
         m.update(aabb, objects, lights, max_texture_size);
         m
@@ -156,14 +240,13 @@ impl ImpostersMaterial {
         lights: &[&dyn Light],
         max_texture_size: u32,
     ) {
+        self.max_texture_size = max_texture_size;
         if !aabb.is_empty() {
-            let (min, max) = (aabb.min(), aabb.max());
-            let width = f32::sqrt(f32::powi(max.x - min.x, 2) + f32::powi(max.z - min.z, 2));
-            let height = max.y - min.y;
+            let (width, height) = view_size(aabb, self.elevation_bands);
             let texture_width = (max_texture_size as f32 * (width / height).min(1.0)) as u32;
             let texture_height = (max_texture_size as f32 * (height / width).min(1.0)) as u32;
             let viewport = Viewport::new_at_origo(texture_width, texture_height);
-            let center = 0.5 * min + 0.5 * max;
+            let center = aabb.center();
             let mut camera = Camera::new_orthographic(
                 viewport,
                 center + vec3(0.0, 0.0, -1.0),
@@ -174,38 +257,57 @@ impl ImpostersMaterial {
                 2.0 * (width + height),
             );
             camera.disable_tone_and_color_mapping();
-            self.texture = Texture2DArray::new_empty::<[f16; 4]>(
-                &self.context,
-                texture_width,
-                texture_height,
-                NO_VIEW_ANGLES,
-                Interpolation::Linear,
-                Interpolation::Linear,
-                None,
-                Wrapping::ClampToEdge,
-                Wrapping::ClampToEdge,
-            );
-            let mut depth_texture = DepthTexture2D::new::<f32>(
-                &self.context,
-                texture_width,
-                texture_height,
-                Wrapping::ClampToEdge,
-                Wrapping::ClampToEdge,
-            );
-            for i in 0..NO_VIEW_ANGLES {
-                let layers = [i];
-                let angle = i as f32 * 2.0 * PI / NO_VIEW_ANGLES as f32;
-                camera.set_view(
-                    center + vec3(f32::cos(angle), 0.0, f32::sin(angle)),
-                    center,
-                    vec3(0.0, 1.0, 0.0),
+            // Only allocate new textures if the size has changed
+            if self.texture.width() != texture_width || self.texture.height() != texture_height {
+                self.texture = Texture2DArray::new_empty::<[f16; 4]>(
+                    &self.context,
+                    texture_width,
+                    texture_height,
+                    NO_VIEW_ANGLES * self.elevation_bands,
+                    Interpolation::Linear,
+                    Interpolation::Linear,
+                    None,
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
                 );
-                RenderTarget::new(
-                    self.texture.as_color_target(&layers, None),
-                    depth_texture.as_depth_target(),
-                )
-                .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0))
-                .render(&camera, objects.clone(), lights);
+            }
+            if self
+                .depth_texture
+                .as_ref()
+                .map(|t| t.width() != texture_width || t.height() != texture_height)
+                .unwrap_or(true)
+            {
+                self.depth_texture = Some(DepthTexture2D::new::<f32>(
+                    &self.context,
+                    texture_width,
+                    texture_height,
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
+                ));
+            }
+            let depth_texture = self.depth_texture.as_mut().unwrap();
+            for band in 0..self.elevation_bands {
+                let elevation = band as f32 * 0.5 * PI / self.elevation_bands as f32;
+                for i in 0..NO_VIEW_ANGLES {
+                    let layers = [band * NO_VIEW_ANGLES + i];
+                    let angle = i as f32 * 2.0 * PI / NO_VIEW_ANGLES as f32;
+                    camera.set_view(
+                        center
+                            + vec3(
+                                f32::cos(angle) * f32::cos(elevation),
+                                f32::sin(elevation),
+                                f32::sin(angle) * f32::cos(elevation),
+                            ),
+                        center,
+                        vec3(0.0, 1.0, 0.0),
+                    );
+                    RenderTarget::new(
+                        self.texture.as_color_target(&layers, None),
+                        depth_texture.as_depth_target(),
+                    )
+                    .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0))
+                    .render(&camera, objects.clone(), lights);
+                }
             }
         }
     }
@@ -227,9 +329,12 @@ impl Material for ImpostersMaterial {
     }
 
     fn use_uniforms(&self, program: &Program, viewer: &dyn Viewer, _lights: &[&dyn Light]) {
-        viewer.tone_mapping().use_uniforms(program);
+        viewer
+            .tone_mapping()
+            .use_uniforms_with_exposure(program, viewer.exposure());
         viewer.color_mapping().use_uniforms(program);
         program.use_uniform("no_views", NO_VIEW_ANGLES as i32);
+        program.use_uniform("no_elevation_bands", self.elevation_bands as i32);
         program.use_uniform("view", viewer.view());
         program.use_texture_array("tex", &self.texture);
     }
@@ -245,3 +350,41 @@ impl Material for ImpostersMaterial {
         MaterialType::Transparent
     }
 }
+
+#[cfg(all(test, feature = "headless", not(target_arch = "wasm32")))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    #[allow(unsafe_code)]
+    fn update_reuses_texture() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let model = Gm::new(
+            Mesh::new(&context, &CpuMesh::cube()),
+            ColorMaterial::default(),
+        );
+        let mut imposters = Imposters::new(&context, &[Vec3::zero()], &model, &[], 64);
+        let texture_id = unsafe { imposters.material.texture.id() };
+        let size = (
+            imposters.material.texture.width(),
+            imposters.material.texture.height(),
+        );
+
+        // Updating with the same objects keeps the size and therefore the texture
+        imposters.update(&model, &[]);
+        imposters.update(&model, &[]);
+        assert!(unsafe { imposters.material.texture.id() } == texture_id);
+
+        // A different maximum texture size changes the size and therefore reallocates the texture
+        imposters.update_texture(&model, &[], 32);
+        assert!(unsafe { imposters.material.texture.id() } != texture_id);
+        assert_eq!(
+            (
+                imposters.material.texture.width(),
+                imposters.material.texture.height()
+            ),
+            (size.0 / 2, size.1 / 2)
+        );
+    }
+}
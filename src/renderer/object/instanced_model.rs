@@ -163,6 +163,39 @@ impl<M: Material + FromCpuMaterial + Clone + Default> InstancedModel<M> {
     }
 }
 
+impl<M: Material> InstancedModel<M> {
+    ///
+    /// Returns the number of instances that is rendered.
+    ///
+    pub fn instance_count(&self) -> u32 {
+        self.0
+            .first()
+            .map(|part| part.instance_count())
+            .unwrap_or(0)
+    }
+
+    ///
+    /// Update the instances of all parts of the model, for example the per instance transformations and colors.
+    /// The instances are applied after the transformation of each part, so each instance is a copy of the entire model.
+    ///
+    pub fn set_instances(&mut self, instances: &Instances) {
+        for part in self.0.iter_mut() {
+            part.set_instances(instances);
+        }
+    }
+
+    ///
+    /// Returns the [AxisAlignedBoundingBox] containing all instances of all parts of the model in the global coordinate system.
+    ///
+    pub fn aabb(&self) -> AxisAlignedBoundingBox {
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for part in self.0.iter() {
+            aabb.expand_with_aabb(part.aabb());
+        }
+        aabb
+    }
+}
+
 impl<M: Material> std::ops::Deref for InstancedModel<M> {
     type Target = Vec<InstancedModelPart<M>>;
     fn deref(&self) -> &Self::Target {
@@ -0,0 +1,146 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// A group of objects representing the same object at different levels of detail, where only one of the levels is rendered depending on the distance from the viewer to the center of the group.
+/// This is useful for reducing the cost of rendering many detailed objects, for example trees in a forest, where a simplified version is indistinguishable from the detailed one when far away.
+///
+/// The center of the group is the center of the combined [AxisAlignedBoundingBox] of all of the levels.
+/// Each level is rendered when the distance to the center is below its maximum distance and above the maximum distance of the previous level.
+/// Nothing is rendered when the distance is larger than the maximum distance of all levels.
+///
+pub struct LodGroup {
+    levels: Vec<(f32, Box<dyn Object>)>,
+}
+
+impl LodGroup {
+    ///
+    /// Creates a new group from a list of levels, each defined by the maximum distance at which it is rendered and the object to render.
+    /// The levels can be given in any order.
+    ///
+    /// # Panic
+    /// Will panic if no levels are given.
+    ///
+    pub fn new(mut levels: Vec<(f32, Box<dyn Object>)>) -> Self {
+        if levels.is_empty() {
+            panic!("a level of detail group must have at least one level");
+        }
+        levels.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        Self { levels }
+    }
+
+    ///
+    /// Returns the levels of this group sorted by increasing maximum distance.
+    ///
+    pub fn levels(&self) -> &[(f32, Box<dyn Object>)] {
+        &self.levels
+    }
+
+    ///
+    /// Returns the index into [Self::levels] of the level which is rendered when viewed by the given viewer, or `None` if nothing is rendered.
+    ///
+    pub fn level_index(&self, viewer: &dyn Viewer) -> Option<usize> {
+        let distance = self.aabb().center().distance(viewer.position());
+        self.levels
+            .iter()
+            .position(|(max_distance, _)| distance <= *max_distance)
+    }
+
+    fn level(&self, viewer: &dyn Viewer) -> Option<&dyn Object> {
+        self.level_index(viewer)
+            .map(|index| self.levels[index].1.as_ref())
+    }
+
+    // The highest level of detail is used when the viewer is not known
+    fn first(&self) -> &dyn Object {
+        self.levels[0].1.as_ref()
+    }
+}
+
+impl<'a> IntoIterator for &'a LodGroup {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for LodGroup {
+    fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
+        self.first().draw(viewer, program, render_states)
+    }
+
+    fn vertex_shader_source(&self) -> String {
+        self.first().vertex_shader_source()
+    }
+
+    fn id(&self) -> GeometryId {
+        self.first().id()
+    }
+
+    fn render_with_material(
+        &self,
+        material: &dyn Material,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+    ) {
+        if let Some(level) = self.level(viewer) {
+            level.render_with_material(material, viewer, lights)
+        }
+    }
+
+    fn render_with_effect(
+        &self,
+        material: &dyn Effect,
+        viewer: &dyn Viewer,
+        lights: &[&dyn Light],
+        color_texture: Option<ColorTexture>,
+        depth_texture: Option<DepthTexture>,
+    ) {
+        if let Some(level) = self.level(viewer) {
+            level.render_with_effect(material, viewer, lights, color_texture, depth_texture)
+        }
+    }
+
+    fn aabb(&self) -> AxisAlignedBoundingBox {
+        let mut aabb = AxisAlignedBoundingBox::EMPTY;
+        for (_, level) in self.levels.iter() {
+            aabb.expand_with_aabb(level.aabb());
+        }
+        aabb
+    }
+
+    fn animate(&mut self, time: f32) {
+        for (_, level) in self.levels.iter_mut() {
+            level.animate(time);
+        }
+    }
+}
+
+impl Object for LodGroup {
+    fn render(&self, viewer: &dyn Viewer, lights: &[&dyn Light]) {
+        if let Some(level) = self.level(viewer) {
+            level.render(viewer, lights)
+        }
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.first().material_type()
+    }
+
+    fn render_order(&self) -> i32 {
+        self.first().render_order()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    #[should_panic(expected = "at least one level")]
+    fn new_without_levels() {
+        LodGroup::new(Vec::new());
+    }
+}
@@ -100,13 +100,26 @@ impl<M: Material + FromCpuMaterial + Clone + Default> Model<M> {
     /// a [material] type specified by the generic parameter which implement [FromCpuMaterial] (constructed from the [CpuMaterial]s in the [CpuModel]).
     ///
     pub fn new(context: &Context, cpu_model: &CpuModel) -> Result<Self, RendererError> {
+        Self::new_with_skins(context, cpu_model, &[])
+    }
+
+    ///
+    /// Same as [Model::new] except that the meshes are skinned using the given skins, see [Mesh::set_skin].
+    /// The skins correspond to the geometries in the [CpuModel] in the same order and geometries without a skin are not skinned.
+    /// Use [Model::set_joint_transformations] to set the pose of the joints.
+    ///
+    pub fn new_with_skins(
+        context: &Context,
+        cpu_model: &CpuModel,
+        skins: &[Option<Skin>],
+    ) -> Result<Self, RendererError> {
         let materials = cpu_model
             .materials
             .iter()
             .map(|m| M::from_cpu_material(context, m))
             .collect::<Vec<_>>();
         let mut gms = Vec::new();
-        for primitive in cpu_model.geometries.iter() {
+        for (index, primitive) in cpu_model.geometries.iter().enumerate() {
             if let CpuGeometry::Triangles(geometry) = &primitive.geometry {
                 let material = if let Some(material_index) = primitive.material_index {
                     materials
@@ -126,6 +139,9 @@ impl<M: Material + FromCpuMaterial + Clone + Default> Model<M> {
                     material,
                 };
                 gm.set_transformation(primitive.transformation);
+                if let Some(skin) = skins.get(index).and_then(|skin| skin.as_ref()) {
+                    gm.set_skin(skin)?;
+                }
                 gms.push(ModelPart {
                     gm,
                     animations: primitive.animations.clone(),
@@ -159,6 +175,15 @@ impl<M: Material + FromCpuMaterial + Clone + Default> Model<M> {
         }
     }
 
+    ///
+    /// Sets the transformation of each joint for all the skinned parts of this model, see [Mesh::set_joint_transformations].
+    ///
+    pub fn set_joint_transformations(&mut self, joint_transformations: &[Mat4]) {
+        for part in self.0.iter_mut().filter(|part| part.is_skinned()) {
+            part.set_joint_transformations(joint_transformations);
+        }
+    }
+
     ///
     /// For updating the animation. The time parameter should be some continious time, for example the time since start.
     ///
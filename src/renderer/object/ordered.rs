@@ -0,0 +1,85 @@
+use crate::renderer::*;
+
+///
+/// An [Object] with a [Object::render_order] which is different from the render order of the wrapped object,
+/// for example to always render a [Gm] before or after the other objects, see [Gm::with_render_order].
+///
+pub struct Ordered<O: Object> {
+    /// The object which is rendered with the render order
+    pub object: O,
+    render_order: i32,
+}
+
+impl<O: Object> Ordered<O> {
+    ///
+    /// Creates a new [Ordered] which renders the given object with the given render order.
+    ///
+    pub fn new(object: O, render_order: i32) -> Self {
+        Self {
+            object,
+            render_order,
+        }
+    }
+
+    ///
+    /// Sets the render order of this object, see [Object::render_order].
+    /// Objects with a lower render order are rendered before objects with a higher render order.
+    ///
+    pub fn set_render_order(&mut self, render_order: i32) {
+        self.render_order = render_order;
+    }
+}
+
+impl<'a, O: Object> IntoIterator for &'a Ordered<O> {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl<O: Object + Clone> Clone for Ordered<O> {
+    fn clone(&self) -> Self {
+        Self {
+            object: self.object.clone(),
+            render_order: self.render_order,
+        }
+    }
+}
+
+use std::ops::Deref;
+impl<O: Object> std::ops::Deref for Ordered<O> {
+    type Target = O;
+    fn deref(&self) -> &Self::Target {
+        &self.object
+    }
+}
+
+impl<O: Object> std::ops::DerefMut for Ordered<O> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.object
+    }
+}
+
+impl<O: Object> Geometry for Ordered<O> {
+    impl_geometry_body!(deref);
+
+    fn animate(&mut self, time: f32) {
+        self.object.animate(time)
+    }
+}
+
+impl<O: Object> Object for Ordered<O> {
+    fn render(&self, viewer: &dyn Viewer, lights: &[&dyn Light]) {
+        self.object.render(viewer, lights)
+    }
+
+    fn material_type(&self) -> MaterialType {
+        self.object.material_type()
+    }
+
+    fn render_order(&self) -> i32 {
+        self.render_order
+    }
+}
@@ -0,0 +1,35 @@
+uniform mat4 viewProjectionInverse;
+uniform mat4 decalInverseTransformation;
+uniform vec3 decalDirection;
+uniform vec3 cameraPosition;
+uniform vec4 viewport;
+uniform float normalThreshold;
+uniform sampler2D decalTexture;
+uniform mat3 decalTexTransform;
+uniform vec4 decalColor;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec2 screen_uv = (gl_FragCoord.xy - viewport.xy) / viewport.zw;
+    float depth = sample_depth(screen_uv);
+    vec3 world_pos = world_pos_from_depth(viewProjectionInverse, depth, screen_uv);
+
+    // The surface normal is computed before discarding any fragments, since derivatives are undefined in non-uniform control flow
+    vec3 normal = normalize(cross(dFdx(world_pos), dFdy(world_pos)));
+    if (dot(normal, cameraPosition - world_pos) < 0.0) {
+        normal = -normal;
+    }
+
+    vec3 local_pos = (decalInverseTransformation * vec4(world_pos, 1.0)).xyz;
+    if (depth >= 1.0 || any(greaterThan(abs(local_pos), vec3(1.0)))) {
+        discard;
+    }
+    if (dot(normal, -decalDirection) < normalThreshold) {
+        discard;
+    }
+
+    outColor = decalColor * texture(decalTexture, (decalTexTransform * vec3(0.5 * local_pos.xy + 0.5, 1.0)).xy);
+    outColor.rgb = color_mapping(outColor.rgb);
+}
@@ -0,0 +1,57 @@
+
+uniform mat4 viewProjection;
+uniform mat4 viewProjectionInverse;
+uniform vec3 cameraPosition;
+uniform float spacing;
+uniform vec4 lineColor;
+uniform vec4 xAxisColor;
+uniform vec4 zAxisColor;
+uniform float fadeDistance;
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    // Intersect the ray through this pixel with the xz-plane
+    vec3 near = world_pos_from_depth(viewProjectionInverse, 0.0, uvs);
+    vec3 far = world_pos_from_depth(viewProjectionInverse, 1.0, uvs);
+    vec3 dir = far - near;
+    bool parallel = abs(dir.y) < 0.000001;
+    float t = parallel ? -1.0 : -near.y / dir.y;
+    vec3 p = near + t * dir;
+
+    // The derivatives are computed before discarding any fragments, since derivatives are undefined in non-uniform control flow
+    vec2 coord = p.xz / spacing;
+    vec2 coordWidth = fwidth(coord);
+    vec2 axisWidth = fwidth(p.zx);
+    if(parallel || t < 0.0 || t > 1.0) {
+        discard;
+    }
+
+    // Grid lines
+    vec2 grid = abs(fract(coord - 0.5) - 0.5) / coordWidth;
+    float line = 1.0 - min(min(grid.x, grid.y), 1.0);
+    vec4 color = lineColor;
+
+    // Axes
+    float xAxis = 1.0 - min(abs(p.z) / axisWidth.x, 1.0);
+    float zAxis = 1.0 - min(abs(p.x) / axisWidth.y, 1.0);
+    color = mix(color, xAxisColor, xAxis);
+    color = mix(color, zAxisColor, zAxis);
+    line = max(line, max(xAxis, zAxis));
+
+    // Fade with the distance to the viewer
+    float fade = 1.0 - smoothstep(0.0, fadeDistance, distance(cameraPosition, p));
+    outColor = vec4(color.rgb, color.a * line * fade);
+    if(outColor.a < 0.001) {
+        discard;
+    }
+
+    vec4 clipPos = viewProjection * vec4(p, 1.0);
+    gl_FragDepth = 0.5 * clipPos.z / clipPos.w + 0.5;
+
+    outColor.rgb = tone_mapping(outColor.rgb);
+    outColor.rgb = color_mapping(outColor.rgb);
+}
@@ -2,21 +2,29 @@ uniform sampler2DArray tex;
 
 uniform mat4 view;
 uniform int no_views;
+uniform int no_elevation_bands;
 in vec2 uvs;
 
 layout (location = 0) out vec4 outColor;
 
 void main()
 {
-    vec3 dir = normalize(vec3(view[0][2], 0.0, view[2][2]));
+    vec3 dir = vec3(view[0][2], 0.0, view[2][2]);
+    dir = dot(dir, dir) > 0.000001 ? normalize(dir) : vec3(1.0, 0.0, 0.0);
     float a = acos(dir.x);
     float angle = (dir.z > 0.0 ? a : 2.0 * PI - a) / (2.0 * PI);
 
     float layer = float(no_views) * clamp(angle, 0.0, 0.999);
 
+    // The views are rendered at elevations from 0 to almost 90 degrees in equal steps
+    float elevation = asin(clamp(view[1][2], -1.0, 1.0));
+    float band = clamp(floor(elevation * 2.0 * float(no_elevation_bands) / PI + 0.5), 0.0, float(no_elevation_bands - 1));
+
     float index0 = floor(layer);
     float index1 = float((int(index0) + 1) % no_views);
     float frac = layer - index0;
+    index0 += band * float(no_views);
+    index1 += band * float(no_views);
 
     vec4 color0 = texture(tex, vec3(uvs.x, uvs.y, index0));
     vec4 color1 = texture(tex, vec3(uvs.x, uvs.y, index1));
@@ -0,0 +1,61 @@
+uniform vec3 direction;
+uniform vec3 up;
+
+#ifdef USE_ATMOSPHERE
+uniform vec3 sunDirection;
+#else
+uniform vec4 topColor;
+uniform vec4 horizonColor;
+uniform vec4 bottomColor;
+#endif
+
+in vec2 uvs;
+
+layout (location = 0) out vec4 outColor;
+
+#ifdef USE_ATMOSPHERE
+// Scattering coefficients per meter and scale heights in meters
+const vec3 betaRayleigh = vec3(5.5e-6, 13.0e-6, 22.4e-6);
+const float betaMie = 21e-6;
+const float heightRayleigh = 8000.0;
+const float heightMie = 1200.0;
+const float sunIntensity = 20.0;
+
+// Approximates the transmittance along the path from the ground in the given direction out of the atmosphere
+vec3 transmittance(float y) {
+    float d = 1.0 / (max(y, 0.0) + 0.05);
+    return exp(-(betaRayleigh * heightRayleigh + betaMie * heightMie) * d);
+}
+
+vec3 sky(vec3 dir) {
+    vec3 sun = normalize(sunDirection);
+    float mu = dot(dir, sun);
+    float d = 1.0 / (max(dir.y, 0.0) + 0.05);
+    vec3 opticalDepth = (betaRayleigh * heightRayleigh + betaMie * heightMie) * d;
+
+    float phaseRayleigh = 3.0 / (16.0 * PI) * (1.0 + mu * mu);
+    float g = 0.76;
+    float phaseMie = (1.0 - g * g) / (4.0 * PI * pow(1.0 + g * g - 2.0 * g * mu, 1.5));
+    vec3 scattering = betaRayleigh * phaseRayleigh + betaMie * phaseMie;
+    vec3 extinction = betaRayleigh + betaMie;
+
+    vec3 color = sunIntensity * scattering / extinction * (1.0 - exp(-opticalDepth)) * transmittance(sun.y);
+    color += sunIntensity * 0.5 * smoothstep(0.9998, 0.99995, mu) * transmittance(dir.y);
+    if (dir.y < 0.0) {
+        // Fade to a dark ground below the horizon
+        color *= mix(1.0, 0.2, smoothstep(0.0, 0.1, -dir.y));
+    }
+    return color;
+}
+#endif
+
+void main()
+{
+    vec3 right = cross(direction, up);
+    vec3 dir = normalize(up * (uvs.y - 0.5) * 2.0 + right * (uvs.x - 0.5) * 2.0 + direction);
+#ifdef USE_ATMOSPHERE
+    outColor = vec4(sky(dir), 1.0);
+#else
+    outColor = dir.y > 0.0 ? mix(horizonColor, topColor, dir.y) : mix(horizonColor, bottomColor, -dir.y);
+#endif
+}
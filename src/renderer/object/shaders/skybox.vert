@@ -1,6 +1,7 @@
 
 uniform mat4 view;
 uniform mat4 projection;
+uniform mat3 rotation;
 
 in vec3 position;
 
@@ -8,6 +9,6 @@ out vec3 coords;
 
 void main()
 {
-    coords = position;
+    coords = transpose(rotation) * position;
     gl_Position = (projection * mat4(mat3(view)) * vec4(position, 1.)).xyww;
 }
\ No newline at end of file
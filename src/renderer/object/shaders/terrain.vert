@@ -1,7 +1,21 @@
 uniform mat4 viewProjectionMatrix;
 
 in vec3 position;
+
+#ifdef USE_HEIGHTMAP
+uniform sampler2D heightMap;
+uniform mat3 heightMapTransformation;
+uniform vec2 heightMapSize;
+uniform float vertexDistance;
+
+float height_at(vec2 p)
+{
+    vec2 uv = (heightMapTransformation * vec3(p / heightMapSize + 0.5, 1.0)).xy;
+    return textureLod(heightMap, uv, 0.0).r;
+}
+#else
 in vec3 normal;
+#endif
 
 out vec3 pos;
 out vec3 nor;
@@ -13,11 +27,20 @@ flat out int instance_id;
 
 void main()
 {
+#ifdef USE_HEIGHTMAP
+    vec2 p = position.xz;
+    vec4 worldPos = vec4(p.x, height_at(p), p.y, 1.);
+    float h = vertexDistance;
+    float dx = height_at(p + vec2(h, 0.0)) - height_at(p - vec2(h, 0.0));
+    float dz = height_at(p + vec2(0.0, h)) - height_at(p - vec2(0.0, h));
+    nor = normalize(vec3(-dx, 2.0 * h, -dz));
+#else
     vec4 worldPos = vec4(position, 1.);
+    nor = normalize(normal);
+#endif
     pos = worldPos.xyz;
     uvs = worldPos.xz;
     col = vec4(1.0);
-    nor = normalize(normal);
     tang = cross(vec3(1.0, 0.0, 0.0), nor);
     bitang = cross(nor, tang);
     gl_Position = viewProjectionMatrix * worldPos;
@@ -9,6 +9,7 @@ pub struct Skybox {
     context: Context,
     vertex_buffer: VertexBuffer<Vec3>,
     material: SkyboxMaterial,
+    rotation: Mat3,
 }
 
 impl Skybox {
@@ -77,6 +78,39 @@ impl Skybox {
         Self::new_with_texture(context, Arc::new(texture))
     }
 
+    ///
+    /// Creates a new skybox with a simple procedural gradient going from the top color straight up over the horizon color at the horizon to the bottom color straight down.
+    /// The gradient is rendered into a cube map once, which is available through [Skybox::texture], for example for use with [AmbientLight::new_with_environment].
+    /// Returns an error if the shader used for rendering the gradient fails to compile.
+    ///
+    pub fn new_gradient(
+        context: &Context,
+        top_color: Srgba,
+        horizon_color: Srgba,
+        bottom_color: Srgba,
+    ) -> Result<Self, CoreError> {
+        let texture = procedural_cube_map(context, 64, "", |program| {
+            program.use_uniform("topColor", top_color.to_linear_srgb());
+            program.use_uniform("horizonColor", horizon_color.to_linear_srgb());
+            program.use_uniform("bottomColor", bottom_color.to_linear_srgb());
+        })?;
+        Ok(Self::new_with_texture(context, Arc::new(texture)))
+    }
+
+    ///
+    /// Creates a new skybox with a procedural sky approximating the scattering of sunlight in the atmosphere, given the direction towards the sun.
+    /// The sky is blue when the sun is high, turns red towards the horizon when the sun is low and is dark when the sun is below the horizon.
+    /// The sky is rendered into a high dynamic range cube map once, which is available through [Skybox::texture], for example for use with [AmbientLight::new_with_environment].
+    /// To change the time of day, create a new skybox with a different sun direction.
+    /// Returns an error if the shader used for rendering the sky fails to compile.
+    ///
+    pub fn new_atmosphere(context: &Context, sun_direction: Vec3) -> Result<Self, CoreError> {
+        let texture = procedural_cube_map(context, 256, "#define USE_ATMOSPHERE\n", |program| {
+            program.use_uniform("sunDirection", sun_direction.normalize());
+        })?;
+        Ok(Self::new_with_texture(context, Arc::new(texture)))
+    }
+
     ///
     /// Creates a new skybox with the given [TextureCubeMap].
     /// The colors are assumed to be in linear sRGB (`RgbU8`), linear sRGB with an alpha channel (`RgbaU8`) or HDR color space.
@@ -128,9 +162,26 @@ impl Skybox {
             context: context.clone(),
             vertex_buffer,
             material: SkyboxMaterial { texture },
+            rotation: Mat3::identity(),
         }
     }
 
+    ///
+    /// Returns the rotation applied to the sky.
+    ///
+    pub fn rotation(&self) -> Mat3 {
+        self.rotation
+    }
+
+    ///
+    /// Sets the rotation applied to the sky, for example a slow rotation around the y-axis to animate drifting clouds.
+    /// The rotation is applied when sampling the texture, so changing it is cheap.
+    /// Use the same rotation for [AmbientLight::set_rotation] to keep the lighting consistent with the sky.
+    ///
+    pub fn set_rotation(&mut self, rotation: Mat3) {
+        self.rotation = rotation;
+    }
+
     ///
     /// Returns a reference to the cube map texture
     ///
@@ -139,6 +190,50 @@ impl Skybox {
     }
 }
 
+fn procedural_cube_map(
+    context: &Context,
+    size: u32,
+    defines: &str,
+    use_uniforms: impl Fn(&Program),
+) -> Result<TextureCubeMap, CoreError> {
+    let mut texture = TextureCubeMap::new_empty::<[f16; 4]>(
+        context,
+        size,
+        size,
+        Interpolation::Linear,
+        Interpolation::Linear,
+        Some(Mipmap::default()),
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+    );
+    let program = Program::from_source(
+        context,
+        full_screen_vertex_shader_source(),
+        &format!(
+            "{}{}{}",
+            defines,
+            include_str!("../../core/shared.frag"),
+            include_str!("shaders/sky.frag")
+        ),
+    )?;
+    let viewport = Viewport::new_at_origo(size, size);
+    for side in CubeMapSide::iter() {
+        texture
+            .as_color_target(side, Some(0))
+            .clear(ClearState::default())
+            .write::<CoreError>(|| {
+                use_uniforms(&program);
+                program.use_uniform("direction", side.direction());
+                program.use_uniform("up", side.up());
+                full_screen_draw(context, &program, RenderStates::default(), viewport);
+                Ok(())
+            })?;
+    }
+    texture.generate_mip_maps();
+    Ok(texture)
+}
+
 impl<'a> IntoIterator for &'a Skybox {
     type Item = &'a dyn Object;
     type IntoIter = std::iter::Once<&'a dyn Object>;
@@ -152,6 +247,7 @@ impl Geometry for Skybox {
     fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
         program.use_uniform("view", viewer.view());
         program.use_uniform("projection", viewer.projection());
+        program.use_uniform("rotation", self.rotation);
         program.use_vertex_attribute("position", &self.vertex_buffer);
         program.draw_arrays(render_states, viewer.viewport(), 36);
     }
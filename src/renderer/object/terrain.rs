@@ -27,6 +27,7 @@ pub struct Terrain<M: Material> {
     material: M,
     lod: Arc<dyn Fn(f32) -> Lod + Send + Sync>,
     height_map: Arc<dyn Fn(f32, f32) -> f32 + Send + Sync>,
+    heightmap_texture: Option<HeightmapTexture>,
     side_length: f32,
     vertex_distance: f32,
 }
@@ -42,6 +43,103 @@ impl<M: Material + Clone> Terrain<M> {
         side_length: f32,
         vertex_distance: f32,
         center: Vec2,
+    ) -> Self {
+        Self::new_internal(
+            context,
+            material,
+            height_map,
+            None,
+            side_length,
+            vertex_distance,
+            center,
+        )
+    }
+
+    ///
+    /// Creates a new [Terrain] where the height is given by the red channel of the given heightmap texture.
+    /// The heightmap is sampled in the vertex shader, so the heights are not evaluated on the CPU for each vertex.
+    /// A copy of the heightmap is read back to the CPU once, which is used by [Terrain::height_at] and [Terrain::normal_at].
+    ///
+    /// The heightmap covers an area of the given `world_size` in the (x, z) plane centered at origo.
+    /// Outside of this area, the height is determined by the wrapping of the heightmap texture.
+    /// The `side_length`, `vertex_distance` and `center` are the same as for [Terrain::new].
+    ///
+    pub fn new_from_heightmap(
+        context: &Context,
+        material: M,
+        heightmap: Texture2DRef,
+        world_size: Vec2,
+        side_length: f32,
+        vertex_distance: f32,
+        center: Vec2,
+    ) -> Self {
+        let width = heightmap.width();
+        let height = heightmap.height();
+        let mut copy = Texture2D::new_empty::<[f32; 4]>(
+            context,
+            width,
+            height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let heights = copy
+            .as_color_target(None)
+            .apply_screen_effect(
+                &CopyEffect::default(),
+                Camera::new_2d(Viewport::new_at_origo(width, height)),
+                &[],
+                Some(ColorTexture::Single(&heightmap)),
+                None,
+            )
+            .read::<[f32; 4]>()
+            .into_iter()
+            .map(|c| c[0])
+            .collect::<Vec<_>>();
+        let heightmap_texture = HeightmapTexture {
+            min_height: heights.iter().copied().fold(f32::MAX, f32::min),
+            max_height: heights.iter().copied().fold(f32::MIN, f32::max),
+            transformation: heightmap.transformation,
+            texture: heightmap,
+            world_size,
+        };
+        let transformation = heightmap_texture.transformation;
+        let height_map = Arc::new(move |x: f32, z: f32| {
+            let uv = transformation * vec3(x / world_size.x + 0.5, z / world_size.y + 0.5, 1.0);
+            let sx = (uv.x * width as f32 - 0.5).clamp(0.0, (width - 1) as f32);
+            let sy = (uv.y * height as f32 - 0.5).clamp(0.0, (height - 1) as f32);
+            let x0 = sx.floor() as usize;
+            let y0 = sy.floor() as usize;
+            let x1 = (x0 + 1).min(width as usize - 1);
+            let y1 = (y0 + 1).min(height as usize - 1);
+            let tx = sx - x0 as f32;
+            let ty = sy - y0 as f32;
+            let h = |x: usize, y: usize| heights[y * width as usize + x];
+            let h0 = h(x0, y0) * (1.0 - tx) + h(x1, y0) * tx;
+            let h1 = h(x0, y1) * (1.0 - tx) + h(x1, y1) * tx;
+            h0 * (1.0 - ty) + h1 * ty
+        });
+        Self::new_internal(
+            context,
+            material,
+            height_map,
+            Some(heightmap_texture),
+            side_length,
+            vertex_distance,
+            center,
+        )
+    }
+
+    fn new_internal(
+        context: &Context,
+        material: M,
+        height_map: Arc<dyn Fn(f32, f32) -> f32 + Send + Sync>,
+        heightmap_texture: Option<HeightmapTexture>,
+        side_length: f32,
+        vertex_distance: f32,
+        center: Vec2,
     ) -> Self {
         let index_buffer1 = Self::indices(context, 1);
         let mut patches = Vec::new();
@@ -52,6 +150,7 @@ impl<M: Material + Clone> Terrain<M> {
                 let patch = TerrainPatch::new(
                     context,
                     &*height_map.clone(),
+                    heightmap_texture.clone(),
                     (ix, iy),
                     index_buffer1.clone(),
                     vertex_distance,
@@ -69,6 +168,7 @@ impl<M: Material + Clone> Terrain<M> {
             lod: Arc::new(|_| Lod::High),
             material,
             height_map,
+            heightmap_texture,
             side_length,
             vertex_distance,
         }
@@ -81,6 +181,27 @@ impl<M: Material + Clone> Terrain<M> {
         (*self.height_map)(position.x, position.y)
     }
 
+    ///
+    /// Returns the normal of the surface at the given position.
+    /// The normal is interpolated between the normals at the nearest vertices in the same way as when rendering the terrain,
+    /// so it is consistent with the rendered geometry, for example when aligning an object to the slope of the terrain.
+    ///
+    pub fn normal_at(&self, position: Vec2) -> Vec3 {
+        let h = self.vertex_distance;
+        let x0 = (position.x / h).floor() * h;
+        let z0 = (position.y / h).floor() * h;
+        let tx = (position.x - x0) / h;
+        let tz = (position.y - z0) / h;
+        let normal = |x: f32, z: f32| {
+            let dx = (*self.height_map)(x + h, z) - (*self.height_map)(x - h, z);
+            let dz = (*self.height_map)(x, z + h) - (*self.height_map)(x, z - h);
+            vec3(-dx, 2.0 * h, -dz).normalize()
+        };
+        let n0 = normal(x0, z0).lerp(normal(x0 + h, z0), tx);
+        let n1 = normal(x0, z0 + h).lerp(normal(x0 + h, z0 + h), tx);
+        n0.lerp(n1, tz).normalize()
+    }
+
     ///
     /// Set the function that specifies when a certain level of detail [Lod] is uses.
     /// The input to the function is the distance from the current camera to the center of a part of the terrain.
@@ -106,6 +227,7 @@ impl<M: Material + Clone> Terrain<M> {
                     TerrainPatch::new(
                         &self.context,
                         &*self.height_map.clone(),
+                        self.heightmap_texture.clone(),
                         (self.center.0 + half_patches_per_side, iy),
                         self.index_buffer1.clone(),
                         self.vertex_distance,
@@ -124,6 +246,7 @@ impl<M: Material + Clone> Terrain<M> {
                     TerrainPatch::new(
                         &self.context,
                         &*self.height_map.clone(),
+                        self.heightmap_texture.clone(),
                         (self.center.0 - half_patches_per_side, iy),
                         self.index_buffer1.clone(),
                         self.vertex_distance,
@@ -141,6 +264,7 @@ impl<M: Material + Clone> Terrain<M> {
                     TerrainPatch::new(
                         &self.context,
                         &*self.height_map.clone(),
+                        self.heightmap_texture.clone(),
                         (ix, self.center.1 + half_patches_per_side),
                         self.index_buffer1.clone(),
                         self.vertex_distance,
@@ -159,6 +283,7 @@ impl<M: Material + Clone> Terrain<M> {
                     TerrainPatch::new(
                         &self.context,
                         &*self.height_map.clone(),
+                        self.heightmap_texture.clone(),
                         (ix, self.center.1 - half_patches_per_side),
                         self.index_buffer1.clone(),
                         self.vertex_distance,
@@ -232,11 +357,22 @@ fn pos2patch(vertex_distance: f32, position: Vec2) -> (i32, i32) {
     )
 }
 
+#[derive(Clone)]
+struct HeightmapTexture {
+    texture: Texture2DRef,
+    transformation: Mat3,
+    world_size: Vec2,
+    min_height: f32,
+    max_height: f32,
+}
+
 struct TerrainPatch {
     context: Context,
     index: (i32, i32),
     positions_buffer: VertexBuffer<Vec3>,
-    normals_buffer: VertexBuffer<Vec3>,
+    normals_buffer: Option<VertexBuffer<Vec3>>,
+    heightmap_texture: Option<HeightmapTexture>,
+    vertex_distance: f32,
     center: Vec2,
     aabb: AxisAlignedBoundingBox,
     pub index_buffer: Arc<ElementBuffer<u32>>,
@@ -246,24 +382,45 @@ impl TerrainPatch {
     pub fn new(
         context: &Context,
         height_map: impl Fn(f32, f32) -> f32 + Clone,
+        heightmap_texture: Option<HeightmapTexture>,
         index: (i32, i32),
         index_buffer: Arc<ElementBuffer<u32>>,
         vertex_distance: f32,
     ) -> Self {
         let patch_size = patch_size(vertex_distance);
         let offset = vec2(index.0 as f32 * patch_size, index.1 as f32 * patch_size);
-        let positions = Self::positions(height_map.clone(), offset, vertex_distance);
-        let aabb = AxisAlignedBoundingBox::new_with_positions(&positions);
-        let normals = Self::normals(height_map, offset, &positions, vertex_distance);
+        let (positions, normals_buffer, aabb) = if let Some(heightmap) = &heightmap_texture {
+            // The heights and normals are calculated in the vertex shader
+            let positions = Self::positions(|_, _| 0.0, offset, vertex_distance);
+            let aabb = AxisAlignedBoundingBox::new_with_positions(&[
+                vec3(offset.x, heightmap.min_height, offset.y),
+                vec3(
+                    offset.x + patch_size,
+                    heightmap.max_height,
+                    offset.y + patch_size,
+                ),
+            ]);
+            (positions, None, aabb)
+        } else {
+            let positions = Self::positions(height_map.clone(), offset, vertex_distance);
+            let aabb = AxisAlignedBoundingBox::new_with_positions(&positions);
+            let normals = Self::normals(height_map, offset, &positions, vertex_distance);
+            (
+                positions,
+                Some(VertexBuffer::new_with_data(context, &normals)),
+                aabb,
+            )
+        };
 
         let positions_buffer = VertexBuffer::new_with_data(context, &positions);
-        let normals_buffer = VertexBuffer::new_with_data(context, &normals);
         Self {
             context: context.clone(),
             index,
             index_buffer,
             positions_buffer,
             normals_buffer,
+            heightmap_texture,
+            vertex_distance,
             aabb,
             center: offset + vec2(0.5 * patch_size, 0.5 * patch_size),
         }
@@ -338,20 +495,38 @@ impl TerrainPatch {
 
 impl Geometry for TerrainPatch {
     fn vertex_shader_source(&self) -> String {
-        include_str!("shaders/terrain.vert").to_owned()
+        if self.heightmap_texture.is_some() {
+            format!(
+                "#define USE_HEIGHTMAP\n{}",
+                include_str!("shaders/terrain.vert")
+            )
+        } else {
+            include_str!("shaders/terrain.vert").to_owned()
+        }
     }
 
     fn draw(&self, viewer: &dyn Viewer, program: &Program, render_states: RenderStates) {
         program.use_uniform("viewProjectionMatrix", viewer.projection() * viewer.view());
         program.use_vertex_attribute("position", &self.positions_buffer);
-        if program.requires_attribute("normal") {
-            program.use_vertex_attribute("normal", &self.normals_buffer);
+        if let Some(heightmap) = &self.heightmap_texture {
+            program.use_texture("heightMap", &heightmap.texture);
+            program.use_uniform("heightMapTransformation", heightmap.transformation);
+            program.use_uniform("heightMapSize", heightmap.world_size);
+            program.use_uniform("vertexDistance", self.vertex_distance);
+        } else if program.requires_attribute("normal") {
+            if let Some(normals_buffer) = &self.normals_buffer {
+                program.use_vertex_attribute("normal", normals_buffer);
+            }
         }
         program.draw_elements(render_states, viewer.viewport(), &self.index_buffer);
     }
 
     fn id(&self) -> GeometryId {
-        GeometryId::TerrainPatch
+        if self.heightmap_texture.is_some() {
+            GeometryId::TerrainPatchHeightmap
+        } else {
+            GeometryId::TerrainPatch
+        }
     }
 
     fn render_with_material(
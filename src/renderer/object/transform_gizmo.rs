@@ -0,0 +1,368 @@
+use crate::renderer::*;
+
+///
+/// The type of transformation applied when dragging the handles of a [TransformGizmo].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GizmoMode {
+    /// Arrows which translates along the axes.
+    Translate,
+    /// Rings which rotates around the axes.
+    Rotate,
+    /// Boxes which scales along the axes.
+    Scale,
+}
+
+///
+/// The orientation of the axes of a [TransformGizmo].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GizmoOrientation {
+    /// The axes follow the rotation of the transformation.
+    Local,
+    /// The axes are the world x, y and z axes.
+    Global,
+}
+
+///
+/// A gizmo with handles for translating, rotating and scaling a transformation along/around the x- (red), y- (green) and z-axis (blue) using the mouse.
+/// The handles are placed at the translation of the transformation.
+///
+/// Call [TransformGizmo::handle_events] each frame to update the transformation, it returns the new transformation when it has changed.
+/// Since the gizmo is placed inside the object it is transforming, it is usually rendered after the rest of the scene on top of a cleared depth buffer,
+/// for example using `RenderTarget::clear(ClearState::depth(1.0))` before rendering the gizmo.
+///
+pub struct TransformGizmo {
+    context: Context,
+    /// The type of transformation applied when dragging the handles.
+    pub mode: GizmoMode,
+    orientation: GizmoOrientation,
+    size: f32,
+    transformation: Mat4,
+    translate_handles: Gm<InstancedMesh, ColorMaterial>,
+    rotate_handles: Gm<InstancedMesh, ColorMaterial>,
+    scale_handles: Gm<InstancedMesh, ColorMaterial>,
+    drag: Option<(usize, Vec3)>,
+}
+
+impl TransformGizmo {
+    ///
+    /// Creates a new gizmo in translate mode with global orientation, placed at the given transformation.
+    /// The size is the length of the handles.
+    ///
+    pub fn new(context: &Context, transformation: Mat4, size: f32) -> Self {
+        let mut translate = CpuMesh::arrow(0.85, 0.3, 16);
+        translate
+            .transform(Mat4::from_nonuniform_scale(1.0, 0.05, 0.05))
+            .unwrap();
+
+        let mut cube = CpuMesh::cube();
+        cube.transform(Mat4::from_translation(vec3(0.94, 0.0, 0.0)) * Mat4::from_scale(0.06))
+            .unwrap();
+        let mut tail = CpuMesh::cylinder(16);
+        tail.transform(Mat4::from_nonuniform_scale(0.88, 0.015, 0.015))
+            .unwrap();
+        let scale = merge(tail, cube);
+
+        let rotate = torus(0.8, 0.015, 64, 8);
+
+        let mut gizmo = Self {
+            context: context.clone(),
+            mode: GizmoMode::Translate,
+            orientation: GizmoOrientation::Global,
+            size,
+            transformation,
+            translate_handles: handles(context, &translate),
+            rotate_handles: handles(context, &rotate),
+            scale_handles: handles(context, &scale),
+            drag: None,
+        };
+        gizmo.update_handles();
+        gizmo
+    }
+
+    ///
+    /// Returns the transformation controlled by this gizmo.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Sets the transformation controlled by this gizmo.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+        self.update_handles();
+    }
+
+    ///
+    /// Returns the orientation of the axes.
+    ///
+    pub fn orientation(&self) -> GizmoOrientation {
+        self.orientation
+    }
+
+    ///
+    /// Sets the orientation of the axes.
+    ///
+    pub fn set_orientation(&mut self, orientation: GizmoOrientation) {
+        self.orientation = orientation;
+        self.update_handles();
+    }
+
+    ///
+    /// Returns the length of the handles.
+    ///
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    ///
+    /// Sets the length of the handles.
+    ///
+    pub fn set_size(&mut self, size: f32) {
+        self.size = size;
+        self.update_handles();
+    }
+
+    ///
+    /// Handles the events. Must be called each frame.
+    /// A press with the left mouse button on one of the handles starts a drag which continues until the button is released.
+    /// The events used by the gizmo are marked as handled, so they are not used by for example a camera control afterwards.
+    /// Returns the new transformation if it was changed.
+    ///
+    /// **Note:** Scaling is always applied along the local axes of the transformation, also when the orientation is [GizmoOrientation::Global].
+    ///
+    pub fn handle_events(&mut self, camera: &Camera, events: &mut [Event]) -> Option<Mat4> {
+        let mut change = false;
+        for event in events.iter_mut() {
+            match event {
+                Event::MousePress {
+                    button,
+                    position,
+                    handled,
+                    ..
+                } if !*handled && *button == MouseButton::Left => {
+                    if let Some(pick) =
+                        pick(&self.context, camera, *position, [&self.handles().geometry])
+                    {
+                        let axis = pick.instance_id as usize;
+                        let (origin, direction) = camera.screen_to_ray(*position);
+                        if let Some(point) = self.drag_point(axis, origin, direction) {
+                            self.drag = Some((axis, point));
+                            *handled = true;
+                        }
+                    }
+                }
+                Event::MouseMotion {
+                    position, handled, ..
+                } => {
+                    if let Some((axis, last_point)) = self.drag {
+                        let (origin, direction) = camera.screen_to_ray(*position);
+                        if let Some(point) = self.drag_point(axis, origin, direction) {
+                            self.apply_drag(axis, last_point, point);
+                            self.drag = Some((axis, point));
+                            change = true;
+                        }
+                        *handled = true;
+                    }
+                }
+                Event::MouseRelease {
+                    button, handled, ..
+                } if self.drag.is_some() && *button == MouseButton::Left => {
+                    self.drag = None;
+                    *handled = true;
+                }
+                _ => {}
+            }
+        }
+        if change {
+            self.update_handles();
+            Some(self.transformation)
+        } else {
+            None
+        }
+    }
+
+    fn handles(&self) -> &Gm<InstancedMesh, ColorMaterial> {
+        match self.mode {
+            GizmoMode::Translate => &self.translate_handles,
+            GizmoMode::Rotate => &self.rotate_handles,
+            GizmoMode::Scale => &self.scale_handles,
+        }
+    }
+
+    fn center(&self) -> Vec3 {
+        self.transformation.w.truncate()
+    }
+
+    fn rotation(&self) -> Mat3 {
+        match self.orientation {
+            GizmoOrientation::Local => Mat3::from_cols(
+                self.transformation.x.truncate().normalize(),
+                self.transformation.y.truncate().normalize(),
+                self.transformation.z.truncate().normalize(),
+            ),
+            GizmoOrientation::Global => Mat3::identity(),
+        }
+    }
+
+    fn axis(&self, axis: usize) -> Vec3 {
+        self.rotation()[axis]
+    }
+
+    ///
+    /// Returns the point on the axis closest to the ray when translating or scaling
+    /// and the intersection between the ray and the plane orthogonal to the axis when rotating.
+    ///
+    fn drag_point(&self, axis: usize, origin: Vec3, direction: Vec3) -> Option<Vec3> {
+        let center = self.center();
+        let axis = self.axis(axis);
+        match self.mode {
+            GizmoMode::Translate | GizmoMode::Scale => {
+                let b = axis.dot(direction);
+                let denominator = 1.0 - b * b;
+                if denominator.abs() < 0.0001 {
+                    return None;
+                }
+                let w = center - origin;
+                let s = (b * direction.dot(w) - axis.dot(w)) / denominator;
+                Some(center + s * axis)
+            }
+            GizmoMode::Rotate => {
+                let denominator = axis.dot(direction);
+                if denominator.abs() < 0.0001 {
+                    return None;
+                }
+                let t = axis.dot(center - origin) / denominator;
+                Some(origin + t * direction)
+            }
+        }
+    }
+
+    fn apply_drag(&mut self, axis_index: usize, from: Vec3, to: Vec3) {
+        let center = self.center();
+        let axis = self.axis(axis_index);
+        match self.mode {
+            GizmoMode::Translate => {
+                self.transformation = Mat4::from_translation(to - from) * self.transformation;
+            }
+            GizmoMode::Rotate => {
+                let (v0, v1) = (from - center, to - center);
+                let angle = f32::atan2(axis.dot(v0.cross(v1)), v0.dot(v1));
+                self.transformation = Mat4::from_translation(center)
+                    * Mat4::from_axis_angle(axis, radians(angle))
+                    * Mat4::from_translation(-center)
+                    * self.transformation;
+            }
+            GizmoMode::Scale => {
+                let (s0, s1) = ((from - center).dot(axis), (to - center).dot(axis));
+                if s0.abs() > 0.0001 && s1 / s0 > 0.0001 {
+                    let mut factor = vec3(1.0, 1.0, 1.0);
+                    factor[axis_index] = s1 / s0;
+                    self.transformation = self.transformation
+                        * Mat4::from_nonuniform_scale(factor.x, factor.y, factor.z);
+                }
+            }
+        }
+    }
+
+    fn update_handles(&mut self) {
+        let transformation = Mat4::from_translation(self.center())
+            * Mat4::from(self.rotation())
+            * Mat4::from_scale(self.size);
+        self.translate_handles.set_transformation(transformation);
+        self.rotate_handles.set_transformation(transformation);
+        self.scale_handles.set_transformation(transformation);
+    }
+}
+
+impl<'a> IntoIterator for &'a TransformGizmo {
+    type Item = &'a dyn Object;
+    type IntoIter = std::iter::Once<&'a dyn Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl Geometry for TransformGizmo {
+    impl_geometry_body!(handles);
+}
+
+impl Object for TransformGizmo {
+    impl_object_body!(handles);
+}
+
+fn handles(context: &Context, cpu_mesh: &CpuMesh) -> Gm<InstancedMesh, ColorMaterial> {
+    Gm::new(
+        InstancedMesh::new(
+            context,
+            &Instances {
+                transformations: vec![
+                    Mat4::identity(),
+                    Mat4::from_angle_z(degrees(90.0)),
+                    Mat4::from_angle_y(degrees(-90.0)),
+                ],
+                texture_transformations: None,
+                colors: Some(vec![Srgba::RED, Srgba::GREEN, Srgba::BLUE]),
+                ..Default::default()
+            },
+            cpu_mesh,
+        ),
+        ColorMaterial::default(),
+    )
+}
+
+fn merge(mut mesh: CpuMesh, other: CpuMesh) -> CpuMesh {
+    let mut positions = mesh.positions.into_f32();
+    let offset = positions.len() as u32;
+    positions.extend(other.positions.into_f32());
+    let mut indices = mesh.indices.into_u32().unwrap();
+    indices.extend(other.indices.into_u32().unwrap().iter().map(|i| i + offset));
+    mesh.positions = Positions::F32(positions);
+    mesh.indices = Indices::U32(indices);
+    mesh.compute_normals();
+    mesh
+}
+
+///
+/// A torus around the x-axis.
+///
+fn torus(radius: f32, tube_radius: f32, subdivisions: u32, tube_subdivisions: u32) -> CpuMesh {
+    let mut positions = Vec::new();
+    let mut indices = Vec::new();
+    for i in 0..subdivisions {
+        let angle = 2.0 * std::f32::consts::PI * i as f32 / subdivisions as f32;
+        let direction = vec3(0.0, angle.cos(), angle.sin());
+        for j in 0..tube_subdivisions {
+            let tube_angle = 2.0 * std::f32::consts::PI * j as f32 / tube_subdivisions as f32;
+            positions.push(
+                radius * direction
+                    + tube_radius
+                        * (tube_angle.cos() * direction + tube_angle.sin() * vec3(1.0, 0.0, 0.0)),
+            );
+        }
+    }
+    for i in 0..subdivisions {
+        let i1 = (i + 1) % subdivisions;
+        for j in 0..tube_subdivisions {
+            let j1 = (j + 1) % tube_subdivisions;
+            indices.push(i * tube_subdivisions + j);
+            indices.push(i1 * tube_subdivisions + j);
+            indices.push(i1 * tube_subdivisions + j1);
+
+            indices.push(i * tube_subdivisions + j);
+            indices.push(i1 * tube_subdivisions + j1);
+            indices.push(i * tube_subdivisions + j1);
+        }
+    }
+    let mut mesh = CpuMesh {
+        positions: Positions::F32(positions),
+        indices: Indices::U32(indices),
+        ..Default::default()
+    };
+    mesh.compute_normals();
+    mesh
+}
@@ -0,0 +1,180 @@
+use crate::renderer::*;
+use std::collections::HashMap;
+
+struct QueryState {
+    query: OcclusionQuery,
+    pending: bool,
+    visible: bool,
+}
+
+///
+/// Tests whether objects are hidden behind other objects, ie. occluded, using GPU occlusion queries,
+/// so that fully occluded objects can be skipped when rendering, for example objects behind walls in a complex scene.
+///
+/// The bounding box of each object is rendered with depth test only, without writing color or depth, after the occluders have been rendered
+/// and the GPU counts whether any pixels passed the depth test. To avoid stalling the CPU while waiting for the GPU,
+/// the result is read when it becomes available, usually in the next frame, and is then returned by [OcclusionQueries::was_visible].
+/// This means that the visibility is one or more frames old, so an object might be missing for a frame when it becomes visible.
+///
+/// A typical frame looks like this:
+/// ```ignore
+/// let target = frame_input.screen();
+/// target.clear(ClearState::default()).render(&camera, &occluders, &lights);
+/// for (id, object) in objects.iter().enumerate() {
+///     if occlusion_queries.was_visible(id as u32) {
+///         target.render(&camera, object, &lights);
+///     }
+/// }
+/// occlusion_queries.update(&target, &camera, objects.iter().enumerate().map(|(id, object)| (id as u32, object.aabb())));
+/// ```
+///
+pub struct OcclusionQueries {
+    context: Context,
+    program: Program,
+    positions: VertexBuffer<Vec3>,
+    queries: HashMap<u32, QueryState>,
+}
+
+impl OcclusionQueries {
+    ///
+    /// Creates a new set of occlusion queries without any tested objects.
+    ///
+    pub fn new(context: &Context) -> Self {
+        let positions = CpuMesh::cube()
+            .positions
+            .to_f32()
+            .into_iter()
+            .map(|p| 0.5 * (p + vec3(1.0, 1.0, 1.0)))
+            .collect::<Vec<_>>();
+        let indices = CpuMesh::cube().indices.to_u32().unwrap();
+        let positions = indices
+            .iter()
+            .map(|i| positions[*i as usize])
+            .collect::<Vec<_>>();
+        Self {
+            context: context.clone(),
+            program: Program::from_source(
+                context,
+                "
+                uniform mat4 viewProjection;
+                uniform vec3 minimum;
+                uniform vec3 size;
+                in vec3 position;
+                void main()
+                {
+                    gl_Position = viewProjection * vec4(minimum + position * size, 1.0);
+                }
+                ",
+                "
+                layout (location = 0) out vec4 outColor;
+                void main()
+                {
+                    outColor = vec4(1.0);
+                }
+                ",
+            )
+            .expect("Failed compiling shader"),
+            positions: VertexBuffer::new_with_data(context, &positions),
+            queries: HashMap::new(),
+        }
+    }
+
+    ///
+    /// Returns whether or not any part of the bounding box with the given id was visible the last time the result of a query was available.
+    /// Returns `true` if the visibility is not known yet, for example because the id has not been tested, or if occlusion queries are not supported,
+    /// so that objects are never skipped wrongly.
+    ///
+    pub fn was_visible(&self, id: u32) -> bool {
+        self.queries.get(&id).map(|q| q.visible).unwrap_or(true)
+    }
+
+    ///
+    /// Reads the results of the queries which have finished and then tests the given bounding boxes against the depth of the given render target as seen from the given viewer.
+    /// A new query is only issued for an id when the previous query for that id has finished, so the GPU is never waited for.
+    /// Call this each frame after rendering the occluders into the render target.
+    ///
+    pub fn update(
+        &mut self,
+        target: &RenderTarget,
+        viewer: impl Viewer,
+        bounding_boxes: impl IntoIterator<Item = (u32, AxisAlignedBoundingBox)>,
+    ) {
+        self.read_results();
+        let view_projection = viewer.projection() * viewer.view();
+        let position = viewer.position();
+        let margin = vec3(1.0, 1.0, 1.0) * viewer.z_near();
+        let render_states = RenderStates {
+            write_mask: WriteMask::NONE,
+            depth_test: DepthTest::Less,
+            cull: Cull::None,
+            ..Default::default()
+        };
+        target
+            .write::<RendererError>(|| {
+                self.program.use_uniform("viewProjection", view_projection);
+                for (id, aabb) in bounding_boxes {
+                    let context = &self.context;
+                    let query = self.queries.entry(id).or_insert_with(|| QueryState {
+                        query: OcclusionQuery::new(context),
+                        pending: false,
+                        visible: true,
+                    });
+                    if query.pending {
+                        continue;
+                    }
+                    if aabb.is_empty() {
+                        query.visible = false;
+                        continue;
+                    }
+                    // The box is clipped by the near plane if the viewer is inside it
+                    if aabb.is_infinite()
+                        || AxisAlignedBoundingBox::new_with_positions(&[
+                            aabb.min() - margin,
+                            aabb.max() + margin,
+                        ])
+                        .is_inside(position)
+                    {
+                        query.visible = true;
+                        continue;
+                    }
+                    self.program.use_uniform("minimum", aabb.min());
+                    self.program.use_uniform("size", aabb.size());
+                    query.query.test(|| {
+                        self.program
+                            .use_vertex_attribute("position", &self.positions);
+                        self.program.draw_arrays(
+                            render_states,
+                            viewer.viewport(),
+                            self.positions.vertex_count(),
+                        );
+                    });
+                    query.pending = true;
+                }
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    ///
+    /// Removes the query with the given id, for example when the object is removed from the scene.
+    ///
+    pub fn remove(&mut self, id: u32) {
+        self.queries.remove(&id);
+    }
+
+    ///
+    /// Removes all queries.
+    ///
+    pub fn clear(&mut self) {
+        self.queries.clear();
+    }
+
+    fn read_results(&mut self) {
+        for query in self.queries.values_mut().filter(|q| q.pending) {
+            if let Some(visible) = query.query.result() {
+                query.visible = visible;
+                query.pending = false;
+            }
+        }
+    }
+}
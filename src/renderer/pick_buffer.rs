@@ -0,0 +1,157 @@
+use crate::renderer::*;
+
+///
+/// A buffer containing the id and distance to the camera of the closest geometry in each pixel,
+/// which can be queried for any number of pixels after rendering it once, for example once each frame for hover highlighting.
+/// This is much faster than calling [pick] for each query, since the geometries are only rendered once.
+///
+pub struct PickBuffer {
+    context: Context,
+    camera: Camera,
+    texture: Texture2D,
+    depth_texture: DepthTexture2D,
+}
+
+impl PickBuffer {
+    ///
+    /// Creates a new pick buffer with the size of the viewport of the given camera.
+    /// Use [PickBuffer::render] to fill the buffer before querying it.
+    ///
+    pub fn new(context: &Context, camera: &Camera) -> Self {
+        let viewport = camera.viewport();
+        Self {
+            context: context.clone(),
+            camera: camera.clone(),
+            texture: Self::new_texture(context, viewport),
+            depth_texture: Self::new_depth_texture(context, viewport),
+        }
+    }
+
+    ///
+    /// Renders the ids and distances of the given geometries as seen from the given camera into the buffer.
+    /// The id of a geometry is its index in the list of geometries, as for [pick].
+    /// The buffer is resized if the size of the viewport of the camera has changed.
+    ///
+    pub fn render(&mut self, camera: &Camera, geometries: impl IntoIterator<Item = impl Geometry>) {
+        let context = &self.context;
+        let viewport = camera.viewport();
+        if self.texture.width() != viewport.width || self.texture.height() != viewport.height {
+            self.texture = Self::new_texture(context, viewport);
+            self.depth_texture = Self::new_depth_texture(context, viewport);
+        }
+        self.camera = camera.clone();
+        let mut material = IntersectionMaterial {
+            min_distance: Some(0.0),
+            max_distance: Some(camera.z_far()),
+            ..Default::default()
+        };
+        RenderTarget::new(
+            self.texture.as_color_target(None),
+            self.depth_texture.as_depth_target(),
+        )
+        .clear(ClearState::color_and_depth(1.0, 1.0, 1.0, 1.0, 1.0))
+        .write::<RendererError>(|| {
+            for (id, geometry) in geometries.into_iter().enumerate() {
+                material.geometry_id = id as u32;
+                render_with_material(
+                    context,
+                    GeometryPassCamera(camera),
+                    &geometry,
+                    &material,
+                    &[],
+                );
+            }
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    ///
+    /// Returns the id of the closest geometry in the given pixel, ie. the index in the list of geometries given to [PickBuffer::render],
+    /// or ```None``` if no geometry was rendered in that pixel.
+    /// The pixel coordinate must be in physical pixels, as for [pick].
+    ///
+    pub fn id_at(&mut self, pixel: impl Into<PhysicalPoint>) -> Option<u32> {
+        self.read(pixel.into()).map(|result| result[1].to_bits())
+    }
+
+    ///
+    /// Returns the distance from the camera position to the closest geometry in the given pixel,
+    /// or ```None``` if no geometry was rendered in that pixel.
+    /// The pixel coordinate must be in physical pixels, as for [pick].
+    ///
+    pub fn depth_at(&mut self, pixel: impl Into<PhysicalPoint>) -> Option<f32> {
+        self.read(pixel.into())
+            .map(|result| result[0] * self.camera.z_far())
+    }
+
+    ///
+    /// Returns the intersection with the closest geometry in the given pixel, including the position and the instance id,
+    /// or ```None``` if no geometry was rendered in that pixel.
+    /// The pixel coordinate must be in physical pixels, as for [pick].
+    ///
+    pub fn pick(&mut self, pixel: impl Into<PhysicalPoint>) -> Option<IntersectionResult> {
+        let pixel = pixel.into();
+        let result = self.read(pixel)?;
+        // The distance to the camera position is stored, so find the position on the ray with that distance
+        let distance = result[0] * self.camera.z_far();
+        let (position, direction) = self.camera.screen_to_ray(pixel);
+        let offset = position - self.camera.position();
+        let b = offset.dot(direction);
+        let t = -b
+            + (b * b - offset.magnitude2() + distance * distance)
+                .max(0.0)
+                .sqrt();
+        Some(IntersectionResult {
+            position: position + direction * t,
+            geometry_id: result[1].to_bits(),
+            instance_id: result[2].to_bits(),
+        })
+    }
+
+    fn read(&mut self, pixel: PhysicalPoint) -> Option<[f32; 4]> {
+        let viewport = self.camera.viewport();
+        let x = (pixel.x - viewport.x as f32).floor();
+        let y = (pixel.y - viewport.y as f32).floor();
+        if x < 0.0 || y < 0.0 || x >= viewport.width as f32 || y >= viewport.height as f32 {
+            return None;
+        }
+        let result = self
+            .texture
+            .as_color_target(None)
+            .read_partially::<[f32; 4]>(ScissorBox {
+                x: x as i32,
+                y: y as i32,
+                width: 1,
+                height: 1,
+            })[0];
+        if result[0] < 1.0 {
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    fn new_texture(context: &Context, viewport: Viewport) -> Texture2D {
+        Texture2D::new_empty::<[f32; 4]>(
+            context,
+            viewport.width,
+            viewport.height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        )
+    }
+
+    fn new_depth_texture(context: &Context, viewport: Viewport) -> DepthTexture2D {
+        DepthTexture2D::new::<f32>(
+            context,
+            viewport.width,
+            viewport.height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        )
+    }
+}
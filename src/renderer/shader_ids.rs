@@ -95,22 +95,34 @@ pub enum GeometryId {
     Screen = 0x8000,
     Skybox = 0x8001,
     TerrainPatch = 0x8002,
+    TerrainPatchHeightmap = 0x8003,
     Sprites = 0x8004,
     WaterPatch = 0x8005,
-    MeshBase = 0x8010,           // To 0x801F
     ParticleSystemBase = 0x8040, // To 0x807F
     InstancedMeshBase = 0x8080,  // To 0x80FF
+    MeshBase = 0x8100,           // To 0x813F
 }
 
 impl GeometryId {
-    enum_bitfield!(MeshBase, Mesh(normal, tangents, uv, color));
+    enum_bitfield!(
+        MeshBase,
+        Mesh(normal, tangents, uv, color, skinning, morph_targets)
+    );
     enum_bitfield!(
         ParticleSystemBase,
         ParticleSystem(normal, tangents, uv, color, instance_color, instance_uv)
     );
     enum_bitfield!(
         InstancedMeshBase,
-        InstancedMesh(normal, tangents, uv, color, instance_color, instance_uv)
+        InstancedMesh(
+            normal,
+            tangents,
+            uv,
+            color,
+            instance_color,
+            instance_uv,
+            instance_layer
+        )
     );
 }
 
@@ -122,12 +134,18 @@ impl GeometryId {
 #[open_enum]
 #[repr(u16)]
 pub enum EffectMaterialId {
-    LightingPassEffectBase = 0x5000, // To 0x503F
-    WaterEffectBase = 0x5800,        // To 0x583F
-    CopyEffectBase = 0x6000,         // To 0x603F
-    ScreenEffectBase = 0x6800,       // To 0x683F
-    FogEffectBase = 0x7000,          // To 0x703F
-    FxaaEffectBase = 0x7800,         // To 0x7838 (has holes)
+    LightingPassEffectBase = 0x5000,           // To 0x50DA (has holes)
+    WaterEffectBase = 0x5800,                  // To 0x583F
+    ScreenSpaceReflectionsEffectBase = 0x5900, // To 0x59DA (has holes)
+    CopyEffectBase = 0x6000,                   // To 0x603F
+    ScreenEffectBase = 0x6800,                 // To 0x683F
+    FogEffectBase = 0x7000,                    // To 0x703F
+    FxaaEffectBase = 0x7800,                   // To 0x7838 (has holes)
+    VignetteEffectBase = 0x7900,               // To 0x7950 (has holes)
+    ChromaticAberrationEffectBase = 0x7A00,    // To 0x7A50 (has holes)
+    MotionBlurEffectBase = 0x7B00,             // To 0x7B50 (has holes)
+    DecalEffectBase = 0x7C00,                  // To 0x7C0A (has holes)
+    LinearDepthEffectBase = 0x7D00,            // To 0x7D0A (has holes)
 
     ColorMaterialBase = 0x8000, // To 0x8001
     DepthMaterial = 0x8002,
@@ -135,24 +153,87 @@ pub enum EffectMaterialId {
     SkyboxMaterial = 0x8004,
     UVMaterial = 0x8005,
     NormalMaterialBase = 0x8006, // To 0x8007
+    VelocityMaterial = 0x8008,
+    ColorMaterialTextureArray = 0x8009,
     IntersectionMaterial = 0x800B,
     IsosurfaceMaterial = 0x800C,
     ImpostersMaterial = 0x800D,
     BrdfMaterial = 0x800E,
     IrradianceMaterial = 0x800F,
     ORMMaterialBase = 0x8010,              // To 0x8013
-    PhysicalMaterialBase = 0x8020,         // To 0x803F
     DeferredPhysicalMaterialBase = 0x8040, // To 0x807F
     PrefilterMaterial = 0x8080,
+    GridMaterial = 0x8081,
+    PhysicalMaterialBase = 0x8100,             // To 0x81FF
+    PhysicalMaterialTransmissionBase = 0x8200, // To 0x87FF (has holes)
 }
 
 impl EffectMaterialId {
-    enum_effectfield!(LightingPassEffectBase, LightingPassEffect(...Default));
+    #[allow(non_snake_case)]
+    #[inline]
+    pub(crate) fn LightingPassEffect(
+        color_texture: ColorTexture,
+        depth_texture: DepthTexture,
+        emissive_texture: bool,
+    ) -> Self {
+        Self(
+            Self::LightingPassEffectBase.0
+                | color_texture.id()
+                | depth_texture.id()
+                | if emissive_texture { 1 << 7 } else { 0 },
+        )
+    }
+
     enum_effectfield!(WaterEffectBase, WaterEffect(...Default));
+
+    #[allow(non_snake_case)]
+    #[inline]
+    pub(crate) fn PhysicalMaterialTransmission(
+        physical_material: EffectMaterialId,
+        color_texture: ColorTexture,
+        transmission_texture: bool,
+    ) -> Self {
+        Self(
+            Self::PhysicalMaterialTransmissionBase.0
+                | (physical_material.0 - Self::PhysicalMaterialBase.0)
+                | match color_texture {
+                    ColorTexture::Single(_) => 0,
+                    _ => 1 << 8,
+                }
+                | if transmission_texture { 1 << 10 } else { 0 },
+        )
+    }
+
+    #[allow(non_snake_case)]
+    #[inline]
+    pub(crate) fn ScreenSpaceReflectionsEffect(
+        color_texture: ColorTexture,
+        depth_texture: DepthTexture,
+        background_texture: bool,
+    ) -> Self {
+        Self(
+            Self::ScreenSpaceReflectionsEffectBase.0
+                | color_texture.id()
+                | depth_texture.id()
+                | if background_texture { 1 << 7 } else { 0 },
+        )
+    }
+
     enum_effectfield!(CopyEffectBase, CopyEffect(Option<...Default>));
     enum_effectfield!(ScreenEffectBase, ScreenEffect(Option<...Default>));
     enum_effectfield!(FogEffectBase, FogEffect(...Default));
     enum_effectfield!(FxaaEffectBase, FxaaEffect(color_texture: ColorTexture));
+    enum_effectfield!(VignetteEffectBase, VignetteEffect(color_texture: ColorTexture));
+    enum_effectfield!(
+        ChromaticAberrationEffectBase,
+        ChromaticAberrationEffect(color_texture: ColorTexture)
+    );
+    enum_effectfield!(MotionBlurEffectBase, MotionBlurEffect(color_texture: ColorTexture));
+    enum_effectfield!(DecalEffectBase, DecalEffect(depth_texture: DepthTexture));
+    enum_effectfield!(
+        LinearDepthEffectBase,
+        LinearDepthEffect(depth_texture: DepthTexture)
+    );
 
     enum_bitfield!(ColorMaterialBase, ColorMaterial(texture));
     enum_bitfield!(NormalMaterialBase, NormalMaterial(normal_texture));
@@ -168,6 +249,9 @@ impl EffectMaterialId {
             occlusion_texture,
             normal_texture,
             emissive_texture,
+            triplanar,
+            clearcoat_texture,
+            clearcoat_roughness_texture,
         )
     );
     enum_bitfield!(
@@ -194,11 +278,11 @@ pub enum LightId {
     AmbientLightBase = 0x80,     // To 0x81
     DirectionalLightBase = 0x82, // To 0x83
     PointLight = 0x84,
-    SpotLightBase = 0x86, // To 0x87
+    SpotLightBase = 0x88, // To 0x8B
 }
 
 impl LightId {
     enum_bitfield!(AmbientLightBase, AmbientLight(environment));
     enum_bitfield!(DirectionalLightBase, DirectionalLight(shadow_texture));
-    enum_bitfield!(SpotLightBase, SpotLight(shadow_texture));
+    enum_bitfield!(SpotLightBase, SpotLight(shadow_texture, cookie));
 }
@@ -0,0 +1,89 @@
+use crate::renderer::*;
+
+///
+/// A texture containing a grid of animation frames of the same size, for example an explosion, which is played over time.
+/// The frames are ordered from left to right and then from the top row to the bottom row, like reading a page.
+/// Use [SpriteSheet::texture] to get the texture with the transformation selecting the current frame, for example to use as the texture of a [ColorMaterial] applied to [Sprites].
+///
+#[derive(Clone)]
+pub struct SpriteSheet {
+    /// The texture containing the frames.
+    pub texture: Texture2DRef,
+    /// The number of frames in each row of the texture.
+    pub columns: u32,
+    /// The number of rows of frames in the texture.
+    pub rows: u32,
+    /// The number of frames in the animation, which can be less than the number of cells in the grid if the last row is not full.
+    pub frame_count: u32,
+    /// The number of frames played per second.
+    pub fps: f32,
+    /// Whether the animation starts over after the last frame, otherwise the last frame is shown when the animation has finished.
+    pub looping: bool,
+}
+
+impl SpriteSheet {
+    ///
+    /// Creates a new looping sprite sheet from a texture containing frames with the given size in texels.
+    ///
+    pub fn new(
+        texture: impl Into<Texture2DRef>,
+        frame_width: u32,
+        frame_height: u32,
+        frame_count: u32,
+        fps: f32,
+    ) -> Self {
+        let texture = texture.into();
+        let columns = (texture.width() / frame_width.max(1)).max(1);
+        let rows = (texture.height() / frame_height.max(1)).max(1);
+        Self {
+            texture,
+            columns,
+            rows,
+            frame_count: frame_count.clamp(1, columns * rows),
+            fps,
+            looping: true,
+        }
+    }
+
+    ///
+    /// Returns the index of the frame shown at the given time in milliseconds since the animation started, for example the accumulated time of the frame input.
+    ///
+    pub fn frame(&self, time: f64) -> u32 {
+        let frame = (time.max(0.0) * 0.001 * self.fps as f64) as u32;
+        if self.looping {
+            frame % self.frame_count
+        } else {
+            frame.min(self.frame_count - 1)
+        }
+    }
+
+    ///
+    /// Returns the transformation of the uv coordinates which selects the given frame in the texture.
+    ///
+    pub fn frame_transformation(&self, frame: u32) -> Mat3 {
+        let frame = frame.min(self.frame_count - 1);
+        let column = frame % self.columns;
+        let row = frame / self.columns;
+        let width = 1.0 / self.columns as f32;
+        let height = 1.0 / self.rows as f32;
+        // The first row is at the top of the texture which is at v equal to 1
+        Mat3::from_translation(vec2(column as f32 * width, 1.0 - (row + 1) as f32 * height))
+            * Mat3::from_nonuniform_scale(width, height)
+    }
+
+    ///
+    /// Returns the texture with the transformation selecting the frame shown at the given time in milliseconds since the animation started, see [SpriteSheet::frame].
+    ///
+    pub fn texture(&self, time: f64) -> Texture2DRef {
+        self.texture
+            .clone()
+            .with_transform(self.frame_transformation(self.frame(time)))
+    }
+
+    ///
+    /// Returns whether the animation has finished at the given time in milliseconds since the animation started, which is never the case for a looping animation.
+    ///
+    pub fn is_finished(&self, time: f64) -> bool {
+        !self.looping && time * 0.001 * self.fps as f64 >= self.frame_count as f64
+    }
+}
@@ -4,12 +4,21 @@ pub use tone_mapping::*;
 mod color_space;
 pub use color_space::*;
 
+mod fog;
+pub use fog::*;
+
 mod camera;
 pub use camera::*;
 
+mod frustum_ext;
+pub use frustum_ext::*;
+
+mod auto_exposure;
+pub use auto_exposure::*;
+
 use crate::*;
 
-pub use three_d_asset::Frustum;
+pub use three_d_asset::{Frustum, ProjectionType};
 
 macro_rules! impl_viewer_body {
     ($inner:ident) => {
@@ -44,6 +53,14 @@ macro_rules! impl_viewer_body {
         fn tone_mapping(&self) -> ToneMapping {
             self.$inner().tone_mapping()
         }
+
+        fn exposure(&self) -> f32 {
+            self.$inner().exposure()
+        }
+
+        fn fog(&self) -> Option<Fog> {
+            self.$inner().fog()
+        }
     };
 }
 
@@ -75,6 +92,16 @@ pub trait Viewer {
 
     /// Defines the [ToneMapping] applied to the final rendered image.
     fn tone_mapping(&self) -> ToneMapping;
+
+    /// Defines the exposure which the color is multiplied by before the [ToneMapping] is applied. The default is 1.
+    fn exposure(&self) -> f32 {
+        1.0
+    }
+
+    /// Defines the [Fog] applied when shading objects. The default is no fog.
+    fn fog(&self) -> Option<Fog> {
+        None
+    }
 }
 
 use std::ops::Deref;
@@ -134,4 +161,12 @@ impl<T: Viewer> Viewer for std::sync::RwLock<T> {
     fn tone_mapping(&self) -> ToneMapping {
         self.read().unwrap().tone_mapping()
     }
+
+    fn exposure(&self) -> f32 {
+        self.read().unwrap().exposure()
+    }
+
+    fn fog(&self) -> Option<Fog> {
+        self.read().unwrap().fog()
+    }
 }
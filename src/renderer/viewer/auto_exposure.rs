@@ -0,0 +1,131 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// Automatically adjusts the [Camera::exposure] to the average luminance of the rendered scene, similar to how the eye adapts to bright and dark environments.
+/// The scene must be rendered into an intermediate HDR texture, preferably with mipmaps, after which [AutoExposure::update] is called before the final render pass applies the tone mapping.
+/// The exposure is smoothed over frames to avoid flicker.
+///
+pub struct AutoExposure {
+    context: Context,
+    program: Program,
+    target: Texture2D,
+    exposure: Option<f32>,
+    /// The average luminance of the scene is mapped to this value, usually called the key value. The default is 0.18 (middle grey).
+    pub key_value: f32,
+    /// The minimum exposure.
+    pub min_exposure: f32,
+    /// The maximum exposure.
+    pub max_exposure: f32,
+    /// How fast the exposure adapts to changes in the luminance, the higher the faster. The default is 1.5 per second.
+    pub adaptation_speed: f32,
+}
+
+impl AutoExposure {
+    ///
+    /// Creates a new auto exposure with default parameters.
+    ///
+    pub fn new(context: &Context) -> Self {
+        let size = 16;
+        Self {
+            context: context.clone(),
+            program: Program::from_source(
+                context,
+                full_screen_vertex_shader_source(),
+                "
+                uniform sampler2D colorMap;
+                in vec2 uvs;
+                layout (location = 0) out vec4 outColor;
+                void main()
+                {
+                    vec3 color = texture(colorMap, uvs).rgb;
+                    float luminance = dot(color, vec3(0.2126, 0.7152, 0.0722));
+                    outColor = vec4(log(max(luminance, 0.0) + 0.0001), 0.0, 0.0, 1.0);
+                }
+                ",
+            )
+            .expect("Failed compiling shader"),
+            target: Texture2D::new_empty::<[f16; 4]>(
+                context,
+                size,
+                size,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+            ),
+            exposure: None,
+            key_value: 0.18,
+            min_exposure: 0.0001,
+            max_exposure: 100.0,
+            adaptation_speed: 1.5,
+        }
+    }
+
+    ///
+    /// Returns the current exposure or `None` if [AutoExposure::update] has not been called yet.
+    ///
+    pub fn exposure(&self) -> Option<f32> {
+        self.exposure
+    }
+
+    ///
+    /// Resets the exposure so that the next call to [AutoExposure::update] sets the exposure directly without smoothing, for example after a scene change.
+    ///
+    pub fn reset(&mut self) {
+        self.exposure = None;
+    }
+
+    ///
+    /// Measures the average luminance of the given HDR color texture, which should be rendered without tone mapping,
+    /// and moves the exposure of the camera towards the exposure that maps the average luminance to the [AutoExposure::key_value].
+    /// The elapsed time in milliseconds since the last update is used to smooth the exposure over frames, for example the elapsed time given by the frame input.
+    /// Returns the new exposure.
+    ///
+    /// Note that this reads the measured luminance back from the GPU, which forces a synchronization between the CPU and the GPU.
+    ///
+    pub fn update(
+        &mut self,
+        camera: &mut Camera,
+        color_texture: &Texture2D,
+        elapsed_time: f64,
+    ) -> f32 {
+        let viewport = Viewport::new_at_origo(self.target.width(), self.target.height());
+        let log_luminances = self
+            .target
+            .as_color_target(None)
+            .clear(ClearState::default())
+            .write::<RendererError>(|| {
+                self.program.use_texture("colorMap", color_texture);
+                full_screen_draw(
+                    &self.context,
+                    &self.program,
+                    RenderStates {
+                        write_mask: WriteMask::COLOR,
+                        depth_test: DepthTest::Always,
+                        ..Default::default()
+                    },
+                    viewport,
+                );
+                Ok(())
+            })
+            .unwrap()
+            .read::<[f32; 4]>();
+        let average_luminance =
+            (log_luminances.iter().map(|l| l[0]).sum::<f32>() / log_luminances.len() as f32).exp();
+        let target_exposure =
+            (self.key_value / average_luminance).clamp(self.min_exposure, self.max_exposure);
+        let exposure = match self.exposure {
+            Some(exposure) => {
+                // Interpolate in log space, since the eye perceives changes in exposure relative to the current exposure
+                let t = 1.0 - (-self.adaptation_speed * elapsed_time as f32 * 0.001).exp();
+                (exposure.ln() + (target_exposure.ln() - exposure.ln()) * t).exp()
+            }
+            None => target_exposure,
+        };
+        self.exposure = Some(exposure);
+        camera.exposure = exposure;
+        exposure
+    }
+}
@@ -8,8 +8,13 @@ pub struct Camera {
     camera: three_d_asset::Camera,
     /// This tone mapping is applied to the final color of renders using this camera.
     pub tone_mapping: ToneMapping,
+    /// The color of renders using this camera is multiplied by this exposure before the tone mapping is applied. The default is 1.
+    /// Use [Camera::set_exposure_ev100] when the lights are specified in photometric units, for example using [DirectionalLight::new_lux].
+    pub exposure: f32,
     /// This color mapping is applied to the final color of renders using this camera.
     pub color_mapping: ColorMapping,
+    /// This fog is applied when shading objects rendered using this camera. The default is no fog.
+    pub fog: Option<Fog>,
 }
 
 impl Viewer for Camera {
@@ -44,6 +49,14 @@ impl Viewer for Camera {
     fn tone_mapping(&self) -> ToneMapping {
         self.tone_mapping
     }
+
+    fn exposure(&self) -> f32 {
+        self.exposure
+    }
+
+    fn fog(&self) -> Option<Fog> {
+        self.fog
+    }
 }
 
 impl Camera {
@@ -64,7 +77,9 @@ impl Camera {
                 viewport, position, target, up, height, z_near, z_far,
             ),
             tone_mapping: ToneMapping::default(),
+            exposure: 1.0,
             color_mapping: ColorMapping::default(),
+            fog: None,
         }
     }
 
@@ -91,7 +106,9 @@ impl Camera {
                 z_far,
             ),
             tone_mapping: ToneMapping::default(),
+            exposure: 1.0,
             color_mapping: ColorMapping::default(),
+            fog: None,
         }
     }
 
@@ -121,6 +138,137 @@ impl Camera {
         )
     }
 
+    ///
+    /// Returns an orthographic camera for viewing 2D content where one unit in world space corresponds exactly to one physical pixel.
+    /// The (0, 0) position is at the given `origin` corner of the viewport and the x-axis points right,
+    /// while the y-axis points up for [Origin::BottomLeft] and down for [Origin::TopLeft].
+    /// This means that the pixel with index (i, j) counted from the origin corner covers the world space area from (i, j) to (i + 1, j + 1),
+    /// so the center of that pixel is at (i + 0.5, j + 0.5).
+    /// Content should be placed in the plane `z = 0`.
+    /// Note that with [Origin::TopLeft] the content is seen from the negative z direction, so triangles with a counter-clockwise winding in the xy-plane
+    /// are seen from the back, which matters if back faces are culled.
+    ///
+    /// Use [Camera::pixel_to_world_2d] and [Camera::world_to_pixel_2d] to convert between pixels, for example from events, and world space.
+    ///
+    pub fn new_2d_pixels(viewport: Viewport, origin: Origin) -> Self {
+        let (width, height) = (viewport.width as f32, viewport.height as f32);
+        let (z, up) = match origin {
+            Origin::BottomLeft => (1.0, vec3(0.0, 1.0, 0.0)),
+            Origin::TopLeft => (-1.0, vec3(0.0, -1.0, 0.0)),
+        };
+        let center = vec3(width * 0.5, height * 0.5, 0.0);
+        Self::new_orthographic(
+            viewport,
+            center + vec3(0.0, 0.0, z),
+            center,
+            up,
+            height,
+            0.0,
+            10.0,
+        )
+    }
+
+    ///
+    /// Returns the position in the `z = 0` plane in world space which is visible at the given pixel.
+    /// The pixel coordinate must be in physical pixels, where (viewport.x, viewport.y) indicate the bottom left corner of the viewport
+    /// and (viewport.x + viewport.width, viewport.y + viewport.height) indicate the top right corner, as for the positions in [Event]s.
+    /// For a camera constructed with [Camera::new_2d_pixels], this is the pixel coordinate relative to the chosen origin corner.
+    ///
+    pub fn pixel_to_world_2d(&self, pixel: impl Into<PhysicalPoint> + Copy) -> Vec2 {
+        let (origin, direction) = self.screen_to_ray(pixel);
+        let p = if direction.z.abs() > f32::EPSILON {
+            origin - direction * (origin.z / direction.z)
+        } else {
+            origin
+        };
+        p.truncate()
+    }
+
+    ///
+    /// Returns the pixel where the given position in the `z = 0` plane in world space is visible, which is the inverse of [Camera::pixel_to_world_2d].
+    /// The pixel coordinate is in physical pixels, where (viewport.x, viewport.y) indicate the bottom left corner of the viewport
+    /// and (viewport.x + viewport.width, viewport.y + viewport.height) indicate the top right corner.
+    ///
+    pub fn world_to_pixel_2d(&self, position: Vec2) -> PhysicalPoint {
+        self.pixel_at_position(position.extend(0.0))
+    }
+
+    ///
+    /// Returns the ray through the given pixel as the origin and the normalized direction of the ray in world space.
+    /// The origin is on the near plane of the camera, so the ray can be used directly with [ray_intersect](crate::renderer::ray_intersect).
+    /// The pixel coordinate must be in physical pixels, where (viewport.x, viewport.y) indicate the bottom left corner of the viewport
+    /// and (viewport.x + viewport.width, viewport.y + viewport.height) indicate the top right corner.
+    ///
+    pub fn screen_to_ray(&self, pixel: impl Into<PhysicalPoint> + Copy) -> (Vec3, Vec3) {
+        let direction = self.view_direction_at_pixel(pixel).normalize();
+        let origin = self.position_at_pixel(pixel) + direction * self.z_near();
+        (origin, direction)
+    }
+
+    ///
+    /// Moves the camera, keeping its view direction and up direction, such that the given bounding box fills the view, also known as 'zoom to fit'.
+    /// The `margin` is the fraction of the viewport width and height to leave empty on each side of the bounding box and is clamped to the range `[0..0.49]`.
+    /// For a perspective camera, the camera is moved closer to or further away from the bounding box and for an orthographic camera, the height of the projection is changed.
+    /// In both cases the target of the camera is set to the center of the bounding box.
+    ///
+    pub fn fit_to_bounds(&mut self, aabb: AxisAlignedBoundingBox, margin: f32) {
+        if aabb.is_empty() || aabb.is_infinite() {
+            return;
+        }
+        let center = aabb.center();
+        let direction = (self.target() - self.position()).normalize();
+        let right = direction.cross(self.up()).normalize();
+        let up = right.cross(direction);
+        let fill = 1.0 - 2.0 * margin.clamp(0.0, 0.49);
+        let aspect = self.viewport().aspect();
+        let (min, max) = (aabb.min(), aabb.max());
+        let corners = (0..8).map(|i| {
+            let corner = vec3(
+                if i & 1 == 0 { min.x } else { max.x },
+                if i & 2 == 0 { min.y } else { max.y },
+                if i & 4 == 0 { min.z } else { max.z },
+            ) - center;
+            vec3(corner.dot(right), corner.dot(up), corner.dot(direction))
+        });
+        let depth = corners.clone().map(|c| c.z.abs()).fold(0.0, f32::max);
+
+        let (z_near, z_far) = (self.z_near(), self.z_far());
+        match *self.projection_type() {
+            ProjectionType::Orthographic { .. } => {
+                let height = corners
+                    .map(|c| (2.0 * c.y.abs()).max(2.0 * c.x.abs() / aspect))
+                    .fold(0.0, f32::max)
+                    / fill;
+                let distance = z_near + depth + (0.01 * depth).max(f32::EPSILON);
+                self.set_view(center - direction * distance, center, up);
+                // The orthographic height is scaled by the distance to the target
+                self.set_orthographic_projection(
+                    (height / distance).max(f32::EPSILON),
+                    z_near,
+                    z_far,
+                );
+            }
+            ProjectionType::Perspective { field_of_view_y } => {
+                let tan_y = (field_of_view_y.0 * 0.5).tan() * fill;
+                let tan_x = tan_y * aspect;
+                // The distance where each corner is exactly at the edge of the view
+                let distance = corners
+                    .map(|c| (c.x.abs() / tan_x).max(c.y.abs() / tan_y) - c.z)
+                    .fold(z_near + depth, f32::max);
+                self.set_view(center - direction * distance, center, up);
+            }
+        }
+    }
+
+    ///
+    /// Sets the exposure from an exposure value at ISO 100, like the exposure settings of a physical camera.
+    /// For example, 15 corresponds to a sunny day and 7 to an indoor scene.
+    /// This is useful when the lights are specified in photometric units, for example using [DirectionalLight::new_lux] and [PointLight::new_lumens].
+    ///
+    pub fn set_exposure_ev100(&mut self, ev100: f32) {
+        self.exposure = 1.0 / (1.2 * 2.0f32.powf(ev100));
+    }
+
     ///
     /// Disables the tone and color mapping so as to be ready for rendering into an intermediate render target with this camera.
     ///
@@ -150,3 +298,128 @@ impl std::ops::DerefMut for Camera {
         &mut self.camera
     }
 }
+
+///
+/// The corner of the viewport which is the origin of the world space coordinates when using [Camera::new_2d_pixels].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Origin {
+    /// The origin is at the bottom left corner of the viewport and the y-axis points up.
+    BottomLeft,
+    /// The origin is at the top left corner of the viewport and the y-axis points down, as is common for UI layouts.
+    TopLeft,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VIEWPORT: Viewport = Viewport {
+        x: 10,
+        y: 20,
+        width: 200,
+        height: 100,
+    };
+
+    fn assert_pixel(pixel: PhysicalPoint, x: f32, y: f32) {
+        assert!(
+            (pixel.x - x).abs() < 1e-3 && (pixel.y - y).abs() < 1e-3,
+            "{:?} != ({}, {})",
+            pixel,
+            x,
+            y
+        );
+    }
+
+    #[test]
+    fn new_2d_pixels_bottom_left() {
+        let camera = Camera::new_2d_pixels(VIEWPORT, Origin::BottomLeft);
+        assert_pixel(camera.world_to_pixel_2d(vec2(0.0, 0.0)), 10.0, 20.0);
+        assert_pixel(camera.world_to_pixel_2d(vec2(200.0, 100.0)), 210.0, 120.0);
+        assert_pixel(camera.world_to_pixel_2d(vec2(3.5, 7.5)), 13.5, 27.5);
+    }
+
+    #[test]
+    fn new_2d_pixels_top_left() {
+        let camera = Camera::new_2d_pixels(VIEWPORT, Origin::TopLeft);
+        assert_pixel(camera.world_to_pixel_2d(vec2(0.0, 0.0)), 10.0, 120.0);
+        assert_pixel(camera.world_to_pixel_2d(vec2(200.0, 100.0)), 210.0, 20.0);
+        assert_pixel(camera.world_to_pixel_2d(vec2(3.5, 7.5)), 13.5, 112.5);
+    }
+
+    #[test]
+    fn pixel_to_world_2d_round_trip() {
+        let perspective = Camera::new_perspective(
+            VIEWPORT,
+            vec3(1.0, 2.0, 5.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            degrees(45.0),
+            0.1,
+            100.0,
+        );
+        for camera in [
+            Camera::new_2d_pixels(VIEWPORT, Origin::BottomLeft),
+            Camera::new_2d_pixels(VIEWPORT, Origin::TopLeft),
+            perspective,
+        ] {
+            for (x, y) in [(10.0, 20.0), (10.5, 20.5), (73.25, 101.0), (209.5, 119.5)] {
+                let world = camera.pixel_to_world_2d((x, y));
+                assert_pixel(camera.world_to_pixel_2d(world), x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn fit_to_bounds() {
+        let aabb = AxisAlignedBoundingBox::new_with_positions(&[
+            vec3(-1.0, 2.0, -3.0),
+            vec3(4.0, 3.0, 0.5),
+        ]);
+        let margin = 0.1;
+        let orthographic = Camera::new_orthographic(
+            VIEWPORT,
+            vec3(10.0, 5.0, 10.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            1.0,
+            0.1,
+            100.0,
+        );
+        let perspective = Camera::new_perspective(
+            VIEWPORT,
+            vec3(10.0, 5.0, 10.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            degrees(45.0),
+            0.1,
+            100.0,
+        );
+        for mut camera in [orthographic, perspective] {
+            let direction = camera.view_direction();
+            camera.fit_to_bounds(aabb, margin);
+            assert!(camera.view_direction().distance(direction) < 1e-5);
+            assert!(camera.target().distance(aabb.center()) < 1e-5);
+
+            // All corners are inside the viewport minus the margin and at least one corner touches the margin
+            let (min, max) = (aabb.min(), aabb.max());
+            let mut closest = f32::MAX;
+            for i in 0..8 {
+                let corner = vec3(
+                    if i & 1 == 0 { min.x } else { max.x },
+                    if i & 2 == 0 { min.y } else { max.y },
+                    if i & 4 == 0 { min.z } else { max.z },
+                );
+                assert!(camera
+                    .frustum()
+                    .contains(AxisAlignedBoundingBox::new_with_positions(&[corner])));
+                let uv = camera.uv_coordinates_at_position(corner);
+                for c in [uv.u, uv.v] {
+                    assert!(c >= margin - 1e-4 && c <= 1.0 - margin + 1e-4);
+                    closest = closest.min(c - margin).min(1.0 - margin - c);
+                }
+            }
+            assert!(closest < 1e-4);
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use crate::core::*;
+use crate::renderer::*;
+
+///
+/// The type of falloff used when computing the amount of fog, see [Fog] and [FogEffect].
+///
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum FogType {
+    /// The amount of fog increases linearly from no fog at the start distance to full fog at the end distance. The density is not used.
+    Linear,
+    /// The amount of fog increases exponentially with the distance multiplied by the density.
+    Exponential,
+    /// The amount of fog increases exponentially with the square of the distance multiplied by the density.
+    #[default]
+    ExponentialSquared,
+    /// The density of the fog decreases exponentially with the height above the base height, ie. the fog is denser near the ground.
+    /// The amount of fog is the density integrated along the line from the viewer to the object.
+    Height {
+        /// The height in world space where the density of the fog is equal to the specified density.
+        base: f32,
+        /// How fast the density decreases with the height above the base height.
+        falloff: f32,
+    },
+}
+
+impl FogType {
+    ///
+    /// Returns the fragment shader source for computing the amount of fog at a position in a shader.
+    /// Defines the function `fog_factor(position, eye_position)` which returns a value between 0 (no fog) and 1 (full fog).
+    ///
+    pub(crate) fn fragment_shader_source() -> &'static str {
+        "
+        uniform uint fogType;
+        uniform float fogDensity;
+        uniform float fogStart;
+        uniform float fogEnd;
+        uniform float fogHeightBase;
+        uniform float fogHeightFalloff;
+
+        float fog_factor(vec3 position, vec3 eye_position) {
+            float dist = distance(position, eye_position);
+            if (fogType == 0u) {
+                return 0.0;
+            }
+            if (dist >= fogEnd) {
+                return 1.0;
+            }
+            float d = max(dist - fogStart, 0.0);
+            float factor = 0.0;
+            if (fogType == 1u) {
+                factor = d / max(fogEnd - fogStart, 0.0001);
+            } else if (fogType == 2u) {
+                factor = 1.0 - exp(-d * fogDensity);
+            } else if (fogType == 3u) {
+                float x = d * fogDensity;
+                factor = 1.0 - exp(-x * x);
+            } else if (fogType == 4u) {
+                float eye_density = exp(-fogHeightFalloff * (eye_position.y - fogHeightBase));
+                float dy = position.y - eye_position.y;
+                float amount = eye_density;
+                if (abs(fogHeightFalloff * dy) > 0.0001) {
+                    amount *= (1.0 - exp(-fogHeightFalloff * dy)) / (fogHeightFalloff * dy);
+                }
+                factor = 1.0 - exp(-fogDensity * d * amount);
+            }
+            return clamp(factor, 0.0, 1.0);
+        }
+        "
+    }
+
+    ///
+    /// Sends the uniform data needed to compute the amount of fog to the fragment shader.
+    ///
+    pub(crate) fn use_uniforms(&self, program: &Program, density: f32, start: f32, end: f32) {
+        let (id, base, falloff) = match *self {
+            FogType::Linear => (1u32, 0.0, 0.0),
+            FogType::Exponential => (2u32, 0.0, 0.0),
+            FogType::ExponentialSquared => (3u32, 0.0, 0.0),
+            FogType::Height { base, falloff } => (4u32, base, falloff),
+        };
+        program.use_uniform_if_required("fogType", id);
+        program.use_uniform_if_required("fogDensity", density);
+        program.use_uniform_if_required("fogStart", start);
+        program.use_uniform_if_required("fogEnd", end);
+        program.use_uniform_if_required("fogHeightBase", base);
+        program.use_uniform_if_required("fogHeightFalloff", falloff);
+    }
+}
+
+///
+/// Distance fog which is applied directly when shading an object, ie. the color of the object is blended towards the fog color the further away it is from the viewer.
+/// Contrary to the [FogEffect], this does not require rendering the scene into a color and depth texture first.
+/// Assign it to the [Camera::fog] field to apply it to all objects with a [PhysicalMaterial], [DeferredPhysicalMaterial] or [ColorMaterial] rendered with that camera.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fog {
+    /// The color of the fog.
+    pub color: Srgba,
+    /// The type of falloff used when computing the amount of fog.
+    pub fog_type: FogType,
+    /// The density of the fog. Not used by [FogType::Linear].
+    pub density: f32,
+    /// The distance from the viewer where the fog starts.
+    pub start: f32,
+    /// The distance from the viewer where the fog completely covers the objects.
+    pub end: f32,
+}
+
+impl Default for Fog {
+    fn default() -> Self {
+        Self {
+            color: Srgba::WHITE,
+            fog_type: FogType::default(),
+            density: 0.2,
+            start: 0.0,
+            end: f32::MAX,
+        }
+    }
+}
+
+impl Fog {
+    ///
+    /// Returns the fragment shader source for applying fog in a shader.
+    /// Call `apply_fog(color, position)` with a color in linear color space and a position in world space to apply the fog
+    /// or `fog_position()` to get the world space position of the current fragment.
+    ///
+    pub fn fragment_shader_source() -> String {
+        format!(
+            "{}
+        uniform vec3 fogColor;
+        uniform vec3 fogEyePosition;
+        uniform mat4 fogViewProjectionInverse;
+        uniform vec4 fogViewport;
+
+        vec3 fog_position() {{
+            vec2 ndc = 2.0 * (gl_FragCoord.xy - fogViewport.xy) / fogViewport.zw - 1.0;
+            vec4 p = fogViewProjectionInverse * vec4(ndc, 2.0 * gl_FragCoord.z - 1.0, 1.0);
+            return p.xyz / p.w;
+        }}
+
+        vec3 apply_fog(vec3 color, vec3 position) {{
+            return mix(color, fogColor, fog_factor(position, fogEyePosition));
+        }}
+        ",
+            FogType::fragment_shader_source()
+        )
+    }
+
+    ///
+    /// Sends the uniform data needed to apply the fog of the given viewer, if any, to the fragment shader.
+    ///
+    pub fn use_uniforms(program: &Program, viewer: &dyn Viewer) {
+        if let Some(fog) = viewer.fog() {
+            let viewport = viewer.viewport();
+            fog.fog_type
+                .use_uniforms(program, fog.density, fog.start, fog.end);
+            program.use_uniform_if_required("fogColor", fog.color.to_linear_srgb().truncate());
+            program.use_uniform_if_required("fogEyePosition", viewer.position());
+            program.use_uniform_if_required(
+                "fogViewProjectionInverse",
+                (viewer.projection() * viewer.view()).invert().unwrap(),
+            );
+            program.use_uniform_if_required(
+                "fogViewport",
+                vec4(
+                    viewport.x as f32,
+                    viewport.y as f32,
+                    viewport.width as f32,
+                    viewport.height as f32,
+                ),
+            );
+        } else {
+            program.use_uniform_if_required("fogType", 0u32);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "headless", not(target_arch = "wasm32")))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    #[ignore = "requires a graphics device"]
+    fn fog_position() {
+        let context = crate::window::HeadlessContext::new().unwrap();
+        let (size, offset) = (8, 2);
+        let (fov, distance) = (degrees(60.0), 5.0);
+        // The viewport does not start at the origin to test that the viewport is taken into account
+        let viewport = Viewport {
+            x: offset as i32,
+            y: offset as i32,
+            width: size,
+            height: size,
+        };
+        let mut camera = Camera::new_perspective(
+            viewport,
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 0.0, -1.0),
+            vec3(0.0, 1.0, 0.0),
+            fov,
+            1.0,
+            11.0,
+        );
+        camera.fog = Some(Fog::default());
+
+        // A triangle in the plane at the given distance in front of the camera which covers the entire viewport
+        let program = Program::from_source(
+            &context,
+            "
+            uniform mat4 viewProjection;
+            uniform float distance;
+            void main()
+            {
+                vec3 vertices[3] = vec3[3](
+                    vec3(-30.0, -10.0, -distance),
+                    vec3(30.0, -10.0, -distance),
+                    vec3(0.0, 20.0, -distance)
+                );
+                gl_Position = viewProjection * vec4(vertices[gl_VertexID], 1.0);
+            }
+            ",
+            &format!(
+                "{}
+                layout (location = 0) out vec4 outColor;
+                void main()
+                {{
+                    outColor = vec4(fog_position(), 1.0);
+                }}",
+                Fog::fragment_shader_source()
+            ),
+        )
+        .unwrap();
+        program.use_uniform("viewProjection", camera.projection() * camera.view());
+        program.use_uniform("distance", distance);
+        Fog::use_uniforms(&program, &camera);
+
+        let texture_size = size + 2 * offset;
+        let mut color = Texture2D::new_empty::<[f32; 4]>(
+            &context,
+            texture_size,
+            texture_size,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let mut depth = DepthTexture2D::new::<f32>(
+            &context,
+            texture_size,
+            texture_size,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+        );
+        let pixels = RenderTarget::new(color.as_color_target(None), depth.as_depth_target())
+            .clear(ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0))
+            .write::<RendererError>(|| {
+                crate::core::full_screen_draw(
+                    &context,
+                    &program,
+                    RenderStates::default(),
+                    viewport,
+                );
+                Ok(())
+            })
+            .unwrap()
+            .read_color_partially::<[f32; 4]>(viewport.into());
+
+        // The reconstructed position is the intersection between the plane and the ray through the center of each pixel
+        let half_height = distance * Rad::from(fov / 2.0).0.tan();
+        for (i, pixel) in pixels.iter().enumerate() {
+            // The first row of pixels is the top row
+            let x = (i as u32 % size) as f32 + 0.5;
+            let y = (size - 1 - i as u32 / size) as f32 + 0.5;
+            let expected = vec3(
+                (2.0 * x / size as f32 - 1.0) * half_height,
+                (2.0 * y / size as f32 - 1.0) * half_height,
+                -distance,
+            );
+            let position = vec3(pixel[0], pixel[1], pixel[2]);
+            assert!(
+                position.distance(expected) < 1e-3,
+                "pixel {} is at {:?} but should be at {:?}",
+                i,
+                position,
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::renderer::*;
+
+///
+/// Additional functionality for a [Frustum], for example the one returned by [Camera::frustum](three_d_asset::Camera::frustum), which can be used for custom culling or level of detail selection.
+/// The tests use the same planes as the frustum culling done when rendering.
+///
+pub trait FrustumExt {
+    ///
+    /// Returns true if the given bounding box is at least partially inside the frustum.
+    /// Note that the test is conservative, ie. it can return true for some bounding boxes close to the corners of the frustum even though they are outside.
+    ///
+    fn intersects(&self, aabb: &AxisAlignedBoundingBox) -> bool;
+
+    ///
+    /// Returns true if the given point is inside the frustum.
+    ///
+    fn contains_point(&self, point: Vec3) -> bool;
+}
+
+impl FrustumExt for Frustum {
+    fn intersects(&self, aabb: &AxisAlignedBoundingBox) -> bool {
+        self.contains(*aabb)
+    }
+
+    fn contains_point(&self, point: Vec3) -> bool {
+        // A bounding box with the same min and max is only outside a plane if the point is
+        self.contains(AxisAlignedBoundingBox::new_with_positions(&[point]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frustum() -> Frustum {
+        Camera::new_perspective(
+            Viewport::new_at_origo(100, 100),
+            vec3(0.0, 0.0, 5.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            degrees(90.0),
+            1.0,
+            10.0,
+        )
+        .frustum()
+    }
+
+    #[test]
+    fn contains_point() {
+        let frustum = frustum();
+        assert!(frustum.contains_point(vec3(0.0, 0.0, 0.0)));
+        assert!(frustum.contains_point(vec3(3.5, -3.5, 0.0)));
+        // Behind the camera, outside the sides and outside the near and far planes
+        assert!(!frustum.contains_point(vec3(0.0, 0.0, 6.0)));
+        assert!(!frustum.contains_point(vec3(5.5, 0.0, 0.0)));
+        assert!(!frustum.contains_point(vec3(0.0, -5.5, 0.0)));
+        assert!(!frustum.contains_point(vec3(0.0, 0.0, 4.5)));
+        assert!(!frustum.contains_point(vec3(0.0, 0.0, -5.5)));
+    }
+
+    #[test]
+    fn intersects() {
+        let frustum = frustum();
+        let aabb = |min: Vec3, max: Vec3| AxisAlignedBoundingBox::new_with_positions(&[min, max]);
+        assert!(frustum.intersects(&aabb(vec3(-1.0, -1.0, -1.0), vec3(1.0, 1.0, 1.0))));
+        // Partially inside
+        assert!(frustum.intersects(&aabb(vec3(4.0, -1.0, -1.0), vec3(8.0, 1.0, 1.0))));
+        assert!(frustum.intersects(&aabb(vec3(-1.0, -1.0, 3.0), vec3(1.0, 1.0, 8.0))));
+        // Completely outside
+        assert!(!frustum.intersects(&aabb(vec3(-1.0, -1.0, 6.0), vec3(1.0, 1.0, 8.0))));
+        assert!(!frustum.intersects(&aabb(vec3(7.0, -1.0, -1.0), vec3(9.0, 1.0, 1.0))));
+        assert!(!frustum.intersects(&aabb(vec3(-1.0, -1.0, -8.0), vec3(1.0, 1.0, -6.0))));
+    }
+}
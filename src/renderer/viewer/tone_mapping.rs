@@ -24,8 +24,12 @@ impl ToneMapping {
     pub fn fragment_shader_source() -> &'static str {
         "
         uniform uint toneMappingType;
+        uniform float exposure;
 
         vec3 tone_mapping(vec3 color) {
+            if (toneMappingType != 0u) {
+                color *= exposure;
+            }
             if (toneMappingType == 1u) {
                 color = color / (color + vec3(1.0));
                 color = clamp(color, 0.0, 1.0);
@@ -53,8 +57,19 @@ impl ToneMapping {
 
     ///
     /// Sends the uniform data needed to apply this tone mapping to the fragment shader.
+    /// The exposure is 1, use [ToneMapping::use_uniforms_with_exposure] to specify another exposure.
     ///
     pub fn use_uniforms(&self, program: &Program) {
+        self.use_uniforms_with_exposure(program, 1.0);
+    }
+
+    ///
+    /// Sends the uniform data needed to apply this tone mapping to the fragment shader.
+    /// The color is multiplied by the given exposure before the tone mapping is applied.
+    /// The exposure is not applied if the tone mapping is [ToneMapping::None], since the colors are then written to an intermediate render target.
+    ///
+    pub fn use_uniforms_with_exposure(&self, program: &Program, exposure: f32) {
         program.use_uniform("toneMappingType", *self as u32);
+        program.use_uniform_if_required("exposure", exposure);
     }
 }
@@ -1,3 +1,4 @@
+use crate::context::HasContext;
 use crate::{Context, CoreError};
 use glutin_029::{
     dpi::PhysicalSize, event_loop::EventLoop, ContextBuilder, ContextCurrentState, CreationError,
@@ -18,6 +19,37 @@ pub enum HeadlessError {
     GlutinContextError(#[from] glutin_029::ContextError),
     #[error("error in three-d")]
     ThreeDError(#[from] CoreError),
+    #[error("no graphics device with a name containing '{0}' is available, the available devices are: {1}")]
+    DeviceNotFound(String, String),
+}
+
+///
+/// Settings for creating a [HeadlessContext].
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeadlessSettings {
+    /// The size of the default framebuffer (pbuffer) in physical pixels.
+    /// Since everything is usually rendered into textures when using a headless context, this rarely needs to be changed.
+    /// The default is `(1, 1)`.
+    pub size: (u32, u32),
+    /// Selects the level of hardware graphics acceleration.
+    /// `Some(true)` requires hardware acceleration, `Some(false)` forces software rendering, for example on a CI server,
+    /// and `None` uses hardware acceleration if available. The default is `None`.
+    pub hardware_acceleration: Option<bool>,
+    /// If specified, only a graphics device with a name containing this string (ignoring case) is used,
+    /// for example `"nvidia"` to select the discrete GPU or `"llvmpipe"` to select software rendering.
+    /// See [HeadlessContext::device_name] for the name of the selected device. The default is `None`.
+    pub device: Option<String>,
+}
+
+impl Default for HeadlessSettings {
+    fn default() -> Self {
+        Self {
+            size: (1, 1),
+            hardware_acceleration: None,
+            device: None,
+        }
+    }
 }
 
 ///
@@ -25,30 +57,80 @@ pub enum HeadlessError {
 /// For a graphics context associated with a window, see [WindowedContext](crate::WindowedContext).
 /// Can only be created on native, not on web.
 ///
+/// Since a headless context has no default framebuffer to render into, everything is rendered into textures.
+/// To avoid aliasing, render into a [RenderTargetMultisample](crate::RenderTargetMultisample) and read the antialiased result using [RenderTargetMultisample::read_color](crate::RenderTargetMultisample::read_color).
+///
 #[derive(Clone)]
 pub struct HeadlessContext {
     context: Context,
+    device_name: String,
     _glutin_context: Rc<glutin_029::Context<PossiblyCurrent>>,
 }
 
 impl HeadlessContext {
     ///
-    /// Creates a new headless graphics context.
+    /// Creates a new headless graphics context with default settings.
     ///
-    #[allow(unsafe_code)]
     pub fn new() -> Result<Self, HeadlessError> {
-        let cb = ContextBuilder::new();
-        let glutin_context = build_context(cb)?;
-        let glutin_context = unsafe { glutin_context.make_current().map_err(|(_, e)| e)? };
-        let context = Context::from_gl_context(std::sync::Arc::new(unsafe {
-            crate::context::Context::from_loader_function(|s| {
-                glutin_context.get_proc_address(s) as *const _
-            })
-        }))?;
-        Ok(Self {
-            context,
-            _glutin_context: Rc::new(glutin_context),
-        })
+        Self::new_with_settings(HeadlessSettings::default())
+    }
+
+    ///
+    /// Creates a new headless graphics context with the given settings.
+    /// Returns an error if no graphics device is available or no device matches [HeadlessSettings::device].
+    ///
+    #[allow(unsafe_code)]
+    pub fn new_with_settings(settings: HeadlessSettings) -> Result<Self, HeadlessError> {
+        let size = PhysicalSize::new(settings.size.0.max(1), settings.size.1.max(1));
+        let mut error = None;
+        let mut skipped_devices = Vec::new();
+        for backend in BACKENDS {
+            let cb =
+                ContextBuilder::new().with_hardware_acceleration(settings.hardware_acceleration);
+            let glutin_context = match build_context(cb, size, *backend) {
+                Ok(glutin_context) => glutin_context,
+                Err(e) => {
+                    error = Some(e);
+                    continue;
+                }
+            };
+            let glutin_context = unsafe { glutin_context.make_current().map_err(|(_, e)| e)? };
+            let context = Context::from_gl_context(std::sync::Arc::new(unsafe {
+                crate::context::Context::from_loader_function(|s| {
+                    glutin_context.get_proc_address(s) as *const _
+                })
+            }))?;
+            let device_name = unsafe { context.get_parameter_string(crate::context::RENDERER) };
+            if let Some(ref device) = settings.device {
+                if !device_name.to_lowercase().contains(&device.to_lowercase()) {
+                    skipped_devices.push(device_name);
+                    continue;
+                }
+            }
+            return Ok(Self {
+                context,
+                device_name,
+                _glutin_context: Rc::new(glutin_context),
+            });
+        }
+        match (settings.device, error) {
+            (Some(device), _) if !skipped_devices.is_empty() => Err(HeadlessError::DeviceNotFound(
+                device,
+                skipped_devices.join(", "),
+            )),
+            (_, Some(error)) => Err(error.into()),
+            (device, None) => Err(HeadlessError::DeviceNotFound(
+                device.unwrap_or_default(),
+                String::new(),
+            )),
+        }
+    }
+
+    ///
+    /// Returns the name of the graphics device used by this context, for example for logging which device was selected.
+    ///
+    pub fn device_name(&self) -> &str {
+        &self.device_name
     }
 }
 
@@ -59,69 +141,40 @@ impl std::ops::Deref for HeadlessContext {
     }
 }
 
-/*#[cfg(target_os = "linux")]
-fn build_context_surfaceless<T1: ContextCurrentState>(
-    cb: ContextBuilder<T1>,
-    el: &EventLoop<()>,
-) -> Result<glutin_029::Context<NotCurrent>, CreationError> {
-    use glutin_029::platform::unix::HeadlessContextExt;
-    cb.build_surfaceless(&el)
-}*/
-
-fn build_context_headless<T1: ContextCurrentState>(
-    cb: ContextBuilder<T1>,
-    el: &EventLoop<()>,
-) -> Result<glutin_029::Context<NotCurrent>, CreationError> {
-    let size_one = PhysicalSize::new(1, 1);
-    cb.build_headless(&el, size_one)
+#[derive(Clone, Copy, Debug)]
+enum Backend {
+    #[cfg(target_os = "linux")]
+    OsMesa,
+    PBuffer,
 }
 
+// On unix operating systems, you should always try for surfaceless first,
+// and if that does not work, headless (pbuffers), and if that too fails,
+// finally osmesa.
+//
+// If willing, you could attempt to use hidden windows instead of os mesa,
+// but note that you must handle events for the window that come on the
+// events loop.
 #[cfg(target_os = "linux")]
-fn build_context_osmesa<T1: ContextCurrentState>(
-    cb: ContextBuilder<T1>,
-) -> Result<glutin_029::Context<NotCurrent>, CreationError> {
-    use glutin_029::platform::unix::HeadlessContextExt;
-    let size_one = PhysicalSize::new(1, 1);
-    cb.build_osmesa(size_one)
-}
-
-#[cfg(target_os = "linux")]
-fn build_context<T1: ContextCurrentState>(
-    cb: ContextBuilder<T1>,
-) -> Result<glutin_029::Context<NotCurrent>, CreationError> {
-    // On unix operating systems, you should always try for surfaceless first,
-    // and if that does not work, headless (pbuffers), and if that too fails,
-    // finally osmesa.
-    //
-    // If willing, you could attempt to use hidden windows instead of os mesa,
-    // but note that you must handle events for the window that come on the
-    // events loop.
-
-    /*
-    let err1 = match build_context_surfaceless(cb.clone(), &el) {
-        Ok(ctx) => return Ok((ctx, el)),
-        Err(err) => err,
-    };*/
-
-    let _err3 = match build_context_osmesa(cb.clone()) {
-        Ok(ctx) => return Ok(ctx),
-        Err(err) => err,
-    };
-
-    let el = EventLoop::new();
-
-    let err2 = match build_context_headless(cb, &el) {
-        Ok(ctx) => return Ok(ctx),
-        Err(err) => err,
-    };
-
-    Err(err2)
-}
+const BACKENDS: &[Backend] = &[Backend::OsMesa, Backend::PBuffer];
 
 #[cfg(not(target_os = "linux"))]
+const BACKENDS: &[Backend] = &[Backend::PBuffer];
+
 fn build_context<T1: ContextCurrentState>(
     cb: ContextBuilder<T1>,
+    size: PhysicalSize<u32>,
+    backend: Backend,
 ) -> Result<glutin_029::Context<NotCurrent>, CreationError> {
-    let el = EventLoop::new();
-    build_context_headless(cb.clone(), &el)
+    match backend {
+        #[cfg(target_os = "linux")]
+        Backend::OsMesa => {
+            use glutin_029::platform::unix::HeadlessContextExt;
+            cb.build_osmesa(size)
+        }
+        Backend::PBuffer => {
+            let el = EventLoop::new();
+            cb.build_headless(&el, size)
+        }
+    }
 }
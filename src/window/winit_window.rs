@@ -1,5 +1,9 @@
 #![allow(unsafe_code)]
 use crate::core::{Context, CoreError, Viewport};
+#[cfg(target_arch = "wasm32")]
+use instant::{Duration, Instant};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
 use winit::event::{Event, WindowEvent};
 use winit::event_loop::{ControlFlow, EventLoop};
 use winit::window::WindowBuilder;
@@ -17,6 +21,11 @@ pub use frame_input_generator::*;
 mod windowed_context;
 pub use windowed_context::*;
 
+mod clipboard;
+pub use clipboard::*;
+
+pub use winit::window::CursorIcon;
+
 use thiserror::Error;
 ///
 /// Error associated with a window.
@@ -31,6 +40,12 @@ pub enum WindowError {
     WinitError(#[from] winit::error::OsError),
     #[error("error in three-d")]
     ThreeDError(#[from] CoreError),
+    #[error("failed to grab the cursor")]
+    CursorGrabError(#[from] winit::error::ExternalError),
+    #[error("failed to access the clipboard: {0}")]
+    ClipboardError(String),
+    #[error("invalid window icon: {0}")]
+    InvalidIcon(String),
     #[error("the number of MSAA samples must be a power of two")]
     InvalidNumberOfMSAASamples,
     #[error("it's not possible to create a graphics context/surface with the given settings")]
@@ -48,6 +63,10 @@ pub enum WindowError {
     WinitError(#[from] winit::error::OsError),
     #[error("failed creating a new window")]
     WindowCreation,
+    #[error("failed to grab the cursor")]
+    CursorGrabError(#[from] winit::error::ExternalError),
+    #[error("failed to access the clipboard: {0}")]
+    ClipboardError(String),
     #[error("unable to get document from canvas")]
     DocumentMissing,
     #[error("unable to convert canvas to html canvas: {0}")]
@@ -62,6 +81,10 @@ pub enum WindowError {
     ThreeDError(#[from] CoreError),
 }
 
+/// The name and content of a file dropped onto the canvas on web.
+#[cfg(target_arch = "wasm32")]
+type DroppedFile = (String, Vec<u8>);
+
 ///
 /// Default window, context and event handling which uses [winit](https://crates.io/crates/winit).
 ///
@@ -74,6 +97,21 @@ pub struct Window {
     event_loop: EventLoop<()>,
     #[cfg(target_arch = "wasm32")]
     closure: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::Event)>,
+    #[cfg(target_arch = "wasm32")]
+    pointer_lock_closure: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::Event)>,
+    #[cfg(target_arch = "wasm32")]
+    pointer_locked: std::rc::Rc<std::cell::Cell<bool>>,
+    #[cfg(target_arch = "wasm32")]
+    drag_over_closure: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::Event)>,
+    #[cfg(target_arch = "wasm32")]
+    drop_closure: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::Event)>,
+    #[cfg(target_arch = "wasm32")]
+    dropped_files: std::rc::Rc<std::cell::RefCell<Vec<DroppedFile>>>,
+    #[cfg(target_arch = "wasm32")]
+    pasted_text: std::rc::Rc<std::cell::RefCell<Vec<String>>>,
+    cursor_grabbed: std::cell::Cell<bool>,
+    windowed_size: std::cell::Cell<Option<dpi::PhysicalSize<u32>>>,
+    max_fps: std::cell::Cell<Option<f64>>,
     gl: WindowedContext,
     #[allow(dead_code)]
     maximized: bool,
@@ -103,7 +141,8 @@ impl Window {
                     window_settings.min_size.0,
                     window_settings.min_size.1,
                 ))
-                .with_decorations(!window_settings.borderless);
+                .with_decorations(!window_settings.borderless)
+                .with_window_icon(window_settings.icon.as_ref().map(icon).transpose()?);
 
             match (window_settings.initial_size, window_settings.max_size) {
                 (Some((width, height)), Some((max_width, max_height))) => window_builder
@@ -206,12 +245,106 @@ impl Window {
             closure
         };
 
+        #[cfg(target_arch = "wasm32")]
+        let pointer_locked = std::rc::Rc::new(std::cell::Cell::new(false));
+        #[cfg(target_arch = "wasm32")]
+        let pointer_lock_closure = {
+            use wasm_bindgen::JsCast;
+            let document = web_sys::window()
+                .and_then(|window| window.document())
+                .ok_or(WindowError::DocumentMissing)?;
+            let locked = pointer_locked.clone();
+            let closure = wasm_bindgen::closure::Closure::wrap(Box::new(move |_: web_sys::Event| {
+                locked.set(
+                    web_sys::window()
+                        .and_then(|window| window.document())
+                        .and_then(|document| document.pointer_lock_element())
+                        .is_some(),
+                );
+            }) as Box<dyn FnMut(_)>);
+            document
+                .add_event_listener_with_callback(
+                    "pointerlockchange",
+                    closure.as_ref().unchecked_ref(),
+                )
+                .expect("failed to listen to pointer lock changes");
+            closure
+        };
+
+        #[cfg(target_arch = "wasm32")]
+        let dropped_files = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        #[cfg(target_arch = "wasm32")]
+        let (drag_over_closure, drop_closure) = {
+            use wasm_bindgen::JsCast;
+            use winit::platform::web::WindowExtWebSys;
+            // Dropping is only allowed if the default handling of dragging over the canvas is prevented
+            let drag_over_closure =
+                wasm_bindgen::closure::Closure::wrap(Box::new(move |event: web_sys::Event| {
+                    event.prevent_default();
+                }) as Box<dyn FnMut(_)>);
+            let files = dropped_files.clone();
+            let drop_closure =
+                wasm_bindgen::closure::Closure::wrap(Box::new(move |event: web_sys::Event| {
+                    event.prevent_default();
+                    let Some(file_list) = event
+                        .dyn_ref::<web_sys::DragEvent>()
+                        .and_then(|event| event.data_transfer())
+                        .and_then(|data_transfer| data_transfer.files())
+                    else {
+                        return;
+                    };
+                    for file in (0..file_list.length()).filter_map(|i| file_list.get(i)) {
+                        // The content is read asynchronously and added to the dropped files when it is available
+                        let reader = web_sys::FileReader::new().unwrap();
+                        let name = file.name();
+                        let files = files.clone();
+                        let result_reader = reader.clone();
+                        let onload = wasm_bindgen::closure::Closure::once_into_js(
+                            move |_: web_sys::Event| {
+                                if let Ok(result) = result_reader.result() {
+                                    let data = web_sys::js_sys::Uint8Array::new(&result).to_vec();
+                                    files.borrow_mut().push((name, data));
+                                }
+                            },
+                        );
+                        reader.set_onload(Some(onload.unchecked_ref()));
+                        reader.read_as_array_buffer(&file).ok();
+                    }
+                }) as Box<dyn FnMut(_)>);
+            let canvas = winit_window.canvas();
+            canvas
+                .add_event_listener_with_callback(
+                    "dragover",
+                    drag_over_closure.as_ref().unchecked_ref(),
+                )
+                .expect("failed to listen to canvas drag over");
+            canvas
+                .add_event_listener_with_callback("drop", drop_closure.as_ref().unchecked_ref())
+                .expect("failed to listen to canvas drop");
+            (drag_over_closure, drop_closure)
+        };
+
         Ok(Self {
             window: winit_window,
             event_loop,
             gl: gl?,
             #[cfg(target_arch = "wasm32")]
             closure,
+            #[cfg(target_arch = "wasm32")]
+            pointer_lock_closure,
+            #[cfg(target_arch = "wasm32")]
+            pointer_locked,
+            #[cfg(target_arch = "wasm32")]
+            drag_over_closure,
+            #[cfg(target_arch = "wasm32")]
+            drop_closure,
+            #[cfg(target_arch = "wasm32")]
+            dropped_files,
+            #[cfg(target_arch = "wasm32")]
+            pasted_text: std::rc::Rc::new(std::cell::RefCell::new(Vec::new())),
+            cursor_grabbed: std::cell::Cell::new(false),
+            windowed_size: std::cell::Cell::new(None),
+            max_fps: std::cell::Cell::new(None),
             maximized,
         })
     }
@@ -221,6 +354,10 @@ impl Window {
     ///
     pub fn render_loop<F: 'static + FnMut(FrameInput) -> FrameOutput>(self, mut callback: F) {
         let mut frame_input_generator = FrameInputGenerator::from_winit_window(&self.window);
+        frame_input_generator.set_cursor_grabbed(self.cursor_grabbed.get());
+        #[cfg(target_arch = "wasm32")]
+        let mut modifiers = event::ModifiersState::empty();
+        let mut next_frame_time: Option<Instant> = None;
         self.event_loop
             .run(move |event, _, control_flow| match event {
                 Event::LoopDestroyed => {
@@ -235,9 +372,35 @@ impl Window {
                                 self.closure.as_ref().unchecked_ref(),
                             )
                             .unwrap();
+                        self.window
+                            .canvas()
+                            .remove_event_listener_with_callback(
+                                "dragover",
+                                self.drag_over_closure.as_ref().unchecked_ref(),
+                            )
+                            .unwrap();
+                        self.window
+                            .canvas()
+                            .remove_event_listener_with_callback(
+                                "drop",
+                                self.drop_closure.as_ref().unchecked_ref(),
+                            )
+                            .unwrap();
+                        if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+                            document
+                                .remove_event_listener_with_callback(
+                                    "pointerlockchange",
+                                    self.pointer_lock_closure.as_ref().unchecked_ref(),
+                                )
+                                .unwrap();
+                        }
                     }
                 }
-                Event::MainEventsCleared => {
+                Event::MainEventsCleared
+                    if next_frame_time
+                        .map(|time| Instant::now() >= time)
+                        .unwrap_or(true) =>
+                {
                     self.window.request_redraw();
                 }
                 Event::RedrawRequested(_) => {
@@ -258,6 +421,27 @@ impl Window {
                         });
                     }
 
+                    // The browser releases the pointer lock, for example when pressing Escape, and only locks it some time after it is requested
+                    #[cfg(target_arch = "wasm32")]
+                    if self.pointer_locked.get() != self.cursor_grabbed.get() {
+                        let locked = self.pointer_locked.get();
+                        self.cursor_grabbed.set(locked);
+                        frame_input_generator.set_cursor_grabbed(locked);
+                        self.window.set_cursor_visible(!locked);
+                    }
+
+                    #[cfg(target_arch = "wasm32")]
+                    for (name, data) in self.dropped_files.borrow_mut().drain(..) {
+                        frame_input_generator.handle_dropped_file(name, data);
+                    }
+                    #[cfg(target_arch = "wasm32")]
+                    for text in self.pasted_text.borrow_mut().drain(..) {
+                        frame_input_generator.handle_paste(text);
+                    }
+
+                    frame_input_generator.set_fullscreen(self.window.fullscreen().is_some());
+                    let frame_start_time = Instant::now();
+                    self.gl.reset_statistics();
                     let frame_input = frame_input_generator.generate(&self.gl);
                     let frame_output = callback(frame_input);
                     if frame_output.exit {
@@ -267,8 +451,48 @@ impl Window {
                         {
                             self.gl.swap_buffers().unwrap();
                         }
+                        if let Some(vsync) = frame_output.vsync {
+                            // Changing the swap interval is not supported by some drivers, in which case the current setting is kept
+                            self.gl.set_vsync(vsync).ok();
+                        }
+                        if let Some(fullscreen) = frame_output.fullscreen {
+                            set_fullscreen(&self.window, &self.windowed_size, fullscreen);
+                        }
+                        if let Some(cursor_icon) = frame_output.cursor_icon {
+                            self.window.set_cursor_icon(cursor_icon);
+                        }
+                        if let Some(grab) = frame_output.cursor_grab {
+                            let grabbed = grab_cursor(&self.window, grab).is_ok() && grab;
+                            // On web, the cursor is grabbed when the browser has locked the pointer
+                            if !cfg!(target_arch = "wasm32") || !grabbed {
+                                self.cursor_grabbed.set(grabbed);
+                                frame_input_generator.set_cursor_grabbed(grabbed);
+                            }
+                        }
+                        next_frame_time = None;
+                        // Returns None if the time is infinite or too far in the future to be represented
+                        let time_after = |seconds: f64| {
+                            Duration::try_from_secs_f64(seconds)
+                                .ok()
+                                .and_then(|duration| frame_start_time.checked_add(duration))
+                        };
                         if frame_output.wait_next_event {
-                            *control_flow = ControlFlow::Wait;
+                            // Any event wakes up the loop before the time given by redraw_after
+                            *control_flow = match frame_output
+                                .redraw_after
+                                .and_then(|time| time_after(time.max(0.0) * 0.001))
+                            {
+                                Some(time) => ControlFlow::WaitUntil(time),
+                                None => ControlFlow::Wait,
+                            };
+                        } else if let Some(time) = frame_output
+                            .max_fps
+                            .or(self.max_fps.get())
+                            .filter(|max_fps| *max_fps > 0.0)
+                            .and_then(|max_fps| time_after(1.0 / max_fps))
+                        {
+                            next_frame_time = Some(time);
+                            *control_flow = ControlFlow::WaitUntil(time);
                         } else {
                             *control_flow = ControlFlow::Poll;
                             self.window.request_redraw();
@@ -284,14 +508,92 @@ impl Window {
                         WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
                             self.gl.resize(**new_inner_size);
                         }
+                        WindowEvent::Focused(false) if self.cursor_grabbed.get() => {
+                            grab_cursor(&self.window, false).ok();
+                            self.cursor_grabbed.set(false);
+                        }
+                        #[cfg(target_arch = "wasm32")]
+                        WindowEvent::ModifiersChanged(state) => modifiers = *state,
+                        // The paste event is not fired by the browser since the default key handling is prevented, so read the clipboard instead
+                        #[cfg(target_arch = "wasm32")]
+                        WindowEvent::KeyboardInput { input, .. }
+                            if input.state == event::ElementState::Pressed
+                                && input.virtual_keycode == Some(event::VirtualKeyCode::V)
+                                && (modifiers.ctrl() || modifiers.logo()) =>
+                        {
+                            let pasted_text = self.pasted_text.clone();
+                            clipboard::read_web_clipboard(move |text| {
+                                pasted_text.borrow_mut().push(text)
+                            });
+                        }
                         WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
                         _ => (),
                     }
                 }
+                Event::DeviceEvent { ref event, .. } => {
+                    frame_input_generator.handle_winit_device_event(event);
+                }
                 _ => (),
             });
     }
 
+    ///
+    /// Enables or disables vsync. Use [FrameOutput::vsync] to change it while the render loop is running.
+    /// The initial value is given by [SurfaceSettings::vsync].
+    ///
+    pub fn set_vsync(&self, enabled: bool) -> Result<(), WindowError> {
+        self.gl.set_vsync(enabled)
+    }
+
+    ///
+    /// Sets the maximum number of frames per second of the render loop, which is used when [FrameOutput::max_fps] is not specified.
+    /// Only positive values limit the frame rate, zero, negative and NaN values are the same as `None`.
+    ///
+    /// **Note:** There is no such setting on [WindowedContext] since it does not have a render loop,
+    /// so when using it directly, the application decides when to render each frame.
+    ///
+    pub fn set_max_fps(&self, max_fps: Option<f64>) {
+        self.max_fps.set(max_fps);
+    }
+
+    ///
+    /// Grabs or releases the cursor. Use [FrameOutput::cursor_grab] to change it while the render loop is running.
+    /// When the cursor is grabbed, it is hidden and locked to the window, or confined to the window on platforms that do not support locking it.
+    /// On web, the pointer lock can only be requested after a user interaction, so use [FrameOutput::cursor_grab] instead.
+    ///
+    pub fn set_cursor_grab(&self, grab: bool) -> Result<(), WindowError> {
+        grab_cursor(&self.window, grab)?;
+        if !cfg!(target_arch = "wasm32") {
+            self.cursor_grabbed.set(grab);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Enters or exits borderless fullscreen on the current monitor. Use [FrameOutput::fullscreen] to change it while the render loop is running.
+    /// The size of the window before entering fullscreen is restored when exiting fullscreen.
+    /// On web, the canvas is shown in fullscreen using the Fullscreen API, which is only allowed shortly after a user interaction, for example a key press.
+    ///
+    pub fn set_fullscreen(&self, fullscreen: bool) {
+        set_fullscreen(&self.window, &self.windowed_size, fullscreen);
+    }
+
+    ///
+    /// Returns whether or not the window is in fullscreen, see [Window::set_fullscreen].
+    /// On web, the user can exit fullscreen at any time, for example by pressing Escape.
+    ///
+    pub fn is_fullscreen(&self) -> bool {
+        self.window.fullscreen().is_some()
+    }
+
+    ///
+    /// Sets the icon of the cursor when it is inside the window. Use [FrameOutput::cursor_icon] to change it while the render loop is running.
+    /// On web, the icon is set as the CSS cursor of the canvas.
+    ///
+    pub fn set_cursor_icon(&self, cursor_icon: CursorIcon) {
+        self.window.set_cursor_icon(cursor_icon);
+    }
+
     ///
     /// Return the current logical size of the window.
     ///
@@ -317,6 +619,14 @@ impl Window {
         self.window.scale_factor() as f32
     }
 
+    ///
+    /// Returns the actual number of samples used for multisample anti-aliasing (MSAA) of this window,
+    /// which can be less than [SurfaceSettings::multisamples] if the requested number of samples is not supported.
+    ///
+    pub fn multisamples(&self) -> u8 {
+        self.gl.multisamples()
+    }
+
     ///
     /// Returns the graphics context for this window.
     ///
@@ -324,3 +634,52 @@ impl Window {
         (*self.gl).clone()
     }
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+fn icon(texture: &crate::core::CpuTexture) -> Result<winit::window::Icon, WindowError> {
+    use crate::core::TextureData;
+    let rgba = match &texture.data {
+        TextureData::RgbaU8(data) => data.iter().flatten().copied().collect(),
+        TextureData::RgbU8(data) => data
+            .iter()
+            .flat_map(|[r, g, b]| [*r, *g, *b, u8::MAX])
+            .collect(),
+        _ => Err(WindowError::InvalidIcon(
+            "the icon must contain 8 bit RGBA or RGB data".to_string(),
+        ))?,
+    };
+    winit::window::Icon::from_rgba(rgba, texture.width, texture.height)
+        .map_err(|e| WindowError::InvalidIcon(e.to_string()))
+}
+
+fn set_fullscreen(
+    window: &winit::window::Window,
+    windowed_size: &std::cell::Cell<Option<dpi::PhysicalSize<u32>>>,
+    fullscreen: bool,
+) {
+    if fullscreen == window.fullscreen().is_some() {
+        return;
+    }
+    if fullscreen {
+        windowed_size.set(Some(window.inner_size()));
+        window.set_fullscreen(Some(winit::window::Fullscreen::Borderless(None)));
+    } else {
+        window.set_fullscreen(None);
+        if let Some(size) = windowed_size.take() {
+            window.set_inner_size(size);
+        }
+    }
+}
+
+fn grab_cursor(window: &winit::window::Window, grab: bool) -> Result<(), WindowError> {
+    use winit::window::CursorGrabMode;
+    if grab {
+        window
+            .set_cursor_grab(CursorGrabMode::Locked)
+            .or_else(|_| window.set_cursor_grab(CursorGrabMode::Confined))?;
+    } else {
+        window.set_cursor_grab(CursorGrabMode::None)?;
+    }
+    window.set_cursor_visible(!grab);
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use super::WindowError;
+
+///
+/// Access to the text in the system clipboard, for example to copy and paste values in an editor.
+///
+/// On web, the clipboard can only be read asynchronously when the user pastes, so use the [Event::Paste](crate::Event::Paste) event instead,
+/// which is fired when the user presses the paste shortcut, also on desktop.
+///
+pub struct Clipboard {
+    #[cfg(not(target_arch = "wasm32"))]
+    clipboard: arboard::Clipboard,
+}
+
+impl Clipboard {
+    ///
+    /// Creates a new connection to the system clipboard.
+    ///
+    pub fn new() -> Result<Self, WindowError> {
+        Ok(Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            clipboard: arboard::Clipboard::new()
+                .map_err(|e| WindowError::ClipboardError(e.to_string()))?,
+        })
+    }
+
+    ///
+    /// Copies the given text to the clipboard.
+    /// On web, this is only allowed shortly after a user interaction, for example a mouse or key press.
+    ///
+    pub fn set_text(&mut self, text: &str) -> Result<(), WindowError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.clipboard
+                .set_text(text)
+                .map_err(|e| WindowError::ClipboardError(e.to_string()))
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let (clipboard, write_text) = web_clipboard_function("writeText")?;
+            write_text
+                .call1(&clipboard, &text.into())
+                .map_err(|e| WindowError::ClipboardError(format!("{:?}", e)))?;
+            Ok(())
+        }
+    }
+
+    ///
+    /// Returns the text in the clipboard.
+    /// Not available on web, use the [Event::Paste](crate::Event::Paste) event instead.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn text(&mut self) -> Result<String, WindowError> {
+        self.clipboard
+            .get_text()
+            .map_err(|e| WindowError::ClipboardError(e.to_string()))
+    }
+}
+
+///
+/// Reads the text in the clipboard on web and calls the callback with the text when it is available.
+/// The browser might ask the user for permission first.
+///
+#[cfg(target_arch = "wasm32")]
+pub(super) fn read_web_clipboard(callback: impl FnOnce(String) + 'static) {
+    use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+    if let Ok(promise) = web_clipboard_function("readText")
+        .and_then(|(clipboard, read_text)| {
+            read_text
+                .call0(&clipboard)
+                .map_err(|e| WindowError::ClipboardError(format!("{:?}", e)))
+        })
+        .map(|promise| promise.unchecked_into::<web_sys::js_sys::Promise>())
+    {
+        let on_read = Closure::once(move |text: JsValue| {
+            if let Some(text) = text.as_string() {
+                callback(text);
+            }
+        });
+        // Ignore that reading the clipboard is not allowed
+        let on_error = Closure::once(|_: JsValue| {});
+        let _ = promise.then2(&on_read, &on_error);
+        on_read.forget();
+        on_error.forget();
+    }
+}
+
+///
+/// Returns the clipboard and the function with the given name, which are accessed dynamically since the clipboard API is unstable in web-sys.
+///
+#[cfg(target_arch = "wasm32")]
+fn web_clipboard_function(
+    name: &str,
+) -> Result<(wasm_bindgen::JsValue, web_sys::js_sys::Function), WindowError> {
+    use wasm_bindgen::JsCast;
+    use web_sys::js_sys::{Function, Reflect};
+    let navigator = web_sys::window()
+        .ok_or(WindowError::WindowCreation)?
+        .navigator();
+    let clipboard = Reflect::get(&navigator, &"clipboard".into())
+        .ok()
+        .filter(|clipboard| !clipboard.is_undefined())
+        .ok_or_else(|| WindowError::ClipboardError("the clipboard is not available".to_string()))?;
+    let function = Reflect::get(&clipboard, &name.into())
+        .ok()
+        .and_then(|function| function.dyn_into::<Function>().ok())
+        .ok_or_else(|| {
+            WindowError::ClipboardError(format!("the clipboard function {} is not available", name))
+        })?;
+    Ok((clipboard, function))
+}
@@ -3,9 +3,11 @@ use crate::control::*;
 use crate::core::*;
 #[cfg(target_arch = "wasm32")]
 use instant::Instant;
+use std::collections::HashSet;
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::Instant;
 use winit::dpi::PhysicalSize;
+use winit::event::DeviceEvent;
 use winit::event::TouchPhase;
 use winit::event::WindowEvent;
 
@@ -28,6 +30,10 @@ pub struct FrameInputGenerator {
     secondary_finger_id: Option<u64>,
     modifiers: Modifiers,
     mouse_pressed: Option<MouseButton>,
+    held_keys: HashSet<Key>,
+    held_mouse_buttons: HashSet<MouseButton>,
+    cursor_grabbed: bool,
+    fullscreen: bool,
 }
 
 impl FrameInputGenerator {
@@ -52,6 +58,10 @@ impl FrameInputGenerator {
             secondary_finger_id: None,
             modifiers: Modifiers::default(),
             mouse_pressed: None,
+            held_keys: HashSet::new(),
+            held_mouse_buttons: HashSet::new(),
+            cursor_grabbed: false,
+            fullscreen: false,
         }
     }
 
@@ -83,6 +93,10 @@ impl FrameInputGenerator {
             device_pixel_ratio: self.device_pixel_ratio as f32,
             first_frame: self.first_frame,
             context: context.clone(),
+            held_keys: self.held_keys.clone(),
+            held_mouse_buttons: self.held_mouse_buttons.clone(),
+            cursor_grabbed: self.cursor_grabbed,
+            fullscreen: self.fullscreen,
         };
         self.first_frame = false;
 
@@ -114,6 +128,65 @@ impl FrameInputGenerator {
         frame_input
     }
 
+    ///
+    /// Sets whether or not the cursor is grabbed, which must be called when grabbing or releasing the cursor of a custom [winit](https://crates.io/crates/winit) window.
+    /// When the cursor is grabbed, [crate::Event::MouseMotion] events are generated from the relative mouse movement given to [FrameInputGenerator::handle_winit_device_event]
+    /// instead of from the cursor position, which does not change or is confined to the window.
+    ///
+    pub fn set_cursor_grabbed(&mut self, grabbed: bool) {
+        self.cursor_grabbed = grabbed;
+    }
+
+    ///
+    /// Adds an event for text pasted on web, which is not handled by [winit](https://crates.io/crates/winit).
+    ///
+    #[cfg(target_arch = "wasm32")]
+    pub(super) fn handle_paste(&mut self, text: String) {
+        self.events.push(crate::Event::Paste(text));
+    }
+
+    ///
+    /// Adds an event for a file dropped onto the canvas on web, which is not handled by [winit](https://crates.io/crates/winit).
+    ///
+    #[cfg(target_arch = "wasm32")]
+    pub(super) fn handle_dropped_file(&mut self, name: String, data: Vec<u8>) {
+        self.events.push(crate::Event::FileDropped {
+            path: name.into(),
+            data: Some(data),
+        });
+    }
+
+    ///
+    /// Sets whether or not the window is in fullscreen, which is returned by [FrameInput::is_fullscreen].
+    ///
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.fullscreen = fullscreen;
+    }
+
+    ///
+    /// Handle the [DeviceEvent] generated by a [winit](https://crates.io/crates/winit) event loop.
+    /// This is only needed to get the relative mouse movement while the cursor is grabbed, see [FrameInputGenerator::set_cursor_grabbed].
+    ///
+    pub fn handle_winit_device_event(&mut self, event: &DeviceEvent) {
+        if let DeviceEvent::MouseMotion { delta } = event {
+            if self.cursor_grabbed {
+                let position = self.cursor_pos.unwrap_or(LogicalPoint {
+                    x: 0.5 * self.window_width as f32,
+                    y: 0.5 * self.window_height as f32,
+                    device_pixel_ratio: self.device_pixel_ratio as f32,
+                    height: self.viewport.height as f32,
+                });
+                self.events.push(crate::Event::MouseMotion {
+                    button: self.mouse_pressed,
+                    delta: (delta.0 as f32, delta.1 as f32),
+                    position: position.into(),
+                    modifiers: self.modifiers,
+                    handled: false,
+                });
+            }
+        }
+    }
+
     ///
     /// Handle the [WindowEvent] generated by a [winit](https://crates.io/crates/winit) event loop.
     ///
@@ -135,6 +208,23 @@ impl FrameInputGenerator {
                 self.window_width = logical_size.width;
                 self.window_height = logical_size.height;
             }
+            WindowEvent::Focused(false) => {
+                // The release events are not received when the window is not focused, so release everything now
+                for kind in self.held_keys.drain() {
+                    self.events.push(crate::Event::KeyRelease {
+                        kind,
+                        modifiers: self.modifiers,
+                        handled: false,
+                    });
+                }
+                self.held_mouse_buttons.clear();
+                self.mouse_pressed = None;
+                self.cursor_grabbed = false;
+                self.modifiers = Modifiers::default();
+                self.events.push(crate::Event::ModifiersChange {
+                    modifiers: self.modifiers,
+                });
+            }
             WindowEvent::Occluded(false) => {
                 self.first_frame = true;
             }
@@ -143,6 +233,11 @@ impl FrameInputGenerator {
                     use winit::event::VirtualKeyCode;
                     let state = input.state == winit::event::ElementState::Pressed;
                     if let Some(kind) = translate_virtual_key_code(keycode) {
+                        if state {
+                            self.held_keys.insert(kind);
+                        } else {
+                            self.held_keys.remove(&kind);
+                        }
                         self.events.push(if state {
                             crate::Event::KeyPress {
                                 kind,
@@ -156,6 +251,12 @@ impl FrameInputGenerator {
                                 handled: false,
                             }
                         });
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if state && kind == Key::V && self.modifiers.command {
+                            if let Ok(text) = super::Clipboard::new().and_then(|mut c| c.text()) {
+                                self.events.push(crate::Event::Paste(text));
+                            }
+                        }
                     } else if keycode == VirtualKeyCode::LControl
                         || keycode == VirtualKeyCode::RControl
                     {
@@ -247,6 +348,7 @@ impl FrameInputGenerator {
                         self.events
                             .push(if *state == winit::event::ElementState::Pressed {
                                 self.mouse_pressed = Some(b);
+                                self.held_mouse_buttons.insert(b);
                                 crate::Event::MousePress {
                                     button: b,
                                     position: position.into(),
@@ -255,6 +357,7 @@ impl FrameInputGenerator {
                                 }
                             } else {
                                 self.mouse_pressed = None;
+                                self.held_mouse_buttons.remove(&b);
                                 crate::Event::MouseRelease {
                                     button: b,
                                     position: position.into(),
@@ -278,13 +381,16 @@ impl FrameInputGenerator {
                     device_pixel_ratio: self.device_pixel_ratio as f32,
                     height: self.viewport.height as f32,
                 };
-                self.events.push(crate::Event::MouseMotion {
-                    button: self.mouse_pressed,
-                    delta,
-                    position: position.into(),
-                    modifiers: self.modifiers,
-                    handled: false,
-                });
+                // The relative movement is given by the device events when the cursor is grabbed
+                if !self.cursor_grabbed {
+                    self.events.push(crate::Event::MouseMotion {
+                        button: self.mouse_pressed,
+                        delta,
+                        position: position.into(),
+                        modifiers: self.modifiers,
+                        handled: false,
+                    });
+                }
                 self.cursor_pos = Some(position);
             }
             WindowEvent::ReceivedCharacter(ch) => {
@@ -292,11 +398,25 @@ impl FrameInputGenerator {
                     self.events.push(crate::Event::Text(ch.to_string()));
                 }
             }
+            WindowEvent::HoveredFile(path) => {
+                self.events
+                    .push(crate::Event::FileHovered { path: path.clone() });
+            }
+            WindowEvent::HoveredFileCancelled => {
+                self.events.push(crate::Event::FileHoverCancelled);
+            }
+            WindowEvent::DroppedFile(path) => {
+                self.events.push(crate::Event::FileDropped {
+                    path: path.clone(),
+                    data: None,
+                });
+            }
             WindowEvent::CursorEntered { .. } => {
                 self.events.push(crate::Event::MouseEnter);
             }
             WindowEvent::CursorLeft { .. } => {
                 self.mouse_pressed = None;
+                self.held_mouse_buttons.clear();
                 self.events.push(crate::Event::MouseLeave);
             }
             WindowEvent::Touch(touch) => {
@@ -318,6 +438,7 @@ impl FrameInputGenerator {
                             });
                             self.cursor_pos = Some(position);
                             self.finger_id = Some(touch.id);
+                            self.held_mouse_buttons.insert(MouseButton::Left);
                         } else if self.secondary_finger_id.is_none() {
                             self.secondary_cursor_pos = Some(position);
                             self.secondary_finger_id = Some(touch.id);
@@ -333,6 +454,7 @@ impl FrameInputGenerator {
                             });
                             self.cursor_pos = None;
                             self.finger_id = None;
+                            self.held_mouse_buttons.remove(&MouseButton::Left);
                         } else if self
                             .secondary_finger_id
                             .map(|id| id == touch.id)
@@ -346,15 +468,7 @@ impl FrameInputGenerator {
                         if self.finger_id.map(|id| id == touch.id).unwrap_or(false) {
                             let last_pos = self.cursor_pos.unwrap();
                             if let Some(p) = self.secondary_cursor_pos {
-                                self.events.push(crate::Event::MouseWheel {
-                                    position: position.into(),
-                                    modifiers: self.modifiers,
-                                    handled: false,
-                                    delta: (
-                                        (position.x - p.x).abs() - (last_pos.x - p.x).abs(),
-                                        (position.y - p.y).abs() - (last_pos.y - p.y).abs(),
-                                    ),
-                                });
+                                self.push_two_finger_gesture((last_pos, p), (position, p));
                             } else {
                                 self.events.push(crate::Event::MouseMotion {
                                     button: Some(MouseButton::Left),
@@ -372,15 +486,7 @@ impl FrameInputGenerator {
                         {
                             let last_pos = self.secondary_cursor_pos.unwrap();
                             if let Some(p) = self.cursor_pos {
-                                self.events.push(crate::Event::MouseWheel {
-                                    position: p.into(),
-                                    modifiers: self.modifiers,
-                                    handled: false,
-                                    delta: (
-                                        (position.x - p.x).abs() - (last_pos.x - p.x).abs(),
-                                        (position.y - p.y).abs() - (last_pos.y - p.y).abs(),
-                                    ),
-                                });
+                                self.push_two_finger_gesture((p, last_pos), (p, position));
                             }
                             self.secondary_cursor_pos = Some(position);
                         }
@@ -390,6 +496,42 @@ impl FrameInputGenerator {
             _ => (),
         }
     }
+
+    ///
+    /// Pushes a [crate::Event::PinchGesture] and a [crate::Event::PanGesture] event from the change in the positions of two fingers.
+    /// The pinch is the relative change in distance between the fingers and the pan is the movement of the point between them.
+    ///
+    fn push_two_finger_gesture(
+        &mut self,
+        last: (LogicalPoint, LogicalPoint),
+        current: (LogicalPoint, LogicalPoint),
+    ) {
+        let distance = |(a, b): (LogicalPoint, LogicalPoint)| (a.x - b.x).hypot(a.y - b.y);
+        let center = |(a, b): (LogicalPoint, LogicalPoint)| LogicalPoint {
+            x: 0.5 * (a.x + b.x),
+            y: 0.5 * (a.y + b.y),
+            ..a
+        };
+        let last_distance = distance(last);
+        if last_distance > 0.0 {
+            self.events.push(crate::Event::PinchGesture {
+                delta: distance(current) / last_distance - 1.0,
+                position: center(current).into(),
+                modifiers: self.modifiers,
+                handled: false,
+            });
+        }
+        let (last_center, current_center) = (center(last), center(current));
+        self.events.push(crate::Event::PanGesture {
+            delta: (
+                current_center.x - last_center.x,
+                current_center.y - last_center.y,
+            ),
+            position: current_center.into(),
+            modifiers: self.modifiers,
+            handled: false,
+        });
+    }
 }
 
 fn is_printable_char(chr: char) -> bool {
@@ -1,5 +1,6 @@
-use crate::control::Event;
+use crate::control::{Event, Key, MouseButton};
 use crate::core::{Context, RenderTarget, Viewport};
+use std::collections::HashSet;
 
 ///
 /// Input for rendering (and whatever else needs it) each frame.
@@ -38,6 +39,18 @@ pub struct FrameInput {
 
     /// The graphics context for the window.
     pub context: Context,
+
+    /// The keys that are held down at the end of this frame, see [FrameInput::held_keys].
+    pub held_keys: HashSet<Key>,
+
+    /// The mouse buttons that are held down at the end of this frame, see [FrameInput::held_mouse_buttons].
+    pub held_mouse_buttons: HashSet<MouseButton>,
+
+    /// Whether or not the cursor is grabbed, see [FrameInput::is_cursor_grabbed].
+    pub cursor_grabbed: bool,
+
+    /// Whether or not the window is in fullscreen, see [FrameInput::is_fullscreen].
+    pub fullscreen: bool,
 }
 
 impl FrameInput {
@@ -52,6 +65,38 @@ impl FrameInput {
     pub fn screen(&self) -> RenderTarget {
         RenderTarget::screen(&self.context, self.viewport.width, self.viewport.height)
     }
+
+    ///
+    /// Returns the keys that are held down at the end of this frame, which is useful for smooth movement, for example while a key is held.
+    /// The keys are released when the window loses focus, since the release events are not received in that case.
+    ///
+    pub fn held_keys(&self) -> &HashSet<Key> {
+        &self.held_keys
+    }
+
+    ///
+    /// Returns the mouse buttons that are held down at the end of this frame, where touching the screen with one finger counts as the left button.
+    /// The buttons are released when the window loses focus or the mouse leaves the window.
+    ///
+    pub fn held_mouse_buttons(&self) -> &HashSet<MouseButton> {
+        &self.held_mouse_buttons
+    }
+
+    ///
+    /// Returns whether or not the cursor is grabbed, see [FrameOutput::cursor_grab].
+    /// The grab is released when the window loses focus and on web when the user presses Escape,
+    /// so check this to know when to grab the cursor again, for example on the next mouse press.
+    ///
+    pub fn is_cursor_grabbed(&self) -> bool {
+        self.cursor_grabbed
+    }
+
+    ///
+    /// Returns whether or not the window is in fullscreen, see [FrameOutput::fullscreen].
+    ///
+    pub fn is_fullscreen(&self) -> bool {
+        self.fullscreen
+    }
 }
 
 ///
@@ -76,9 +121,51 @@ pub struct FrameOutput {
     pub swap_buffers: bool,
 
     ///
-    /// Whether to stop the render loop until next event.
+    /// Whether to stop the render loop until next event, for example to only render when the user interacts with the scene and save battery.
+    /// Use [FrameOutput::redraw_after] to also render a new frame after some time, for example when an animation is playing.
     ///
     pub wait_next_event: bool,
+
+    ///
+    /// If specified when [FrameOutput::wait_next_event] is true, the render loop waits at most this number of milliseconds before rendering the next frame,
+    /// even if no events occur. Ignored if [FrameOutput::wait_next_event] is false.
+    /// A negative or NaN value renders the next frame immediately and an infinite value is the same as `None`.
+    ///
+    pub redraw_after: Option<f64>,
+
+    ///
+    /// The maximum number of frames per second. If specified, the render loop waits until the next frame is due before rendering it,
+    /// for example to save battery without the stutter caused by vsync. Ignored if [FrameOutput::wait_next_event] is true.
+    /// Only positive values limit the frame rate, zero, negative and NaN values are the same as `None`.
+    /// If `None`, the value given by [Window::set_max_fps](crate::window::Window::set_max_fps) is used.
+    ///
+    pub max_fps: Option<f64>,
+
+    ///
+    /// If specified, enables or disables vsync from the next frame, see also [Window::set_vsync](crate::window::Window::set_vsync).
+    /// The setting is unchanged if the driver does not support changing it, use [Window::set_vsync](crate::window::Window::set_vsync) before starting the render loop to get an error in that case.
+    ///
+    pub vsync: Option<bool>,
+
+    ///
+    /// If specified, grabs or releases the cursor from the next frame, see also [Window::set_cursor_grab](crate::window::Window::set_cursor_grab).
+    /// When the cursor is grabbed, it is hidden and locked to the window and [Event::MouseMotion] events contain the relative movement of the mouse,
+    /// which is useful for mouse look, for example with [FirstPersonControl::mouse_look](crate::renderer::control::FirstPersonControl::mouse_look).
+    /// On web, the cursor can only be grabbed shortly after a user interaction, for example a mouse press.
+    ///
+    pub cursor_grab: Option<bool>,
+
+    ///
+    /// If specified, sets the icon of the cursor from the next frame, for example to show that something can be moved while dragging it,
+    /// see also [Window::set_cursor_icon](crate::window::Window::set_cursor_icon).
+    ///
+    pub cursor_icon: Option<crate::window::CursorIcon>,
+
+    ///
+    /// If specified, enters or exits fullscreen from the next frame, for example when pressing F11,
+    /// see also [Window::set_fullscreen](crate::window::Window::set_fullscreen).
+    ///
+    pub fullscreen: Option<bool>,
 }
 
 impl Default for FrameOutput {
@@ -87,6 +174,12 @@ impl Default for FrameOutput {
             exit: false,
             swap_buffers: true,
             wait_next_event: false,
+            redraw_after: None,
+            max_fps: None,
+            vsync: None,
+            cursor_grab: None,
+            cursor_icon: None,
+            fullscreen: None,
         }
     }
 }
@@ -30,10 +30,12 @@ pub struct SurfaceSettings {
     /// The default value is 0.
     /// On web, this can only be off (0) or on (>0).
     pub stencil_buffer: u8,
-    /// Set the level of the multisampling anti-aliasing (MSAA).
+    /// Set the level of the multisampling anti-aliasing (MSAA) of the window, for example 0, 2, 4 or 8.
     /// Must be a power-of-two. Higher = more smooth edges.
     /// A value of 0 turns it off.
     /// The default value is 4.
+    /// If the number of samples is not supported, the highest supported number of samples below it is used instead,
+    /// see [Window::multisamples](crate::window::Window::multisamples) for the actual number of samples.
     /// On web, this can only be off (0) or on (>0).
     /// The actual number of samples depends on browser settings.
     pub multisamples: u8,
@@ -57,7 +59,7 @@ impl Default for SurfaceSettings {
 ///
 /// Settings for the default [Window](super::Window).
 ///
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct WindowSettings {
     /// The title of the window.
     ///
@@ -81,6 +83,11 @@ pub struct WindowSettings {
     ///
     /// On web this has no effect.
     pub borderless: bool,
+    /// The icon of the window, which must contain 8 bit RGBA or RGB data, for example loaded from a PNG file.
+    /// If `None` is specified, the default icon of the platform is used.
+    ///
+    /// On web this has no effect, use a favicon instead.
+    pub icon: Option<crate::core::CpuTexture>,
     /// An optional [canvas element][web_sys::HtmlCanvasElement] for using as winit window.
     /// If this is `None`, the DOM (`index.html`) must contain a canvas element
     #[cfg(target_arch = "wasm32")]
@@ -97,6 +104,7 @@ impl Default for WindowSettings {
             max_size: None,
             initial_size: None,
             borderless: false,
+            icon: None,
             #[cfg(target_arch = "wasm32")]
             canvas: None,
             surface_settings: SurfaceSettings::default(),
@@ -25,6 +25,7 @@ mod inner {
     /// A context used for rendering
     pub struct WindowedContext {
         pub(super) context: Context,
+        multisamples: u8,
     }
 
     impl WindowedContext {
@@ -66,13 +67,26 @@ mod inner {
                 .get_extension("OES_texture_half_float_linear")
                 .map_err(|e| WindowError::OESTextureFloatNotSupported(format!(": {:?}", e)))?;
 
+            // The number of samples is chosen by the browser
+            let multisamples = webgl_context
+                .get_parameter(web_sys::WebGl2RenderingContext::SAMPLES)
+                .ok()
+                .and_then(|samples| samples.as_f64())
+                .unwrap_or(0.0) as u8;
+
             Ok(Self {
                 context: Context::from_gl_context(Arc::new(
                     crate::context::Context::from_webgl2_context(webgl_context),
                 ))?,
+                multisamples,
             })
         }
 
+        /// Returns the actual number of samples used for multisample anti-aliasing (MSAA) of the window, see [SurfaceSettings::multisamples].
+        pub fn multisamples(&self) -> u8 {
+            self.multisamples
+        }
+
         /// Resizes the context
         pub fn resize(&self, _physical_size: winit::dpi::PhysicalSize<u32>) {}
 
@@ -81,6 +95,11 @@ mod inner {
             Ok(())
         }
 
+        /// Enables or disabled vsync. Does nothing on web, since the browser always synchronizes the rendering with the display.
+        pub fn set_vsync(&self, _enabled: bool) -> Result<(), WindowError> {
+            Ok(())
+        }
+
         /// Swap buffers - should always be called after rendering.
         pub fn swap_buffers(&self) -> Result<(), WindowError> {
             Ok(())
@@ -101,6 +120,7 @@ mod inner {
         pub(super) context: Context,
         surface: Surface<WindowSurface>,
         glutin_context: glutin::context::PossiblyCurrentContext,
+        multisamples: u8,
     }
 
     impl WindowedContext {
@@ -151,28 +171,44 @@ mod inner {
                 crate::HardwareAcceleration::Preferred => None,
                 crate::HardwareAcceleration::Off => Some(false),
             };
-            let config_template = glutin::config::ConfigTemplateBuilder::new()
-                .prefer_hardware_accelerated(hardware_acceleration)
-                .with_depth_size(settings.depth_buffer);
-            // we don't know if multi sampling option is set. so, check if its more than 0.
-            let config_template = if settings.multisamples > 0 {
-                config_template.with_multisampling(settings.multisamples)
-            } else {
+            let config_template = |multisamples: u8| {
+                let config_template = glutin::config::ConfigTemplateBuilder::new()
+                    .prefer_hardware_accelerated(hardware_acceleration)
+                    .with_depth_size(settings.depth_buffer);
+                // we don't know if multi sampling option is set. so, check if its more than 0.
+                let config_template = if multisamples > 0 {
+                    config_template.with_multisampling(multisamples)
+                } else {
+                    config_template
+                };
                 config_template
+                    .with_stencil_size(settings.stencil_buffer)
+                    .compatible_with_native_window(raw_window_handle)
+                    .build()
             };
-            let config_template = config_template
-                .with_stencil_size(settings.stencil_buffer)
-                .compatible_with_native_window(raw_window_handle)
-                .build();
-            // finds all valid configurations supported by this display that match the
-            // config_template this is where we will try to get a "fallback" config if
-            // we are okay with ignoring some native options required by user like multi
-            // sampling, srgb, transparency etc..
-            let config = unsafe {
-                gl_display
-                    .find_configs(config_template)?
-                    .next()
-                    .ok_or(WindowError::SurfaceCreationError)?
+            // finds the configuration with the most samples that does not exceed the requested number of samples.
+            // If none is found, the number of samples is halved until a configuration is found, which is the fallback
+            // when the requested number of samples is not supported.
+            let mut multisamples = settings.multisamples;
+            let config = loop {
+                let config = unsafe { gl_display.find_configs(config_template(multisamples)) }
+                    .ok()
+                    .and_then(|configs| {
+                        configs
+                            .filter(|config| config.num_samples() <= settings.multisamples)
+                            .reduce(|best, config| {
+                                if config.num_samples() > best.num_samples() {
+                                    config
+                                } else {
+                                    best
+                                }
+                            })
+                    });
+                match config {
+                    Some(config) => break config,
+                    None if multisamples > 0 => multisamples /= 2,
+                    None => Err(WindowError::SurfaceCreationError)?,
+                }
             };
 
             let context_attributes =
@@ -203,9 +239,16 @@ mod inner {
                 }))?,
                 glutin_context: gl_context,
                 surface: gl_surface,
+                multisamples: config.num_samples(),
             })
         }
 
+        /// Returns the actual number of samples used for multisample anti-aliasing (MSAA) of the window,
+        /// which can be less than [SurfaceSettings::multisamples] if the requested number of samples is not supported.
+        pub fn multisamples(&self) -> u8 {
+            self.multisamples
+        }
+
         /// Resizes the context
         pub fn resize(&self, physical_size: winit::dpi::PhysicalSize<u32>) {
             let width = std::num::NonZeroU32::new(physical_size.width.max(1)).unwrap();